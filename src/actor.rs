@@ -1,6 +1,12 @@
 use crate::{
-    bot::Bot, character::Character, level::UpdateContext, message::Message, player::Player,
+    bot::{self, Bot, Intent, ThinkSnapshot},
+    character::{Character, Team},
+    level::UpdateContext,
+    message::Message,
+    player::Player,
+    voice,
 };
+use rayon::prelude::*;
 use rg3d::{
     core::{
         algebra::Vector3,
@@ -9,10 +15,18 @@ use rg3d::{
         },
         visitor::{Visit, VisitResult, Visitor},
     },
-    physics3d::rapier::geometry::ContactEvent,
+    physics3d::rapier::geometry::{ColliderHandle, ContactEvent},
     scene::Scene,
+    utils::navmesh::Navmesh,
 };
-use std::ops::{Deref, DerefMut};
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    sync::Mutex,
+};
+
+/// Max distance between actor and item at which item will be picked up.
+const PICK_UP_RADIUS: f32 = 1.25;
 
 #[allow(clippy::large_enum_variant)]
 pub enum Actor {
@@ -58,6 +72,13 @@ impl Actor {
     pub fn clean_up(&mut self, scene: &mut Scene) {
         static_dispatch!(self, clean_up, scene)
     }
+
+    pub fn voice(&self) -> &'static str {
+        match self {
+            Actor::Player(_) => voice::PLAYER_VOICE,
+            Actor::Bot(bot) => bot.definition.voice,
+        }
+    }
 }
 
 impl Deref for Actor {
@@ -107,12 +128,23 @@ pub struct TargetDescriptor {
     pub handle: Handle<Actor>,
     pub health: f32,
     pub position: Vector3<f32>,
+    pub velocity: Vector3<f32>,
+    /// Actor holds a weapon which bots consider dangerous.
+    pub high_priority: bool,
+    pub team: Team,
 }
 
 #[derive(Default)]
 pub struct ActorContainer {
     pool: Pool<Actor>,
     target_descriptors: Vec<TargetDescriptor>,
+    // Maps capsule collider of an actor to the actor, rebuilt every frame.
+    collider_map: HashMap<ColliderHandle, Handle<Actor>>,
+    think_snapshots: Vec<ThinkSnapshot>,
+    intents: Vec<Intent>,
+    // Not serialized, copies of level's navmesh for path finding in think stage, one per worker
+    // thread. Navmesh does not change during a match, so they are made once on first think.
+    navmeshes: Vec<Mutex<Navmesh>>,
 }
 
 impl ActorContainer {
@@ -120,6 +152,10 @@ impl ActorContainer {
         Self {
             pool: Default::default(),
             target_descriptors: Default::default(),
+            collider_map: Default::default(),
+            think_snapshots: Default::default(),
+            intents: Default::default(),
+            navmeshes: Default::default(),
         }
     }
 
@@ -149,34 +185,68 @@ impl ActorContainer {
         self.pool.free(actor_handle);
     }
 
+    /// Returns snapshot of every actor made at last update.
+    pub fn target_descriptors(&self) -> &[TargetDescriptor] {
+        &self.target_descriptors
+    }
+
     pub fn count(&self) -> u32 {
         self.pool.alive_count()
     }
 
     pub fn update(&mut self, context: &mut UpdateContext) {
         self.target_descriptors.clear();
+        self.collider_map.clear();
         for (handle, actor) in self.pool.pair_iter() {
+            let weapon = actor.current_weapon();
             self.target_descriptors.push(TargetDescriptor {
                 handle,
                 health: actor.health,
                 position: actor.position(&context.scene.physics),
+                velocity: context
+                    .scene
+                    .physics
+                    .bodies
+                    .get(&actor.get_body())
+                    .map_or_else(Default::default, |body| *body.linvel()),
+                high_priority: context.weapons.contains(weapon)
+                    && context.weapons[weapon].definition.high_priority,
+                team: actor.team(),
             });
+            if let Some(body) = context.scene.physics.bodies.get(&actor.get_body()) {
+                if let Some(&collider) = body.colliders().first() {
+                    self.collider_map.insert(collider, handle);
+                }
+            }
         }
 
+        self.think(context);
+
         for (handle, actor) in self.pool.pair_iter_mut() {
             let is_dead = actor.is_dead();
 
             match actor {
-                Actor::Bot(bot) => bot.update(handle, context, &self.target_descriptors),
+                Actor::Bot(bot) => bot.update(context),
                 Actor::Player(player) => player.update(context),
             }
             if !is_dead {
-                for (item_handle, item) in context.items.pair_iter() {
-                    let body = context.scene.physics.bodies.get(&actor.get_body()).unwrap();
-                    let distance = (context.scene.graph[item.get_pivot()].global_position()
-                        - body.position().translation.vector)
-                        .norm();
-                    if distance < 1.25 && !item.is_picked_up() {
+                let position = context
+                    .scene
+                    .physics
+                    .bodies
+                    .get(&actor.get_body())
+                    .unwrap()
+                    .position()
+                    .translation
+                    .vector;
+                for item_handle in context.items.query(position, PICK_UP_RADIUS) {
+                    if !context.items.contains(item_handle) {
+                        continue;
+                    }
+                    let item = context.items.get(item_handle);
+                    let distance =
+                        (context.scene.graph[item.get_pivot()].global_position() - position).norm();
+                    if distance < PICK_UP_RADIUS && !item.is_picked_up() {
                         actor
                             .sender
                             .as_ref()
@@ -203,63 +273,87 @@ impl ActorContainer {
         }
     }
 
+    // Bots make their decisions in parallel using immutable snapshots, decisions then applied
+    // serially. Visibility of targets is checked while snapshots are gathered, because physics
+    // can't be queried from several threads. Path finding needs mutable navmesh, so every worker
+    // thread finds paths on its own copy.
+    fn think(&mut self, context: &UpdateContext) {
+        let physics = &context.scene.physics;
+        let targets = &self.target_descriptors;
+
+        self.think_snapshots.clear();
+        for (handle, actor) in self.pool.pair_iter() {
+            if let Actor::Bot(bot) = actor {
+                if !bot.is_dead() {
+                    self.think_snapshots
+                        .push(bot.snapshot(handle, physics, targets, context.time));
+                }
+            }
+        }
+
+        if self.navmeshes.is_empty() && context.navmesh.is_some() {
+            let navmesh = &context.scene.navmeshes[context.navmesh];
+            self.navmeshes = (0..rayon::current_num_threads())
+                .map(|_| Mutex::new(navmesh.clone()))
+                .collect();
+        }
+
+        let navmeshes = &self.navmeshes;
+        self.intents.clear();
+        self.intents
+            .par_extend(self.think_snapshots.par_iter().map(|snapshot| {
+                // Every worker thread locks only its own copy, so lock is never contended.
+                let mut navmesh = navmeshes
+                    .get(rayon::current_thread_index().unwrap_or_default())
+                    .map(|navmesh| navmesh.lock().unwrap());
+                bot::think(snapshot, targets, navmesh.as_deref_mut())
+            }));
+
+        for intent in self.intents.drain(..) {
+            if let Actor::Bot(bot) = self.pool.borrow_mut(intent.handle) {
+                bot.apply_intent(intent, context.time);
+            }
+        }
+    }
+
     pub fn handle_event(&mut self, contact_event: &ContactEvent, context: &mut UpdateContext) {
         if let &ContactEvent::Started(a, b) = contact_event {
-            for actor in self.pool.iter_mut() {
-                for jump_pad in context.jump_pads.iter() {
-                    let coll_a = context
-                        .scene
-                        .physics
-                        .bodies
-                        .handle_map()
-                        .key_of(
-                            &context
-                                .scene
-                                .physics
-                                .colliders
-                                .native_ref(a)
-                                .unwrap()
-                                .parent()
-                                .unwrap(),
-                        )
-                        .cloned()
-                        .unwrap();
-                    let coll_b = context
-                        .scene
-                        .physics
-                        .bodies
-                        .handle_map()
-                        .key_of(
-                            &context
-                                .scene
-                                .physics
-                                .colliders
-                                .native_ref(b)
-                                .unwrap()
-                                .parent()
-                                .unwrap(),
-                        )
-                        .cloned()
-                        .unwrap();
-
-                    let body = context
-                        .scene
-                        .physics
-                        .bodies
-                        .get_mut(&actor.get_body())
-                        .unwrap();
-                    let capsule_collider = body.colliders()[0];
-
-                    if capsule_collider == a && coll_b == jump_pad.rigid_body()
-                        || capsule_collider == b && coll_a == jump_pad.rigid_body()
-                    {
-                        body.set_linvel(jump_pad.get_force(), true);
-                    }
-                }
+            // Resolve contact pair once and only then look for an actor touching a jump pad.
+            let contact = self
+                .find_jump_pad_contact(a, b, context)
+                .or_else(|| self.find_jump_pad_contact(b, a, context));
+
+            if let Some((actor, force)) = contact {
+                let body = context
+                    .scene
+                    .physics
+                    .bodies
+                    .get_mut(&self.pool.borrow(actor).get_body())
+                    .unwrap();
+                body.set_linvel(force, true);
             }
         }
     }
 
+    fn find_jump_pad_contact(
+        &self,
+        actor_collider: ColliderHandle,
+        jump_pad_collider: ColliderHandle,
+        context: &UpdateContext,
+    ) -> Option<(Handle<Actor>, Vector3<f32>)> {
+        let actor = *self.collider_map.get(&actor_collider)?;
+        if !self.pool.is_valid_handle(actor) {
+            return None;
+        }
+        let physics = &context.scene.physics;
+        let parent = physics.colliders.native_ref(jump_pad_collider)?.parent()?;
+        let body = physics.bodies.handle_map().key_of(&parent).cloned()?;
+        context
+            .jump_pads
+            .find_by_body(body)
+            .map(|jump_pad| (actor, jump_pad.get_force()))
+    }
+
     pub fn iter(&self) -> PoolIterator<Actor> {
         self.pool.iter()
     }
@@ -0,0 +1,166 @@
+//! Announcer calls out important changes of match state - lead changes and remaining time.
+//! Level detects such changes and sends `Message::Announce`, announcer shows them one by one
+//! in the middle of the screen accompanied by a sound.
+
+use crate::{character::Team, gui::UiScale, pak};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    engine::{resource_manager::ResourceManager, Engine},
+    gui::{
+        brush::Brush,
+        message::MessageDirection,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+    sound::{
+        context::SoundContext,
+        source::{generic::GenericSourceBuilder, Status},
+    },
+};
+use std::collections::VecDeque;
+
+/// How long (in seconds) each announcement stays on screen.
+const ANNOUNCEMENT_TIME: f32 = 2.5;
+
+const ANNOUNCEMENT_SOUND: &str = "data/sounds/item_pickup.ogg";
+
+/// Announcements are relative to player, level knows who the player is so it decides
+/// whether player's team leads or not. Team of a flag announcement is the owner of the flag,
+/// except for capture where it is the team that scores.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Announcement {
+    LeadTaken,
+    LeadLost,
+    TiedForLead,
+    TeamLeads { team: Team, own: bool },
+    TimeRemaining { minutes: u32 },
+    FlagTaken { team: Team, own: bool },
+    FlagDropped { team: Team, own: bool },
+    FlagReturned { team: Team, own: bool },
+    FlagCaptured { team: Team, own: bool },
+}
+
+impl Announcement {
+    pub fn text(&self) -> String {
+        match self {
+            Announcement::LeadTaken => "You have taken the lead".to_owned(),
+            Announcement::LeadLost => "You have lost the lead".to_owned(),
+            Announcement::TiedForLead => "Tied for the lead".to_owned(),
+            Announcement::TeamLeads { own: true, .. } => "Your team leads".to_owned(),
+            Announcement::TeamLeads { team, own: false } => format!("{:?} team leads", team),
+            Announcement::TimeRemaining { minutes: 1 } => "One minute remaining".to_owned(),
+            Announcement::TimeRemaining { minutes } => format!("{} minutes remaining", minutes),
+            Announcement::FlagTaken { own: true, .. } => "Your flag has been taken".to_owned(),
+            Announcement::FlagTaken { team, own: false } => format!("{:?} flag taken", team),
+            Announcement::FlagDropped { own: true, .. } => "Your flag has been dropped".to_owned(),
+            Announcement::FlagDropped { team, own: false } => format!("{:?} flag dropped", team),
+            Announcement::FlagReturned { own: true, .. } => "Your flag has returned".to_owned(),
+            Announcement::FlagReturned { team, own: false } => {
+                format!("{:?} flag returned", team)
+            }
+            Announcement::FlagCaptured { own: true, .. } => "Your team scores".to_owned(),
+            Announcement::FlagCaptured { team, own: false } => format!("{:?} team scores", team),
+        }
+    }
+}
+
+pub struct Announcer {
+    text: Handle<UiNode>,
+    queue: VecDeque<Announcement>,
+    timeout: f32,
+    sound_context: SoundContext,
+    resource_manager: ResourceManager,
+}
+
+impl Announcer {
+    pub fn new(engine: &mut Engine, ui_scale: &mut UiScale) -> Self {
+        let font = ui_scale.font("data/ui/SquaresBold.ttf", 40.0);
+
+        let text = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .on_row(0)
+                .on_column(1)
+                .with_foreground(Brush::Solid(Color::opaque(255, 140, 0)))
+                .with_vertical_alignment(VerticalAlignment::Top)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_margin(Thickness::top(160.0)),
+        )
+        .with_font(font)
+        .build(&mut engine.user_interface.build_ctx());
+
+        // Announcements are not positional, so they're played in a separate context.
+        let sound_context = SoundContext::new();
+        engine
+            .sound_engine
+            .lock()
+            .unwrap()
+            .add_context(sound_context.clone());
+
+        Self {
+            text,
+            queue: Default::default(),
+            timeout: 0.0,
+            sound_context,
+            resource_manager: engine.resource_manager.clone(),
+        }
+    }
+
+    /// Returns handle of text widget, it must be attached to HUD.
+    pub fn widget(&self) -> Handle<UiNode> {
+        self.text
+    }
+
+    pub fn announce(&mut self, announcement: Announcement) {
+        // Same announcement queued twice in a row is just noise.
+        if self.queue.back() != Some(&announcement) {
+            self.queue.push_back(announcement);
+        }
+    }
+
+    fn play_sound(&self) {
+        if let Ok(buffer) = rg3d::core::futures::executor::block_on(
+            self.resource_manager
+                .request_sound_buffer(pak::resolve(ANNOUNCEMENT_SOUND), false),
+        ) {
+            self.sound_context.state().add_source(
+                GenericSourceBuilder::new()
+                    .with_buffer(buffer.into())
+                    .with_status(Status::Playing)
+                    .with_play_once(true)
+                    .build_source()
+                    .unwrap(),
+            );
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        if self.timeout > 0.0 {
+            self.timeout -= dt;
+            if self.timeout > 0.0 {
+                return;
+            }
+            ui.send_message(WidgetMessage::visibility(
+                self.text,
+                MessageDirection::ToWidget,
+                false,
+            ));
+        }
+
+        if let Some(announcement) = self.queue.pop_front() {
+            ui.send_message(TextMessage::text(
+                self.text,
+                MessageDirection::ToWidget,
+                announcement.text(),
+            ));
+            ui.send_message(WidgetMessage::visibility(
+                self.text,
+                MessageDirection::ToWidget,
+                true,
+            ));
+            self.play_sound();
+            self.timeout = ANNOUNCEMENT_TIME;
+        }
+    }
+}
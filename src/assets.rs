@@ -0,0 +1,187 @@
+//! Asset integrity checks. Assets that definitions of the game refer to are checked at startup
+//! and maps are checked on level load. An asset that is missing or fails to load is replaced
+//! by a placeholder (pink checker cube for models, silence for sounds) and reported, the game
+//! shows reported assets in a dialog instead of panicking in the middle of loading.
+
+use crate::{
+    bot::{Bot, BotKind},
+    item::{Item, ItemKind},
+    logger::{self, Category},
+    maps, pak,
+    weapon::{Weapon, WeaponKind},
+};
+use rg3d::{
+    core::{algebra::Matrix4, color::Color, pool::Handle},
+    engine::resource_manager::{MaterialSearchOptions, ResourceManager},
+    resource::texture::{Texture, TextureKind, TexturePixelKind},
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder,
+        },
+        node::Node,
+        Scene,
+    },
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Assets that are not part of any definition.
+const OTHER_ASSETS: [&str; 10] = [
+    "data/models/rocket.FBX",
+    "data/particles/circle_05.png",
+    "data/particles/light_01.png",
+    "data/particles/smoke_04.tga",
+    "data/particles/star_09.png",
+    "data/sounds/heartbeat.wav",
+    "data/ui/ammo_icon.png",
+    "data/ui/crosshair.tga",
+    "data/ui/health_icon.png",
+    "data/ui/shield_icon.png",
+];
+
+/// Size (in pixels) of placeholder texture and of its cells.
+const CHECKER_SIZE: usize = 64;
+const CHECKER_CELL: usize = 8;
+
+const CHECKER_COLORS: [Color; 2] = [Color::opaque(255, 0, 255), Color::opaque(0, 0, 0)];
+
+/// Half-size (in meters) of placeholder cube.
+const PLACEHOLDER_SIZE: f32 = 0.25;
+
+// Reported assets which are not shown to player yet.
+static MISSING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+// Every asset is reported once, it would be requested again and again otherwise.
+static REPORTED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+pub fn report_missing(path: &Path, reason: &str) {
+    let mut reported = REPORTED.lock().unwrap();
+    if reported.iter().any(|reported| reported == path) {
+        return;
+    }
+    reported.push(path.to_owned());
+    logger::error(
+        Category::General,
+        format!(
+            "Asset {} is missing or broken, placeholder is used. Reason: {}",
+            path.display(),
+            reason
+        ),
+    );
+    MISSING.lock().unwrap().push(path.to_owned());
+}
+
+/// Returns assets that were reported since last call.
+pub fn take_missing() -> Vec<PathBuf> {
+    std::mem::take(&mut *MISSING.lock().unwrap())
+}
+
+pub fn validate<'a, I: IntoIterator<Item = &'a str>>(paths: I) {
+    for path in paths {
+        let path = Path::new(path);
+        if !pak::resolve(path).is_file() {
+            report_missing(path, "file not found");
+        }
+    }
+}
+
+/// Checks assets of weapons, bots, items and maps, must be called once on start.
+pub fn validate_definitions() {
+    let mut paths = OTHER_ASSETS.to_vec();
+
+    let mut id = 0;
+    while let Ok(kind) = WeaponKind::new(id) {
+        let definition = Weapon::get_definition(kind);
+        paths.push(definition.model);
+        paths.push(definition.shot_sound);
+        id += 1;
+    }
+
+    let mut id = 0;
+    while let Ok(kind) = BotKind::from_id(id) {
+        let definition = Bot::get_definition(kind);
+        paths.extend_from_slice(&[
+            definition.model,
+            definition.idle_animation,
+            definition.walk_animation,
+            definition.aim_animation,
+            definition.whip_animation,
+            definition.jump_animation,
+            definition.falling_animation,
+            definition.hit_reaction_animation,
+            definition.dying_animation,
+            definition.dead_animation,
+        ]);
+        id += 1;
+    }
+
+    let mut id = 0;
+    while let Ok(kind) = ItemKind::from_id(id) {
+        paths.push(Item::get_definition(kind).model);
+        id += 1;
+    }
+
+    paths.extend(maps::MAPS.iter().map(|map| map.path));
+
+    paths.sort_unstable();
+    paths.dedup();
+    validate(paths);
+}
+
+fn make_checker_texture() -> Option<Texture> {
+    let mut bytes = Vec::with_capacity(CHECKER_SIZE * CHECKER_SIZE * 4);
+    for y in 0..CHECKER_SIZE {
+        for x in 0..CHECKER_SIZE {
+            let color = CHECKER_COLORS[(x / CHECKER_CELL + y / CHECKER_CELL) % 2];
+            bytes.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+    Texture::from_bytes(
+        TextureKind::Rectangle {
+            width: CHECKER_SIZE as u32,
+            height: CHECKER_SIZE as u32,
+        },
+        TexturePixelKind::RGBA8,
+        bytes,
+        false,
+    )
+}
+
+pub fn make_placeholder(graph: &mut Graph) -> Handle<Node> {
+    let mut surface = SurfaceBuilder::new(Arc::new(RwLock::new(SurfaceData::make_cube(
+        Matrix4::new_scaling(PLACEHOLDER_SIZE * 2.0),
+    ))))
+    .build();
+    surface.set_diffuse_texture(make_checker_texture());
+    MeshBuilder::new(BaseBuilder::new().with_name("Placeholder"))
+        .with_surfaces(vec![surface])
+        .build(graph)
+}
+
+/// Loads a model and instantiates it, placeholder is instantiated if the model can't be loaded.
+pub async fn instantiate_model<P: AsRef<Path>>(
+    resource_manager: &ResourceManager,
+    path: P,
+    scene: &mut Scene,
+) -> Handle<Node> {
+    let path = path.as_ref();
+    let resolved = pak::resolve(path);
+    match resource_manager
+        .request_model(
+            &resolved,
+            MaterialSearchOptions::MaterialsDirectory(pak::textures_dir(&resolved)),
+        )
+        .await
+    {
+        Ok(model) => model.instantiate_geometry(scene),
+        Err(e) => {
+            report_missing(path, &format!("{:?}", e));
+            make_placeholder(&mut scene.graph)
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! End-of-match awards. Awards are computed from counters of actors in `weapon_stats` and from
+//! leader board, intermission shows them as cards with names of winners.
+
+use crate::{
+    leader_board::LeaderBoard,
+    weapon_stats::{ActorTally, WeaponStats},
+};
+
+/// Actors with fewer shots can not win accuracy award, a lucky single shot is not an achievement.
+const MIN_SHOTS_FOR_ACCURACY: u64 = 20;
+
+#[derive(Clone, Debug)]
+pub struct Award {
+    pub title: &'static str,
+    pub name: String,
+    pub value: String,
+}
+
+/// Returns awards of the match, award is skipped if nobody qualifies for it. Ties are resolved
+/// in favor of the name that comes first.
+pub fn compute(stats: &WeaponStats, leader_board: &LeaderBoard) -> Vec<Award> {
+    let mut awards = Vec::new();
+
+    let mut scores = leader_board.values().iter().collect::<Vec<_>>();
+    scores.sort_by(|(a_name, a), (b_name, b)| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.kills.cmp(&a.kills))
+            .then_with(|| a_name.cmp(b_name))
+    });
+    if let Some((name, score)) = scores.first().filter(|(_, score)| score.score > 0) {
+        awards.push(Award {
+            title: "MVP",
+            name: (*name).clone(),
+            value: format!("{} points", score.score),
+        });
+    }
+
+    let actors = stats.actors();
+    if let Some((name, kills)) = best_by(&actors, |tally| tally.kills as f64) {
+        awards.push(Award {
+            title: "Most Kills",
+            name: name.to_owned(),
+            value: format!("{} kills", kills),
+        });
+    }
+    if let Some((name, accuracy)) = best_by(&actors, |tally| {
+        if tally.shots >= MIN_SHOTS_FOR_ACCURACY {
+            tally.accuracy()
+        } else {
+            0.0
+        }
+    }) {
+        awards.push(Award {
+            title: "Best Accuracy",
+            name: name.to_owned(),
+            value: format!("{:.1}%", accuracy),
+        });
+    }
+    if let Some((name, captures)) = best_by(&actors, |tally| tally.captures as f64) {
+        awards.push(Award {
+            title: "Most Captures",
+            name: name.to_owned(),
+            value: format!("{} flags", captures),
+        });
+    }
+    if let Some((name, damage)) = best_by(&actors, |tally| tally.damage) {
+        awards.push(Award {
+            title: "Most Damage",
+            name: name.to_owned(),
+            value: format!("{:.0} damage", damage),
+        });
+    }
+
+    awards
+}
+
+/// Actor with highest positive value, actors must be sorted by name.
+fn best_by<'a, F>(actors: &[(&'a str, &ActorTally)], value: F) -> Option<(&'a str, f64)>
+where
+    F: Fn(&ActorTally) -> f64,
+{
+    let mut best: Option<(&str, f64)> = None;
+    for (name, tally) in actors {
+        let value = value(tally);
+        if value > 0.0 && best.map_or(true, |(_, best)| value > best) {
+            best = Some((*name, value));
+        }
+    }
+    best
+}
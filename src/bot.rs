@@ -1,11 +1,20 @@
 use crate::{
     actor::{Actor, TargetDescriptor},
-    character::Character,
+    assets,
+    character::{Character, Team},
+    debug_overlay::{DebugOverlay, DebugOverlays},
     item::ItemContainer,
+    jump_pad::JumpPadContainer,
     level::UpdateContext,
+    logger::{self, Category},
     message::Message,
-    weapon::WeaponContainer,
-    GameTime,
+    pak,
+    ping::{self, PingKind},
+    projectile::Projectile,
+    saves,
+    squad::Role,
+    weapon::{Shot, Weapon, WeaponContainer},
+    Difficulty, GameTime, ItemEconomy,
 };
 use rg3d::engine::resource_manager::MaterialSearchOptions;
 use rg3d::{
@@ -15,7 +24,7 @@ use rg3d::{
     },
     core::rand::Rng,
     core::{
-        algebra::{Matrix4, Point3, UnitQuaternion, Vector3},
+        algebra::{Matrix4, Point3, Unit, UnitQuaternion, Vector3},
         color::Color,
         math::{frustum::Frustum, ray::Ray, SmoothAngle, Vector3Ext},
         pool::Handle,
@@ -33,11 +42,11 @@ use rg3d::{
         self, base::BaseBuilder, debug::SceneDrawingContext, graph::Graph, node::Node,
         physics::Physics, transform::TransformBuilder, Scene,
     },
-    utils::log::{Log, MessageKind},
     utils::navmesh::Navmesh,
 };
 use std::path::PathBuf;
 use std::{
+    fmt::Debug,
     ops::{Deref, DerefMut},
     path::Path,
     sync::mpsc::Sender,
@@ -53,6 +62,8 @@ pub enum BotKind {
 }
 
 impl BotKind {
+    pub const ALL: [BotKind; 3] = [BotKind::Mutant, BotKind::Parasite, BotKind::Maw];
+
     pub fn from_id(id: i32) -> Result<Self, String> {
         match id {
             0 => Ok(BotKind::Mutant),
@@ -79,9 +90,118 @@ impl BotKind {
     }
 }
 
+/// How much of a bot is culled by distance to the camera. There are no lower-detail models,
+/// distant bots are animated less often and stop casting shadows.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Lod {
+    High,
+    Medium,
+    Low,
+}
+
+impl Lod {
+    pub fn from_distance(distance: f32, near: f32, far: f32) -> Self {
+        if distance < near {
+            Lod::High
+        } else if distance < far {
+            Lod::Medium
+        } else {
+            Lod::Low
+        }
+    }
+
+    /// Amount of frames between updates of animation machines.
+    fn animation_interval(self) -> u32 {
+        match self {
+            Lod::High => 1,
+            Lod::Medium => 2,
+            Lod::Low => 4,
+        }
+    }
+
+    fn cast_shadows(self) -> bool {
+        self != Lod::Low
+    }
+}
+
+/// Immutable state of a bot which is enough to make decisions. Snapshots are gathered
+/// serially and then processed in parallel by [`think`]. Visibility of targets is checked while
+/// snapshot is gathered, ray casts use query pipeline of physics which can't be shared between
+/// threads.
+pub struct ThinkSnapshot {
+    pub handle: Handle<Actor>,
+    position: Vector3<f32>,
+    // Indices of target descriptors which bot can see, teammates are not included.
+    visible_targets: Vec<usize>,
+    // Start and end of a path that must be rebuilt.
+    path_request: Option<(Vector3<f32>, Vector3<f32>)>,
+}
+
+/// Decision made by a bot during think stage, applied serially.
+pub struct Intent {
+    pub handle: Handle<Actor>,
+    target: Option<Target>,
+    path: Option<Vec<Vector3<f32>>>,
+}
+
+fn find_path(
+    navmesh: &mut Navmesh,
+    from: Vector3<f32>,
+    to: Vector3<f32>,
+) -> Option<Vec<Vector3<f32>>> {
+    let from_index = navmesh.query_closest(from)?;
+    let to_index = navmesh.query_closest(to)?;
+    let mut path = Vec::new();
+    navmesh.build_path(from_index, to_index, &mut path).ok()?;
+    path.reverse();
+    Some(path)
+}
+
+/// Think stage of a bot, selects closest visible target, high priority targets are preferred
+/// over closer ones. Path is rebuilt on given navmesh if it was requested. It does not mutate
+/// anything but the navmesh, so it can be done for every bot in parallel as long as every
+/// thread has its own navmesh.
+pub fn think(
+    snapshot: &ThinkSnapshot,
+    targets: &[TargetDescriptor],
+    navmesh: Option<&mut Navmesh>,
+) -> Intent {
+    let mut target = None;
+    let mut closest_distance = std::f32::MAX;
+    let mut high_priority = false;
+
+    for desc in snapshot.visible_targets.iter().map(|&i| &targets[i]) {
+        let sqr_d = snapshot.position.sqr_distance(&desc.position);
+        if desc.high_priority && !high_priority
+            || desc.high_priority == high_priority && sqr_d < closest_distance
+        {
+            target = Some(Target {
+                position: desc.position,
+                velocity: desc.velocity,
+                handle: desc.handle,
+            });
+            closest_distance = sqr_d;
+            high_priority = desc.high_priority;
+        }
+    }
+
+    let path = match (navmesh, snapshot.path_request) {
+        (Some(navmesh), Some((from, to))) => find_path(navmesh, from, to),
+        _ => None,
+    };
+
+    Intent {
+        handle: snapshot.handle,
+        target,
+        path,
+    }
+}
+
 #[derive(Debug)]
 pub struct Target {
     position: Vector3<f32>,
+    // Not serialized, velocity is known again after next think.
+    velocity: Vector3<f32>,
     handle: Handle<Actor>,
 }
 
@@ -89,11 +209,54 @@ impl Default for Target {
     fn default() -> Self {
         Self {
             position: Default::default(),
+            velocity: Default::default(),
             handle: Default::default(),
         }
     }
 }
 
+/// Returns point where a projectile fired now from `origin` meets a target that keeps moving
+/// with constant velocity. Target position is returned if projectile can never catch up with
+/// the target. Used by anything that fires slow projectiles at moving actors.
+pub fn lead_target(
+    origin: Vector3<f32>,
+    target: Vector3<f32>,
+    target_velocity: Vector3<f32>,
+    projectile_speed: f32,
+) -> Vector3<f32> {
+    // Solve |d + v * t| = s * t for smallest positive t.
+    let d = target - origin;
+    let a = target_velocity.norm_squared() - projectile_speed * projectile_speed;
+    let b = 2.0 * d.dot(&target_velocity);
+    let c = d.norm_squared();
+    let time = if a.abs() < std::f32::EPSILON {
+        if b.abs() < std::f32::EPSILON {
+            None
+        } else {
+            Some(-c / b)
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            None
+        } else {
+            let root = discriminant.sqrt();
+            let t1 = (-b - root) / (2.0 * a);
+            let t2 = (-b + root) / (2.0 * a);
+            match (t1 > 0.0, t2 > 0.0) {
+                (true, true) => Some(t1.min(t2)),
+                (true, false) => Some(t1),
+                (false, true) => Some(t2),
+                (false, false) => None,
+            }
+        }
+    };
+    match time {
+        Some(time) if time > 0.0 => target + target_velocity.scale(time),
+        _ => target,
+    }
+}
+
 impl Visit for Target {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         visitor.enter_region(name)?;
@@ -127,6 +290,38 @@ pub struct Bot {
     spine: Handle<Node>,
     yaw: SmoothAngle,
     pitch: SmoothAngle,
+    // Direction to unseen attacker, bot will look at it until it finds a target.
+    threat_direction: Option<Vector3<f32>>,
+    threat_timer: f32,
+    evade_timer: f32,
+    evade_side: f32,
+    lod: Lod,
+    // Frame counter and accumulated time used to throttle animation of distant bots.
+    animation_frame: u32,
+    animation_time: f32,
+    /// Target dummies of training level stand still and never fight back.
+    pub dummy: bool,
+    /// Moving target dummies of practice mode walk back and forth around this point.
+    pub patrol_origin: Option<Vector3<f32>>,
+    // Not serialized, moving dummy of loaded game goes to the positive side first.
+    patrol_side: f32,
+    // Not serialized, bot that is on fire runs away from its target.
+    fleeing: bool,
+    // Not serialized, aim model starts over after loading. Target that bot aims at, direction
+    // of aim that lags behind actual direction to target, time left until bot reacts to new
+    // target and for how long target is in sight.
+    aimed_target: Handle<Actor>,
+    aim_dir: Vector3<f32>,
+    reaction_timer: f32,
+    tracking_time: f32,
+    // Not serialized, time left until bot may switch weapon again.
+    weapon_switch_timer: f32,
+    // Not serialized, combat movement is restarted after loading. Time until next strafe,
+    // direction of sidestep away from incoming projectile and whether bot should jump to
+    // dodge it.
+    strafe_timer: f32,
+    dodge_direction: Option<Vector3<f32>>,
+    dodge_jump: bool,
 }
 
 impl Deref for Bot {
@@ -175,6 +370,25 @@ impl Default for Bot {
                 target: 0.0,
                 speed: 260.0f32.to_radians(), // rad/s
             },
+            threat_direction: None,
+            threat_timer: 0.0,
+            evade_timer: 0.0,
+            evade_side: 1.0,
+            lod: Lod::High,
+            animation_frame: 0,
+            animation_time: 0.0,
+            dummy: false,
+            patrol_origin: None,
+            patrol_side: 1.0,
+            fleeing: false,
+            aimed_target: Handle::NONE,
+            aim_dir: Vector3::z(),
+            reaction_timer: 0.0,
+            tracking_time: 0.0,
+            weapon_switch_timer: 0.0,
+            strafe_timer: 0.0,
+            dodge_direction: None,
+            dodge_jump: false,
         }
     }
 }
@@ -199,15 +413,27 @@ pub struct BotDefinition {
     pub left_leg_name: &'static str,
     pub right_leg_name: &'static str,
     pub spine: &'static str,
+    /// Name of voice set, see `voice` module.
+    pub voice: &'static str,
     pub v_aim_angle_hack: f32,
 }
 
-fn prepare_animation(
+// Empty animation is used if model of the animation can't be loaded, bot just stays in bind
+// pose then.
+fn prepare_animation<E: Debug>(
     scene: &mut Scene,
-    model: Model,
+    model: Result<Model, E>,
+    path: &str,
     root: Handle<Node>,
     spine: Handle<Node>,
 ) -> Handle<Animation> {
+    let model = match model {
+        Ok(model) => model,
+        Err(e) => {
+            assets::report_missing(Path::new(path), &format!("{:?}", e));
+            return scene.animations.add(Animation::default());
+        }
+    };
     let animation = model.retarget_animations(root, scene)[0];
     scene
         .animations
@@ -272,27 +498,51 @@ impl LocomotionMachine {
     ) -> Self {
         let (idle_animation, walk_animation, jump_animation, falling_animation) = rg3d::core::futures::join!(
             resource_manager.request_model(
-                definition.idle_animation,
+                pak::resolve(definition.idle_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             ),
             resource_manager.request_model(
-                definition.walk_animation,
+                pak::resolve(definition.walk_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             ),
             resource_manager.request_model(
-                definition.jump_animation,
+                pak::resolve(definition.jump_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             ),
             resource_manager.request_model(
-                definition.falling_animation,
+                pak::resolve(definition.falling_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             )
         );
 
-        let idle_animation = prepare_animation(scene, idle_animation.unwrap(), model, spine);
-        let walk_animation = prepare_animation(scene, walk_animation.unwrap(), model, spine);
-        let jump_animation = prepare_animation(scene, jump_animation.unwrap(), model, spine);
-        let falling_animation = prepare_animation(scene, falling_animation.unwrap(), model, spine);
+        let idle_animation = prepare_animation(
+            scene,
+            idle_animation,
+            definition.idle_animation,
+            model,
+            spine,
+        );
+        let walk_animation = prepare_animation(
+            scene,
+            walk_animation,
+            definition.walk_animation,
+            model,
+            spine,
+        );
+        let jump_animation = prepare_animation(
+            scene,
+            jump_animation,
+            definition.jump_animation,
+            model,
+            spine,
+        );
+        let falling_animation = prepare_animation(
+            scene,
+            falling_animation,
+            definition.falling_animation,
+            model,
+            spine,
+        );
 
         scene
             .animations
@@ -446,17 +696,29 @@ impl DyingMachine {
     ) -> Self {
         let (dying_animation, dead_animation) = rg3d::core::futures::join!(
             resource_manager.request_model(
-                definition.dying_animation,
+                pak::resolve(definition.dying_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             ),
             resource_manager.request_model(
-                definition.dead_animation,
+                pak::resolve(definition.dead_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             )
         );
 
-        let dying_animation = prepare_animation(scene, dying_animation.unwrap(), model, spine);
-        let dead_animation = prepare_animation(scene, dead_animation.unwrap(), model, spine);
+        let dying_animation = prepare_animation(
+            scene,
+            dying_animation,
+            definition.dying_animation,
+            model,
+            spine,
+        );
+        let dead_animation = prepare_animation(
+            scene,
+            dead_animation,
+            definition.dead_animation,
+            model,
+            spine,
+        );
 
         scene
             .animations
@@ -566,23 +828,35 @@ impl CombatMachine {
     ) -> Self {
         let (aim_animation, whip_animation, hit_reaction_animation) = rg3d::core::futures::join!(
             resource_manager.request_model(
-                definition.aim_animation,
+                pak::resolve(definition.aim_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             ),
             resource_manager.request_model(
-                definition.whip_animation,
+                pak::resolve(definition.whip_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             ),
             resource_manager.request_model(
-                definition.hit_reaction_animation,
+                pak::resolve(definition.hit_reaction_animation),
                 MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures"))
             )
         );
 
-        let aim_animation = prepare_animation(scene, aim_animation.unwrap(), model, spine);
-        let whip_animation = prepare_animation(scene, whip_animation.unwrap(), model, spine);
-        let hit_reaction_animation =
-            prepare_animation(scene, hit_reaction_animation.unwrap(), model, spine);
+        let aim_animation =
+            prepare_animation(scene, aim_animation, definition.aim_animation, model, spine);
+        let whip_animation = prepare_animation(
+            scene,
+            whip_animation,
+            definition.whip_animation,
+            model,
+            spine,
+        );
+        let hit_reaction_animation = prepare_animation(
+            scene,
+            hit_reaction_animation,
+            definition.hit_reaction_animation,
+            model,
+            spine,
+        );
 
         scene
             .animations
@@ -745,6 +1019,34 @@ impl Visit for CombatMachine {
 }
 
 impl Bot {
+    /// How far from bot attacker is assumed to be when only direction of damage is known.
+    const THREAT_SEARCH_DISTANCE: f32 = 10.0;
+    /// Burning bot runs this far away from its target to break line of sight.
+    const FLEE_DISTANCE: f32 = 10.0;
+    /// Rating of a weapon is multiplied by these when target is out of weapon's range or when
+    /// weapon is running out of ammo.
+    const OUT_OF_RANGE_RATING: f32 = 0.25;
+    const LOW_AMMO_RATING: f32 = 0.5;
+    const LOW_AMMO_FRACTION: f32 = 0.1;
+    /// Other weapon must be rated this many times higher than current one to switch to it.
+    const WEAPON_SWITCH_THRESHOLD: f32 = 1.2;
+    /// Min time (in seconds) between weapon switches.
+    const WEAPON_SWITCH_INTERVAL: f32 = 2.0;
+    /// Dodge of a projectile is a sidestep of this duration (in seconds) with a jump.
+    const DODGE_TIME: f32 = 0.5;
+    const DODGE_JUMP_SPEED: f32 = 4.0;
+    /// Wounded bot with less health than this runs to a jump pad nearby to escape from fight.
+    const ESCAPE_HEALTH: f32 = 30.0;
+    /// Max distance (in meters) to a jump pad that is used to escape.
+    const ESCAPE_DISTANCE: f32 = 15.0;
+    /// Moving dummy walks this far (in meters) to each side of its patrol origin.
+    const PATROL_DISTANCE: f32 = 3.0;
+    /// Aim error doubles at this distance (in meters) to target.
+    const AIM_ERROR_DISTANCE: f32 = 20.0;
+    /// Time (in seconds) of keeping target in sight after which aim error is the lowest, shots
+    /// right after target came into view are twice as inaccurate.
+    const AIM_SETTLE_TIME: f32 = 1.5;
+
     pub fn get_definition(kind: BotKind) -> &'static BotDefinition {
         match kind {
             BotKind::Mutant => {
@@ -764,6 +1066,7 @@ impl Bot {
                     left_leg_name: "Mutant:LeftUpLeg",
                     right_leg_name: "Mutant:RightUpLeg",
                     spine: "Mutant:Spine",
+                    voice: "mutant",
                     walk_speed: 4.0,
                     scale: 0.0085,
                     weapon_scale: 2.6,
@@ -789,6 +1092,7 @@ impl Bot {
                     left_leg_name: "LeftUpLeg",
                     right_leg_name: "RightUpLeg",
                     spine: "Spine",
+                    voice: "parasite",
                     walk_speed: 4.0,
                     scale: 0.0085,
                     weapon_scale: 2.5,
@@ -814,6 +1118,7 @@ impl Bot {
                     left_leg_name: "LeftUpLeg",
                     right_leg_name: "RightUpLeg",
                     spine: "Spine",
+                    voice: "maw",
                     walk_speed: 4.0,
                     scale: 0.0085,
                     weapon_scale: 2.5,
@@ -836,14 +1141,7 @@ impl Bot {
 
         let body_height = 1.25;
 
-        let model = resource_manager
-            .request_model(
-                Path::new(definition.model),
-                MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures")),
-            )
-            .await
-            .unwrap()
-            .instantiate_geometry(scene);
+        let model = assets::instantiate_model(&resource_manager, definition.model, scene).await;
 
         scene.graph[model]
             .local_transform_mut()
@@ -856,8 +1154,8 @@ impl Bot {
 
         let spine = scene.graph.find_by_name(model, definition.spine);
         if spine.is_none() {
-            Log::writeln(
-                MessageKind::Warning,
+            logger::warning(
+                Category::Ai,
                 "Spine bone not found, bot won't aim vertically!".to_owned(),
             );
         }
@@ -939,21 +1237,27 @@ impl Bot {
         self.combat_machine.machine.active_state() == self.combat_machine.aim_state
     }
 
-    fn select_target(
-        &mut self,
+    /// Gathers state needed for think stage.
+    pub fn snapshot(
+        &self,
         self_handle: Handle<Actor>,
-        scene: &mut Scene,
+        physics: &Physics,
         targets: &[TargetDescriptor],
-    ) {
-        self.target = None;
-        let position = self.character.position(&scene.physics);
-        let mut closest_distance = std::f32::MAX;
+        time: GameTime,
+    ) -> ThinkSnapshot {
+        let position = self.character.position(physics);
+        let team = self.character.team();
 
+        let mut visible_targets = Vec::new();
         let mut query_buffer = Vec::default();
-        'target_loop: for desc in targets {
-            if desc.handle != self_handle && self.frustum.is_contains_point(desc.position) {
+        'target_loop: for (i, desc) in targets.iter().enumerate() {
+            let teammate = team != Team::None && desc.team == team;
+            if desc.handle != self_handle
+                && !teammate
+                && self.frustum.is_contains_point(desc.position)
+            {
                 let ray = Ray::from_two_points(desc.position, position);
-                scene.physics.cast_ray(
+                physics.cast_ray(
                     RayCastOptions {
                         ray_origin: Point3::from(ray.origin),
                         ray_direction: ray.dir,
@@ -965,9 +1269,8 @@ impl Bot {
                 );
 
                 'hit_loop: for hit in query_buffer.iter() {
-                    let collider = scene.physics.colliders.get(&hit.collider).unwrap();
-                    let body = scene
-                        .physics
+                    let collider = physics.colliders.get(&hit.collider).unwrap();
+                    let body = physics
                         .bodies
                         .handle_map()
                         .key_of(&collider.parent().unwrap())
@@ -985,27 +1288,67 @@ impl Bot {
                     }
                 }
 
-                let sqr_d = position.sqr_distance(&desc.position);
-                if sqr_d < closest_distance {
-                    self.target = Some(Target {
-                        position: desc.position,
-                        handle: desc.handle,
-                    });
-                    closest_distance = sqr_d;
-                }
+                visible_targets.push(i);
             }
         }
+
+        let path_request = if time.elapsed - self.last_path_rebuild_time >= 1.0 {
+            Some((
+                position - Vector3::new(0.0, 1.0, 0.0),
+                self.point_of_interest,
+            ))
+        } else {
+            None
+        };
+
+        ThinkSnapshot {
+            handle: self_handle,
+            position,
+            visible_targets,
+            path_request,
+        }
+    }
+
+    pub fn apply_intent(&mut self, intent: Intent, time: GameTime) {
+        if !self.dummy {
+            self.target = intent.target;
+        }
+        if let Some(path) = intent.path {
+            self.path = path;
+            self.current_path_point = 0;
+            self.last_path_rebuild_time = time.elapsed;
+        }
     }
 
-    fn select_point_of_interest(&mut self, items: &ItemContainer, scene: &Scene, time: &GameTime) {
+    fn select_point_of_interest(
+        &mut self,
+        items: &ItemContainer,
+        weapons: &WeaponContainer,
+        economy: &ItemEconomy,
+        scene: &Scene,
+        time: &GameTime,
+    ) {
         if time.elapsed - self.last_poi_update_time >= 1.25 {
-            // Select closest non-despawned item as point of interest.
+            // Select closest non-despawned item as point of interest. Items dropped by dead
+            // actors are preferred, they will disappear soon and usually contain good stuff.
             let self_position = self.position(&scene.physics);
             let mut closest_distance = std::f32::MAX;
             for item in items.iter() {
-                if !item.is_picked_up() {
+                // Weapon that stays is never picked up, bot that has it can't take it again.
+                let owned_stay_weapon = economy.weapon_stay
+                    && !item.is_dropped()
+                    && item.get_kind().weapon().map_or(false, |kind| {
+                        self.character
+                            .weapons()
+                            .iter()
+                            .any(|&weapon| weapons[weapon].get_kind() == kind)
+                    });
+                if !item.is_picked_up() && !owned_stay_weapon {
                     let item_position = item.position(&scene.graph);
-                    let sqr_d = item_position.sqr_distance(&self_position);
+                    let mut sqr_d = item_position.sqr_distance(&self_position);
+                    if item.is_dropped() {
+                        sqr_d *= 0.25;
+                    }
                     if sqr_d < closest_distance {
                         closest_distance = sqr_d;
                         self.point_of_interest = item_position;
@@ -1016,31 +1359,200 @@ impl Bot {
         }
     }
 
-    fn select_weapon(&mut self, weapons: &WeaponContainer) {
-        if self.character.current_weapon().is_some()
-            && weapons[self.character.current_weapon()].ammo() == 0
+    /// Bot that fights at range strafes from side to side in short bursts, so it is harder
+    /// to hit.
+    fn update_strafing(&mut self, in_close_combat: bool, dt: f32) {
+        self.strafe_timer -= dt;
+        if self.target.is_none() || in_close_combat || self.fleeing || self.dummy {
+            return;
+        }
+        if self.strafe_timer <= 0.0 && self.evade_timer <= 0.0 {
+            let mut rng = rand::thread_rng();
+            self.evade_timer = rng.gen_range(0.4..0.9);
+            self.evade_side = -self.evade_side;
+            self.strafe_timer = self.evade_timer + rng.gen_range(0.5..1.5);
+        }
+    }
+
+    /// Wounded bot runs to closest jump pad, so it is thrown away from its attacker.
+    fn update_escape(
+        &mut self,
+        position: Vector3<f32>,
+        jump_pads: &JumpPadContainer,
+        physics: &Physics,
+        time: GameTime,
+    ) {
+        if self.target.is_none()
+            || self.fleeing
+            || self.dummy
+            || self.character.health >= Self::ESCAPE_HEALTH
         {
-            for (i, handle) in self.character.weapons().iter().enumerate() {
-                if weapons[*handle].ammo() > 0 {
-                    self.character.set_current_weapon(i);
-                    break;
-                }
+            return;
+        }
+        let closest = jump_pads
+            .iter()
+            .filter_map(|jump_pad| physics.bodies.get(&jump_pad.rigid_body()))
+            .map(|body| body.position().translation.vector)
+            .map(|pad_position| (pad_position, pad_position.metric_distance(&position)))
+            .filter(|(_, distance)| *distance < Self::ESCAPE_DISTANCE)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        if let Some((pad_position, _)) = closest {
+            self.set_point_of_interest(pad_position, time);
+        }
+    }
+
+    /// Rates weapon for fight at given distance, None if weapon can't be used at all.
+    fn rate_weapon(weapon: &Weapon, distance: Option<f32>) -> Option<f32> {
+        let definition = Weapon::get_definition(weapon.get_kind());
+        if weapon.ammo() == 0 || definition.bot_rating <= 0.0 {
+            return None;
+        }
+        let mut rating = definition.bot_rating;
+        if let Some(distance) = distance {
+            let (min, max) = definition.bot_range;
+            if distance < min || distance > max {
+                rating *= Self::OUT_OF_RANGE_RATING;
             }
         }
+        if (weapon.ammo() as f32) < definition.ammo as f32 * Self::LOW_AMMO_FRACTION {
+            rating *= Self::LOW_AMMO_RATING;
+        }
+        Some(rating)
     }
 
-    pub fn debug_draw(&self, context: &mut SceneDrawingContext) {
-        for pts in self.path.windows(2) {
-            let a = pts[0];
-            let b = pts[1];
-            context.add_line(scene::debug::Line {
-                begin: a,
-                end: b,
-                color: Color::from_rgba(255, 0, 0, 255),
-            });
+    /// Switches to the weapon that suits distance to target and ammo supply best. Weapon is
+    /// switched only if it is notably better than current one, so bot won't juggle weapons
+    /// when target is at the edge of a range.
+    fn select_weapon(&mut self, weapons: &WeaponContainer, position: Vector3<f32>, dt: f32) {
+        self.weapon_switch_timer -= dt;
+
+        let distance = self
+            .target
+            .as_ref()
+            .map(|target| target.position.metric_distance(&position));
+        let current = self.character.current_weapon();
+        let current_rating = if current.is_some() {
+            Self::rate_weapon(&weapons[current], distance)
+        } else {
+            None
+        };
+        // Empty weapon is dropped right away, otherwise bot switches weapons not too often.
+        if current_rating.is_some() && self.weapon_switch_timer > 0.0 {
+            return;
         }
 
-        context.draw_frustum(&self.frustum, Color::from_rgba(0, 200, 0, 255));
+        let best = self
+            .character
+            .weapons()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, handle)| {
+                Self::rate_weapon(&weapons[*handle], distance).map(|rating| (i, rating))
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        if let Some((index, rating)) = best {
+            let better = match current_rating {
+                Some(current_rating) => rating > current_rating * Self::WEAPON_SWITCH_THRESHOLD,
+                None => true,
+            };
+            if better && index != self.character.current_weapon as usize {
+                self.character.set_current_weapon(index);
+                self.weapon_switch_timer = Self::WEAPON_SWITCH_INTERVAL;
+            }
+        }
+    }
+
+    /// Burning bot stops fighting and runs away from its target, so it breaks line of sight
+    /// instead of standing in the fire.
+    fn update_fleeing(&mut self, position: Vector3<f32>, time: GameTime) {
+        let fleeing = self.character.is_burning() && self.target.is_some() && !self.dummy;
+        if fleeing {
+            let target = self.target.as_ref().unwrap();
+            let mut away = position - target.position;
+            away.y = 0.0;
+            if let Some(away) = away.try_normalize(std::f32::EPSILON) {
+                self.set_point_of_interest(position + away.scale(Self::FLEE_DISTANCE), time);
+            }
+            if !self.fleeing {
+                // Path must be rebuilt right away, not on next scheduled rebuild.
+                self.last_path_rebuild_time = -10.0;
+            }
+        }
+        self.fleeing = fleeing;
+    }
+
+    /// Short description of what bot is busy with, it is shown by debug overlay.
+    pub fn ai_state(&self) -> &'static str {
+        if self.dummy {
+            "Dummy"
+        } else if self.fleeing {
+            "Fleeing"
+        } else if self.evade_timer > 0.0 {
+            "Evading"
+        } else if self.target.is_some() && self.character.health < Self::ESCAPE_HEALTH {
+            "Escaping"
+        } else if self.target.is_some() && self.reaction_timer > 0.0 {
+            "Reacting"
+        } else if self.target.is_some() {
+            "Attacking"
+        } else if self.threat_direction.is_some() && self.threat_timer > 0.0 {
+            "Searching"
+        } else {
+            "Roaming"
+        }
+    }
+
+    pub fn debug_draw(
+        &self,
+        context: &mut SceneDrawingContext,
+        overlays: &DebugOverlays,
+        physics: &Physics,
+    ) {
+        if overlays.is_enabled(DebugOverlay::Paths) {
+            for pts in self.path.windows(2) {
+                let a = pts[0];
+                let b = pts[1];
+                context.add_line(scene::debug::Line {
+                    begin: a,
+                    end: b,
+                    color: Color::from_rgba(255, 0, 0, 255),
+                });
+            }
+        }
+
+        if overlays.is_enabled(DebugOverlay::Vision) {
+            context.draw_frustum(&self.frustum, Color::from_rgba(0, 200, 0, 255));
+        }
+
+        if overlays.is_enabled(DebugOverlay::Targets) {
+            let position = self.character.position(physics);
+            if let Some(target) = self.target.as_ref() {
+                let color = Color::from_rgba(255, 200, 0, 255);
+                context.add_line(scene::debug::Line {
+                    begin: position,
+                    end: target.position,
+                    color,
+                });
+                // Cross at last known position of target.
+                for axis in &[Vector3::x(), Vector3::y(), Vector3::z()] {
+                    context.add_line(scene::debug::Line {
+                        begin: target.position - axis.scale(0.25),
+                        end: target.position + axis.scale(0.25),
+                        color,
+                    });
+                }
+            } else if let Some(threat_direction) = self
+                .threat_direction
+                .and_then(|direction| direction.try_normalize(std::f32::EPSILON))
+            {
+                context.add_line(scene::debug::Line {
+                    begin: position,
+                    end: position + threat_direction.scale(3.0),
+                    color: Color::from_rgba(255, 0, 255, 255),
+                });
+            }
+        }
     }
 
     fn update_frustum(&mut self, position: Vector3<f32>, graph: &Graph) {
@@ -1082,37 +1594,102 @@ impl Bot {
         body.set_position(position, true);
     }
 
-    fn rebuild_path(&mut self, position: Vector3<f32>, navmesh: &mut Navmesh, time: GameTime) {
-        let from = position - Vector3::new(0.0, 1.0, 0.0);
-        if let Some(from_index) = navmesh.query_closest(from) {
-            if let Some(to_index) = navmesh.query_closest(self.point_of_interest) {
-                self.current_path_point = 0;
-                // Rebuild path if target path vertex has changed.
-                if navmesh
-                    .build_path(from_index, to_index, &mut self.path)
-                    .is_ok()
-                {
-                    self.path.reverse();
-                    self.last_path_rebuild_time = time.elapsed;
-                }
+    /// Turns aim towards the target with limited speed, bot needs some time to react to a new
+    /// target before it can shoot.
+    fn update_aim(&mut self, look_dir: Vector3<f32>, context: &UpdateContext) {
+        let target = self
+            .target
+            .as_ref()
+            .map_or(Handle::NONE, |target| target.handle);
+        if target != self.aimed_target {
+            self.aimed_target = target;
+            self.reaction_timer = context.difficulty.bot_reaction_time();
+            self.tracking_time = 0.0;
+        }
+        self.reaction_timer -= context.time.delta;
+        self.tracking_time += context.time.delta;
+
+        let look_dir = match look_dir.try_normalize(std::f32::EPSILON) {
+            Some(look_dir) => look_dir,
+            None => return,
+        };
+        let angle = self.aim_dir.angle(&look_dir);
+        let max_step = context.difficulty.bot_tracking_speed() * context.time.delta;
+        self.aim_dir = match Unit::try_new(self.aim_dir.cross(&look_dir), std::f32::EPSILON) {
+            Some(axis) if angle > max_step => {
+                UnitQuaternion::from_axis_angle(&axis, max_step).transform_vector(&self.aim_dir)
             }
+            _ => look_dir,
+        };
+    }
+
+    /// Returns flight speed of projectiles of current weapon, None if weapon hits instantly.
+    fn projectile_speed(&self, weapons: &WeaponContainer) -> Option<f32> {
+        let weapon = *self
+            .character
+            .weapons
+            .get(self.character.current_weapon as usize)?;
+        if !weapons.contains(weapon) {
+            return None;
+        }
+        match weapons[weapon].definition.shot {
+            Shot::Projectile(kind) => Some(Projectile::get_definition(kind).flight_speed()),
+            _ => None,
         }
     }
 
-    pub fn update(
-        &mut self,
-        self_handle: Handle<Actor>,
-        context: &mut UpdateContext,
-        targets: &[TargetDescriptor],
-    ) {
+    /// Returns direction of a shot, it deviates from aim more at long range and right after
+    /// target came into view.
+    fn shot_direction(&self, distance: f32, difficulty: Difficulty) -> Vector3<f32> {
+        let settle = (1.0 - self.tracking_time / Self::AIM_SETTLE_TIME).max(0.0);
+        let max_error = difficulty.bot_aim_error()
+            * (1.0 + distance / Self::AIM_ERROR_DISTANCE)
+            * (1.0 + settle);
+
+        let mut rng = rand::thread_rng();
+        let side = self
+            .aim_dir
+            .cross(&Vector3::y())
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::x);
+        // Random axis perpendicular to aim.
+        let axis = UnitQuaternion::from_axis_angle(
+            &Unit::new_normalize(self.aim_dir),
+            rng.gen_range(0.0..std::f32::consts::TAU),
+        )
+        .transform_vector(&side);
+        UnitQuaternion::from_axis_angle(&Unit::new_normalize(axis), rng.gen_range(0.0..=max_error))
+            .transform_vector(&self.aim_dir)
+    }
+
+    pub fn update(&mut self, context: &mut UpdateContext) {
+        self.update_lod(context);
+
         if self.character.is_dead() {
             self.dying_machine
                 .apply(context.scene, context.time, self.character.is_dead());
         } else {
-            self.select_target(self_handle, context.scene, targets);
-            self.select_weapon(context.weapons);
-            self.select_point_of_interest(context.items, context.scene, &context.time);
+            self.select_weapon(
+                context.weapons,
+                self.character.position(&context.scene.physics),
+                context.time.delta,
+            );
+            self.select_point_of_interest(
+                context.items,
+                context.weapons,
+                &context.economy,
+                context.scene,
+                &context.time,
+            );
+            self.update_escape(
+                self.character.position(&context.scene.physics),
+                context.jump_pads,
+                &context.scene.physics,
+                context.time,
+            );
 
+            let projectile_speed = self.projectile_speed(context.weapons);
+            let walk_speed = self.definition.walk_speed * self.character.speed_scale;
             let has_ground_contact = self.character.has_ground_contact(&context.scene.physics);
             let body = context
                 .scene
@@ -1121,19 +1698,46 @@ impl Bot {
                 .get_mut(&self.character.body)
                 .unwrap();
             let (in_close_combat, look_dir) = match self.target.as_ref() {
-                None => (
-                    false,
-                    self.point_of_interest - body.position().translation.vector,
-                ),
+                None => match self.threat_direction {
+                    Some(threat_direction) if self.threat_timer > 0.0 => (false, threat_direction),
+                    _ => (
+                        false,
+                        self.point_of_interest - body.position().translation.vector,
+                    ),
+                },
                 Some(target) => {
-                    let d = target.position - body.position().translation.vector;
+                    // Attacker is found, no need to look around anymore.
+                    self.threat_direction = None;
+                    let origin = body.position().translation.vector;
+                    let d = target.position - origin;
                     let close_combat_threshold = 2.0;
-                    (d.norm() <= close_combat_threshold, d)
+                    // Slow projectiles are aimed where target will be when they arrive.
+                    let aim_point = match projectile_speed {
+                        Some(speed) => lead_target(origin, target.position, target.velocity, speed),
+                        None => target.position,
+                    };
+                    (d.norm() <= close_combat_threshold, aim_point - origin)
                 }
             };
 
             let position = body.position().translation.vector;
 
+            if self.dummy {
+                self.path.clear();
+                self.move_target = match self.patrol_origin {
+                    Some(origin) => {
+                        let mut target = origin
+                            + Vector3::new(self.patrol_side * Self::PATROL_DISTANCE, 0.0, 0.0);
+                        target.y = position.y;
+                        if target.metric_distance(&position) <= 0.5 {
+                            self.patrol_side = -self.patrol_side;
+                        }
+                        target
+                    }
+                    None => position,
+                };
+            }
+
             if let Some(path_point) = self.path.get(self.current_path_point) {
                 self.move_target = *path_point;
                 if self.move_target.metric_distance(&position) <= 1.0
@@ -1143,7 +1747,16 @@ impl Bot {
                 }
             }
 
+            self.update_fleeing(position, context.time);
             self.update_frustum(position, &context.scene.graph);
+            self.update_strafing(in_close_combat, context.time.delta);
+
+            if self.dodge_jump && has_ground_contact {
+                let mut vel = *body.linvel();
+                vel.y = Self::DODGE_JUMP_SPEED;
+                body.set_linvel(vel, true);
+            }
+            self.dodge_jump = false;
 
             let need_jump = look_dir.y >= 0.3 && has_ground_contact && in_close_combat;
             if need_jump {
@@ -1164,18 +1777,32 @@ impl Bot {
             self.last_health = self.character.health;
 
             if !in_close_combat {
-                if has_ground_contact {
+                if has_ground_contact && self.evade_timer > 0.0 {
+                    // Strafe away from line of fire.
+                    let side = self.dodge_direction.or_else(|| {
+                        look_dir
+                            .cross(&Vector3::y())
+                            .try_normalize(std::f32::EPSILON)
+                            .map(|side| side.scale(self.evade_side))
+                    });
+                    if let Some(side) = side {
+                        let mut vel = side.scale(walk_speed);
+                        vel.y = body.linvel().y;
+                        body.set_linvel(vel, true);
+                        self.last_move_dir = side;
+                    }
+                } else if has_ground_contact {
                     if let Some(move_dir) =
                         (self.move_target - position).try_normalize(std::f32::EPSILON)
                     {
-                        let mut vel = move_dir.scale(self.definition.walk_speed);
+                        let mut vel = move_dir.scale(walk_speed);
                         vel.y = body.linvel().y;
                         body.set_linvel(vel, true);
                         self.last_move_dir = move_dir;
                     }
                 } else {
                     // A bit of air control. This helps jump of ledges when there is jump pad below bot.
-                    let mut vel = self.last_move_dir.scale(self.definition.walk_speed);
+                    let mut vel = self.last_move_dir.scale(walk_speed);
                     vel.y = body.linvel().y;
                     body.set_linvel(vel, true);
                 }
@@ -1185,25 +1812,45 @@ impl Bot {
                 self.aim_vertically(look_dir, &mut context.scene.graph, context.time);
                 self.aim_horizontally(look_dir, &mut context.scene.physics, context.time);
             }
-
-            self.locomotion_machine.apply(
-                context.scene,
-                context.time,
-                in_close_combat,
-                need_jump,
-                has_ground_contact,
-            );
-            self.combat_machine.apply(
-                context.scene,
-                context.time,
-                in_close_combat,
-                was_damaged,
-                can_aim,
-            );
+            self.update_aim(look_dir, context);
+
+            // Distant bots are animated less often, skipped time is accumulated so animations
+            // keep their speed.
+            self.animation_time += context.time.delta;
+            self.animation_frame += 1;
+            if was_damaged || self.animation_frame >= self.lod.animation_interval() {
+                let time = GameTime {
+                    delta: self.animation_time,
+                    ..context.time
+                };
+                self.animation_frame = 0;
+                self.animation_time = 0.0;
+
+                self.locomotion_machine.apply(
+                    context.scene,
+                    time,
+                    in_close_combat,
+                    need_jump,
+                    has_ground_contact,
+                );
+                self.combat_machine.apply(
+                    context.scene,
+                    time,
+                    in_close_combat,
+                    was_damaged,
+                    can_aim,
+                );
+            }
 
             let sender = self.character.sender.as_ref().unwrap();
 
-            if !in_close_combat && can_aim && self.can_shoot() && self.target.is_some() {
+            if !in_close_combat
+                && can_aim
+                && !self.fleeing
+                && self.reaction_timer <= 0.0
+                && self.can_shoot()
+                && self.target.is_some()
+            {
                 if let Some(weapon) = self
                     .character
                     .weapons
@@ -1213,7 +1860,9 @@ impl Bot {
                         .send(Message::ShootWeapon {
                             weapon: *weapon,
                             initial_velocity: Vector3::default(),
-                            direction: Some(look_dir),
+                            direction: Some(
+                                self.shot_direction(look_dir.norm(), context.difficulty),
+                            ),
                         })
                         .unwrap();
                 }
@@ -1233,6 +1882,8 @@ impl Bot {
                                 actor: target.handle,
                                 who: Default::default(),
                                 amount: 20.0,
+                                direction: Some(target.position - position),
+                                zone: None,
                             })
                             .unwrap();
                     }
@@ -1268,17 +1919,53 @@ impl Bot {
                 }
             }
 
-            if context.time.elapsed - self.last_path_rebuild_time >= 1.0 {
-                if context.navmesh.is_some() {
-                    let navmesh = &mut context.scene.navmeshes[context.navmesh];
+            self.restoration_time -= context.time.delta;
+            self.threat_timer -= context.time.delta;
+            self.evade_timer -= context.time.delta;
+            if self.evade_timer <= 0.0 {
+                self.dodge_direction = None;
+            }
+        }
+    }
 
-                    self.rebuild_path(position, navmesh, context.time);
+    fn update_lod(&mut self, context: &mut UpdateContext) {
+        let distance = context
+            .observer_position
+            .metric_distance(&self.character.position(&context.scene.physics));
+        let lod = Lod::from_distance(
+            distance,
+            context.lod_near_distance,
+            context.lod_far_distance,
+        );
+        if lod != self.lod {
+            if lod.cast_shadows() != self.lod.cast_shadows() {
+                let descendants = context
+                    .scene
+                    .graph
+                    .traverse_handle_iter(self.model)
+                    .collect::<Vec<_>>();
+                for handle in descendants {
+                    if let Node::Mesh(mesh) = &mut context.scene.graph[handle] {
+                        mesh.set_cast_shadows(lod.cast_shadows());
+                    }
                 }
             }
-            self.restoration_time -= context.time.delta;
+            self.lod = lod;
         }
     }
 
+    /// Scales playback speed of every animation of the bot, must be called once right after
+    /// the bot was created.
+    pub fn set_time_scale(&self, scene: &mut Scene, time_scale: f32) {
+        scale_machine(&self.locomotion_machine.machine, scene, time_scale);
+        scale_machine(&self.combat_machine.machine, scene, time_scale);
+        scale_machine(&self.dying_machine.machine, scene, time_scale);
+    }
+
+    pub fn set_model_visibility(&self, graph: &mut Graph, visibility: bool) {
+        graph[self.model].set_visibility(visibility);
+    }
+
     pub fn clean_up(&mut self, scene: &mut Scene) {
         self.combat_machine.clean_up(scene);
         self.dying_machine.clean_up(scene);
@@ -1298,6 +1985,107 @@ impl Bot {
         self.point_of_interest = poi;
         self.last_poi_update_time = time.elapsed;
     }
+
+    /// Carries out order of squad coordinator, see `squad` module. Fight goes first, bot
+    /// returns to its order when it has no target.
+    pub fn follow_order(&mut self, role: &Role, physics: &Physics, time: GameTime) {
+        if self.dummy || self.fleeing || self.target.is_some() {
+            return;
+        }
+        if let Some(facing) = role.facing {
+            self.threat_direction = Some(facing);
+            self.threat_timer = 0.5;
+        }
+        let position = self.character.position(physics);
+        if position.metric_distance(&role.destination) > role.order.hold_radius() {
+            self.set_point_of_interest(role.destination, time);
+        } else {
+            self.set_point_of_interest(position, time);
+        }
+    }
+
+    /// Follows ping of a teammate - walks to pinged point and looks out for pinged enemy. Pings
+    /// are ignored while bot has a target or flees, and if bot is too far from pinged point.
+    pub fn on_ping(
+        &mut self,
+        kind: PingKind,
+        position: Vector3<f32>,
+        physics: &Physics,
+        time: GameTime,
+    ) {
+        if self.dummy || self.fleeing || self.target.is_some() {
+            return;
+        }
+        let self_position = self.character.position(physics);
+        if self_position.metric_distance(&position) > ping::HINT_RADIUS {
+            return;
+        }
+        if kind == PingKind::Enemy {
+            self.threat_direction = Some(position - self_position);
+            self.threat_timer = 0.5;
+        }
+        self.set_point_of_interest(position, time);
+    }
+
+    /// Sidesteps away from flight path of incoming projectile and jumps over its blast.
+    pub fn on_projectile_threat(
+        &mut self,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        physics: &Physics,
+    ) {
+        if self.dummy {
+            return;
+        }
+        if let Some(side) = direction
+            .cross(&Vector3::y())
+            .try_normalize(std::f32::EPSILON)
+        {
+            let away = self.character.position(physics) - position;
+            self.dodge_direction = Some(if away.dot(&side) >= 0.0 { side } else { -side });
+            self.evade_timer = Self::DODGE_TIME;
+            self.dodge_jump = true;
+        }
+    }
+
+    /// Reacts to damage. Source of damage becomes new point of interest, and if it came from
+    /// outside of field of view, bot turns towards it and dodges for a short period of time.
+    pub fn on_damaged(
+        &mut self,
+        source: Option<Vector3<f32>>,
+        direction: Option<Vector3<f32>>,
+        physics: &Physics,
+        time: GameTime,
+    ) {
+        // Fleeing bot keeps running away instead of turning to attacker.
+        if self.dummy || self.fleeing {
+            return;
+        }
+
+        let position = self.character.position(physics);
+        let source = match (source, direction) {
+            (Some(source), _) => source,
+            // Only direction is known, so guess where attacker might be.
+            (None, Some(direction)) => match direction.try_normalize(std::f32::EPSILON) {
+                Some(direction) => position - direction.scale(Self::THREAT_SEARCH_DISTANCE),
+                None => return,
+            },
+            (None, None) => return,
+        };
+
+        if self.target.is_none() && !self.frustum.is_contains_point(source) {
+            self.threat_direction = Some(source - position);
+            self.threat_timer = 1.5;
+            self.evade_timer = 0.6;
+            self.evade_side = if rand::thread_rng().gen_bool(0.5) {
+                1.0
+            } else {
+                -1.0
+            };
+        }
+
+        self.set_point_of_interest(source, time);
+    }
 }
 
 fn clean_machine(machine: &Machine, scene: &mut Scene) {
@@ -1308,6 +2096,16 @@ fn clean_machine(machine: &Machine, scene: &mut Scene) {
     }
 }
 
+fn scale_machine(machine: &Machine, scene: &mut Scene, time_scale: f32) {
+    for node in machine.nodes() {
+        if let PoseNode::PlayAnimation(node) = node {
+            let animation = scene.animations.get_mut(node.animation);
+            let speed = animation.get_speed();
+            animation.set_speed(speed * time_scale);
+        }
+    }
+}
+
 impl Visit for Bot {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         visitor.enter_region(name)?;
@@ -1328,6 +2126,10 @@ impl Visit for Bot {
         self.restoration_time.visit("RestorationTime", visitor)?;
         self.yaw.visit("Yaw", visitor)?;
         self.pitch.visit("Pitch", visitor)?;
+        self.dummy.visit("Dummy", visitor)?;
+        if saves::read_version(visitor) >= 12 {
+            self.patrol_origin.visit("PatrolOrigin", visitor)?;
+        }
 
         visitor.leave_region()
     }
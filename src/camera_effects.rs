@@ -0,0 +1,90 @@
+//! Procedural effects applied to player's camera on top of head bob: shake from nearby
+//! explosions, dip on landing and flinch on taking damage. Every effect decays by itself,
+//! so sources just add to it.
+
+use crate::GameTime;
+use rg3d::core::algebra::{UnitQuaternion, Vector3};
+
+/// Max angle (in degrees) of camera shake at full trauma.
+const MAX_SHAKE_ANGLE: f32 = 3.0;
+
+/// How much trauma fades per second.
+const TRAUMA_DECAY: f32 = 1.5;
+
+/// Falls slower than this speed (in m/s) do not dip camera.
+const LANDING_THRESHOLD: f32 = 3.0;
+
+const MAX_LANDING_DIP: f32 = 0.25;
+
+/// Max angle (in degrees) of camera flinch.
+const MAX_FLINCH_ANGLE: f32 = 6.0;
+
+#[derive(Default)]
+pub struct CameraEffects {
+    /// Amount of shake in [0; 1] range, actual shake is quadratic to it so small shakes are
+    /// barely noticeable.
+    trauma: f32,
+    landing_dip: f32,
+    flinch_pitch: f32,
+    flinch_roll: f32,
+}
+
+impl CameraEffects {
+    /// Adds shake, `strength` is in [0; 1] range.
+    pub fn shake(&mut self, strength: f32) {
+        self.trauma = (self.trauma + strength).min(1.0);
+    }
+
+    /// Dips camera proportionally to fall speed.
+    pub fn land(&mut self, fall_speed: f32) {
+        let dip = (fall_speed - LANDING_THRESHOLD) * 0.03;
+        self.landing_dip = self.landing_dip.max(dip.min(MAX_LANDING_DIP));
+    }
+
+    /// Kicks camera up, `side` is in [-1; 1] range and tells from which side hit came,
+    /// camera rolls away from it. `strength` is in [0; 1] range.
+    pub fn flinch(&mut self, strength: f32, side: f32) {
+        let strength = strength.min(1.0);
+        self.flinch_pitch -= MAX_FLINCH_ANGLE * strength;
+        self.flinch_roll += MAX_FLINCH_ANGLE * strength * side;
+        self.flinch_pitch = self.flinch_pitch.max(-MAX_FLINCH_ANGLE);
+        self.flinch_roll = self
+            .flinch_roll
+            .max(-MAX_FLINCH_ANGLE)
+            .min(MAX_FLINCH_ANGLE);
+    }
+
+    /// Advances effects and returns camera offset and rotation scaled by `intensity`.
+    pub fn update(
+        &mut self,
+        time: GameTime,
+        intensity: f32,
+    ) -> (Vector3<f32>, UnitQuaternion<f32>) {
+        self.trauma = (self.trauma - TRAUMA_DECAY * time.delta).max(0.0);
+        self.landing_dip -= self.landing_dip * time.smoothing(0.1);
+        self.flinch_pitch -= self.flinch_pitch * time.smoothing(0.12);
+        self.flinch_roll -= self.flinch_roll * time.smoothing(0.12);
+
+        // Sum of sines with unrelated frequencies looks chaotic enough and, unlike random
+        // offsets, does not jitter at high tick rates.
+        let shake = MAX_SHAKE_ANGLE * self.trauma * self.trauma;
+        let t = time.elapsed as f32;
+        let shake_yaw = shake * (t * 37.0).sin();
+        let shake_pitch = shake * (t * 41.0 + 1.0).sin();
+        let shake_roll = shake * (t * 29.0 + 2.0).sin();
+
+        let offset = Vector3::new(0.0, -self.landing_dip * intensity, 0.0);
+        let rotation = UnitQuaternion::from_axis_angle(
+            &Vector3::y_axis(),
+            (shake_yaw * intensity).to_radians(),
+        ) * UnitQuaternion::from_axis_angle(
+            &Vector3::x_axis(),
+            ((shake_pitch + self.flinch_pitch) * intensity).to_radians(),
+        ) * UnitQuaternion::from_axis_angle(
+            &Vector3::z_axis(),
+            ((shake_roll + self.flinch_roll) * intensity).to_radians(),
+        );
+
+        (offset, rotation)
+    }
+}
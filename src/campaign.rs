@@ -0,0 +1,107 @@
+//! Campaign is an ordered chain of single-player levels. Every level has an objective that
+//! must be completed to unlock the next level, player's weapons, ammo, health and armor are
+//! carried from one level to the next one. Progress is stored in player's profile.
+
+use crate::{
+    level::Level,
+    localization::{tr, tr_format},
+    weapon::WeaponKind,
+    DeathMatch, Difficulty, ItemEconomy, MatchOptions,
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Objective {
+    /// Finish the match with the highest score.
+    Win,
+    /// Score given amount of frags before time runs out.
+    Frags(u32),
+    /// Win the match dying no more than given amount of times.
+    WinWithDeaths(u32),
+}
+
+impl Objective {
+    pub fn description(self) -> String {
+        match self {
+            Objective::Win => tr("Finish the match in the lead"),
+            Objective::Frags(count) => tr_format("Score {} frags before time runs out", &[&count]),
+            Objective::WinWithDeaths(0) => tr("Win the match without dying"),
+            Objective::WinWithDeaths(deaths) => {
+                tr_format("Win the match dying no more than {} times", &[&deaths])
+            }
+        }
+    }
+
+    pub fn is_complete(self, level: &Level) -> bool {
+        let (kills, deaths) = level.player_score();
+        match self {
+            Objective::Win => level.is_player_leading(),
+            Objective::Frags(count) => kills >= count,
+            Objective::WinWithDeaths(max_deaths) => {
+                level.is_player_leading() && deaths <= max_deaths
+            }
+        }
+    }
+}
+
+pub struct CampaignLevel {
+    pub name: &'static str,
+    pub map: &'static str,
+    pub objective: Objective,
+    pub frag_limit: u32,
+    pub time_limit_secs: f32,
+}
+
+impl CampaignLevel {
+    pub fn options(&self, difficulty: Difficulty) -> MatchOptions {
+        MatchOptions::DeathMatch(DeathMatch {
+            time_limit_secs: self.time_limit_secs,
+            frag_limit: self.frag_limit,
+            difficulty,
+            economy: ItemEconomy::for_difficulty(difficulty),
+            ..Default::default()
+        })
+    }
+}
+
+pub static LEVELS: [CampaignLevel; 4] = [
+    CampaignLevel {
+        name: "First Blood",
+        map: "data/models/dm6.fbx",
+        objective: Objective::Frags(5),
+        frag_limit: 5,
+        time_limit_secs: 300.0,
+    },
+    CampaignLevel {
+        name: "Outpost Assault",
+        map: "data/models/map.FBX",
+        objective: Objective::Win,
+        frag_limit: 10,
+        time_limit_secs: 480.0,
+    },
+    CampaignLevel {
+        name: "Last Stand",
+        map: "data/models/dm6.fbx",
+        objective: Objective::WinWithDeaths(2),
+        frag_limit: 15,
+        time_limit_secs: 600.0,
+    },
+    CampaignLevel {
+        name: "Showdown",
+        map: "data/models/map.FBX",
+        objective: Objective::WinWithDeaths(0),
+        frag_limit: 20,
+        time_limit_secs: 600.0,
+    },
+];
+
+pub fn is_unlocked(index: usize, progress: u32) -> bool {
+    index <= progress as usize
+}
+
+/// Weapons and state of player at the end of a level, next level starts with them.
+#[derive(Clone, Debug)]
+pub struct Loadout {
+    pub health: f32,
+    pub armor: f32,
+    pub weapons: Vec<(WeaponKind, u32)>,
+}
@@ -0,0 +1,165 @@
+use crate::{
+    campaign::{self, LEVELS},
+    localization::tr,
+    message::Message,
+    settings::Settings,
+};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::sync::{mpsc::Sender, Arc, RwLock};
+
+pub struct CampaignMenu {
+    sender: Sender<Message>,
+    settings: Arc<RwLock<Settings>>,
+    pub window: Handle<UiNode>,
+    progress: Handle<UiNode>,
+    // One button per campaign level, locked levels are hidden.
+    level_buttons: Vec<Handle<UiNode>>,
+    objectives: Vec<Handle<UiNode>>,
+}
+
+impl CampaignMenu {
+    pub fn new(
+        ui: &mut UserInterface,
+        settings: Arc<RwLock<Settings>>,
+        sender: Sender<Message>,
+    ) -> Self {
+        let common_row = Row::strict(36.0);
+
+        let ctx = &mut ui.build_ctx();
+
+        let mut level_buttons = Vec::new();
+        let mut objectives = Vec::new();
+        let mut children = Vec::new();
+        for (i, level) in LEVELS.iter().enumerate() {
+            let row = i + 1;
+            let button = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .on_row(row)
+                    .on_column(0)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(&format!("{}. {}", i + 1, level.name))
+            .build(ctx);
+            let objective = TextBuilder::new(
+                WidgetBuilder::new()
+                    .on_row(row)
+                    .on_column(1)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(level.objective.description())
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .build(ctx);
+            children.push(button);
+            children.push(objective);
+            level_buttons.push(button);
+            objectives.push(objective);
+        }
+
+        let progress;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(500.0))
+            .with_title(WindowTitle::text(tr("Campaign")))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            progress = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .on_column(0)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .build(ctx);
+                            progress
+                        })
+                        .with_children(children),
+                )
+                .add_column(Column::strict(200.0))
+                .add_column(Column::stretch())
+                .add_rows((0..LEVELS.len() + 1).map(|_| common_row).collect())
+                .add_row(Row::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            sender,
+            settings,
+            window,
+            progress,
+            level_buttons,
+            objectives,
+        }
+    }
+
+    /// Opens the window, list of levels is synced with progress in profile first.
+    pub fn open(&self, ui: &mut UserInterface) {
+        let progress = self.settings.read().unwrap().profile.campaign_progress;
+
+        ui.send_message(TextMessage::text(
+            self.progress,
+            MessageDirection::ToWidget,
+            format!(
+                "Completed {} of {}",
+                (progress as usize).min(LEVELS.len()),
+                LEVELS.len()
+            ),
+        ));
+        for (i, (&button, &objective)) in self
+            .level_buttons
+            .iter()
+            .zip(self.objectives.iter())
+            .enumerate()
+        {
+            let unlocked = campaign::is_unlocked(i, progress);
+            ui.send_message(WidgetMessage::visibility(
+                button,
+                MessageDirection::ToWidget,
+                unlocked,
+            ));
+            ui.send_message(WidgetMessage::visibility(
+                objective,
+                MessageDirection::ToWidget,
+                unlocked,
+            ));
+        }
+
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    pub fn handle_ui_event(&mut self, ui: &mut UserInterface, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if let Some(index) = self
+                .level_buttons
+                .iter()
+                .position(|&button| button == message.destination())
+            {
+                self.sender
+                    .send(Message::StartCampaign {
+                        level: index as u32,
+                    })
+                    .unwrap();
+                ui.send_message(WindowMessage::close(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Closed captions for significant sounds. Captions are driven by the same `PlaySound` and
+//! `Announce` messages the audio consumes, positional sounds get an arrow that points to the
+//! source relative to where the listener looks.
+
+use crate::{localization::tr, message::Message};
+use rg3d::{
+    core::{
+        algebra::{Matrix3, Vector3},
+        color::Color,
+        pool::Handle,
+    },
+    gui::{
+        brush::Brush,
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::path::Path;
+
+/// Sounds that have captions, every other sound is considered insignificant.
+const CAPTIONS: [(&str, &str); 5] = [
+    ("data/sounds/m4_shot.ogg", "Gunfire"),
+    ("data/sounds/ak47.ogg", "Gunfire"),
+    ("data/sounds/plasma_shot.ogg", "Plasma fire"),
+    ("data/sounds/grenade_launcher_fire.ogg", "Rocket launch"),
+    ("data/sounds/explosion.ogg", "Explosion"),
+];
+
+/// Sounds further than this distance from listener are not captioned.
+const MAX_DISTANCE: f32 = 30.0;
+
+/// Sounds closer than this distance are considered to be made by listener, they have no arrow.
+const SELF_DISTANCE: f32 = 1.5;
+
+/// How long (in seconds) each caption stays on screen.
+const CAPTION_TIME: f32 = 2.0;
+
+const MAX_CAPTIONS: usize = 4;
+
+struct Caption {
+    text: String,
+    widget: Handle<UiNode>,
+    timeout: f32,
+}
+
+pub struct Captions {
+    panel: Handle<UiNode>,
+    captions: Vec<Caption>,
+    enabled: bool,
+    listener_position: Vector3<f32>,
+    listener_basis: Matrix3<f32>,
+}
+
+impl Captions {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .on_column(0)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_margin(Thickness {
+                    left: 20.0,
+                    top: 0.0,
+                    right: 0.0,
+                    bottom: 200.0,
+                }),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            panel,
+            captions: Default::default(),
+            enabled: false,
+            listener_position: Default::default(),
+            listener_basis: Matrix3::identity(),
+        }
+    }
+
+    /// Returns handle of captions panel, it must be attached to HUD.
+    pub fn widget(&self) -> Handle<UiNode> {
+        self.panel
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Listener's basis has side, up and backward vectors in its columns, just like one
+    /// used by the sound engine.
+    pub fn set_listener(&mut self, position: Vector3<f32>, basis: Matrix3<f32>) {
+        self.listener_position = position;
+        self.listener_basis = basis;
+    }
+
+    fn arrow(&self, position: Vector3<f32>) -> Option<&'static str> {
+        let offset = position - self.listener_position;
+        let distance = offset.norm();
+        if distance < SELF_DISTANCE {
+            return Some("");
+        } else if distance > MAX_DISTANCE {
+            return None;
+        }
+        let local = self.listener_basis.transpose() * offset;
+        // Side vector points to the left, third column points backwards.
+        let (left, ahead) = (local.x, -local.z);
+        Some(if ahead.abs() >= left.abs() {
+            if ahead > 0.0 {
+                "^ "
+            } else {
+                "v "
+            }
+        } else if left > 0.0 {
+            "< "
+        } else {
+            "> "
+        })
+    }
+
+    fn show(&mut self, ui: &mut UserInterface, text: String) {
+        // Repeated sound (like automatic fire) just keeps its caption on screen.
+        if let Some(caption) = self.captions.iter_mut().find(|c| c.text == text) {
+            caption.timeout = CAPTION_TIME;
+            return;
+        }
+
+        if self.captions.len() >= MAX_CAPTIONS {
+            let oldest = self.captions.remove(0);
+            ui.send_message(WidgetMessage::remove(
+                oldest.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let widget = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(2.0))
+                .with_foreground(Brush::Solid(Color::opaque(230, 230, 230))),
+        )
+        .with_text(format!("[{}]", tr(&text)))
+        .build(&mut ui.build_ctx());
+        ui.send_message(WidgetMessage::link(
+            widget,
+            MessageDirection::ToWidget,
+            self.panel,
+        ));
+
+        self.captions.push(Caption {
+            text,
+            widget,
+            timeout: CAPTION_TIME,
+        });
+    }
+
+    pub fn handle_message(&mut self, ui: &mut UserInterface, message: &Message) {
+        if !self.enabled {
+            return;
+        }
+
+        match message {
+            Message::PlaySound { path, position, .. } => {
+                if let Some((_, caption)) = CAPTIONS
+                    .iter()
+                    .find(|(sound, _)| Path::new(sound) == path.as_path())
+                {
+                    if let Some(arrow) = self.arrow(*position) {
+                        self.show(ui, format!("{}{}", arrow, caption));
+                    }
+                }
+            }
+            Message::Announce { announcement } => {
+                self.show(ui, format!("Announcer: {}", announcement.text()))
+            }
+            _ => (),
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        for caption in self.captions.iter_mut() {
+            caption.timeout -= dt;
+            if caption.timeout <= 0.0 {
+                ui.send_message(WidgetMessage::remove(
+                    caption.widget,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        }
+        self.captions.retain(|caption| caption.timeout > 0.0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{message::Message, weapon::Weapon};
+use crate::{actor::Actor, message::Message, saves, weapon::Weapon};
 use rg3d::{
     core::{
         algebra::Vector3,
@@ -10,6 +10,36 @@ use rg3d::{
 };
 use std::sync::mpsc::Sender;
 
+/// Height of center of head above center of body of a character.
+pub const HEAD_HEIGHT: f32 = 0.6;
+
+/// How long (in seconds) character burns after last contact with fire.
+const BURN_TIME: f32 = 3.0;
+
+/// Burning deals damage once per this amount of seconds.
+const BURN_TICK: f32 = 0.5;
+
+/// Damage dealt by each tick of burning.
+pub const BURN_DAMAGE: f32 = 4.0;
+
+/// How long (in seconds) character is invulnerable after spawn unless it fires.
+pub const SPAWN_PROTECTION_TIME: f32 = 3.0;
+
+/// Amount of damage full shield barrier absorbs.
+pub const SHIELD_CAPACITY: f32 = 50.0;
+
+/// Shield recharges this many points per second once recharge delay is over.
+const SHIELD_REGEN_RATE: f32 = 10.0;
+
+/// With regeneration mutator health starts to restore after this many seconds without damage.
+const HEALTH_REGEN_DELAY: f32 = 5.0;
+
+/// Health restores this many points per second.
+const HEALTH_REGEN_RATE: f32 = 4.0;
+
+/// Regeneration does not restore health above this amount, medkits still do.
+const HEALTH_REGEN_CAP: f32 = 100.0;
+
 pub struct Character {
     pub name: String,
     pub pivot: Handle<Node>,
@@ -18,9 +48,25 @@ pub struct Character {
     pub armor: f32,
     pub weapons: Vec<Handle<Weapon>>,
     pub current_weapon: u32,
+    pub last_weapon: u32,
     pub weapon_pivot: Handle<Node>,
     pub sender: Option<Sender<Message>>,
     pub team: Team,
+    burn_time: f32,
+    burn_tick: f32,
+    // Actor who set character on fire, burn damage is dealt on their behalf.
+    burner: Handle<Actor>,
+    spawn_protection: f32,
+    /// Energy barrier that absorbs damage before armor and health.
+    pub shield: f32,
+    // Zero until character picks up a shield, barrier recharges up to this amount.
+    shield_capacity: f32,
+    // Time since last damage, shield and health recover when it is long enough.
+    idle_time: f32,
+    // Not serialized, whether health was restored by regeneration on last update.
+    regenerating: bool,
+    // Not serialized, level sets it every frame.
+    pub speed_scale: f32,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -36,6 +82,16 @@ impl Default for Team {
     }
 }
 
+impl Team {
+    pub fn opponent(self) -> Team {
+        match self {
+            Team::None => Team::None,
+            Team::Red => Team::Blue,
+            Team::Blue => Team::Red,
+        }
+    }
+}
+
 impl Visit for Team {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         let mut id = match self {
@@ -66,9 +122,19 @@ impl Default for Character {
             armor: 100.0,
             weapons: Vec::new(),
             current_weapon: 0,
+            last_weapon: 0,
             weapon_pivot: Handle::NONE,
             sender: None,
             team: Team::None,
+            burn_time: 0.0,
+            burn_tick: 0.0,
+            burner: Handle::NONE,
+            spawn_protection: 0.0,
+            shield: 0.0,
+            shield_capacity: 0.0,
+            idle_time: 0.0,
+            regenerating: false,
+            speed_scale: 1.0,
         }
     }
 }
@@ -84,8 +150,22 @@ impl Visit for Character {
         self.armor.visit("Armor", visitor)?;
         self.weapons.visit("Weapons", visitor)?;
         self.current_weapon.visit("CurrentWeapon", visitor)?;
+        self.last_weapon.visit("LastWeapon", visitor)?;
         self.weapon_pivot.visit("WeaponPivot", visitor)?;
         self.team.visit("Team", visitor)?;
+        if saves::read_version(visitor) >= 4 {
+            self.burn_time.visit("BurnTime", visitor)?;
+            self.burn_tick.visit("BurnTick", visitor)?;
+            self.burner.visit("Burner", visitor)?;
+        }
+        if saves::read_version(visitor) >= 10 {
+            self.spawn_protection.visit("SpawnProtection", visitor)?;
+        }
+        if saves::read_version(visitor) >= 13 {
+            self.shield.visit("Shield", visitor)?;
+            self.shield_capacity.visit("ShieldCapacity", visitor)?;
+            self.idle_time.visit("IdleTime", visitor)?;
+        }
 
         visitor.leave_region()
     }
@@ -124,6 +204,14 @@ impl Character {
         self.armor
     }
 
+    pub fn set_health(&mut self, health: f32) {
+        self.health = health;
+    }
+
+    pub fn set_armor(&mut self, armor: f32) {
+        self.armor = armor;
+    }
+
     pub fn set_position(&mut self, physics: &mut Physics, position: Vector3<f32>) {
         let body = physics.bodies.get_mut(&self.get_body()).unwrap();
         let mut body_position = *body.position();
@@ -141,8 +229,22 @@ impl Character {
             .vector
     }
 
+    pub fn head_position(&self, physics: &Physics) -> Vector3<f32> {
+        self.position(physics) + Vector3::new(0.0, HEAD_HEIGHT, 0.0)
+    }
+
     pub fn damage(&mut self, amount: f32) {
-        let amount = amount.abs();
+        let mut amount = amount.abs();
+        self.idle_time = 0.0;
+        self.regenerating = false;
+        if self.shield > 0.0 {
+            let absorbed = amount.min(self.shield);
+            self.shield -= absorbed;
+            amount -= absorbed;
+            if amount <= 0.0 {
+                return;
+            }
+        }
         if self.armor > 0.0 {
             self.armor -= amount;
             if self.armor < 0.0 {
@@ -165,6 +267,90 @@ impl Character {
         self.health <= 0.0
     }
 
+    /// Sets character on fire or prolongs burning, `who` is credited for burn damage.
+    pub fn ignite(&mut self, who: Handle<Actor>) {
+        if !self.is_burning() {
+            self.burn_tick = BURN_TICK;
+        }
+        self.burn_time = BURN_TIME;
+        self.burner = who;
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.burn_time > 0.0
+    }
+
+    /// Advances burning and returns actor who must be credited for burn damage if it is
+    /// time to deal it.
+    pub fn update_burning(&mut self, dt: f32) -> Option<Handle<Actor>> {
+        if !self.is_burning() {
+            return None;
+        }
+        self.burn_time -= dt;
+        self.burn_tick -= dt;
+        if self.burn_tick <= 0.0 {
+            self.burn_tick += BURN_TICK;
+            Some(self.burner)
+        } else {
+            None
+        }
+    }
+
+    pub fn protect(&mut self, time: f32) {
+        self.spawn_protection = time;
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.spawn_protection > 0.0
+    }
+
+    /// Returns remaining time of spawn protection in seconds.
+    pub fn protection_time(&self) -> f32 {
+        self.spawn_protection.max(0.0)
+    }
+
+    pub fn cancel_protection(&mut self) {
+        self.spawn_protection = 0.0;
+    }
+
+    pub fn update_protection(&mut self, dt: f32) {
+        if self.is_protected() {
+            self.spawn_protection -= dt;
+        }
+    }
+
+    /// Fills shield barrier, character keeps it until death.
+    pub fn give_shield(&mut self) {
+        self.shield_capacity = SHIELD_CAPACITY;
+        self.shield = SHIELD_CAPACITY;
+    }
+
+    pub fn shield_capacity(&self) -> f32 {
+        self.shield_capacity
+    }
+
+    /// Recharges shield if character was not damaged for `shield_delay` seconds, None means
+    /// that shield does not recharge. If `regenerate_health` is set, health is slowly restored
+    /// up to a cap as well.
+    pub fn update_recovery(&mut self, dt: f32, shield_delay: Option<f32>, regenerate_health: bool) {
+        self.idle_time += dt;
+        if let Some(shield_delay) = shield_delay {
+            if self.idle_time >= shield_delay && self.shield < self.shield_capacity {
+                self.shield = (self.shield + SHIELD_REGEN_RATE * dt).min(self.shield_capacity);
+            }
+        }
+        self.regenerating = regenerate_health
+            && self.idle_time >= HEALTH_REGEN_DELAY
+            && self.health < HEALTH_REGEN_CAP;
+        if self.regenerating {
+            self.health = (self.health + HEALTH_REGEN_RATE * dt).min(HEALTH_REGEN_CAP);
+        }
+    }
+
+    pub fn is_regenerating(&self) -> bool {
+        self.regenerating
+    }
+
     pub fn weapon_pivot(&self) -> Handle<Node> {
         self.weapon_pivot
     }
@@ -185,6 +371,7 @@ impl Character {
             }
         }
 
+        self.last_weapon = self.current_weapon;
         self.current_weapon = self.weapons.len() as u32;
         self.weapons.push(weapon);
 
@@ -212,30 +399,31 @@ impl Character {
         }
     }
 
+    /// Selects next weapon in the arsenal, wraps around to the first one.
     pub fn next_weapon(&mut self) {
-        if !self.weapons.is_empty() && (self.current_weapon as usize) < self.weapons.len() - 1 {
-            self.request_current_weapon_visible(false);
-
-            self.current_weapon += 1;
-
-            self.request_current_weapon_visible(true);
+        if !self.weapons.is_empty() {
+            self.set_current_weapon((self.current_weapon as usize + 1) % self.weapons.len());
         }
     }
 
+    /// Selects previous weapon in the arsenal, wraps around to the last one.
     pub fn prev_weapon(&mut self) {
-        if self.current_weapon > 0 {
-            self.request_current_weapon_visible(false);
-
-            self.current_weapon -= 1;
-
-            self.request_current_weapon_visible(true);
+        if !self.weapons.is_empty() {
+            let count = self.weapons.len();
+            self.set_current_weapon((self.current_weapon as usize + count - 1) % count);
         }
     }
 
+    /// Switches back to weapon that was selected before current one.
+    pub fn switch_to_last_weapon(&mut self) {
+        self.set_current_weapon(self.last_weapon as usize);
+    }
+
     pub fn set_current_weapon(&mut self, i: usize) {
-        if i < self.weapons.len() {
+        if i < self.weapons.len() && i != self.current_weapon as usize {
             self.request_current_weapon_visible(false);
 
+            self.last_weapon = self.current_weapon;
             self.current_weapon = i as u32;
 
             self.request_current_weapon_visible(true);
@@ -0,0 +1,365 @@
+//! Text chat. Messages go through regular `ChatMessage` message, so they can be carried by
+//! network protocol the same way as any other message. Lines starting with `/` are console
+//! commands which are handled locally, for example muting of players or toggling of debug
+//! overlays.
+
+use crate::{
+    debug_overlay::DebugOverlay,
+    localization::{tr, tr_format},
+    message::Message,
+    vote::VoteSubject,
+};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        brush::Brush,
+        grid::{Column, GridBuilder, Row},
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        text_box::{TextBox, TextBoxBuilder, TextBoxMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::collections::HashSet;
+
+/// How long (in seconds) new line stays on screen, whole history is shown while typing.
+const LINE_TIME: f32 = 8.0;
+
+const MAX_HISTORY: usize = 10;
+
+/// Every sent message adds this amount of seconds to flood counter which goes down with time.
+const FLOOD_COST: f32 = 1.5;
+
+/// Messages are rejected while flood counter is above this value, so at most three messages
+/// can be sent in a row.
+const FLOOD_LIMIT: f32 = 3.0;
+
+const MAX_LENGTH: usize = 120;
+
+struct ChatLine {
+    widget: Handle<UiNode>,
+    timeout: f32,
+}
+
+pub struct Chat {
+    root: Handle<UiNode>,
+    history: Handle<UiNode>,
+    entry: Handle<UiNode>,
+    entry_panel: Handle<UiNode>,
+    prefix: Handle<UiNode>,
+    lines: Vec<ChatLine>,
+    typing: bool,
+    team: bool,
+    // Key that opened the chat produces a character after it was opened, entry is cleared
+    // on next update to get rid of it.
+    just_opened: bool,
+    flood_counter: f32,
+    muted: HashSet<String>,
+}
+
+impl Chat {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let history;
+        let entry_panel;
+        let prefix;
+        let entry;
+        let root = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .on_column(0)
+                .with_width(400.0)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_margin(Thickness {
+                    left: 20.0,
+                    top: 0.0,
+                    right: 0.0,
+                    bottom: 300.0,
+                })
+                .with_child({
+                    history = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+                    history
+                })
+                .with_child({
+                    entry_panel = GridBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .with_height(26.0)
+                            .with_child({
+                                prefix = TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_column(0)
+                                        .with_margin(Thickness::right(4.0)),
+                                )
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx);
+                                prefix
+                            })
+                            .with_child({
+                                entry = TextBoxBuilder::new(WidgetBuilder::new().on_column(1))
+                                    .build(ctx);
+                                entry
+                            }),
+                    )
+                    .add_column(Column::auto())
+                    .add_column(Column::stretch())
+                    .add_row(Row::stretch())
+                    .build(ctx);
+                    entry_panel
+                }),
+        )
+        .build(ctx);
+
+        Self {
+            root,
+            history,
+            entry,
+            entry_panel,
+            prefix,
+            lines: Default::default(),
+            typing: false,
+            team: false,
+            just_opened: false,
+            flood_counter: 0.0,
+            muted: Default::default(),
+        }
+    }
+
+    /// Returns handle of chat panel, it must be attached to HUD.
+    pub fn widget(&self) -> Handle<UiNode> {
+        self.root
+    }
+
+    /// Player's input must not reach the level while player types.
+    pub fn is_typing(&self) -> bool {
+        self.typing
+    }
+
+    pub fn open(&mut self, ui: &mut UserInterface, team: bool) {
+        self.typing = true;
+        self.team = team;
+        self.just_opened = true;
+        ui.send_message(TextMessage::text(
+            self.prefix,
+            MessageDirection::ToWidget,
+            tr(if team { "Team:" } else { "Say:" }),
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.entry_panel,
+            MessageDirection::ToWidget,
+            true,
+        ));
+        ui.send_message(WidgetMessage::focus(self.entry, MessageDirection::ToWidget));
+        self.sync_visibility(ui);
+    }
+
+    pub fn close(&mut self, ui: &mut UserInterface) {
+        self.typing = false;
+        ui.send_message(TextBoxMessage::text(
+            self.entry,
+            MessageDirection::ToWidget,
+            String::new(),
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.entry_panel,
+            MessageDirection::ToWidget,
+            false,
+        ));
+        ui.send_message(WidgetMessage::unfocus(
+            self.entry,
+            MessageDirection::ToWidget,
+        ));
+        self.sync_visibility(ui);
+    }
+
+    /// Closes the chat and returns message that must be sent, if any. Commands and rejected
+    /// messages are handled right here.
+    pub fn submit(&mut self, ui: &mut UserInterface, sender: &str) -> Option<Message> {
+        let text = ui
+            .node(self.entry)
+            .cast::<TextBox>()
+            .map(|text_box| text_box.text())
+            .unwrap_or_default();
+        let text = text
+            .trim()
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(MAX_LENGTH)
+            .collect::<String>();
+        self.close(ui);
+
+        if text.is_empty() {
+            None
+        } else if let Some(command) = text.strip_prefix('/') {
+            self.execute(ui, sender, command)
+        } else if self.flood_counter > FLOOD_LIMIT {
+            self.add_system_line(ui, tr("You are sending messages too fast"));
+            None
+        } else {
+            self.flood_counter += FLOOD_COST;
+            Some(Message::ChatMessage {
+                sender: sender.to_owned(),
+                text,
+                team: self.team,
+            })
+        }
+    }
+
+    /// Returns message if command must be handled by the game.
+    fn execute(&mut self, ui: &mut UserInterface, sender: &str, command: &str) -> Option<Message> {
+        let mut args = command.splitn(2, ' ');
+        let name = args.next().unwrap_or_default();
+        let arg = args.next().map(|arg| arg.trim()).unwrap_or_default();
+        let reply = match (name, arg) {
+            ("mute", player) if !player.is_empty() => {
+                self.muted.insert(player.to_owned());
+                tr_format("{} is muted", &[&player])
+            }
+            ("unmute", player) if !player.is_empty() => {
+                if self.muted.remove(player) {
+                    tr_format("{} is no longer muted", &[&player])
+                } else {
+                    tr_format("{} is not muted", &[&player])
+                }
+            }
+            ("muted", _) => {
+                if self.muted.is_empty() {
+                    tr("Nobody is muted")
+                } else {
+                    let mut names = self.muted.iter().cloned().collect::<Vec<_>>();
+                    names.sort();
+                    tr_format("Muted: {}", &[&names.join(", ")])
+                }
+            }
+            ("vote", subject) => match VoteSubject::parse(subject) {
+                Ok(subject) => {
+                    return Some(Message::CallVote {
+                        caller: sender.to_owned(),
+                        subject,
+                    })
+                }
+                Err(e) => e,
+            },
+            ("observe", _) => return Some(Message::ToggleObserver),
+            ("yes", _) | ("no", _) => {
+                return Some(Message::CastVote {
+                    voter: sender.to_owned(),
+                    yes: name == "yes",
+                });
+            }
+            ("debug", "off") => {
+                return Some(Message::ToggleDebugOverlay { overlay: None });
+            }
+            ("debug", overlay) if DebugOverlay::from_name(overlay).is_some() => {
+                return Some(Message::ToggleDebugOverlay {
+                    overlay: DebugOverlay::from_name(overlay),
+                });
+            }
+            ("timescale", scale) => match scale.parse::<f32>() {
+                Ok(scale) if scale > 0.0 => return Some(Message::SetTimeScale { scale }),
+                _ => "Usage: /timescale <scale>, for example /timescale 0.25".to_owned(),
+            },
+            ("debug", _) => {
+                let names = DebugOverlay::ALL
+                    .iter()
+                    .map(|overlay| overlay.name())
+                    .collect::<Vec<_>>();
+                format!("Overlays: {}, off", names.join(", "))
+            }
+            _ => "Commands: /mute <name>, /unmute <name>, /muted, /debug <overlay>, /vote, /yes, \
+                  /no, /observe, /timescale <scale>"
+                .to_owned(),
+        };
+        self.add_system_line(ui, reply);
+        None
+    }
+
+    fn add_line(&mut self, ui: &mut UserInterface, text: String, color: Color) {
+        if self.lines.len() >= MAX_HISTORY {
+            let oldest = self.lines.remove(0);
+            ui.send_message(WidgetMessage::remove(
+                oldest.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let widget = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(1.0))
+                .with_foreground(Brush::Solid(color)),
+        )
+        .with_wrap(true)
+        .with_text(text)
+        .build(&mut ui.build_ctx());
+        ui.send_message(WidgetMessage::link(
+            widget,
+            MessageDirection::ToWidget,
+            self.history,
+        ));
+
+        self.lines.push(ChatLine {
+            widget,
+            timeout: LINE_TIME,
+        });
+    }
+
+    /// Adds console-style line that is not sent by any player.
+    pub fn add_system_line(&mut self, ui: &mut UserInterface, text: String) {
+        self.add_line(ui, text, Color::opaque(255, 200, 0));
+    }
+
+    fn sync_visibility(&self, ui: &mut UserInterface) {
+        for line in self.lines.iter() {
+            ui.send_message(WidgetMessage::visibility(
+                line.widget,
+                MessageDirection::ToWidget,
+                self.typing || line.timeout > 0.0,
+            ));
+        }
+    }
+
+    pub fn handle_message(&mut self, ui: &mut UserInterface, message: &Message) {
+        if let Message::ChatMessage { sender, text, team } = message {
+            if self.muted.contains(sender) {
+                return;
+            }
+            let (text, color) = if *team {
+                (
+                    tr_format("[Team] {}: {}", &[sender, text]),
+                    Color::opaque(0, 200, 255),
+                )
+            } else {
+                (format!("{}: {}", sender, text), Color::WHITE)
+            };
+            self.add_line(ui, text, color);
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        if self.just_opened {
+            self.just_opened = false;
+            ui.send_message(TextBoxMessage::text(
+                self.entry,
+                MessageDirection::ToWidget,
+                String::new(),
+            ));
+        }
+
+        self.flood_counter = (self.flood_counter - dt).max(0.0);
+
+        let mut expired = false;
+        for line in self.lines.iter_mut() {
+            if line.timeout > 0.0 {
+                line.timeout -= dt;
+                expired |= line.timeout <= 0.0;
+            }
+        }
+        if expired {
+            self.sync_visibility(ui);
+        }
+    }
+}
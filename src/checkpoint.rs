@@ -0,0 +1,117 @@
+//! Checkpoints of campaign levels. Checkpoint is an invisible trigger volume, either a mesh of
+//! map scene named `Checkpoint*` or a point placed by level editor. When player walks into a
+//! checkpoint, lightweight state of the level is remembered: player, bots, items and scores.
+//! Dead player can restart from the latest checkpoint instead of respawning, so the death does
+//! not count.
+
+use crate::{
+    campaign::Loadout,
+    control_scheme::{ControlButton, ControlScheme},
+    item::Item,
+    leader_board::LeaderBoard,
+};
+use rg3d::{
+    core::{
+        algebra::Vector3,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    event::{DeviceEvent, ElementState, Event, WindowEvent},
+};
+
+/// Half-size (in meters) of trigger volume of a checkpoint placed by level editor.
+const LAYOUT_CHECKPOINT_SIZE: Vector3<f32> = Vector3::new(2.0, 2.0, 2.0);
+
+/// Player appears this high above the floor of checkpoint, so they do not get stuck in it.
+const RESTART_HEIGHT: f32 = 1.5;
+
+#[derive(Default)]
+pub struct Checkpoint {
+    bounds: AxisAlignedBoundingBox,
+    reached: bool,
+}
+
+impl Visit for Checkpoint {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.bounds.visit("Bounds", visitor)?;
+        self.reached.visit("Reached", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl Checkpoint {
+    pub fn new(bounds: AxisAlignedBoundingBox) -> Self {
+        Self {
+            bounds,
+            reached: false,
+        }
+    }
+
+    /// Checkpoint placed by level editor, `position` is on the floor.
+    pub fn around(position: Vector3<f32>) -> Self {
+        let center = position + Vector3::new(0.0, LAYOUT_CHECKPOINT_SIZE.y, 0.0);
+        Self::new(AxisAlignedBoundingBox::from_min_max(
+            center - LAYOUT_CHECKPOINT_SIZE,
+            center + LAYOUT_CHECKPOINT_SIZE,
+        ))
+    }
+
+    /// Every checkpoint is triggered once per level.
+    pub fn is_triggered_by(&self, position: Vector3<f32>) -> bool {
+        !self.reached && self.bounds.is_contains_point(position)
+    }
+
+    pub fn set_reached(&mut self) {
+        self.reached = true;
+    }
+
+    /// Returns position at which restarted player appears.
+    pub fn restart_position(&self) -> Vector3<f32> {
+        Vector3::new(
+            (self.bounds.min.x + self.bounds.max.x) * 0.5,
+            self.bounds.min.y + RESTART_HEIGHT,
+            (self.bounds.min.z + self.bounds.max.z) * 0.5,
+        )
+    }
+}
+
+pub struct BotState {
+    pub name: String,
+    pub position: Vector3<f32>,
+    pub health: f32,
+    pub armor: f32,
+}
+
+/// State of level at the moment player reached a checkpoint. Bots that were dead at that moment
+/// are not brought back, they respawn as usual.
+pub struct Snapshot {
+    pub position: Vector3<f32>,
+    pub loadout: Loadout,
+    pub bots: Vec<BotState>,
+    /// Time until reactivation of every item, None if item was there.
+    pub items: Vec<(Handle<Item>, Option<f32>)>,
+    pub leader_board: LeaderBoard,
+}
+
+/// Dead player restarts from checkpoint by pressing jump.
+pub fn is_restart_event(event: &Event<()>, control_scheme: &ControlScheme) -> bool {
+    let button = match event {
+        Event::WindowEvent {
+            event: WindowEvent::KeyboardInput { input, .. },
+            ..
+        } if input.state == ElementState::Pressed => match input.virtual_keycode {
+            Some(code) => ControlButton::Key(code),
+            None => return false,
+        },
+        Event::DeviceEvent {
+            event: DeviceEvent::Button { button, state },
+            ..
+        } if *state == ElementState::Pressed => ControlButton::Mouse(*button as u16),
+        _ => return false,
+    };
+    button == control_scheme.jump.button
+}
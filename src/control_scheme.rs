@@ -41,7 +41,20 @@ pub struct ControlScheme {
     pub shoot: ControlButtonDefinition,
     pub next_weapon: ControlButtonDefinition,
     pub prev_weapon: ControlButtonDefinition,
+    pub last_weapon: ControlButtonDefinition,
+    pub fire_mode: ControlButtonDefinition,
     pub run: ControlButtonDefinition,
+    pub quick_save: ControlButtonDefinition,
+    pub quick_load: ControlButtonDefinition,
+    pub chat: ControlButtonDefinition,
+    pub team_chat: ControlButtonDefinition,
+    pub scoreboard: ControlButtonDefinition,
+    pub frame_graph: ControlButtonDefinition,
+    pub bullet_time: ControlButtonDefinition,
+    pub weapon_wheel: ControlButtonDefinition,
+    pub ping: ControlButtonDefinition,
+    pub danger_ping: ControlButtonDefinition,
+    pub command_rose: ControlButtonDefinition,
     pub mouse_sens: f32,
     pub mouse_y_inverse: bool,
     pub smooth_mouse: bool,
@@ -87,10 +100,62 @@ impl Default for ControlScheme {
                 description: "Previous Weapon".to_string(),
                 button: ControlButton::WheelDown,
             },
+            last_weapon: ControlButtonDefinition {
+                description: "Last Weapon".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Q),
+            },
+            fire_mode: ControlButtonDefinition {
+                description: "Fire Mode".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::B),
+            },
             run: ControlButtonDefinition {
                 description: "Run".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::LShift),
             },
+            quick_save: ControlButtonDefinition {
+                description: "Quick Save".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::F5),
+            },
+            quick_load: ControlButtonDefinition {
+                description: "Quick Load".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::F9),
+            },
+            chat: ControlButtonDefinition {
+                description: "Chat".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::T),
+            },
+            team_chat: ControlButtonDefinition {
+                description: "Team Chat".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Y),
+            },
+            scoreboard: ControlButtonDefinition {
+                description: "Scoreboard".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Tab),
+            },
+            frame_graph: ControlButtonDefinition {
+                description: "Frame Graph".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::F3),
+            },
+            bullet_time: ControlButtonDefinition {
+                description: "Bullet Time".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::F4),
+            },
+            weapon_wheel: ControlButtonDefinition {
+                description: "Weapon Wheel".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::G),
+            },
+            ping: ControlButtonDefinition {
+                description: "Ping".to_string(),
+                button: ControlButton::Mouse(3),
+            },
+            danger_ping: ControlButtonDefinition {
+                description: "Danger Ping".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Z),
+            },
+            command_rose: ControlButtonDefinition {
+                description: "Bot Commands".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::V),
+            },
             mouse_sens: 0.3,
             mouse_y_inverse: false,
             smooth_mouse: false,
@@ -100,7 +165,7 @@ impl Default for ControlScheme {
 }
 
 impl ControlScheme {
-    pub fn buttons_mut(&mut self) -> [&mut ControlButtonDefinition; 10] {
+    pub fn buttons_mut(&mut self) -> [&mut ControlButtonDefinition; 23] {
         [
             &mut self.move_forward,
             &mut self.move_backward,
@@ -111,11 +176,24 @@ impl ControlScheme {
             &mut self.shoot,
             &mut self.next_weapon,
             &mut self.prev_weapon,
+            &mut self.last_weapon,
+            &mut self.fire_mode,
             &mut self.run,
+            &mut self.quick_save,
+            &mut self.quick_load,
+            &mut self.chat,
+            &mut self.team_chat,
+            &mut self.scoreboard,
+            &mut self.frame_graph,
+            &mut self.bullet_time,
+            &mut self.weapon_wheel,
+            &mut self.ping,
+            &mut self.danger_ping,
+            &mut self.command_rose,
         ]
     }
 
-    pub fn buttons(&self) -> [&ControlButtonDefinition; 10] {
+    pub fn buttons(&self) -> [&ControlButtonDefinition; 23] {
         [
             &self.move_forward,
             &self.move_backward,
@@ -126,7 +204,20 @@ impl ControlScheme {
             &self.shoot,
             &self.next_weapon,
             &self.prev_weapon,
+            &self.last_weapon,
+            &self.fire_mode,
             &self.run,
+            &self.quick_save,
+            &self.quick_load,
+            &self.chat,
+            &self.team_chat,
+            &self.scoreboard,
+            &self.frame_graph,
+            &self.bullet_time,
+            &self.weapon_wheel,
+            &self.ping,
+            &self.danger_ping,
+            &self.command_rose,
         ]
     }
 
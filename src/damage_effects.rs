@@ -0,0 +1,199 @@
+//! Screen effects of taking damage. Edges of the screen turn red proportionally to recent
+//! damage, the screen is washed out while health is low and flashes when armor breaks. Renderer
+//! has no post-processing, so effects are translucent overlays between the scene and the HUD.
+//! Heartbeat of low health is played by level.
+//!
+//! Effects are driven by health and armor values shown on HUD, so they need no messages of
+//! their own. Strength of every effect is scaled by `damage_effects` setting.
+
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        grid::GridBuilder,
+        message::MessageDirection,
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+
+/// Health below this value is low, screen is washed out and heartbeat is heard.
+pub const LOW_HEALTH: f32 = 25.0;
+
+/// Size (in pixels) of red edges of the screen.
+const EDGE_SIZE: f32 = 120.0;
+
+/// Damage of this amount makes edges fully red.
+const FULL_PAIN_DAMAGE: f32 = 50.0;
+
+/// How much pain fades per second.
+const PAIN_DECAY: f32 = 1.2;
+
+const FLASH_TIME: f32 = 0.3;
+
+const MAX_EDGE_OPACITY: f32 = 0.6;
+const MAX_WASH_OPACITY: f32 = 0.5;
+const MAX_FLASH_OPACITY: f32 = 0.5;
+
+struct Overlay {
+    widget: Handle<UiNode>,
+    opacity: f32,
+}
+
+impl Overlay {
+    fn set_opacity(&mut self, ui: &mut UserInterface, opacity: f32) {
+        if opacity == self.opacity {
+            return;
+        }
+        if (opacity > 0.0) != (self.opacity > 0.0) {
+            ui.send_message(WidgetMessage::visibility(
+                self.widget,
+                MessageDirection::ToWidget,
+                opacity > 0.0,
+            ));
+        }
+        ui.send_message(WidgetMessage::opacity(
+            self.widget,
+            MessageDirection::ToWidget,
+            opacity,
+        ));
+        self.opacity = opacity;
+    }
+}
+
+pub struct DamageEffects {
+    root: Handle<UiNode>,
+    edges: Vec<Overlay>,
+    wash: Overlay,
+    flash: Overlay,
+    pain: f32,
+    flash_time: f32,
+    health: f32,
+    armor: f32,
+    intensity: f32,
+}
+
+fn make_overlay(ui: &mut UserInterface, builder: WidgetBuilder, color: Color) -> Overlay {
+    let widget = BorderBuilder::new(
+        builder
+            .with_visibility(false)
+            .with_background(Brush::Solid(color)),
+    )
+    .build(&mut ui.build_ctx());
+    Overlay {
+        widget,
+        opacity: 0.0,
+    }
+}
+
+impl DamageEffects {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let red = Color::opaque(200, 0, 0);
+        let edges = vec![
+            make_overlay(
+                ui,
+                WidgetBuilder::new()
+                    .with_height(EDGE_SIZE)
+                    .with_vertical_alignment(VerticalAlignment::Top),
+                red,
+            ),
+            make_overlay(
+                ui,
+                WidgetBuilder::new()
+                    .with_height(EDGE_SIZE)
+                    .with_vertical_alignment(VerticalAlignment::Bottom),
+                red,
+            ),
+            make_overlay(
+                ui,
+                WidgetBuilder::new()
+                    .with_width(EDGE_SIZE)
+                    .with_horizontal_alignment(HorizontalAlignment::Left),
+                red,
+            ),
+            make_overlay(
+                ui,
+                WidgetBuilder::new()
+                    .with_width(EDGE_SIZE)
+                    .with_horizontal_alignment(HorizontalAlignment::Right),
+                red,
+            ),
+        ];
+        let wash = make_overlay(ui, WidgetBuilder::new(), Color::opaque(128, 128, 128));
+        let flash = make_overlay(ui, WidgetBuilder::new(), Color::opaque(255, 220, 160));
+
+        let mut children = edges.iter().map(|edge| edge.widget).collect::<Vec<_>>();
+        children.push(wash.widget);
+        children.push(flash.widget);
+        let root = GridBuilder::new(WidgetBuilder::new().with_children(&children))
+            .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            edges,
+            wash,
+            flash,
+            pain: 0.0,
+            flash_time: 0.0,
+            health: 0.0,
+            armor: 0.0,
+            intensity: 1.0,
+        }
+    }
+
+    /// Returns handle of effects layer, it must be attached to HUD before other widgets.
+    pub fn widget(&self) -> Handle<UiNode> {
+        self.root
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+
+    pub fn track_health(&mut self, health: f32) {
+        if health < self.health {
+            self.pain = (self.pain + (self.health - health) / FULL_PAIN_DAMAGE).min(1.0);
+        }
+        self.health = health;
+    }
+
+    pub fn track_armor(&mut self, armor: f32) {
+        if armor <= 0.0 && self.armor > 0.0 {
+            self.flash_time = FLASH_TIME;
+        }
+        self.armor = armor;
+    }
+
+    /// Removes every effect, used when level is unloaded.
+    pub fn reset(&mut self, ui: &mut UserInterface) {
+        self.pain = 0.0;
+        self.flash_time = 0.0;
+        self.health = 0.0;
+        self.armor = 0.0;
+        self.update(ui, 0.0);
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        self.pain = (self.pain - PAIN_DECAY * dt).max(0.0);
+        self.flash_time = (self.flash_time - dt).max(0.0);
+
+        let edge_opacity = self.pain * MAX_EDGE_OPACITY * self.intensity;
+        for edge in self.edges.iter_mut() {
+            edge.set_opacity(ui, edge_opacity);
+        }
+
+        let wash = if self.health > 0.0 && self.health < LOW_HEALTH {
+            1.0 - self.health / LOW_HEALTH
+        } else {
+            0.0
+        };
+        self.wash
+            .set_opacity(ui, wash * MAX_WASH_OPACITY * self.intensity);
+
+        self.flash.set_opacity(
+            ui,
+            self.flash_time / FLASH_TIME * MAX_FLASH_OPACITY * self.intensity,
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Debug overlays for bot tuning. Overlays are toggled by `/debug <overlay>` chat command and
+//! drawn by level every frame with scene's drawing context, except AI state labels which are
+//! shown by HUD as screen-space markers above heads of bots. Inspector and log viewer are
+//! windows rather than overlays, but they are toggled the same way. Heatmaps of deaths and
+//! pickups of current match help map makers to balance placement of items and spawn points.
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DebugOverlay {
+    Physics,
+    Navmesh,
+    Paths,
+    States,
+    Vision,
+    Targets,
+    Deaths,
+    Pickups,
+    Inspector,
+    Log,
+}
+
+impl DebugOverlay {
+    pub const ALL: [DebugOverlay; 10] = [
+        DebugOverlay::Physics,
+        DebugOverlay::Navmesh,
+        DebugOverlay::Paths,
+        DebugOverlay::States,
+        DebugOverlay::Vision,
+        DebugOverlay::Targets,
+        DebugOverlay::Deaths,
+        DebugOverlay::Pickups,
+        DebugOverlay::Inspector,
+        DebugOverlay::Log,
+    ];
+
+    /// Name of overlay in chat commands.
+    pub fn name(self) -> &'static str {
+        match self {
+            DebugOverlay::Physics => "physics",
+            DebugOverlay::Navmesh => "navmesh",
+            DebugOverlay::Paths => "paths",
+            DebugOverlay::States => "states",
+            DebugOverlay::Vision => "vision",
+            DebugOverlay::Targets => "targets",
+            DebugOverlay::Deaths => "deaths",
+            DebugOverlay::Pickups => "pickups",
+            DebugOverlay::Inspector => "inspector",
+            DebugOverlay::Log => "log",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|overlay| overlay.name() == name)
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct DebugOverlays {
+    enabled: Vec<DebugOverlay>,
+}
+
+impl DebugOverlays {
+    pub fn is_enabled(&self, overlay: DebugOverlay) -> bool {
+        self.enabled.contains(&overlay)
+    }
+
+    pub fn any(&self) -> bool {
+        !self.enabled.is_empty()
+    }
+
+    /// Returns true if overlay is enabled after toggling.
+    pub fn toggle(&mut self, overlay: DebugOverlay) -> bool {
+        if let Some(index) = self.enabled.iter().position(|other| *other == overlay) {
+            self.enabled.remove(index);
+            false
+        } else {
+            self.enabled.push(overlay);
+            true
+        }
+    }
+
+    pub fn disable_all(&mut self) {
+        self.enabled.clear();
+    }
+}
@@ -0,0 +1,169 @@
+//! AI director of survival mode. Bots come in waves, every next wave is bigger, brings tougher
+//! kinds of bots and hits harder. Within a wave director paces spawns by condition of the
+//! player: healthy and well supplied player gets bots quicker and more of them at once, while
+//! struggling player is given some room to breathe.
+
+use crate::bot::BotKind;
+use rg3d::core::visitor::{Visit, VisitResult, Visitor};
+
+const FIRST_WAVE_SIZE: u32 = 3;
+
+/// Every next wave has this many more bots than previous one.
+const WAVE_SIZE_GROWTH: u32 = 2;
+
+/// Pause (in seconds) before a wave, player has time to pick up items.
+const REST_TIME: f32 = 10.0;
+
+/// Spawn intervals (in seconds) for best and worst condition of player.
+const MIN_SPAWN_INTERVAL: f32 = 1.5;
+
+const MAX_SPAWN_INTERVAL: f32 = 6.0;
+
+/// Damage dealt by bots grows by this fraction with every wave.
+const DAMAGE_GROWTH: f32 = 0.1;
+
+/// Condition of player in [0; 1] range, director paces spawns by it.
+pub struct PlayerCondition {
+    pub health: f32,
+    pub ammo: f32,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DirectorEvent {
+    WaveStarted(u32),
+    WaveCleared(u32),
+    Spawn(BotKind),
+}
+
+pub struct Director {
+    // Zero until first wave starts.
+    wave: u32,
+    // Bots of current wave that are not spawned yet.
+    to_spawn: u32,
+    // Total amount of spawned bots, it is used to rotate kinds of bots.
+    spawned: u32,
+    spawn_timer: f32,
+    resting: bool,
+    rest_timer: f32,
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Self {
+            wave: 0,
+            to_spawn: 0,
+            spawned: 0,
+            spawn_timer: 0.0,
+            resting: true,
+            rest_timer: REST_TIME,
+        }
+    }
+}
+
+impl Visit for Director {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.wave.visit("Wave", visitor)?;
+        self.to_spawn.visit("ToSpawn", visitor)?;
+        self.spawned.visit("Spawned", visitor)?;
+        self.spawn_timer.visit("SpawnTimer", visitor)?;
+        self.resting.visit("Resting", visitor)?;
+        self.rest_timer.visit("RestTimer", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+fn wave_size(wave: u32) -> u32 {
+    FIRST_WAVE_SIZE + WAVE_SIZE_GROWTH * wave.saturating_sub(1)
+}
+
+/// Kinds of bots that may appear in given wave, tougher ones join in later waves.
+fn wave_kinds(wave: u32) -> &'static [BotKind] {
+    match wave {
+        0 | 1 => &[BotKind::Mutant],
+        2 => &[BotKind::Mutant, BotKind::Parasite],
+        _ => &[BotKind::Mutant, BotKind::Parasite, BotKind::Maw],
+    }
+}
+
+impl Director {
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Amount of waves player has cleared, this is the score of survival.
+    pub fn waves_cleared(&self) -> u32 {
+        if self.resting {
+            self.wave
+        } else {
+            self.wave.saturating_sub(1)
+        }
+    }
+
+    /// Returns time left before next wave, None if a wave is in progress.
+    pub fn rest_time_left(&self) -> Option<f32> {
+        if self.resting {
+            Some(self.rest_timer.max(0.0))
+        } else {
+            None
+        }
+    }
+
+    /// Bots of current wave that are still to come.
+    pub fn pending(&self) -> u32 {
+        self.to_spawn
+    }
+
+    /// Multiplier of damage dealt by bots of current wave.
+    pub fn damage_scale(&self) -> f32 {
+        1.0 + DAMAGE_GROWTH * self.wave.saturating_sub(1) as f32
+    }
+
+    /// Advances director, `alive_bots` is amount of bots currently fighting the player.
+    pub fn update(
+        &mut self,
+        alive_bots: usize,
+        player: &PlayerCondition,
+        dt: f32,
+    ) -> Option<DirectorEvent> {
+        if self.resting {
+            self.rest_timer -= dt;
+            if self.rest_timer <= 0.0 {
+                self.resting = false;
+                self.wave += 1;
+                self.to_spawn = wave_size(self.wave);
+                self.spawn_timer = 0.0;
+                return Some(DirectorEvent::WaveStarted(self.wave));
+            }
+            return None;
+        }
+
+        if self.to_spawn == 0 {
+            if alive_bots == 0 {
+                self.resting = true;
+                self.rest_timer = REST_TIME;
+                return Some(DirectorEvent::WaveCleared(self.wave));
+            }
+            return None;
+        }
+
+        let condition = player.health.min(player.ammo).max(0.0).min(1.0);
+        // More bots are allowed at once in later waves and when player is doing well.
+        let max_alive = 1 + ((1 + self.wave / 2) as f32 * condition).round() as usize;
+
+        self.spawn_timer -= dt;
+        if self.spawn_timer <= 0.0 && alive_bots < max_alive {
+            self.spawn_timer =
+                MAX_SPAWN_INTERVAL - (MAX_SPAWN_INTERVAL - MIN_SPAWN_INTERVAL) * condition;
+            self.to_spawn -= 1;
+            let kinds = wave_kinds(self.wave);
+            let kind = kinds[self.spawned as usize % kinds.len()];
+            self.spawned += 1;
+            Some(DirectorEvent::Spawn(kind))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Display mode of game window. Window is either a regular window, exclusive fullscreen with
+//! video mode of the monitor, or borderless window that covers the whole monitor. Monitor is
+//! picked by its index in the list of available monitors, primary monitor is used if there is
+//! no such monitor anymore.
+
+use rg3d::{
+    core::visitor::{Visit, VisitResult, Visitor},
+    dpi::{LogicalSize, PhysicalPosition},
+    monitor::{MonitorHandle, VideoMode},
+    window::{Fullscreen, Window},
+};
+
+/// Window takes this fraction of monitor size in windowed mode.
+const WINDOWED_SCALE: f32 = 0.7;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DisplayMode {
+    Windowed,
+    Fullscreen,
+    Borderless,
+}
+
+impl Default for DisplayMode {
+    fn default() -> Self {
+        DisplayMode::Windowed
+    }
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [
+        DisplayMode::Windowed,
+        DisplayMode::Fullscreen,
+        DisplayMode::Borderless,
+    ];
+
+    pub fn id(self) -> u32 {
+        match self {
+            DisplayMode::Windowed => 0,
+            DisplayMode::Fullscreen => 1,
+            DisplayMode::Borderless => 2,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(DisplayMode::Windowed),
+            1 => Ok(DisplayMode::Fullscreen),
+            2 => Ok(DisplayMode::Borderless),
+            _ => Err(format!("Invalid display mode id {}!", id)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Fullscreen => "Fullscreen",
+            DisplayMode::Borderless => "Borderless",
+        }
+    }
+
+    /// Fullscreen state of window on given monitor, `video_mode` is used by exclusive
+    /// fullscreen, best video mode of the monitor is used if there is none.
+    pub fn fullscreen(
+        self,
+        monitor: Option<MonitorHandle>,
+        video_mode: Option<VideoMode>,
+    ) -> Option<Fullscreen> {
+        match self {
+            DisplayMode::Windowed => None,
+            DisplayMode::Fullscreen => video_mode
+                .or_else(|| monitor.as_ref().and_then(best_video_mode))
+                .map(Fullscreen::Exclusive),
+            DisplayMode::Borderless => Some(Fullscreen::Borderless(monitor)),
+        }
+    }
+}
+
+impl Visit for DisplayMode {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut id = self.id();
+        id.visit(name, visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Monitor with given index, falls back to primary monitor and then to any monitor. Some
+/// platforms can't tell which monitor is primary and remote desktop may have no monitors.
+pub fn pick_monitor(
+    monitors: impl Iterator<Item = MonitorHandle>,
+    primary: Option<MonitorHandle>,
+    index: u32,
+) -> Option<MonitorHandle> {
+    let monitors = monitors.collect::<Vec<_>>();
+    monitors
+        .get(index as usize)
+        .cloned()
+        .or(primary)
+        .or_else(|| monitors.first().cloned())
+}
+
+/// Name of monitor for options menu.
+pub fn monitor_name(index: usize, monitor: &MonitorHandle) -> String {
+    let size = monitor.size();
+    format!(
+        "{}. {} ({} x {})",
+        index + 1,
+        monitor.name().unwrap_or_else(|| "Monitor".to_owned()),
+        size.width,
+        size.height
+    )
+}
+
+/// Largest video mode of the monitor with highest refresh rate.
+fn best_video_mode(monitor: &MonitorHandle) -> Option<VideoMode> {
+    monitor.video_modes().max_by_key(|mode| {
+        let size = mode.size();
+        (size.width * size.height, mode.refresh_rate())
+    })
+}
+
+/// Size of window in windowed mode on given monitor.
+pub fn windowed_size(monitor: Option<&MonitorHandle>) -> LogicalSize<f32> {
+    match monitor {
+        Some(monitor) => {
+            let mut monitor_dimensions = monitor.size();
+            monitor_dimensions.height = (monitor_dimensions.height as f32 * WINDOWED_SCALE) as u32;
+            monitor_dimensions.width = (monitor_dimensions.width as f32 * WINDOWED_SCALE) as u32;
+            monitor_dimensions.to_logical::<f32>(monitor.scale_factor())
+        }
+        None => LogicalSize::new(1280.0, 720.0),
+    }
+}
+
+/// Switches display mode of existing window, windowed window is centered on the monitor.
+pub fn apply(
+    window: &Window,
+    mode: DisplayMode,
+    monitor_index: u32,
+    video_mode: Option<VideoMode>,
+) {
+    let monitor = pick_monitor(
+        window.available_monitors(),
+        window.primary_monitor(),
+        monitor_index,
+    );
+    window.set_fullscreen(mode.fullscreen(monitor.clone(), video_mode));
+    if mode == DisplayMode::Windowed {
+        if let Some(monitor) = monitor {
+            let size = windowed_size(Some(&monitor)).to_physical::<i32>(monitor.scale_factor());
+            let origin = monitor.position();
+            let monitor_size = monitor.size();
+            window.set_inner_size(size);
+            window.set_outer_position(PhysicalPosition::new(
+                origin.x + (monitor_size.width as i32 - size.width) / 2,
+                origin.y + (monitor_size.height as i32 - size.height) / 2,
+            ));
+        }
+    }
+}
@@ -0,0 +1,596 @@
+//! Basic level editor. Editor opens a map without any actors, camera flies freely over it and
+//! placements of the map - item spawns, player spawns, jump pads, teleporters and checkpoints -
+//! are placed, moved and deleted by clicking on level geometry. Placements are saved to
+//! placement layer of the map, see `map_layout` module, so next match on the map uses them.
+
+use crate::{
+    assets,
+    character::Team,
+    control_scheme::{ControlButton, ControlScheme},
+    item::ItemKind,
+    level,
+    logger::{self, Category},
+    map_layout::{MapLayout, Placement, PlacementKind},
+    maps,
+    message::Message,
+};
+use rg3d::{
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+    },
+    engine::Engine,
+    event::{DeviceEvent, ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent},
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        message::{MessageDirection, UiMessage},
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, Thickness, UiNode, UserInterface,
+    },
+    scene::{
+        base::BaseBuilder, camera::CameraBuilder, debug::Line, node::Node,
+        transform::TransformBuilder, Scene,
+    },
+};
+use std::{
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc, RwLock},
+};
+
+/// Speed (in meters per second) of camera.
+const FLY_SPEED: f32 = 10.0;
+
+/// Max distance (in meters) from camera at which clicks hit level geometry.
+const PICK_DISTANCE: f32 = 500.0;
+
+/// Placement closer than this distance to clicked point is selected.
+const SELECT_RADIUS: f32 = 2.0;
+
+/// New jump pad or teleporter targets a point this far ahead of it.
+const DEFAULT_TARGET_DISTANCE: f32 = 8.0;
+
+/// Half-size of box that is drawn at every placement.
+const MARKER_SIZE: f32 = 0.4;
+
+const SELECTION_COLOR: Color = Color::opaque(255, 255, 255);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Tool {
+    Select,
+    Move,
+    /// Moves target of selected jump pad or teleporter.
+    Target,
+    Place(PlacementKind),
+}
+
+impl Tool {
+    fn name(self) -> String {
+        match self {
+            Tool::Select => "Select".to_owned(),
+            Tool::Move => "Move".to_owned(),
+            Tool::Target => "Set Target".to_owned(),
+            Tool::Place(kind) => format!("Place {}", kind.name()),
+        }
+    }
+}
+
+fn marker_color(kind: PlacementKind) -> Color {
+    match kind {
+        PlacementKind::SpawnPoint => Color::opaque(0, 255, 0),
+        PlacementKind::Item(_) => Color::opaque(255, 255, 0),
+        PlacementKind::JumpPad => Color::opaque(255, 160, 40),
+        PlacementKind::Teleporter => Color::opaque(80, 160, 255),
+        PlacementKind::Checkpoint => Color::opaque(200, 80, 255),
+    }
+}
+
+#[derive(Default)]
+struct Controller {
+    move_forward: bool,
+    move_backward: bool,
+    move_left: bool,
+    move_right: bool,
+    move_up: bool,
+    move_down: bool,
+    // Camera turns only while right mouse button is held, so cursor can be used for tools.
+    look: bool,
+}
+
+pub struct Editor {
+    scene: Handle<Scene>,
+    map: PathBuf,
+    camera: Handle<Node>,
+    // Angles in degrees, same as player camera.
+    yaw: f32,
+    pitch: f32,
+    controller: Controller,
+    control_scheme: Arc<RwLock<ControlScheme>>,
+    cursor: Vector2<f32>,
+    layout: MapLayout,
+    selected: Option<usize>,
+    tool: Tool,
+    // Kind of item that item tool places.
+    item_kind: ItemKind,
+    // Team of spawn points that spawn point tool places.
+    spawn_team: Team,
+    // Unsaved changes.
+    modified: bool,
+    sender: Sender<Message>,
+    window: Handle<UiNode>,
+    status: Handle<UiNode>,
+    btn_select: Handle<UiNode>,
+    btn_move: Handle<UiNode>,
+    btn_target: Handle<UiNode>,
+    btn_spawn_point: Handle<UiNode>,
+    btn_item: Handle<UiNode>,
+    btn_jump_pad: Handle<UiNode>,
+    btn_teleporter: Handle<UiNode>,
+    btn_checkpoint: Handle<UiNode>,
+    btn_delete: Handle<UiNode>,
+    btn_save: Handle<UiNode>,
+    btn_next_map: Handle<UiNode>,
+    btn_exit: Handle<UiNode>,
+}
+
+fn make_button(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
+    ButtonBuilder::new(
+        WidgetBuilder::new()
+            .with_height(30.0)
+            .with_margin(Thickness::uniform(2.0)),
+    )
+    .with_text(text)
+    .build(ctx)
+}
+
+impl Editor {
+    pub fn new(
+        engine: &mut Engine,
+        map: PathBuf,
+        control_scheme: Arc<RwLock<ControlScheme>>,
+        sender: Sender<Message>,
+    ) -> Self {
+        let mut scene = Scene::new();
+        scene.ambient_lighting_color = Color::opaque(150, 150, 150);
+
+        assets::validate(map.to_str());
+        let map_root = rg3d::core::futures::executor::block_on(assets::instantiate_model(
+            &engine.resource_manager,
+            &map,
+            &mut scene,
+        ));
+
+        // Collision geometry is needed only to place things on it.
+        let polygon_handle = scene.graph.find_by_name(map_root, "Polygon");
+        if polygon_handle.is_some() {
+            scene.physics.mesh_to_trimesh(polygon_handle, &scene.graph);
+        } else {
+            logger::warning(
+                Category::Level,
+                "Map has no Polygon node, nothing can be placed in editor!".to_owned(),
+            );
+        }
+
+        let layout = MapLayout::load(&map).unwrap_or_else(|| MapLayout::from_scene(&scene.graph));
+
+        let position = layout
+            .placements
+            .iter()
+            .find(|placement| placement.kind == PlacementKind::SpawnPoint)
+            .map_or_else(Vector3::default, |placement| placement.position)
+            + Vector3::new(0.0, 3.0, 0.0);
+        let camera = CameraBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .build(&mut scene.graph);
+
+        let title = maps::find(&map).map_or_else(
+            || map.to_string_lossy().into_owned(),
+            |definition| definition.name.to_owned(),
+        );
+
+        let ctx = &mut engine.user_interface.build_ctx();
+        let status;
+        let btn_select = make_button(ctx, "Select");
+        let btn_move = make_button(ctx, "Move");
+        let btn_target = make_button(ctx, "Set Target");
+        let btn_spawn_point = make_button(ctx, "Spawn Point");
+        let btn_item = make_button(ctx, "Item");
+        let btn_jump_pad = make_button(ctx, "Jump Pad");
+        let btn_teleporter = make_button(ctx, "Teleporter");
+        let btn_checkpoint = make_button(ctx, "Checkpoint");
+        let btn_delete = make_button(ctx, "Delete");
+        let btn_save = make_button(ctx, "Save");
+        let btn_next_map = make_button(ctx, "Next Map");
+        let btn_exit = make_button(ctx, "Exit");
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(220.0))
+            .with_title(WindowTitle::text(format!("Editor - {}", title)))
+            .can_close(false)
+            .can_minimize(false)
+            .with_content(
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new().with_margin(Thickness::uniform(4.0)),
+                            )
+                            .with_text(
+                                "Right mouse button - look around\n\
+                                 Movement keys - fly\n\
+                                 Left click - use tool\n\
+                                 Delete - remove selected",
+                            )
+                            .build(ctx),
+                        )
+                        .with_child(btn_select)
+                        .with_child(btn_move)
+                        .with_child(btn_target)
+                        .with_child(btn_spawn_point)
+                        .with_child(btn_item)
+                        .with_child(btn_jump_pad)
+                        .with_child(btn_teleporter)
+                        .with_child(btn_checkpoint)
+                        .with_child(btn_delete)
+                        .with_child(btn_save)
+                        .with_child(btn_next_map)
+                        .with_child(btn_exit)
+                        .with_child({
+                            status = TextBuilder::new(
+                                WidgetBuilder::new().with_margin(Thickness::uniform(4.0)),
+                            )
+                            .build(ctx);
+                            status
+                        }),
+                )
+                .build(ctx),
+            )
+            .build(ctx);
+
+        let mut editor = Self {
+            scene: engine.scenes.add(scene),
+            map,
+            camera,
+            yaw: 0.0,
+            pitch: 0.0,
+            controller: Default::default(),
+            control_scheme,
+            cursor: Default::default(),
+            layout,
+            selected: None,
+            tool: Tool::Select,
+            item_kind: ItemKind::Medkit,
+            spawn_team: Team::None,
+            modified: false,
+            sender,
+            window,
+            status,
+            btn_select,
+            btn_move,
+            btn_target,
+            btn_spawn_point,
+            btn_item,
+            btn_jump_pad,
+            btn_teleporter,
+            btn_checkpoint,
+            btn_delete,
+            btn_save,
+            btn_next_map,
+            btn_exit,
+        };
+        editor.update_status(&mut engine.user_interface, None);
+        editor
+    }
+
+    pub fn destroy(&mut self, engine: &mut Engine) {
+        engine.scenes.remove(self.scene);
+        engine.user_interface.send_message(WidgetMessage::remove(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    fn update_status(&self, ui: &mut UserInterface, note: Option<&str>) {
+        let mut text = format!("Tool: {}", self.tool.name());
+        if self.tool == Tool::Place(PlacementKind::SpawnPoint) {
+            text += &format!(", team {:?}", self.spawn_team);
+        }
+        if let Some(placement) = self.selected.map(|i| &self.layout.placements[i]) {
+            let p = placement.position;
+            text += &format!(
+                "\nSelected: {} at {:.1} {:.1} {:.1}",
+                placement.kind.name(),
+                p.x,
+                p.y,
+                p.z
+            );
+        }
+        if self.modified {
+            text += "\nUnsaved changes";
+        }
+        if let Some(note) = note {
+            text += "\n";
+            text += note;
+        }
+        ui.send_message(TextMessage::text(
+            self.status,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
+    fn set_tool(&mut self, ui: &mut UserInterface, tool: Tool) {
+        self.tool = tool;
+        self.update_status(ui, None);
+    }
+
+    // Returns point of level geometry under cursor.
+    fn pick(&self, engine: &Engine) -> Option<Vector3<f32>> {
+        let scene = &engine.scenes[self.scene];
+        let frame_size = engine.renderer.get_frame_size();
+        let ray = scene.graph[self.camera].as_camera().make_ray(
+            self.cursor,
+            Vector2::new(frame_size.0 as f32, frame_size.1 as f32),
+        );
+        let direction = ray.dir.try_normalize(std::f32::EPSILON)?;
+        level::cast_to_static_geometry(&scene.physics, ray.origin, direction, PICK_DISTANCE)
+            .map(|(position, _)| position)
+    }
+
+    fn is_over_window(&self, ui: &UserInterface) -> bool {
+        ui.node(self.window).screen_bounds().contains(self.cursor)
+    }
+
+    fn click(&mut self, engine: &mut Engine) {
+        if self.is_over_window(&engine.user_interface) {
+            return;
+        }
+        let point = match self.pick(engine) {
+            Some(point) => point,
+            None => return,
+        };
+
+        match self.tool {
+            Tool::Select => {
+                self.selected = self
+                    .layout
+                    .placements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, placement)| (i, (placement.position - point).norm()))
+                    .filter(|(_, distance)| *distance < SELECT_RADIUS)
+                    .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .map(|(i, _)| i);
+            }
+            Tool::Move => {
+                if let Some(selected) = self.selected {
+                    self.layout.placements[selected].position = point;
+                    self.modified = true;
+                }
+            }
+            Tool::Target => {
+                if let Some(selected) = self.selected {
+                    let placement = &mut self.layout.placements[selected];
+                    if placement.kind.has_target() {
+                        placement.target = point;
+                        self.modified = true;
+                    }
+                }
+            }
+            Tool::Place(kind) => {
+                let look = engine.scenes[self.scene].graph[self.camera].look_vector();
+                let ahead = Vector3::new(look.x, 0.0, look.z)
+                    .try_normalize(std::f32::EPSILON)
+                    .unwrap_or_else(Vector3::z);
+                let mut placement = Placement::new(kind, point);
+                placement.target = point + ahead.scale(DEFAULT_TARGET_DISTANCE);
+                if kind == PlacementKind::SpawnPoint {
+                    placement.team = self.spawn_team;
+                }
+                self.layout.placements.push(placement);
+                self.selected = Some(self.layout.placements.len() - 1);
+                self.modified = true;
+            }
+        }
+        self.update_status(&mut engine.user_interface, None);
+    }
+
+    fn delete_selected(&mut self, ui: &mut UserInterface) {
+        if let Some(selected) = self.selected.take() {
+            self.layout.placements.remove(selected);
+            self.modified = true;
+            self.update_status(ui, None);
+        }
+    }
+
+    fn save(&mut self, ui: &mut UserInterface) {
+        match self.layout.save(&self.map) {
+            Ok(()) => {
+                self.modified = false;
+                self.update_status(ui, Some("Saved"));
+            }
+            Err(e) => self.update_status(ui, Some(&e)),
+        }
+    }
+
+    pub fn process_input_event(&mut self, engine: &mut Engine, event: &Event<()>) {
+        match event {
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                if self.controller.look {
+                    let control_scheme = self.control_scheme.read().unwrap();
+                    let sens = if control_scheme.mouse_y_inverse {
+                        -control_scheme.mouse_sens
+                    } else {
+                        control_scheme.mouse_sens
+                    };
+                    self.yaw -= delta.0 as f32 * control_scheme.mouse_sens;
+                    self.pitch = (self.pitch + delta.1 as f32 * sens).max(-90.0).min(90.0);
+                }
+            }
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CursorMoved { position, .. } => {
+                    self.cursor = Vector2::new(position.x as f32, position.y as f32);
+                }
+                WindowEvent::MouseInput { button, state, .. } => match button {
+                    MouseButton::Right => self.controller.look = *state == ElementState::Pressed,
+                    MouseButton::Left if *state == ElementState::Pressed => self.click(engine),
+                    _ => (),
+                },
+                WindowEvent::KeyboardInput { input, .. } => {
+                    if let Some(key) = input.virtual_keycode {
+                        let pressed = input.state == ElementState::Pressed;
+                        if key == VirtualKeyCode::Delete && pressed {
+                            self.delete_selected(&mut engine.user_interface);
+                        }
+                        let button = ControlButton::Key(key);
+                        let control_scheme = self.control_scheme.read().unwrap();
+                        if button == control_scheme.move_forward.button {
+                            self.controller.move_forward = pressed;
+                        } else if button == control_scheme.move_backward.button {
+                            self.controller.move_backward = pressed;
+                        } else if button == control_scheme.move_left.button {
+                            self.controller.move_left = pressed;
+                        } else if button == control_scheme.move_right.button {
+                            self.controller.move_right = pressed;
+                        } else if button == control_scheme.jump.button {
+                            self.controller.move_up = pressed;
+                        } else if button == control_scheme.crouch.button {
+                            self.controller.move_down = pressed;
+                        }
+                    }
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    pub fn handle_ui_event(&mut self, engine: &mut Engine, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            let ui = &mut engine.user_interface;
+            let destination = message.destination();
+            if destination == self.btn_select {
+                self.set_tool(ui, Tool::Select);
+            } else if destination == self.btn_move {
+                self.set_tool(ui, Tool::Move);
+            } else if destination == self.btn_target {
+                self.set_tool(ui, Tool::Target);
+            } else if destination == self.btn_spawn_point {
+                // Every click on the button switches to next team, same as item button.
+                if let Tool::Place(PlacementKind::SpawnPoint) = self.tool {
+                    self.spawn_team = match self.spawn_team {
+                        Team::None => Team::Red,
+                        Team::Red => Team::Blue,
+                        Team::Blue => Team::None,
+                    };
+                }
+                self.set_tool(ui, Tool::Place(PlacementKind::SpawnPoint));
+            } else if destination == self.btn_item {
+                // Every click on the button switches to next kind of item.
+                if let Tool::Place(PlacementKind::Item(_)) = self.tool {
+                    self.item_kind =
+                        ItemKind::from_id(self.item_kind.id() + 1).unwrap_or(ItemKind::Medkit);
+                }
+                self.set_tool(ui, Tool::Place(PlacementKind::Item(self.item_kind)));
+            } else if destination == self.btn_jump_pad {
+                self.set_tool(ui, Tool::Place(PlacementKind::JumpPad));
+            } else if destination == self.btn_teleporter {
+                self.set_tool(ui, Tool::Place(PlacementKind::Teleporter));
+            } else if destination == self.btn_checkpoint {
+                self.set_tool(ui, Tool::Place(PlacementKind::Checkpoint));
+            } else if destination == self.btn_delete {
+                self.delete_selected(ui);
+            } else if destination == self.btn_save {
+                self.save(ui);
+            } else if destination == self.btn_next_map {
+                let all = maps::all();
+                let index = all
+                    .iter()
+                    .position(|map| PathBuf::from(map.path) == self.map)
+                    .map_or(0, |index| (index + 1) % all.len());
+                self.sender
+                    .send(Message::StartEditor {
+                        map: all[index].path.into(),
+                    })
+                    .unwrap();
+            } else if destination == self.btn_exit {
+                self.sender.send(Message::StopEditor).unwrap();
+            }
+        }
+    }
+
+    pub fn update(&mut self, engine: &mut Engine, dt: f32) {
+        let scene = &mut engine.scenes[self.scene];
+
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw.to_radians())
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch.to_radians());
+        let camera = &mut scene.graph[self.camera];
+        let mut velocity = Vector3::default();
+        if self.controller.move_forward {
+            velocity += camera.look_vector();
+        }
+        if self.controller.move_backward {
+            velocity -= camera.look_vector();
+        }
+        if self.controller.move_left {
+            velocity += camera.side_vector();
+        }
+        if self.controller.move_right {
+            velocity -= camera.side_vector();
+        }
+        if self.controller.move_up {
+            velocity += Vector3::y();
+        }
+        if self.controller.move_down {
+            velocity -= Vector3::y();
+        }
+        let offset = velocity
+            .try_normalize(std::f32::EPSILON)
+            .map_or_else(Vector3::default, |v| v.scale(FLY_SPEED * dt));
+        camera
+            .local_transform_mut()
+            .set_rotation(rotation)
+            .offset(offset);
+
+        let context = &mut scene.drawing_context;
+        context.clear_lines();
+        let size = Vector3::new(MARKER_SIZE, MARKER_SIZE, MARKER_SIZE);
+        for (i, placement) in self.layout.placements.iter().enumerate() {
+            let color = if self.selected == Some(i) {
+                SELECTION_COLOR
+            } else {
+                marker_color(placement.kind)
+            };
+            // Markers stand on the point they were placed at.
+            let center = placement.position + Vector3::new(0.0, MARKER_SIZE, 0.0);
+            context.draw_aabb(
+                &AxisAlignedBoundingBox::from_min_max(center - size, center + size),
+                color,
+            );
+            if placement.kind.has_target() {
+                context.add_line(Line {
+                    begin: center,
+                    end: placement.target,
+                    color,
+                });
+                context.draw_aabb(
+                    &AxisAlignedBoundingBox::from_min_max(
+                        placement.target - size.scale(0.5),
+                        placement.target + size.scale(0.5),
+                    ),
+                    color,
+                );
+            }
+        }
+    }
+}
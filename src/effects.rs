@@ -1,14 +1,22 @@
+use crate::pak;
 use rg3d::{
     core::{
-        algebra::Vector3,
+        algebra::{Matrix4, UnitQuaternion, Vector3},
         color::Color,
         color_gradient::{ColorGradient, GradientPoint},
         pool::Handle,
+        rand::Rng,
+        visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::ResourceManager,
+    rand,
     scene::{
         base::BaseBuilder,
         graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder, RenderPath,
+        },
         node::Node,
         particle_system::{
             emitter::base::BaseEmitterBuilder, emitter::cylinder::CylinderEmitterBuilder,
@@ -17,6 +25,7 @@ use rg3d::{
         transform::TransformBuilder,
     },
 };
+use std::sync::{Arc, RwLock};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum EffectKind {
@@ -24,6 +33,47 @@ pub enum EffectKind {
     ItemAppear,
     Smoke,
     Steam,
+    ShieldSpark,
+}
+
+impl Default for EffectKind {
+    fn default() -> Self {
+        EffectKind::BulletImpact
+    }
+}
+
+impl EffectKind {
+    pub fn id(self) -> u32 {
+        match self {
+            EffectKind::BulletImpact => 0,
+            EffectKind::ItemAppear => 1,
+            EffectKind::Smoke => 2,
+            EffectKind::Steam => 3,
+            EffectKind::ShieldSpark => 4,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(EffectKind::BulletImpact),
+            1 => Ok(EffectKind::ItemAppear),
+            2 => Ok(EffectKind::Smoke),
+            3 => Ok(EffectKind::Steam),
+            4 => Ok(EffectKind::ShieldSpark),
+            _ => Err(format!("Invalid effect kind {}", id)),
+        }
+    }
+
+    /// Returns None for endless effects.
+    pub fn lifetime(self) -> Option<f32> {
+        match self {
+            EffectKind::BulletImpact => Some(1.0),
+            EffectKind::ItemAppear => Some(1.4),
+            EffectKind::Smoke => Some(5.0),
+            EffectKind::Steam => None,
+            EffectKind::ShieldSpark => Some(0.6),
+        }
+    }
 }
 
 pub fn create(
@@ -31,12 +81,26 @@ pub fn create(
     graph: &mut Graph,
     resource_manager: ResourceManager,
     pos: Vector3<f32>,
+) -> Handle<Node> {
+    let node = build(kind, graph, resource_manager, pos);
+    if let Some(lifetime) = kind.lifetime() {
+        graph[node].set_lifetime(lifetime);
+    }
+    node
+}
+
+fn build(
+    kind: EffectKind,
+    graph: &mut Graph,
+    resource_manager: ResourceManager,
+    pos: Vector3<f32>,
 ) -> Handle<Node> {
     match kind {
         EffectKind::BulletImpact => create_bullet_impact(graph, resource_manager, pos),
         EffectKind::ItemAppear => create_item_appear(graph, resource_manager, pos),
         EffectKind::Smoke => create_smoke(graph, resource_manager, pos),
         EffectKind::Steam => create_steam(graph, resource_manager, pos),
+        EffectKind::ShieldSpark => create_shield_spark(graph, resource_manager, pos),
     }
 }
 
@@ -68,7 +132,9 @@ fn create_steam(
         .with_height(0.2)
         .with_radius(0.2)
         .build()])
-    .with_texture(resource_manager.request_texture("data/particles/smoke_04.tga", None))
+    .with_texture(
+        resource_manager.request_texture(pak::resolve("data/particles/smoke_04.tga"), None),
+    )
     .build(graph)
 }
 
@@ -79,7 +145,6 @@ fn create_bullet_impact(
 ) -> Handle<Node> {
     ParticleSystemBuilder::new(
         BaseBuilder::new()
-            .with_lifetime(1.0)
             .with_local_transform(TransformBuilder::new().with_local_position(pos).build()),
     )
     .with_acceleration(Vector3::new(0.0, -10.0, 0.0))
@@ -104,7 +169,51 @@ fn create_bullet_impact(
     )
     .with_radius(0.01)
     .build()])
-    .with_texture(resource_manager.request_texture("data/particles/circle_05.png", None))
+    .with_texture(
+        resource_manager.request_texture(pak::resolve("data/particles/circle_05.png"), None),
+    )
+    .build(graph)
+}
+
+/// Burst of cyan sparks that flies in every direction and does not fall, so it is easy to tell
+/// from bullet impact.
+fn create_shield_spark(
+    graph: &mut Graph,
+    resource_manager: ResourceManager,
+    pos: Vector3<f32>,
+) -> Handle<Node> {
+    ParticleSystemBuilder::new(
+        BaseBuilder::new()
+            .with_local_transform(TransformBuilder::new().with_local_position(pos).build()),
+    )
+    .with_acceleration(Vector3::new(0.0, 0.0, 0.0))
+    .with_color_over_lifetime_gradient({
+        let mut gradient = ColorGradient::new();
+        gradient.add_point(GradientPoint::new(0.00, Color::from_rgba(200, 255, 255, 0)));
+        gradient.add_point(GradientPoint::new(
+            0.05,
+            Color::from_rgba(120, 230, 255, 255),
+        ));
+        gradient.add_point(GradientPoint::new(0.70, Color::from_rgba(0, 160, 255, 200)));
+        gradient.add_point(GradientPoint::new(1.00, Color::from_rgba(0, 80, 255, 0)));
+        gradient
+    })
+    .with_emitters(vec![SphereEmitterBuilder::new(
+        BaseEmitterBuilder::new()
+            .with_max_particles(120)
+            .with_spawn_rate(1200)
+            .with_size_modifier_range(-0.03..-0.035)
+            .with_size_range(0.03..0.06)
+            .with_x_velocity_range(-0.04..0.04)
+            .with_y_velocity_range(-0.04..0.04)
+            .with_z_velocity_range(-0.04..0.04)
+            .resurrect_particles(false),
+    )
+    .with_radius(0.2)
+    .build()])
+    .with_texture(
+        resource_manager.request_texture(pak::resolve("data/particles/circle_05.png"), None),
+    )
     .build(graph)
 }
 
@@ -115,7 +224,6 @@ fn create_smoke(
 ) -> Handle<Node> {
     ParticleSystemBuilder::new(
         BaseBuilder::new()
-            .with_lifetime(5.0)
             .with_local_transform(TransformBuilder::new().with_local_position(pos).build()),
     )
     .with_acceleration(Vector3::new(0.0, 0.0, 0.0))
@@ -143,7 +251,9 @@ fn create_smoke(
     )
     .with_radius(0.01)
     .build()])
-    .with_texture(resource_manager.request_texture("data/particles/smoke_04.tga", None))
+    .with_texture(
+        resource_manager.request_texture(pak::resolve("data/particles/smoke_04.tga"), None),
+    )
     .build(graph)
 }
 
@@ -154,7 +264,6 @@ fn create_item_appear(
 ) -> Handle<Node> {
     ParticleSystemBuilder::new(
         BaseBuilder::new()
-            .with_lifetime(1.4)
             .with_local_transform(TransformBuilder::new().with_local_position(pos).build()),
     )
     .with_acceleration(Vector3::new(0.0, -6.0, 0.0))
@@ -179,6 +288,206 @@ fn create_item_appear(
     )
     .with_radius(0.01)
     .build()])
-    .with_texture(resource_manager.request_texture("data/particles/star_09.png", None))
+    .with_texture(
+        resource_manager.request_texture(pak::resolve("data/particles/star_09.png"), None),
+    )
     .build(graph)
 }
+
+#[derive(Default)]
+struct PooledEffect {
+    kind: EffectKind,
+    node: Handle<Node>,
+    // Effect is free for reuse when there is no time left.
+    time_left: f32,
+}
+
+impl Visit for PooledEffect {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        let mut kind_id = self.kind.id();
+        kind_id.visit("Kind", visitor)?;
+        if visitor.is_reading() {
+            self.kind = EffectKind::from_id(kind_id)?;
+        }
+        self.node.visit("Node", visitor)?;
+        self.time_left.visit("TimeLeft", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+/// Finished effects and trail segments are not removed from scene, they're hidden and
+/// reused later. This prevents constant creation and destruction of scene nodes in
+/// heavy firefights.
+#[derive(Default)]
+pub struct EffectPool {
+    effects: Vec<PooledEffect>,
+    segments: Vec<PooledEffect>,
+    // Shared by all trail segments.
+    segment_data: Option<Arc<RwLock<SurfaceData>>>,
+}
+
+impl EffectPool {
+    pub fn spawn(
+        &mut self,
+        kind: EffectKind,
+        graph: &mut Graph,
+        resource_manager: ResourceManager,
+        pos: Vector3<f32>,
+    ) -> Handle<Node> {
+        let lifetime = match kind.lifetime() {
+            Some(lifetime) => lifetime,
+            // Endless effects can't be reused.
+            None => return create(kind, graph, resource_manager, pos),
+        };
+
+        if let Some(effect) = self
+            .effects
+            .iter_mut()
+            .find(|effect| effect.kind == kind && effect.time_left <= 0.0)
+        {
+            effect.time_left = lifetime;
+            let node = &mut graph[effect.node];
+            node.set_visibility(true);
+            node.local_transform_mut().set_position(pos);
+            if let Node::ParticleSystem(particle_system) = node {
+                particle_system.clear_particles();
+            }
+            effect.node
+        } else {
+            let node = build(kind, graph, resource_manager, pos);
+            self.effects.push(PooledEffect {
+                kind,
+                node,
+                time_left: lifetime,
+            });
+            node
+        }
+    }
+
+    /// Shows thin glowing segment between two points for given amount of time. Single long
+    /// segment is a tracer, series of short segments forms a ribbon trail behind a projectile.
+    pub fn spawn_trail_segment(
+        &mut self,
+        graph: &mut Graph,
+        begin: Vector3<f32>,
+        end: Vector3<f32>,
+        radius: f32,
+        color: Color,
+        lifetime: f32,
+    ) {
+        let index = match self
+            .segments
+            .iter()
+            .position(|segment| segment.time_left <= 0.0)
+        {
+            Some(index) => index,
+            None => {
+                let data = self
+                    .segment_data
+                    .get_or_insert_with(|| {
+                        // Unit cylinder along Y axis starting at origin, scaled to actual
+                        // size by node transform.
+                        Arc::new(RwLock::new(SurfaceData::make_cylinder(
+                            6,
+                            1.0,
+                            1.0,
+                            false,
+                            &Matrix4::new_translation(&Vector3::new(0.0, 0.5, 0.0)),
+                        )))
+                    })
+                    .clone();
+                let node = MeshBuilder::new(BaseBuilder::new())
+                    .with_surfaces(vec![SurfaceBuilder::new(data).build()])
+                    .with_cast_shadows(false)
+                    .with_render_path(RenderPath::Forward)
+                    .build(graph);
+                self.segments.push(PooledEffect {
+                    node,
+                    ..Default::default()
+                });
+                self.segments.len() - 1
+            }
+        };
+
+        let segment = &mut self.segments[index];
+        segment.time_left = lifetime;
+
+        let delta = end - begin;
+        let rotation = UnitQuaternion::rotation_between(&Vector3::y(), &delta)
+            .unwrap_or_else(UnitQuaternion::identity);
+        let node = &mut graph[segment.node];
+        node.set_visibility(true);
+        node.local_transform_mut()
+            .set_position(begin)
+            .set_rotation(rotation)
+            .set_scale(Vector3::new(radius, delta.norm(), radius));
+        if let Node::Mesh(mesh) = node {
+            for surface in mesh.surfaces_mut() {
+                surface.set_color(color);
+            }
+        }
+    }
+
+    /// Shows jagged bolt between two points, every bolt is randomized so bolts spawned each
+    /// frame give flickering beam.
+    pub fn spawn_lightning(
+        &mut self,
+        graph: &mut Graph,
+        begin: Vector3<f32>,
+        end: Vector3<f32>,
+        color: Color,
+    ) {
+        const SEGMENT_LENGTH: f32 = 0.5;
+        const MAX_DEVIATION: f32 = 0.15;
+
+        let delta = end - begin;
+        let count = ((delta.norm() / SEGMENT_LENGTH).ceil() as usize).max(1);
+        let side = delta
+            .cross(&Vector3::y())
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::x);
+        let up = side
+            .cross(&delta)
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        let mut rng = rand::thread_rng();
+        let mut last = begin;
+        for i in 1..=count {
+            let point = if i == count {
+                end
+            } else {
+                begin
+                    + delta.scale(i as f32 / count as f32)
+                    + side.scale(rng.gen_range(-MAX_DEVIATION..MAX_DEVIATION))
+                    + up.scale(rng.gen_range(-MAX_DEVIATION..MAX_DEVIATION))
+            };
+            self.spawn_trail_segment(graph, last, point, 0.02, color, 0.06);
+            last = point;
+        }
+    }
+
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
+        for effect in self.effects.iter_mut().chain(self.segments.iter_mut()) {
+            if effect.time_left > 0.0 {
+                effect.time_left -= dt;
+                if effect.time_left <= 0.0 {
+                    graph[effect.node].set_visibility(false);
+                }
+            }
+        }
+    }
+}
+
+impl Visit for EffectPool {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.effects.visit("Effects", visitor)?;
+        self.segments.visit("Segments", visitor)?;
+
+        visitor.leave_region()
+    }
+}
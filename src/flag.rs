@@ -0,0 +1,331 @@
+//! Flags of capture the flag. Each team has a flag on a stand at its base. Enemy takes the flag
+//! by touching it and scores by bringing it to own stand while own flag is at home. Carrier
+//! drops the flag on death, dropped flag returns home after a while, or at once when touched by
+//! its own team.
+
+use crate::{
+    actor::{Actor, ActorContainer},
+    character::Team,
+    teleporter,
+};
+use rg3d::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        color::Color,
+        pool::Handle,
+        visitor::{Visit, VisitError, VisitResult, Visitor},
+    },
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder,
+        },
+        node::Node,
+        physics::Physics,
+        transform::TransformBuilder,
+    },
+};
+use std::sync::{Arc, RwLock};
+
+/// Dropped flag returns home after this many seconds.
+pub const RETURN_TIME: f32 = 20.0;
+
+/// Carrier moves this much slower with heavy flag mutator.
+pub const CARRIER_SPEED_SCALE: f32 = 0.75;
+
+/// Actor whose body center is closer than this to a flag touches it.
+const TOUCH_RADIUS: f32 = 1.5;
+
+const POLE_HEIGHT: f32 = 2.0;
+
+const STAND_RADIUS: f32 = 1.0;
+
+const STAND_HEIGHT: f32 = 0.1;
+
+/// Carried flag is held this high above body center of the carrier.
+const CARRY_HEIGHT: f32 = 0.5;
+
+/// Body center of standing actor is about a meter above the floor.
+const BODY_CENTER_HEIGHT: f32 = 1.0;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FlagState {
+    Home,
+    Carried { carrier: Handle<Actor> },
+    Dropped { time_left: f32 },
+}
+
+impl Default for FlagState {
+    fn default() -> Self {
+        FlagState::Home
+    }
+}
+
+impl Visit for FlagState {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        let mut id: u32 = match self {
+            FlagState::Home => 0,
+            FlagState::Carried { .. } => 1,
+            FlagState::Dropped { .. } => 2,
+        };
+        id.visit("Id", visitor)?;
+        if visitor.is_reading() {
+            *self = match id {
+                0 => FlagState::Home,
+                1 => FlagState::Carried {
+                    carrier: Handle::NONE,
+                },
+                2 => FlagState::Dropped { time_left: 0.0 },
+                _ => return Err(VisitError::User(format!("Invalid flag state id {}", id))),
+            };
+        }
+        match self {
+            FlagState::Home => (),
+            FlagState::Carried { carrier } => carrier.visit("Carrier", visitor)?,
+            FlagState::Dropped { time_left } => time_left.visit("TimeLeft", visitor)?,
+        }
+
+        visitor.leave_region()
+    }
+}
+
+/// Change of state of a flag, level announces it. Team is the owner of the flag. Flag returned
+/// by timeout has no returner.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FlagEvent {
+    Taken { team: Team, carrier: Handle<Actor> },
+    Dropped { team: Team },
+    Returned { team: Team, returner: Handle<Actor> },
+    Captured { team: Team, carrier: Handle<Actor> },
+}
+
+#[derive(Default)]
+pub struct Flag {
+    team: Team,
+    home: Vector3<f32>,
+    position: Vector3<f32>,
+    state: FlagState,
+    model: Handle<Node>,
+    stand: Handle<Node>,
+}
+
+impl Visit for Flag {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.team.visit("Team", visitor)?;
+        self.home.visit("Home", visitor)?;
+        self.position.visit("Position", visitor)?;
+        self.state.visit("State", visitor)?;
+        self.model.visit("Model", visitor)?;
+        self.stand.visit("Stand", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl Flag {
+    pub fn new(graph: &mut Graph, team: Team, home: Vector3<f32>, color: Color) -> Self {
+        Self {
+            team,
+            home,
+            position: home,
+            state: FlagState::Home,
+            model: make_model(graph, home, color),
+            stand: teleporter::make_pad(graph, home, STAND_RADIUS, STAND_HEIGHT, color),
+        }
+    }
+
+    pub fn team(&self) -> Team {
+        self.team
+    }
+
+    pub fn home(&self) -> Vector3<f32> {
+        self.home
+    }
+
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    pub fn state(&self) -> FlagState {
+        self.state
+    }
+
+    fn is_touched_by(&self, position: Vector3<f32>) -> bool {
+        (self.position - position).norm() < TOUCH_RADIUS
+    }
+
+    fn go_home(&mut self) {
+        self.state = FlagState::Home;
+        self.position = self.home;
+    }
+}
+
+#[derive(Default)]
+pub struct FlagContainer {
+    flags: Vec<Flag>,
+}
+
+impl Visit for FlagContainer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        self.flags.visit(name, visitor)
+    }
+}
+
+impl FlagContainer {
+    pub fn add(&mut self, flag: Flag) {
+        self.flags.push(flag);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Flag> {
+        self.flags.iter()
+    }
+
+    pub fn get(&self, team: Team) -> Option<&Flag> {
+        self.flags.iter().find(|flag| flag.team == team)
+    }
+
+    /// Returns team of the flag carried by given actor, if any.
+    pub fn carried_by(&self, actor: Handle<Actor>) -> Option<Team> {
+        self.flags
+            .iter()
+            .find(|flag| flag.state == FlagState::Carried { carrier: actor })
+            .map(|flag| flag.team)
+    }
+
+    pub fn update(
+        &mut self,
+        dt: f32,
+        actors: &ActorContainer,
+        physics: &Physics,
+        graph: &mut Graph,
+    ) -> Vec<FlagEvent> {
+        let mut events = Vec::new();
+
+        for i in 0..self.flags.len() {
+            let team = self.flags[i].team;
+            match self.flags[i].state {
+                FlagState::Carried { carrier } => {
+                    if !actors.contains(carrier) || actors.get(carrier).is_dead() {
+                        // Flag stays where carrier was seen last.
+                        self.flags[i].state = FlagState::Dropped {
+                            time_left: RETURN_TIME,
+                        };
+                        events.push(FlagEvent::Dropped { team });
+                    } else {
+                        let position = actors.get(carrier).position(physics);
+                        self.flags[i].position = position;
+                        let carrier_team = actors.get(carrier).team();
+                        // Capture counts only while own flag is at home.
+                        let captured = self.flags.iter().any(|flag| {
+                            flag.team == carrier_team
+                                && flag.state == FlagState::Home
+                                && flag.is_touched_by(position)
+                        });
+                        if captured {
+                            self.flags[i].go_home();
+                            events.push(FlagEvent::Captured { team, carrier });
+                        }
+                    }
+                }
+                FlagState::Dropped { time_left } => {
+                    let time_left = time_left - dt;
+                    if time_left <= 0.0 {
+                        self.flags[i].go_home();
+                        events.push(FlagEvent::Returned {
+                            team,
+                            returner: Handle::NONE,
+                        });
+                    } else {
+                        self.flags[i].state = FlagState::Dropped { time_left };
+                        match self.toucher(i, actors, physics) {
+                            Some((returner, toucher_team)) if toucher_team == team => {
+                                self.flags[i].go_home();
+                                events.push(FlagEvent::Returned { team, returner });
+                            }
+                            Some((carrier, _)) => {
+                                self.flags[i].state = FlagState::Carried { carrier };
+                                events.push(FlagEvent::Taken { team, carrier });
+                            }
+                            None => (),
+                        }
+                    }
+                }
+                FlagState::Home => match self.toucher(i, actors, physics) {
+                    Some((carrier, toucher_team)) if toucher_team != team => {
+                        self.flags[i].state = FlagState::Carried { carrier };
+                        events.push(FlagEvent::Taken { team, carrier });
+                    }
+                    _ => (),
+                },
+            }
+
+            let flag = &self.flags[i];
+            let offset = match flag.state {
+                FlagState::Home => 0.0,
+                FlagState::Carried { .. } => CARRY_HEIGHT,
+                // Dropped flag lies on the floor under the spot where carrier died.
+                FlagState::Dropped { .. } => -BODY_CENTER_HEIGHT,
+            };
+            graph[flag.model]
+                .local_transform_mut()
+                .set_position(flag.position + Vector3::new(0.0, offset, 0.0));
+        }
+
+        events
+    }
+
+    /// Alive actor of any team that touches given flag, and team of the actor. Actor that
+    /// already carries a flag can not take another one.
+    fn toucher(
+        &self,
+        index: usize,
+        actors: &ActorContainer,
+        physics: &Physics,
+    ) -> Option<(Handle<Actor>, Team)> {
+        actors
+            .pair_iter()
+            .find(|(handle, actor)| {
+                !actor.is_dead()
+                    && actor.team() != Team::None
+                    && self.carried_by(*handle).is_none()
+                    && self.flags[index].is_touched_by(actor.position(physics))
+            })
+            .map(|(handle, actor)| (handle, actor.team()))
+    }
+}
+
+/// Pole with a cloth of team color.
+fn make_model(graph: &mut Graph, position: Vector3<f32>, color: Color) -> Handle<Node> {
+    let pole = SurfaceData::make_cylinder(
+        8,
+        0.03,
+        POLE_HEIGHT,
+        true,
+        &Matrix4::new_translation(&Vector3::new(0.0, POLE_HEIGHT * 0.5, 0.0)),
+    );
+    let mut pole = SurfaceBuilder::new(Arc::new(RwLock::new(pole))).build();
+    pole.set_color(Color::opaque(200, 200, 200));
+
+    let cloth = SurfaceData::make_cube(
+        Matrix4::new_translation(&Vector3::new(0.3, POLE_HEIGHT - 0.25, 0.0))
+            * Matrix4::new_nonuniform_scaling(&Vector3::new(0.6, 0.4, 0.02)),
+    );
+    let mut cloth = SurfaceBuilder::new(Arc::new(RwLock::new(cloth))).build();
+    cloth.set_color(color);
+
+    MeshBuilder::new(
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(position)
+                .build(),
+        ),
+    )
+    .with_surfaces(vec![pole, cloth])
+    .build(graph)
+}
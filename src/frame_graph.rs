@@ -0,0 +1,192 @@
+//! Frame-time graph for spotting hitches. Every bar is one frame, its height is the time of the
+//! frame and bars of frames that missed 60 FPS budget are red. Text above the graph breaks
+//! frame time down into update, render and UI time averaged over the shown frames.
+
+use rg3d::{
+    core::{algebra::Vector2, color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        canvas::CanvasBuilder,
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        UiNode, UserInterface,
+    },
+};
+use std::collections::VecDeque;
+
+const FRAME_COUNT: usize = 240;
+
+const BAR_WIDTH: f32 = 1.0;
+
+const GRAPH_HEIGHT: f32 = 100.0;
+
+/// Frame time (in milliseconds) that spans the whole height of the graph.
+const MAX_FRAME_TIME: f32 = 50.0;
+
+/// Frames longer than this (in milliseconds) missed 60 FPS.
+const FRAME_BUDGET: f32 = 1000.0 / 60.0;
+
+const NORMAL_COLOR: Color = Color::opaque(0, 200, 0);
+
+const HITCH_COLOR: Color = Color::opaque(255, 0, 0);
+
+/// Times (in milliseconds) spent on parts of a frame.
+#[derive(Copy, Clone, Default)]
+pub struct FrameTimes {
+    pub update: f32,
+    pub render: f32,
+    pub ui: f32,
+}
+
+impl FrameTimes {
+    fn total(&self) -> f32 {
+        self.update + self.render + self.ui
+    }
+}
+
+struct Bar {
+    widget: Handle<UiNode>,
+    height: f32,
+    hitch: bool,
+}
+
+pub struct FrameGraph {
+    root: Handle<UiNode>,
+    text: Handle<UiNode>,
+    bars: Vec<Bar>,
+    history: VecDeque<FrameTimes>,
+    visible: bool,
+}
+
+impl FrameGraph {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let bars = (0..FRAME_COUNT)
+            .map(|i| Bar {
+                widget: BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_width(BAR_WIDTH)
+                        .with_height(0.0)
+                        .with_desired_position(Vector2::new(i as f32 * BAR_WIDTH, GRAPH_HEIGHT))
+                        .with_background(Brush::Solid(NORMAL_COLOR)),
+                )
+                .build(ctx),
+                height: 0.0,
+                hitch: false,
+            })
+            .collect::<Vec<_>>();
+
+        let text;
+        let root = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_child({
+                    text = TextBuilder::new(WidgetBuilder::new().with_width(400.0)).build(ctx);
+                    text
+                })
+                .with_child(
+                    CanvasBuilder::new(
+                        WidgetBuilder::new()
+                            .with_width(FRAME_COUNT as f32 * BAR_WIDTH)
+                            .with_height(GRAPH_HEIGHT)
+                            .with_background(Brush::Solid(Color::from_rgba(0, 0, 0, 120)))
+                            .with_children(bars.iter().map(|bar| bar.widget)),
+                    )
+                    .build(ctx),
+                ),
+        )
+        .build(ctx);
+
+        Self {
+            root,
+            text,
+            bars,
+            history: VecDeque::with_capacity(FRAME_COUNT),
+            visible: false,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, ui: &mut UserInterface, visible: bool) {
+        self.visible = visible;
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            visible,
+        ));
+    }
+
+    /// Adds times of a frame, graph is updated only while it is visible. `extra` is appended
+    /// to breakdown text, it is used for renderer statistics.
+    pub fn push(&mut self, ui: &mut UserInterface, times: FrameTimes, extra: &str) {
+        if self.history.len() >= FRAME_COUNT {
+            self.history.pop_front();
+        }
+        self.history.push_back(times);
+
+        if !self.visible {
+            return;
+        }
+
+        // Newest frame is the rightmost bar.
+        let offset = FRAME_COUNT - self.history.len();
+        for (i, times) in (offset..FRAME_COUNT).zip(self.history.iter()) {
+            let bar = &mut self.bars[i];
+            let total = times.total();
+            let height = (total / MAX_FRAME_TIME).min(1.0) * GRAPH_HEIGHT;
+            // Sub-pixel changes are not worth a message.
+            if (height - bar.height).abs() >= 0.5 {
+                bar.height = height;
+                ui.send_message(WidgetMessage::height(
+                    bar.widget,
+                    MessageDirection::ToWidget,
+                    height,
+                ));
+                ui.send_message(WidgetMessage::desired_position(
+                    bar.widget,
+                    MessageDirection::ToWidget,
+                    Vector2::new(i as f32 * BAR_WIDTH, GRAPH_HEIGHT - height),
+                ));
+            }
+            let hitch = total > FRAME_BUDGET;
+            if hitch != bar.hitch {
+                bar.hitch = hitch;
+                ui.send_message(WidgetMessage::background(
+                    bar.widget,
+                    MessageDirection::ToWidget,
+                    Brush::Solid(if hitch { HITCH_COLOR } else { NORMAL_COLOR }),
+                ));
+            }
+        }
+
+        let count = self.history.len() as f32;
+        let mut average = FrameTimes::default();
+        let mut max_total = 0.0f32;
+        for times in self.history.iter() {
+            average.update += times.update / count;
+            average.render += times.render / count;
+            average.ui += times.ui / count;
+            max_total = max_total.max(times.total());
+        }
+        ui.send_message(TextMessage::text(
+            self.text,
+            MessageDirection::ToWidget,
+            format!(
+                "Frame: {:.2} ms (max {:.2} ms)\nUpdate: {:.2} ms\nRender: {:.2} ms\nUI: {:.2} ms\n{}",
+                average.total(),
+                max_total,
+                average.update,
+                average.render,
+                average.ui,
+                extra
+            ),
+        ));
+    }
+}
@@ -4,10 +4,118 @@
 
 use rg3d::core::pool::Handle;
 use rg3d::gui::{
-    check_box::CheckBoxBuilder, scroll_bar::ScrollBarBuilder, scroll_viewer::ScrollViewerBuilder,
-    widget::WidgetBuilder, BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode,
+    check_box::CheckBoxBuilder,
+    message::MessageDirection,
+    scroll_bar::ScrollBarBuilder,
+    scroll_viewer::ScrollViewerBuilder,
+    ttf::{Font, SharedFont},
+    widget::{WidgetBuilder, WidgetMessage},
+    BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
     VerticalAlignment,
 };
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+struct ScaledFont {
+    font: SharedFont,
+    path: &'static str,
+    size: f32,
+}
+
+struct ScaledWidget {
+    handle: Handle<UiNode>,
+    width: f32,
+    height: f32,
+}
+
+/// Remembers base sizes of fonts and fixed-size widgets of HUD and menus, so whole layout can
+/// be rescaled when UI scale option or scale factor of the display changes. Sizes are given
+/// for scale 1.0, fonts are reloaded in place so every widget that uses them picks up new size.
+pub struct UiScale {
+    scale: f32,
+    fonts: Vec<ScaledFont>,
+    widgets: Vec<ScaledWidget>,
+    dirty: bool,
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            fonts: Default::default(),
+            widgets: Default::default(),
+            dirty: false,
+        }
+    }
+}
+
+fn load_font(path: &str, size: f32) -> Font {
+    rg3d::core::futures::executor::block_on(Font::from_file(
+        Path::new(path),
+        size,
+        Font::default_char_set(),
+    ))
+    .unwrap()
+}
+
+impl UiScale {
+    /// Loads font of given base size.
+    pub fn font(&mut self, path: &'static str, size: f32) -> SharedFont {
+        let font = SharedFont(Arc::new(Mutex::new(load_font(path, size * self.scale))));
+        self.fonts.push(ScaledFont {
+            font: font.clone(),
+            path,
+            size,
+        });
+        font
+    }
+
+    /// Registers widget that was built with given base size. Zero width or height means that
+    /// the size along the axis is not fixed.
+    pub fn register(&mut self, handle: Handle<UiNode>, width: f32, height: f32) {
+        self.widgets.push(ScaledWidget {
+            handle,
+            width,
+            height,
+        });
+        self.dirty = true;
+    }
+
+    pub fn set_scale(&mut self, ui: &mut UserInterface, scale: f32) {
+        if (self.scale - scale).abs() > f32::EPSILON {
+            self.scale = scale;
+            for font in self.fonts.iter() {
+                *font.font.0.lock().unwrap() = load_font(font.path, font.size * scale);
+            }
+            self.dirty = true;
+        }
+
+        if self.dirty {
+            self.dirty = false;
+            for widget in self.widgets.iter() {
+                if widget.width > 0.0 {
+                    ui.send_message(WidgetMessage::width(
+                        widget.handle,
+                        MessageDirection::ToWidget,
+                        widget.width * scale,
+                    ));
+                }
+                if widget.height > 0.0 {
+                    ui.send_message(WidgetMessage::height(
+                        widget.handle,
+                        MessageDirection::ToWidget,
+                        widget.height * scale,
+                    ));
+                }
+            }
+        }
+    }
+}
 
 pub struct ScrollBarData {
     pub min: f32,
@@ -0,0 +1,115 @@
+//! Heatmaps of deaths and pickups for map makers. Positions of events recorded by match
+//! recorder are binned into square cells on the ground plane, every cell that has events is
+//! drawn as a square with a column over it - the more events happened in the cell the higher
+//! and brighter the column.
+
+use crate::match_recorder::{MatchEvent, MatchRecord};
+use rg3d::{
+    core::{algebra::Vector3, color::Color},
+    scene::debug::{Line, SceneDrawingContext},
+};
+use std::collections::HashMap;
+
+/// Size of cell in meters.
+const CELL_SIZE: f32 = 2.0;
+
+/// Column of the hottest cell is this high.
+const MAX_COLUMN_HEIGHT: f32 = 4.0;
+
+/// Recorded positions are centers of bodies, floor is about this much lower.
+const FLOOR_OFFSET: f32 = 1.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HeatmapKind {
+    Deaths,
+    Pickups,
+}
+
+impl HeatmapKind {
+    fn color(self) -> Color {
+        match self {
+            HeatmapKind::Deaths => Color::opaque(255, 40, 0),
+            HeatmapKind::Pickups => Color::opaque(0, 255, 120),
+        }
+    }
+
+    fn position(self, event: &MatchEvent) -> Option<Vector3<f32>> {
+        match (self, event) {
+            (HeatmapKind::Deaths, MatchEvent::Killed { position, .. })
+            | (HeatmapKind::Pickups, MatchEvent::PickedUp { position, .. }) => Some(*position),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Cell {
+    count: u32,
+    height_sum: f32,
+}
+
+pub struct Heatmap {
+    kind: HeatmapKind,
+    cells: HashMap<(i32, i32), Cell>,
+}
+
+impl Heatmap {
+    pub fn new(kind: HeatmapKind, records: &[MatchRecord]) -> Self {
+        let mut cells = HashMap::<(i32, i32), Cell>::new();
+        for position in records
+            .iter()
+            .filter_map(|record| kind.position(&record.event))
+        {
+            let key = (
+                (position.x / CELL_SIZE).floor() as i32,
+                (position.z / CELL_SIZE).floor() as i32,
+            );
+            let cell = cells.entry(key).or_default();
+            cell.count += 1;
+            cell.height_sum += position.y;
+        }
+        Self { kind, cells }
+    }
+
+    pub fn draw(&self, context: &mut SceneDrawingContext) {
+        let max = match self.cells.values().map(|cell| cell.count).max() {
+            Some(max) => max as f32,
+            None => return,
+        };
+        let hot = self.kind.color();
+        for (&(x, z), cell) in self.cells.iter() {
+            let heat = cell.count as f32 / max;
+            // Cold cells are dim, so hot spots stand out.
+            let brightness = 0.25 + 0.75 * heat;
+            let color = Color::opaque(
+                (hot.r as f32 * brightness) as u8,
+                (hot.g as f32 * brightness) as u8,
+                (hot.b as f32 * brightness) as u8,
+            );
+
+            let y = cell.height_sum / cell.count as f32 - FLOOR_OFFSET;
+            let min_x = x as f32 * CELL_SIZE;
+            let min_z = z as f32 * CELL_SIZE;
+            let corners = [
+                Vector3::new(min_x, y, min_z),
+                Vector3::new(min_x + CELL_SIZE, y, min_z),
+                Vector3::new(min_x + CELL_SIZE, y, min_z + CELL_SIZE),
+                Vector3::new(min_x, y, min_z + CELL_SIZE),
+            ];
+            for i in 0..corners.len() {
+                context.add_line(Line {
+                    begin: corners[i],
+                    end: corners[(i + 1) % corners.len()],
+                    color,
+                });
+            }
+
+            let center = Vector3::new(min_x + CELL_SIZE * 0.5, y, min_z + CELL_SIZE * 0.5);
+            context.add_line(Line {
+                begin: center,
+                end: center + Vector3::new(0.0, MAX_COLUMN_HEIGHT * heat, 0.0),
+                color,
+            });
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Locational damage. Every character has a single capsule collider, so hit zones are bands of
+//! height on it - head on top, legs at the bottom and torso between them. Projectiles and beams
+//! resolve the zone from the point of impact, damage without a point of impact (explosions,
+//! fire, melee) has no zone and is not scaled.
+
+use crate::character::HEAD_HEIGHT;
+use rg3d::core::algebra::Vector3;
+
+/// Head zone starts this far below center of head.
+const HEAD_RADIUS: f32 = 0.25;
+
+/// Hits lower than this offset from center of body are leg hits.
+const LEGS_HEIGHT: f32 = -0.3;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum HitZone {
+    Head,
+    Torso,
+    Legs,
+}
+
+impl HitZone {
+    /// Finds zone of a character that was hit at given point.
+    pub fn resolve(body_center: Vector3<f32>, hit_point: Vector3<f32>) -> Self {
+        let height = hit_point.y - body_center.y;
+        if height >= HEAD_HEIGHT - HEAD_RADIUS {
+            HitZone::Head
+        } else if height < LEGS_HEIGHT {
+            HitZone::Legs
+        } else {
+            HitZone::Torso
+        }
+    }
+
+    pub fn damage_multiplier(self) -> f32 {
+        match self {
+            HitZone::Head => 2.0,
+            HitZone::Torso => 1.0,
+            HitZone::Legs => 0.75,
+        }
+    }
+}
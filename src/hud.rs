@@ -1,81 +1,244 @@
 use crate::{
+    announcer::Announcer,
+    captions::Captions,
+    chat::Chat,
+    damage_effects::DamageEffects,
+    gui::UiScale,
     leader_board::{LeaderBoard, LeaderBoardUI},
+    level::{MatchPhase, OvertimeState},
+    localization::{tr, tr_format},
     message::Message,
-    GameTime, MatchOptions,
+    pak,
+    pickup_toasts::PickupToasts,
+    score_popups::ScorePopups,
+    squad::Order,
+    theme::Palette,
+    voice::SUBTITLE_TIME,
+    waypoints::{View, Waypoint, Waypoints},
+    weapon::{FireMode, WeaponKind},
+    weapon_wheel, GameTime, MatchOptions,
 };
 use rg3d::{
-    core::{color::Color, pool::Handle},
+    core::{
+        algebra::{Matrix3, Vector2, Vector3},
+        color::Color,
+        pool::Handle,
+    },
     engine::Engine,
     event::{Event, WindowEvent},
     gui::{
         border::BorderBuilder,
         brush::Brush,
+        canvas::CanvasBuilder,
         grid::{Column, GridBuilder, Row},
         image::ImageBuilder,
         message::MessageDirection,
         stack_panel::StackPanelBuilder,
         text::{TextBuilder, TextMessage},
-        ttf::{Font, SharedFont},
+        ttf::SharedFont,
         widget::{WidgetBuilder, WidgetMessage},
-        HorizontalAlignment, Orientation, Thickness, VerticalAlignment,
+        BuildContext, HorizontalAlignment, Orientation, Thickness, VerticalAlignment,
     },
     gui::{UiNode, UserInterface},
     utils,
 };
-use std::{
-    collections::VecDeque,
-    path::Path,
-    sync::{Arc, Mutex},
-};
+use std::collections::VecDeque;
+
+/// Width of shield bar when barrier is full.
+const SHIELD_BAR_WIDTH: f32 = 200.0;
+
+/// Size of weapon wheel and distance from its center to centers of slots.
+const WHEEL_SIZE: f32 = 360.0;
+const WHEEL_SLOT_RADIUS: f32 = 130.0;
+const WHEEL_SLOT_WIDTH: f32 = 150.0;
+const WHEEL_SLOT_HEIGHT: f32 = 40.0;
+
+/// How long (in seconds) hit marker stays over crosshair, kill marker stays longer.
+const HIT_MARKER_TIME: f32 = 0.25;
+const KILL_MARKER_TIME: f32 = 0.6;
+
+/// Health value pulses this many times per second while health regenerates.
+const REGEN_PULSE_FREQUENCY: f32 = 1.5;
+
+/// Tracks how long value shown by a widget stays the same, in minimal mode widgets which
+/// show nothing new for a while are faded out and fade back in when value changes.
+struct ActivityTimer {
+    widget: Handle<UiNode>,
+    idle_time: f32,
+    opacity: f32,
+    last_value: f32,
+}
+
+impl ActivityTimer {
+    const FADE_DELAY: f32 = 4.0;
+    const FADE_OUT_SPEED: f32 = 1.5;
+    const FADE_IN_SPEED: f32 = 6.0;
+
+    fn new(widget: Handle<UiNode>) -> Self {
+        Self {
+            widget,
+            idle_time: 0.0,
+            opacity: 1.0,
+            last_value: 0.0,
+        }
+    }
+
+    fn poke(&mut self) {
+        self.idle_time = 0.0;
+    }
+
+    fn track(&mut self, value: f32) {
+        if value != self.last_value {
+            self.last_value = value;
+            self.poke();
+        }
+    }
+
+    fn update(&mut self, ui: &mut UserInterface, dt: f32, minimal: bool) {
+        self.idle_time += dt;
+        let opacity = if !minimal || self.idle_time < Self::FADE_DELAY {
+            (self.opacity + Self::FADE_IN_SPEED * dt).min(1.0)
+        } else {
+            (self.opacity - Self::FADE_OUT_SPEED * dt).max(0.0)
+        };
+        if opacity != self.opacity {
+            self.opacity = opacity;
+            ui.send_message(WidgetMessage::opacity(
+                self.widget,
+                MessageDirection::ToWidget,
+                opacity,
+            ));
+        }
+    }
+}
 
 pub struct Hud {
     root: Handle<UiNode>,
     health: Handle<UiNode>,
+    hit_marker: Handle<UiNode>,
+    hit_marker_timeout: f32,
+    hit_markers: bool,
+    kill_marker: bool,
+    // Phase of health pulse, None when health does not regenerate.
+    regen_pulse: Option<f32>,
     armor: Handle<UiNode>,
+    shield_bar: Handle<UiNode>,
+    shield_fraction: f32,
     ammo: Handle<UiNode>,
+    fire_mode: Handle<UiNode>,
     time: Handle<UiNode>,
     message: Handle<UiNode>,
     message_queue: VecDeque<String>,
     message_timeout: f32,
     leader_board: LeaderBoardUI,
+    announcer: Announcer,
+    captions: Captions,
+    pickup_toasts: PickupToasts,
+    score_popups: ScorePopups,
+    damage_effects: DamageEffects,
+    chat: Chat,
+    waypoints: Waypoints,
     match_limit: Handle<UiNode>,
     first_score: Handle<UiNode>,
     second_score: Handle<UiNode>,
     died: Handle<UiNode>,
+    killed_by: Handle<UiNode>,
+    killed_by_text: String,
+    phase: Handle<UiNode>,
+    wave: Handle<UiNode>,
+    wave_text: Option<String>,
+    flag_status: Handle<UiNode>,
+    flag_status_text: Option<String>,
+    subtitle: Handle<UiNode>,
+    subtitle_timeout: f32,
+    training_prompt: Handle<UiNode>,
+    vote_prompt: Handle<UiNode>,
+    observer_bar: Handle<UiNode>,
+    observer_text: Option<String>,
+    practice_readout: Handle<UiNode>,
+    practice_text: Option<String>,
+    afk_warning: Handle<UiNode>,
+    afk_warning_text: Option<String>,
+    weapon_carousel: Handle<UiNode>,
+    weapon_carousel_timeout: f32,
+    weapon_wheel: Handle<UiNode>,
+    // Weapon under cursor of open wheel, None when wheel is closed.
+    wheel_selection: Option<Option<usize>>,
+    command_rose: Handle<UiNode>,
+    // Order under cursor of open command rose, None when rose is closed.
+    rose_selection: Option<Option<usize>>,
+    arsenal: Vec<WeaponKind>,
+    selected_weapon: usize,
+    font: SharedFont,
+    minimal: bool,
+    palette: Palette,
+    health_activity: ActivityTimer,
+    armor_activity: ActivityTimer,
+    ammo_activity: ActivityTimer,
+    last_fire_mode: Option<FireMode>,
 }
 
 impl Hud {
-    pub fn new(engine: &mut Engine) -> Self {
+    pub fn new(engine: &mut Engine, ui_scale: &mut UiScale) -> Self {
         let leader_board = LeaderBoardUI::new(engine);
+        let announcer = Announcer::new(engine, ui_scale);
+        let captions = Captions::new(&mut engine.user_interface);
+        let pickup_toasts =
+            PickupToasts::new(&mut engine.user_interface, engine.resource_manager.clone());
+        let score_popups = ScorePopups::new(&mut engine.user_interface);
+        let damage_effects = DamageEffects::new(&mut engine.user_interface);
+        let chat = Chat::new(&mut engine.user_interface);
 
         let frame_size = engine.renderer.get_frame_size();
+        let waypoints = Waypoints::new(
+            &mut engine.user_interface,
+            frame_size.0 as f32,
+            frame_size.1 as f32,
+        );
         let ctx = &mut engine.user_interface.build_ctx();
         let resource_manager = engine.resource_manager.clone();
 
-        let font = rg3d::core::futures::executor::block_on(Font::from_file(
-            Path::new("data/ui/SquaresBold.ttf"),
-            35.0,
-            Font::default_char_set(),
-        ))
-        .unwrap();
-        let font = SharedFont(Arc::new(Mutex::new(font)));
+        let font = ui_scale.font("data/ui/SquaresBold.ttf", 35.0);
+
+        let palette = Palette::default();
+        let theme = palette.theme();
 
         let health;
+        let hit_marker;
         let armor;
+        let shield_bar;
         let ammo;
+        let fire_mode;
         let message;
         let time;
         let first_score;
         let second_score;
         let match_limit;
         let died;
+        let killed_by;
+        let phase;
+        let wave;
+        let flag_status;
+        let subtitle;
+        let training_prompt;
+        let vote_prompt;
+        let observer_bar;
+        let practice_readout;
+        let afk_warning;
+        let weapon_carousel;
+        let weapon_wheel;
+        let command_rose;
+        let health_panel;
+        let armor_panel;
+        let ammo_panel;
         let root = GridBuilder::new(
             WidgetBuilder::new()
                 .with_width(frame_size.0 as f32)
                 .with_height(frame_size.1 as f32)
                 .with_visibility(false)
-                .with_child(
-                    ImageBuilder::new(
+                .with_child(damage_effects.widget())
+                .with_child({
+                    let crosshair = ImageBuilder::new(
                         WidgetBuilder::new()
                             .with_horizontal_alignment(HorizontalAlignment::Center)
                             .with_vertical_alignment(VerticalAlignment::Center)
@@ -85,10 +248,26 @@ impl Hud {
                             .on_column(1),
                     )
                     .with_texture(utils::into_gui_texture(
-                        resource_manager.request_texture("data/ui/crosshair.tga", None),
+                        resource_manager
+                            .request_texture(pak::resolve("data/ui/crosshair.tga"), None),
                     ))
-                    .build(ctx),
-                )
+                    .build(ctx);
+                    ui_scale.register(crosshair, 33.0, 33.0);
+                    crosshair
+                })
+                .with_child({
+                    hit_marker = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .on_row(0)
+                            .on_column(1),
+                    )
+                    .with_font(font.clone())
+                    .build(ctx);
+                    hit_marker
+                })
                 .with_child({
                     time = TextBuilder::new(
                         WidgetBuilder::new()
@@ -98,7 +277,7 @@ impl Hud {
                             .on_row(0),
                     )
                     .with_font(font.clone())
-                    .with_text("00:00:00")
+                    .with_text(tr("00:00:00"))
                     .build(ctx);
                     time
                 })
@@ -114,10 +293,12 @@ impl Hud {
                                 right: 0.0,
                                 bottom: 150.0,
                             })
-                            .with_child(
-                                BorderBuilder::new(
+                            .with_child({
+                                let score = BorderBuilder::new(
                                     WidgetBuilder::new()
                                         .on_column(0)
+                                        .with_width(75.0)
+                                        .with_height(33.0)
                                         .with_background(Brush::Solid(Color::opaque(34, 177, 76)))
                                         .with_foreground(Brush::Solid(Color::opaque(52, 216, 101)))
                                         .with_child({
@@ -131,18 +312,22 @@ impl Hud {
                                                     )
                                                     .with_foreground(Brush::Solid(Color::BLACK)),
                                             )
-                                            .with_text("0")
+                                            .with_text(tr("0"))
                                             .build(ctx);
                                             match_limit
                                         }),
                                 )
                                 .with_stroke_thickness(Thickness::uniform(2.0))
-                                .build(ctx),
-                            )
-                            .with_child(
-                                BorderBuilder::new(
+                                .build(ctx);
+                                ui_scale.register(score, 75.0, 33.0);
+                                score
+                            })
+                            .with_child({
+                                let score = BorderBuilder::new(
                                     WidgetBuilder::new()
                                         .on_column(1)
+                                        .with_width(75.0)
+                                        .with_height(33.0)
                                         .with_background(Brush::Solid(Color::opaque(249, 166, 2)))
                                         .with_foreground(Brush::Solid(Color::opaque(200, 110, 0)))
                                         .with_child({
@@ -156,18 +341,22 @@ impl Hud {
                                                     )
                                                     .with_foreground(Brush::Solid(Color::BLACK)),
                                             )
-                                            .with_text("0")
+                                            .with_text(tr("0"))
                                             .build(ctx);
                                             first_score
                                         }),
                                 )
                                 .with_stroke_thickness(Thickness::uniform(2.0))
-                                .build(ctx),
-                            )
-                            .with_child(
-                                BorderBuilder::new(
+                                .build(ctx);
+                                ui_scale.register(score, 75.0, 33.0);
+                                score
+                            })
+                            .with_child({
+                                let score = BorderBuilder::new(
                                     WidgetBuilder::new()
                                         .on_column(2)
+                                        .with_width(75.0)
+                                        .with_height(33.0)
                                         .with_background(Brush::Solid(Color::opaque(127, 127, 127)))
                                         .with_foreground(Brush::Solid(Color::opaque(80, 80, 80)))
                                         .with_child({
@@ -181,143 +370,198 @@ impl Hud {
                                                     )
                                                     .with_foreground(Brush::Solid(Color::BLACK)),
                                             )
-                                            .with_text("0")
+                                            .with_text(tr("0"))
                                             .build(ctx);
                                             second_score
                                         }),
                                 )
                                 .with_stroke_thickness(Thickness::uniform(2.0))
-                                .build(ctx),
-                            ),
+                                .build(ctx);
+                                ui_scale.register(score, 75.0, 33.0);
+                                score
+                            }),
                     )
-                    .add_column(Column::strict(75.0))
-                    .add_column(Column::strict(75.0))
-                    .add_column(Column::strict(75.0))
-                    .add_row(Row::strict(33.0))
+                    .add_column(Column::auto())
+                    .add_column(Column::auto())
+                    .add_column(Column::auto())
+                    .add_row(Row::auto())
                     .build(ctx),
                 )
-                .with_child(
-                    StackPanelBuilder::new(
+                .with_child({
+                    health_panel = StackPanelBuilder::new(
                         WidgetBuilder::new()
                             .with_margin(Thickness::bottom(10.0))
                             .on_column(0)
                             .with_vertical_alignment(VerticalAlignment::Bottom)
                             .with_horizontal_alignment(HorizontalAlignment::Center)
-                            .with_child(
-                                ImageBuilder::new(
+                            .with_child({
+                                let icon = ImageBuilder::new(
                                     WidgetBuilder::new().with_width(35.0).with_height(35.0),
                                 )
                                 .with_texture(utils::into_gui_texture(
-                                    resource_manager
-                                        .request_texture("data/ui/health_icon.png", None),
+                                    resource_manager.request_texture(
+                                        pak::resolve("data/ui/health_icon.png"),
+                                        None,
+                                    ),
                                 ))
-                                .build(ctx),
-                            )
-                            .with_child(
-                                TextBuilder::new(
+                                .build(ctx);
+                                ui_scale.register(icon, 35.0, 35.0);
+                                icon
+                            })
+                            .with_child({
+                                let label = TextBuilder::new(
                                     WidgetBuilder::new().with_width(170.0).with_height(35.0),
                                 )
-                                .with_text("Health:")
+                                .with_text(tr("Health:"))
                                 .with_font(font.clone())
-                                .build(ctx),
-                            )
+                                .build(ctx);
+                                ui_scale.register(label, 170.0, 35.0);
+                                label
+                            })
                             .with_child({
                                 health = TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .with_foreground(Brush::Solid(Color::opaque(180, 14, 22)))
+                                        .with_foreground(Brush::Solid(theme.health))
                                         .with_width(170.0)
                                         .with_height(35.0),
                                 )
-                                .with_text("100")
+                                .with_text(tr("100"))
                                 .with_font(font.clone())
                                 .build(ctx);
+                                ui_scale.register(health, 170.0, 35.0);
                                 health
                             }),
                     )
                     .with_orientation(Orientation::Horizontal)
-                    .build(ctx),
-                )
-                .with_child(
-                    StackPanelBuilder::new(
+                    .build(ctx);
+                    health_panel
+                })
+                .with_child({
+                    ammo_panel = StackPanelBuilder::new(
                         WidgetBuilder::new()
                             .with_margin(Thickness::bottom(10.0))
                             .on_column(1)
                             .with_vertical_alignment(VerticalAlignment::Bottom)
                             .with_horizontal_alignment(HorizontalAlignment::Center)
-                            .with_child(
-                                ImageBuilder::new(
+                            .with_child({
+                                let icon = ImageBuilder::new(
                                     WidgetBuilder::new().with_width(35.0).with_height(35.0),
                                 )
                                 .with_texture(utils::into_gui_texture(
-                                    resource_manager.request_texture("data/ui/ammo_icon.png", None),
+                                    resource_manager.request_texture(
+                                        pak::resolve("data/ui/ammo_icon.png"),
+                                        None,
+                                    ),
                                 ))
-                                .build(ctx),
-                            )
-                            .with_child(
-                                TextBuilder::new(
+                                .build(ctx);
+                                ui_scale.register(icon, 35.0, 35.0);
+                                icon
+                            })
+                            .with_child({
+                                let label = TextBuilder::new(
                                     WidgetBuilder::new().with_width(170.0).with_height(35.0),
                                 )
                                 .with_font(font.clone())
-                                .with_text("Ammo:")
-                                .build(ctx),
-                            )
+                                .with_text(tr("Ammo:"))
+                                .build(ctx);
+                                ui_scale.register(label, 170.0, 35.0);
+                                label
+                            })
                             .with_child({
                                 ammo = TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .with_foreground(Brush::Solid(Color::opaque(79, 79, 255)))
+                                        .with_foreground(Brush::Solid(theme.ammo))
                                         .with_width(170.0)
                                         .with_height(35.0),
                                 )
                                 .with_font(font.clone())
-                                .with_text("40")
+                                .with_text(tr("40"))
                                 .build(ctx);
+                                ui_scale.register(ammo, 170.0, 35.0);
                                 ammo
+                            })
+                            .with_child({
+                                fire_mode = TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_foreground(Brush::Solid(Color::opaque(180, 180, 180)))
+                                        .with_width(100.0)
+                                        .with_height(35.0),
+                                )
+                                .with_font(font.clone())
+                                .with_text(tr("AUTO"))
+                                .build(ctx);
+                                ui_scale.register(fire_mode, 100.0, 35.0);
+                                fire_mode
                             }),
                     )
                     .with_orientation(Orientation::Horizontal)
-                    .build(ctx),
-                )
-                .with_child(
-                    StackPanelBuilder::new(
+                    .build(ctx);
+                    ammo_panel
+                })
+                .with_child({
+                    armor_panel = StackPanelBuilder::new(
                         WidgetBuilder::new()
                             .with_margin(Thickness::bottom(10.0))
                             .on_column(2)
                             .with_vertical_alignment(VerticalAlignment::Bottom)
                             .with_horizontal_alignment(HorizontalAlignment::Center)
-                            .with_child(
-                                ImageBuilder::new(
+                            .with_child({
+                                let icon = ImageBuilder::new(
                                     WidgetBuilder::new().with_width(35.0).with_height(35.0),
                                 )
                                 .with_texture(utils::into_gui_texture(
-                                    resource_manager
-                                        .request_texture("data/ui/shield_icon.png", None),
+                                    resource_manager.request_texture(
+                                        pak::resolve("data/ui/shield_icon.png"),
+                                        None,
+                                    ),
                                 ))
-                                .build(ctx),
-                            )
-                            .with_child(
-                                TextBuilder::new(
+                                .build(ctx);
+                                ui_scale.register(icon, 35.0, 35.0);
+                                icon
+                            })
+                            .with_child({
+                                let label = TextBuilder::new(
                                     WidgetBuilder::new().with_width(170.0).with_height(35.0),
                                 )
                                 .with_font(font.clone())
-                                .with_text("Armor:")
-                                .build(ctx),
-                            )
+                                .with_text(tr("Armor:"))
+                                .build(ctx);
+                                ui_scale.register(label, 170.0, 35.0);
+                                label
+                            })
                             .with_child({
                                 armor = TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .with_foreground(Brush::Solid(Color::opaque(255, 100, 26)))
+                                        .with_foreground(Brush::Solid(theme.armor))
                                         .with_width(170.0)
                                         .with_height(35.0),
                                 )
                                 .with_font(font.clone())
-                                .with_text("100")
+                                .with_text(tr("100"))
                                 .build(ctx);
+                                ui_scale.register(armor, 170.0, 35.0);
                                 armor
                             }),
                     )
                     .with_orientation(Orientation::Horizontal)
-                    .build(ctx),
-                )
+                    .build(ctx);
+                    armor_panel
+                })
+                .with_child({
+                    shield_bar = BorderBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .with_width(SHIELD_BAR_WIDTH)
+                            .with_height(8.0)
+                            .with_margin(Thickness::bottom(55.0))
+                            .on_column(2)
+                            .with_vertical_alignment(VerticalAlignment::Bottom)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_background(Brush::Solid(theme.shield)),
+                    )
+                    .build(ctx);
+                    shield_bar
+                })
                 .with_child({
                     message = TextBuilder::new(
                         WidgetBuilder::new()
@@ -335,6 +579,7 @@ impl Hud {
                             .with_width(400.0),
                     )
                     .build(ctx);
+                    ui_scale.register(message, 400.0, 40.0);
                     message
                 })
                 .with_child({
@@ -347,10 +592,204 @@ impl Hud {
                             .with_vertical_alignment(VerticalAlignment::Center)
                             .with_horizontal_alignment(HorizontalAlignment::Center),
                     )
-                    .with_font(font)
-                    .with_text("You Died")
+                    .with_font(font.clone())
+                    .with_text(tr("You Died"))
                     .build(ctx);
                     died
+                })
+                .with_child({
+                    killed_by = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(100.0)),
+                    )
+                    .build(ctx);
+                    killed_by
+                })
+                .with_child({
+                    phase = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_foreground(Brush::Solid(Color::opaque(255, 200, 0)))
+                            .with_vertical_alignment(VerticalAlignment::Top)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(100.0)),
+                    )
+                    .with_font(font.clone())
+                    .build(ctx);
+                    phase
+                })
+                .with_child({
+                    wave = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_foreground(Brush::Solid(Color::opaque(255, 120, 0)))
+                            .with_vertical_alignment(VerticalAlignment::Top)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(40.0)),
+                    )
+                    .with_font(font.clone())
+                    .build(ctx);
+                    wave
+                })
+                .with_child({
+                    flag_status = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_vertical_alignment(VerticalAlignment::Top)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(40.0)),
+                    )
+                    .with_font(font.clone())
+                    .build(ctx);
+                    flag_status
+                })
+                .with_child({
+                    subtitle = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_vertical_alignment(VerticalAlignment::Bottom)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::bottom(160.0)),
+                    )
+                    .build(ctx);
+                    subtitle
+                })
+                .with_child({
+                    training_prompt = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_foreground(Brush::Solid(Color::opaque(0, 200, 255)))
+                            .with_vertical_alignment(VerticalAlignment::Top)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(60.0)),
+                    )
+                    .build(ctx);
+                    training_prompt
+                })
+                .with_child({
+                    vote_prompt = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(0)
+                            .with_foreground(Brush::Solid(Color::opaque(255, 200, 0)))
+                            .with_vertical_alignment(VerticalAlignment::Top)
+                            .with_horizontal_alignment(HorizontalAlignment::Left)
+                            .with_margin(Thickness {
+                                left: 20.0,
+                                top: 120.0,
+                                right: 0.0,
+                                bottom: 0.0,
+                            }),
+                    )
+                    .build(ctx);
+                    vote_prompt
+                })
+                .with_child({
+                    observer_bar = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_vertical_alignment(VerticalAlignment::Top)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(140.0)),
+                    )
+                    .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                    .build(ctx);
+                    observer_bar
+                })
+                .with_child({
+                    practice_readout = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_foreground(Brush::Solid(Color::opaque(255, 200, 0)))
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::top(120.0)),
+                    )
+                    .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                    .build(ctx);
+                    practice_readout
+                })
+                .with_child({
+                    afk_warning = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_foreground(Brush::Solid(Color::opaque(255, 80, 80)))
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_margin(Thickness::bottom(120.0)),
+                    )
+                    .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                    .build(ctx);
+                    afk_warning
+                })
+                .with_child(announcer.widget())
+                .with_child(captions.widget())
+                .with_child(pickup_toasts.widget())
+                .with_child(score_popups.widget())
+                .with_child(chat.widget())
+                .with_child({
+                    weapon_carousel = StackPanelBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_margin(Thickness::bottom(70.0))
+                            .with_vertical_alignment(VerticalAlignment::Bottom)
+                            .with_horizontal_alignment(HorizontalAlignment::Center),
+                    )
+                    .with_orientation(Orientation::Horizontal)
+                    .build(ctx);
+                    weapon_carousel
+                })
+                .with_child({
+                    weapon_wheel = CanvasBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_width(WHEEL_SIZE)
+                            .with_height(WHEEL_SIZE)
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_horizontal_alignment(HorizontalAlignment::Center),
+                    )
+                    .build(ctx);
+                    weapon_wheel
+                })
+                .with_child({
+                    command_rose = CanvasBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .on_row(0)
+                            .on_column(1)
+                            .with_width(WHEEL_SIZE)
+                            .with_height(WHEEL_SIZE)
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_horizontal_alignment(HorizontalAlignment::Center),
+                    )
+                    .build(ctx);
+                    command_rose
                 }),
         )
         .add_column(Column::stretch())
@@ -361,22 +800,71 @@ impl Hud {
 
         Self {
             leader_board,
+            announcer,
+            captions,
+            pickup_toasts,
+            score_popups,
+            damage_effects,
+            chat,
+            waypoints,
             root,
             health,
+            hit_marker,
+            hit_marker_timeout: 0.0,
+            hit_markers: true,
+            kill_marker: true,
+            regen_pulse: None,
             armor,
+            shield_bar,
+            shield_fraction: 0.0,
             ammo,
+            fire_mode,
             message,
             time,
             first_score,
             second_score,
             match_limit,
             died,
+            killed_by,
+            killed_by_text: Default::default(),
+            phase,
+            wave,
+            wave_text: None,
+            flag_status,
+            flag_status_text: None,
+            subtitle,
+            subtitle_timeout: 0.0,
+            training_prompt,
+            vote_prompt,
+            observer_bar,
+            observer_text: None,
+            practice_readout,
+            practice_text: None,
+            afk_warning,
+            afk_warning_text: None,
+            weapon_carousel,
+            weapon_carousel_timeout: 0.0,
+            weapon_wheel,
+            wheel_selection: None,
+            command_rose,
+            rose_selection: None,
+            arsenal: Default::default(),
+            selected_weapon: 0,
+            font,
+            minimal: false,
+            palette,
+            health_activity: ActivityTimer::new(health_panel),
+            armor_activity: ActivityTimer::new(armor_panel),
+            ammo_activity: ActivityTimer::new(ammo_panel),
+            last_fire_mode: None,
             message_timeout: 0.0,
             message_queue: Default::default(),
         }
     }
 
     pub fn set_health(&mut self, ui: &mut UserInterface, health: f32) {
+        self.health_activity.track(health);
+        self.damage_effects.track_health(health);
         ui.send_message(TextMessage::text(
             self.health,
             MessageDirection::ToWidget,
@@ -384,7 +872,40 @@ impl Hud {
         ));
     }
 
+    /// Health value pulses while health regenerates.
+    pub fn set_regenerating(&mut self, ui: &mut UserInterface, regenerating: bool) {
+        if regenerating == self.regen_pulse.is_some() {
+            return;
+        }
+        if regenerating {
+            self.regen_pulse = Some(0.0);
+        } else {
+            self.regen_pulse = None;
+            ui.send_message(WidgetMessage::foreground(
+                self.health,
+                MessageDirection::ToWidget,
+                Brush::Solid(self.palette.theme().health),
+            ));
+        }
+    }
+
+    fn update_regen_pulse(&mut self, ui: &mut UserInterface, dt: f32) {
+        if let Some(phase) = self.regen_pulse.as_mut() {
+            *phase += dt * REGEN_PULSE_FREQUENCY * std::f32::consts::PI * 2.0;
+            let t = (phase.sin() + 1.0) * 0.5;
+            let color = self.palette.theme().health;
+            let lerp = |a: u8| (a as f32 + (255.0 - a as f32) * t) as u8;
+            ui.send_message(WidgetMessage::foreground(
+                self.health,
+                MessageDirection::ToWidget,
+                Brush::Solid(Color::opaque(lerp(color.r), lerp(color.g), lerp(color.b))),
+            ));
+        }
+    }
+
     pub fn set_armor(&mut self, ui: &mut UserInterface, armor: f32) {
+        self.armor_activity.track(armor);
+        self.damage_effects.track_armor(armor);
         ui.send_message(TextMessage::text(
             self.armor,
             MessageDirection::ToWidget,
@@ -392,7 +913,32 @@ impl Hud {
         ));
     }
 
+    /// Shows shield barrier as a bar which is as long as much of the barrier is left, bar is
+    /// hidden when there is no barrier.
+    pub fn set_shield(&mut self, ui: &mut UserInterface, shield: f32, capacity: f32) {
+        let fraction = if capacity > 0.0 {
+            (shield / capacity).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        if fraction == self.shield_fraction {
+            return;
+        }
+        ui.send_message(WidgetMessage::visibility(
+            self.shield_bar,
+            MessageDirection::ToWidget,
+            fraction > 0.0,
+        ));
+        ui.send_message(WidgetMessage::width(
+            self.shield_bar,
+            MessageDirection::ToWidget,
+            SHIELD_BAR_WIDTH * fraction,
+        ));
+        self.shield_fraction = fraction;
+    }
+
     pub fn set_ammo(&mut self, ui: &mut UserInterface, ammo: u32) {
+        self.ammo_activity.track(ammo as f32);
         ui.send_message(TextMessage::text(
             self.ammo,
             MessageDirection::ToWidget,
@@ -400,6 +946,136 @@ impl Hud {
         ));
     }
 
+    pub fn set_fire_mode(&mut self, ui: &mut UserInterface, fire_mode: FireMode) {
+        if self.last_fire_mode != Some(fire_mode) {
+            self.last_fire_mode = Some(fire_mode);
+            self.ammo_activity.poke();
+        }
+        ui.send_message(TextMessage::text(
+            self.fire_mode,
+            MessageDirection::ToWidget,
+            tr(fire_mode.name()),
+        ));
+    }
+
+    /// In minimal mode health, armor and ammo widgets are hidden while their values
+    /// stay the same.
+    pub fn set_minimal_mode(&mut self, minimal: bool) {
+        self.minimal = minimal;
+    }
+
+    pub fn set_hit_markers(&mut self, hit_markers: bool, kill_marker: bool) {
+        self.hit_markers = hit_markers;
+        self.kill_marker = kill_marker;
+    }
+
+    /// Flashes marker over crosshair, kills are marked with a large cross, headshots have their
+    /// own color.
+    fn show_hit_marker(&mut self, ui: &mut UserInterface, killed: bool, headshot: bool) {
+        let kill = killed && self.kill_marker;
+        if !kill && !self.hit_markers {
+            return;
+        }
+        let theme = self.palette.theme();
+        let color = if headshot {
+            theme.headshot
+        } else if kill {
+            theme.kill
+        } else {
+            theme.hit
+        };
+        ui.send_message(TextMessage::text(
+            self.hit_marker,
+            MessageDirection::ToWidget,
+            if kill { "X" } else { "x" }.to_owned(),
+        ));
+        ui.send_message(WidgetMessage::foreground(
+            self.hit_marker,
+            MessageDirection::ToWidget,
+            Brush::Solid(color),
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.hit_marker,
+            MessageDirection::ToWidget,
+            true,
+        ));
+        self.hit_marker_timeout = if kill {
+            KILL_MARKER_TIME
+        } else {
+            // Hit must not cut kill marker short.
+            self.hit_marker_timeout.max(HIT_MARKER_TIME)
+        };
+    }
+
+    pub fn set_damage_effects(&mut self, intensity: f32) {
+        self.damage_effects.set_intensity(intensity);
+    }
+
+    pub fn reset_damage_effects(&mut self, ui: &mut UserInterface) {
+        self.damage_effects.reset(ui);
+    }
+
+    pub fn set_palette(&mut self, ui: &mut UserInterface, palette: Palette) {
+        if self.palette == palette {
+            return;
+        }
+        self.palette = palette;
+        self.leader_board.set_palette(palette);
+
+        let theme = palette.theme();
+        for &(widget, color) in &[
+            (self.health, theme.health),
+            (self.armor, theme.armor),
+            (self.ammo, theme.ammo),
+        ] {
+            ui.send_message(WidgetMessage::foreground(
+                widget,
+                MessageDirection::ToWidget,
+                Brush::Solid(color),
+            ));
+        }
+        ui.send_message(WidgetMessage::background(
+            self.shield_bar,
+            MessageDirection::ToWidget,
+            Brush::Solid(theme.shield),
+        ));
+    }
+
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    pub fn chat_mut(&mut self) -> &mut Chat {
+        &mut self.chat
+    }
+
+    /// Shows markers of given waypoints as they are seen from given view, markers are hidden
+    /// if there is no view.
+    pub fn update_waypoints(
+        &mut self,
+        ui: &mut UserInterface,
+        waypoints: &[Waypoint],
+        view: Option<View>,
+    ) {
+        self.waypoints.update(ui, waypoints, view);
+    }
+
+    pub fn clear_pickup_toasts(&mut self, ui: &mut UserInterface) {
+        self.pickup_toasts.clear(ui);
+    }
+
+    pub fn clear_score_popups(&mut self, ui: &mut UserInterface) {
+        self.score_popups.clear(ui);
+    }
+
+    pub fn set_captions_enabled(&mut self, enabled: bool) {
+        self.captions.set_enabled(enabled);
+    }
+
+    pub fn set_listener(&mut self, position: Vector3<f32>, basis: Matrix3<f32>) {
+        self.captions.set_listener(position, basis);
+    }
+
     pub fn set_visible(&mut self, ui: &mut UserInterface, visible: bool) {
         ui.send_message(WidgetMessage::visibility(
             self.root,
@@ -428,6 +1104,341 @@ impl Hud {
         ));
     }
 
+    /// Shows who killed player under "You Died" text.
+    pub fn set_killed_by(&mut self, ui: &mut UserInterface, killed_by: Option<&str>) {
+        let text = killed_by.unwrap_or_default();
+        if text != self.killed_by_text {
+            self.killed_by_text = text.to_owned();
+            ui.send_message(TextMessage::text(
+                self.killed_by,
+                MessageDirection::ToWidget,
+                self.killed_by_text.clone(),
+            ));
+            ui.send_message(WidgetMessage::visibility(
+                self.killed_by,
+                MessageDirection::ToWidget,
+                !self.killed_by_text.is_empty(),
+            ));
+        }
+    }
+
+    /// Training prompt stays on screen until level is destroyed.
+    pub fn hide_training_prompt(&mut self, ui: &mut UserInterface) {
+        ui.send_message(WidgetMessage::visibility(
+            self.training_prompt,
+            MessageDirection::ToWidget,
+            false,
+        ));
+    }
+
+    pub fn hide_vote_prompt(&mut self, ui: &mut UserInterface) {
+        ui.send_message(WidgetMessage::visibility(
+            self.vote_prompt,
+            MessageDirection::ToWidget,
+            false,
+        ));
+    }
+
+    /// Shows warm-up time or countdown before match start, hidden when match is live.
+    pub fn set_match_phase(
+        &mut self,
+        ui: &mut UserInterface,
+        phase: MatchPhase,
+        overtime: OvertimeState,
+    ) {
+        let text = match phase {
+            MatchPhase::WarmUp { time_left } => tr_format("Warm-up {}", &[&time_left.ceil()]),
+            MatchPhase::Countdown { time_left } => format!("{}", time_left.ceil()),
+            MatchPhase::Live => match overtime {
+                OvertimeState::Regular => String::new(),
+                OvertimeState::Extra => tr("Overtime"),
+                OvertimeState::SuddenDeath => tr("Sudden Death"),
+            },
+        };
+        ui.send_message(WidgetMessage::visibility(
+            self.phase,
+            MessageDirection::ToWidget,
+            !phase.is_live() || overtime != OvertimeState::Regular,
+        ));
+        ui.send_message(TextMessage::text(
+            self.phase,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
+    /// Shows wave counter of survival, it is hidden when there is no text.
+    pub fn set_wave(&mut self, ui: &mut UserInterface, text: Option<String>) {
+        if text == self.wave_text {
+            return;
+        }
+        ui.send_message(WidgetMessage::visibility(
+            self.wave,
+            MessageDirection::ToWidget,
+            text.is_some(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.wave,
+            MessageDirection::ToWidget,
+            text.clone().unwrap_or_default(),
+        ));
+        self.wave_text = text;
+    }
+
+    /// Shows states of flags of capture the flag, None hides them.
+    pub fn set_flag_status(&mut self, ui: &mut UserInterface, text: Option<String>) {
+        if text == self.flag_status_text {
+            return;
+        }
+        ui.send_message(WidgetMessage::visibility(
+            self.flag_status,
+            MessageDirection::ToWidget,
+            text.is_some(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.flag_status,
+            MessageDirection::ToWidget,
+            text.clone().unwrap_or_default(),
+        ));
+        self.flag_status_text = text;
+    }
+
+    /// Shows top bar of observer mode, None hides it.
+    pub fn set_observer_bar(&mut self, ui: &mut UserInterface, text: Option<String>) {
+        if text == self.observer_text {
+            return;
+        }
+        ui.send_message(WidgetMessage::visibility(
+            self.observer_bar,
+            MessageDirection::ToWidget,
+            text.is_some(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.observer_bar,
+            MessageDirection::ToWidget,
+            text.clone().unwrap_or_default(),
+        ));
+        self.observer_text = text;
+    }
+
+    /// Shows damage per second and hits of player on target dummies in practice mode.
+    pub fn set_practice_readout(&mut self, ui: &mut UserInterface, text: Option<String>) {
+        if text == self.practice_text {
+            return;
+        }
+        ui.send_message(WidgetMessage::visibility(
+            self.practice_readout,
+            MessageDirection::ToWidget,
+            text.is_some(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.practice_readout,
+            MessageDirection::ToWidget,
+            text.clone().unwrap_or_default(),
+        ));
+        self.practice_text = text;
+    }
+
+    /// Shows countdown before idle player is moved to observers, None hides it.
+    pub fn set_afk_warning(&mut self, ui: &mut UserInterface, text: Option<String>) {
+        if text == self.afk_warning_text {
+            return;
+        }
+        ui.send_message(WidgetMessage::visibility(
+            self.afk_warning,
+            MessageDirection::ToWidget,
+            text.is_some(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.afk_warning,
+            MessageDirection::ToWidget,
+            text.clone().unwrap_or_default(),
+        ));
+        self.afk_warning_text = text;
+    }
+
+    /// Shows owned weapons with currently selected one highlighted for a short period of time,
+    /// does nothing if arsenal and selection didn't change since last call.
+    pub fn set_arsenal(&mut self, ui: &mut UserInterface, arsenal: &[WeaponKind], selected: usize) {
+        if self.arsenal == arsenal && self.selected_weapon == selected {
+            return;
+        }
+
+        // Do not pop carousel up when player just got first weapon after respawn.
+        let show = !self.arsenal.is_empty() && !arsenal.is_empty();
+
+        self.arsenal = arsenal.to_vec();
+        self.selected_weapon = selected;
+        // Open wheel must show new arsenal.
+        if self.wheel_selection.is_some() {
+            self.wheel_selection = Some(None);
+            self.rebuild_weapon_wheel(ui, None);
+        }
+
+        let ctx = &mut ui.build_ctx();
+
+        let old_items = ctx[self.weapon_carousel].children().to_vec();
+
+        let items = arsenal
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| {
+                let (background, foreground) = if i == selected {
+                    (Color::opaque(249, 166, 2), Color::BLACK)
+                } else {
+                    (
+                        Color::from_rgba(40, 40, 40, 180),
+                        Color::opaque(200, 200, 200),
+                    )
+                };
+
+                BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_margin(Thickness::uniform(2.0))
+                        .with_background(Brush::Solid(background))
+                        .with_foreground(Brush::Solid(Color::opaque(80, 80, 80)))
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_margin(Thickness::uniform(4.0))
+                                    .with_foreground(Brush::Solid(foreground)),
+                            )
+                            .with_font(self.font.clone())
+                            .with_text(format!("{} {}", i + 1, tr(kind.name())))
+                            .build(ctx),
+                        ),
+                )
+                .with_stroke_thickness(Thickness::uniform(1.0))
+                .build(ctx)
+            })
+            .collect::<Vec<_>>();
+
+        for item in old_items {
+            ui.send_message(WidgetMessage::remove(item, MessageDirection::ToWidget));
+        }
+        for item in items {
+            ui.send_message(WidgetMessage::link(
+                item,
+                MessageDirection::ToWidget,
+                self.weapon_carousel,
+            ));
+        }
+
+        if show {
+            self.weapon_carousel_timeout = 1.5;
+            ui.send_message(WidgetMessage::visibility(
+                self.weapon_carousel,
+                MessageDirection::ToWidget,
+                true,
+            ));
+        }
+    }
+
+    /// Shows weapon wheel with weapon under cursor highlighted, `None` hides the wheel.
+    pub fn set_weapon_wheel(&mut self, ui: &mut UserInterface, selection: Option<Option<usize>>) {
+        if self.wheel_selection == selection {
+            return;
+        }
+        self.wheel_selection = selection;
+        ui.send_message(WidgetMessage::visibility(
+            self.weapon_wheel,
+            MessageDirection::ToWidget,
+            selection.is_some(),
+        ));
+        if let Some(selection) = selection {
+            self.rebuild_weapon_wheel(ui, selection);
+        }
+    }
+
+    fn rebuild_weapon_wheel(&mut self, ui: &mut UserInterface, selection: Option<usize>) {
+        let ctx = &mut ui.build_ctx();
+
+        let old_slots = ctx[self.weapon_wheel].children().to_vec();
+
+        let count = self.arsenal.len();
+        let slots = self
+            .arsenal
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| {
+                let (background, foreground) = if Some(i) == selection {
+                    (Color::opaque(249, 166, 2), Color::BLACK)
+                } else if i == self.selected_weapon {
+                    (Color::from_rgba(90, 90, 90, 200), Color::WHITE)
+                } else {
+                    (
+                        Color::from_rgba(40, 40, 40, 180),
+                        Color::opaque(200, 200, 200),
+                    )
+                };
+                make_wheel_slot(ctx, i, count, kind.name(), background, foreground)
+            })
+            .collect::<Vec<_>>();
+
+        for slot in old_slots {
+            ui.send_message(WidgetMessage::remove(slot, MessageDirection::ToWidget));
+        }
+        for slot in slots {
+            ui.send_message(WidgetMessage::link(
+                slot,
+                MessageDirection::ToWidget,
+                self.weapon_wheel,
+            ));
+        }
+    }
+
+    /// Shows command rose with order under cursor highlighted, `None` hides the rose.
+    pub fn set_command_rose(&mut self, ui: &mut UserInterface, selection: Option<Option<usize>>) {
+        if self.rose_selection == selection {
+            return;
+        }
+        self.rose_selection = selection;
+        ui.send_message(WidgetMessage::visibility(
+            self.command_rose,
+            MessageDirection::ToWidget,
+            selection.is_some(),
+        ));
+        if let Some(selection) = selection {
+            let ctx = &mut ui.build_ctx();
+
+            let old_slots = ctx[self.command_rose].children().to_vec();
+
+            let slots = Order::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, order)| {
+                    let (background, foreground) = if Some(i) == selection {
+                        (Color::opaque(249, 166, 2), Color::BLACK)
+                    } else {
+                        (
+                            Color::from_rgba(40, 40, 40, 180),
+                            Color::opaque(200, 200, 200),
+                        )
+                    };
+                    make_wheel_slot(
+                        ctx,
+                        i,
+                        Order::ALL.len(),
+                        order.name(),
+                        background,
+                        foreground,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            for slot in old_slots {
+                ui.send_message(WidgetMessage::remove(slot, MessageDirection::ToWidget));
+            }
+            for slot in slots {
+                ui.send_message(WidgetMessage::link(
+                    slot,
+                    MessageDirection::ToWidget,
+                    self.command_rose,
+                ));
+            }
+        }
+    }
+
     pub fn add_message<P: AsRef<str>>(&mut self, message: P) {
         self.message_queue.push_back(message.as_ref().to_owned())
     }
@@ -445,6 +1456,11 @@ impl Hud {
                     MessageDirection::ToWidget,
                     new_size.height as f32,
                 ));
+                self.waypoints.resize(
+                    &mut engine.user_interface,
+                    new_size.width as f32,
+                    new_size.height as f32,
+                );
             }
         }
 
@@ -455,9 +1471,58 @@ impl Hud {
         &self.leader_board
     }
 
+    pub fn leader_board_mut(&mut self) -> &mut LeaderBoardUI {
+        &mut self.leader_board
+    }
+
     pub fn update(&mut self, ui: &mut UserInterface, time: &GameTime) {
         self.message_timeout -= time.delta;
 
+        self.announcer.update(ui, time.delta);
+        self.captions.update(ui, time.delta);
+        self.chat.update(ui, time.delta);
+        self.pickup_toasts.update(ui, time.delta);
+        self.score_popups.update(ui, time.delta);
+
+        if self.subtitle_timeout > 0.0 {
+            self.subtitle_timeout -= time.delta;
+            if self.subtitle_timeout <= 0.0 {
+                ui.send_message(WidgetMessage::visibility(
+                    self.subtitle,
+                    MessageDirection::ToWidget,
+                    false,
+                ));
+            }
+        }
+
+        self.health_activity.update(ui, time.delta, self.minimal);
+        self.update_regen_pulse(ui, time.delta);
+        self.damage_effects.update(ui, time.delta);
+
+        if self.hit_marker_timeout > 0.0 {
+            self.hit_marker_timeout -= time.delta;
+            if self.hit_marker_timeout <= 0.0 {
+                ui.send_message(WidgetMessage::visibility(
+                    self.hit_marker,
+                    MessageDirection::ToWidget,
+                    false,
+                ));
+            }
+        }
+        self.armor_activity.update(ui, time.delta, self.minimal);
+        self.ammo_activity.update(ui, time.delta, self.minimal);
+
+        if self.weapon_carousel_timeout > 0.0 {
+            self.weapon_carousel_timeout -= time.delta;
+            if self.weapon_carousel_timeout <= 0.0 {
+                ui.send_message(WidgetMessage::visibility(
+                    self.weapon_carousel,
+                    MessageDirection::ToWidget,
+                    false,
+                ));
+            }
+        }
+
         if self.message_timeout <= 0.0 {
             if let Some(message) = self.message_queue.pop_front() {
                 ui.send_message(TextMessage::text(
@@ -505,6 +1570,7 @@ impl Hud {
             MatchOptions::DeathMatch(dm) => dm.frag_limit,
             MatchOptions::TeamDeathMatch(tdm) => tdm.team_frag_limit,
             MatchOptions::CaptureTheFlag(ctf) => ctf.flag_limit,
+            MatchOptions::Survival(_) => 0,
         };
         ui.send_message(TextMessage::text(
             self.match_limit,
@@ -520,8 +1586,56 @@ impl Hud {
         leader_board: &LeaderBoard,
         match_options: &MatchOptions,
     ) {
+        self.captions.handle_message(ui, message);
+        self.pickup_toasts.handle_message(ui, message);
+        self.score_popups.handle_message(ui, message);
+        self.chat.handle_message(ui, message);
+
         match message {
             Message::AddNotification { text } => self.add_message(text),
+            Message::HitConfirmed { killed, headshot } => {
+                self.show_hit_marker(ui, *killed, *headshot)
+            }
+            Message::Announce { announcement } => self.announcer.announce(*announcement),
+            Message::ShowSubtitle { speaker, text } => {
+                ui.send_message(TextMessage::text(
+                    self.subtitle,
+                    MessageDirection::ToWidget,
+                    format!("{}: {}", speaker, text),
+                ));
+                ui.send_message(WidgetMessage::visibility(
+                    self.subtitle,
+                    MessageDirection::ToWidget,
+                    true,
+                ));
+                self.subtitle_timeout = SUBTITLE_TIME;
+            }
+            Message::ShowTrainingPrompt { text } => {
+                ui.send_message(TextMessage::text(
+                    self.training_prompt,
+                    MessageDirection::ToWidget,
+                    text.clone(),
+                ));
+                ui.send_message(WidgetMessage::visibility(
+                    self.training_prompt,
+                    MessageDirection::ToWidget,
+                    true,
+                ));
+            }
+            Message::ShowVote { text } => {
+                if let Some(text) = text {
+                    ui.send_message(TextMessage::text(
+                        self.vote_prompt,
+                        MessageDirection::ToWidget,
+                        text.clone(),
+                    ));
+                }
+                ui.send_message(WidgetMessage::visibility(
+                    self.vote_prompt,
+                    MessageDirection::ToWidget,
+                    text.is_some(),
+                ));
+            }
             Message::AddBot { .. }
             | Message::RemoveActor { .. }
             | Message::RespawnActor { .. }
@@ -536,3 +1650,39 @@ impl Hud {
             .handle_message(message, ui, leader_board, match_options);
     }
 }
+
+fn make_wheel_slot(
+    ctx: &mut BuildContext,
+    index: usize,
+    count: usize,
+    text: &str,
+    background: Color,
+    foreground: Color,
+) -> Handle<UiNode> {
+    let center = Vector2::new(WHEEL_SIZE * 0.5, WHEEL_SIZE * 0.5)
+        + weapon_wheel::slot_offset(index, count, WHEEL_SLOT_RADIUS);
+
+    BorderBuilder::new(
+        WidgetBuilder::new()
+            .with_width(WHEEL_SLOT_WIDTH)
+            .with_height(WHEEL_SLOT_HEIGHT)
+            .with_desired_position(
+                center - Vector2::new(WHEEL_SLOT_WIDTH, WHEEL_SLOT_HEIGHT).scale(0.5),
+            )
+            .with_background(Brush::Solid(background))
+            .with_foreground(Brush::Solid(Color::opaque(80, 80, 80)))
+            .with_child(
+                TextBuilder::new(
+                    WidgetBuilder::new()
+                        .with_margin(Thickness::uniform(4.0))
+                        .with_foreground(Brush::Solid(foreground)),
+                )
+                .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .with_text(text)
+                .build(ctx),
+            ),
+    )
+    .with_stroke_thickness(Thickness::uniform(1.0))
+    .build(ctx)
+}
@@ -0,0 +1,214 @@
+//! Entity inspector for developers. It lists actors, items, projectiles and jump pads of the
+//! level with their live fields, selected entity is highlighted in the world. Inspector is
+//! opened by `/debug inspector` chat command.
+
+use crate::{actor::Actor, item::Item, jump_pad::JumpPad, projectile::Projectile};
+use rg3d::{
+    core::{algebra::Vector3, color::Color, math::aabb::AxisAlignedBoundingBox, pool::Handle},
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        scroll_viewer::ScrollViewerBuilder,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        Thickness, UiNode, UserInterface,
+    },
+    scene::debug::SceneDrawingContext,
+};
+
+const HIGHLIGHT_COLOR: Color = Color::opaque(255, 0, 255);
+
+/// Half-size of box that is drawn around selected entity.
+const HIGHLIGHT_SIZE: f32 = 0.6;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Entity {
+    Actor(Handle<Actor>),
+    Item(Handle<Item>),
+    Projectile(Handle<Projectile>),
+    JumpPad(Handle<JumpPad>),
+}
+
+pub struct EntityInfo {
+    pub entity: Entity,
+    pub name: String,
+    pub details: String,
+    pub position: Vector3<f32>,
+}
+
+pub struct Inspector {
+    pub window: Handle<UiNode>,
+    list: Handle<UiNode>,
+    details: Handle<UiNode>,
+    // Button of every listed entity along with the entity and its name.
+    rows: Vec<(Handle<UiNode>, Entity, String)>,
+    selected: Option<Entity>,
+    details_text: String,
+    open: bool,
+}
+
+impl Inspector {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let list;
+        let details;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(360.0).with_height(500.0))
+            .with_title(WindowTitle::text("Inspector"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            ScrollViewerBuilder::new(WidgetBuilder::new().on_row(0))
+                                .with_content({
+                                    list = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+                                    list
+                                })
+                                .build(ctx),
+                        )
+                        .with_child({
+                            details = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_margin(Thickness::uniform(4.0)),
+                            )
+                            .build(ctx);
+                            details
+                        }),
+                )
+                .add_column(Column::stretch())
+                .add_row(Row::stretch())
+                .add_row(Row::strict(120.0))
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            list,
+            details,
+            rows: Default::default(),
+            selected: None,
+            details_text: String::new(),
+            open: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_open(&mut self, ui: &mut UserInterface, open: bool) {
+        self.open = open;
+        if open {
+            ui.send_message(WindowMessage::open(
+                self.window,
+                MessageDirection::ToWidget,
+                false,
+            ));
+        } else {
+            ui.send_message(WindowMessage::close(
+                self.window,
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+
+    fn rebuild(&mut self, ui: &mut UserInterface, entities: &[EntityInfo]) {
+        for (button, _, _) in self.rows.drain(..) {
+            ui.send_message(WidgetMessage::remove(button, MessageDirection::ToWidget));
+        }
+
+        for info in entities {
+            let button = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .with_height(24.0)
+                    .with_margin(Thickness::uniform(1.0)),
+            )
+            .with_text(&info.name)
+            .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                button,
+                MessageDirection::ToWidget,
+                self.list,
+            ));
+            self.rows.push((button, info.entity, info.name.clone()));
+        }
+    }
+
+    /// Syncs list with entities of the level and refreshes fields of selected entity.
+    pub fn update(&mut self, ui: &mut UserInterface, entities: &[EntityInfo]) {
+        if !self.open {
+            return;
+        }
+
+        let changed = self.rows.len() != entities.len()
+            || self
+                .rows
+                .iter()
+                .zip(entities)
+                .any(|((_, entity, name), info)| *entity != info.entity || *name != info.name);
+        if changed {
+            self.rebuild(ui, entities);
+        }
+
+        let selected = self
+            .selected
+            .and_then(|selected| entities.iter().find(|info| info.entity == selected));
+        if selected.is_none() {
+            self.selected = None;
+        }
+        let text = selected.map_or_else(
+            || "Select an entity".to_owned(),
+            |info| format!("{}\n{}", info.name, info.details),
+        );
+        if text != self.details_text {
+            self.details_text = text.clone();
+            ui.send_message(TextMessage::text(
+                self.details,
+                MessageDirection::ToWidget,
+                text,
+            ));
+        }
+    }
+
+    /// Draws box around selected entity, must be called after level drew its debug geometry.
+    pub fn draw_highlight(&self, context: &mut SceneDrawingContext, entities: &[EntityInfo]) {
+        if !self.open {
+            return;
+        }
+        if let Some(info) = self
+            .selected
+            .and_then(|selected| entities.iter().find(|info| info.entity == selected))
+        {
+            let offset = Vector3::new(HIGHLIGHT_SIZE, HIGHLIGHT_SIZE, HIGHLIGHT_SIZE);
+            context.draw_aabb(
+                &AxisAlignedBoundingBox::from_min_max(
+                    info.position - offset,
+                    info.position + offset,
+                ),
+                HIGHLIGHT_COLOR,
+            );
+        }
+    }
+
+    pub fn handle_ui_event(&mut self, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if let Some((_, entity, _)) = self
+                .rows
+                .iter()
+                .find(|(button, _, _)| *button == message.destination())
+            {
+                self.selected = Some(*entity);
+            }
+        } else if let Some(WindowMessage::Close) = message.data() {
+            if message.destination() == self.window {
+                self.open = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Intermission is shown between matches. Final scoreboard and awards of the match stay on
+//! screen while players vote for one of few random maps, the winner is loaded when countdown
+//! ends.
+
+use crate::{
+    awards::Award,
+    localization::{tr, tr_format},
+    maps::{self, MapDefinition},
+    MatchOptions,
+};
+use rg3d::{
+    core::{color::Color, pool::Handle, rand::Rng},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        button::{ButtonBuilder, ButtonMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        VerticalAlignment,
+    },
+    rand,
+};
+use std::path::PathBuf;
+
+/// Time (in seconds) given to players to vote for next map.
+pub const INTERMISSION_TIME: f32 = 15.0;
+
+const MAX_CANDIDATES: usize = 3;
+
+/// Card with title of award, name of winner and the value that won it.
+fn make_card(ctx: &mut BuildContext, award: &Award) -> Handle<UiNode> {
+    BorderBuilder::new(
+        WidgetBuilder::new()
+            .with_width(110.0)
+            .with_margin(Thickness::uniform(2.0))
+            .with_background(Brush::Solid(Color::opaque(40, 40, 40)))
+            .with_foreground(Brush::Solid(Color::opaque(255, 200, 0)))
+            .with_child(
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_margin(Thickness::uniform(4.0))
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_foreground(Brush::Solid(Color::opaque(255, 200, 0))),
+                            )
+                            .with_text(tr(award.title))
+                            .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                            .build(ctx),
+                        )
+                        .with_child(
+                            TextBuilder::new(WidgetBuilder::new())
+                                .with_text(&award.name)
+                                .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                                .build(ctx),
+                        )
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_foreground(Brush::Solid(Color::opaque(180, 180, 180))),
+                            )
+                            .with_text(&award.value)
+                            .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                            .build(ctx),
+                        ),
+                )
+                .build(ctx),
+            ),
+    )
+    .with_stroke_thickness(Thickness::uniform(1.0))
+    .build(ctx)
+}
+
+struct VoteRow {
+    text: Handle<UiNode>,
+    button: Handle<UiNode>,
+}
+
+pub struct Intermission {
+    pub window: Handle<UiNode>,
+    awards: Handle<UiNode>,
+    cards: Vec<Handle<UiNode>>,
+    countdown: Handle<UiNode>,
+    rows: Vec<VoteRow>,
+    candidates: Vec<&'static MapDefinition>,
+    // Votes of bots, player's vote is stored separately so it can be changed.
+    votes: Vec<u32>,
+    player_vote: Option<usize>,
+    time_left: f32,
+    options: MatchOptions,
+}
+
+impl Intermission {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let awards;
+        let countdown;
+        let mut rows = Vec::new();
+        let mut children = Vec::new();
+        for i in 0..MAX_CANDIDATES {
+            let row = i + 2;
+            let text = TextBuilder::new(
+                WidgetBuilder::new()
+                    .on_row(row)
+                    .on_column(0)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .build(ctx);
+            let button = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .on_row(row)
+                    .on_column(1)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(&tr("Vote"))
+            .build(ctx);
+            children.push(text);
+            children.push(button);
+            rows.push(VoteRow { text, button });
+        }
+
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(600.0))
+            .with_title(WindowTitle::text(tr("Next Map")))
+            .can_close(false)
+            .can_minimize(false)
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            awards = StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .on_column(0)
+                                    .with_horizontal_alignment(HorizontalAlignment::Center),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx);
+                            awards
+                        })
+                        .with_child({
+                            countdown = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .on_column(0)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .build(ctx);
+                            countdown
+                        })
+                        .with_children(children),
+                )
+                .add_column(Column::stretch())
+                .add_column(Column::strict(100.0))
+                .add_row(Row::auto())
+                .add_row(Row::strict(36.0))
+                .add_rows((0..MAX_CANDIDATES).map(|_| Row::strict(36.0)).collect())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            awards,
+            cards: Default::default(),
+            countdown,
+            rows,
+            candidates: Default::default(),
+            votes: Default::default(),
+            player_vote: None,
+            time_left: 0.0,
+            options: Default::default(),
+        }
+    }
+
+    /// Starts voting for next map, bots cast their votes randomly right away.
+    pub fn begin(
+        &mut self,
+        ui: &mut UserInterface,
+        options: MatchOptions,
+        bot_count: usize,
+        awards: &[Award],
+    ) {
+        for card in self.cards.drain(..) {
+            ui.send_message(WidgetMessage::remove(card, MessageDirection::ToWidget));
+        }
+        for award in awards {
+            let card = make_card(&mut ui.build_ctx(), award);
+            ui.send_message(WidgetMessage::link(
+                card,
+                MessageDirection::ToWidget,
+                self.awards,
+            ));
+            self.cards.push(card);
+        }
+
+        self.candidates = maps::random_maps(MAX_CANDIDATES);
+        self.votes = vec![0; self.candidates.len()];
+        for _ in 0..bot_count {
+            let index = rand::thread_rng().gen_range(0..self.candidates.len());
+            self.votes[index] += 1;
+        }
+        self.player_vote = None;
+        self.time_left = INTERMISSION_TIME;
+        self.options = options;
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let visible = i < self.candidates.len();
+            ui.send_message(WidgetMessage::visibility(
+                row.text,
+                MessageDirection::ToWidget,
+                visible,
+            ));
+            ui.send_message(WidgetMessage::visibility(
+                row.button,
+                MessageDirection::ToWidget,
+                visible,
+            ));
+        }
+        self.sync_votes(ui);
+
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    pub fn close(&mut self, ui: &mut UserInterface) {
+        ui.send_message(WindowMessage::close(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    fn vote_count(&self, index: usize) -> u32 {
+        self.votes[index]
+            + if self.player_vote == Some(index) {
+                1
+            } else {
+                0
+            }
+    }
+
+    fn sync_votes(&self, ui: &mut UserInterface) {
+        for (i, map) in self.candidates.iter().enumerate() {
+            let mut text = tr_format("{} - {} vote(s)", &[&map.name, &self.vote_count(i)]);
+            if self.player_vote == Some(i) {
+                text += " *";
+            }
+            ui.send_message(TextMessage::text(
+                self.rows[i].text,
+                MessageDirection::ToWidget,
+                text,
+            ));
+        }
+    }
+
+    /// Returns map with most votes, ties are resolved in favor of first candidate.
+    fn winner(&self) -> &'static MapDefinition {
+        let mut winner = 0;
+        for i in 1..self.candidates.len() {
+            if self.vote_count(i) > self.vote_count(winner) {
+                winner = i;
+            }
+        }
+        self.candidates[winner]
+    }
+
+    pub fn handle_ui_event(&mut self, ui: &mut UserInterface, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if let Some(index) = self
+                .rows
+                .iter()
+                .position(|row| row.button == message.destination())
+            {
+                if index < self.candidates.len() {
+                    self.player_vote = Some(index);
+                    self.sync_votes(ui);
+                }
+            }
+        }
+    }
+
+    /// Ticks countdown, returns options and map of next match when it is time to start it.
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) -> Option<(MatchOptions, PathBuf)> {
+        self.time_left -= dt;
+
+        ui.send_message(TextMessage::text(
+            self.countdown,
+            MessageDirection::ToWidget,
+            tr_format(
+                "Next match starts in {} s",
+                &[&self.time_left.max(0.0).ceil()],
+            ),
+        ));
+
+        if self.time_left <= 0.0 {
+            self.close(ui);
+            Some((self.options, PathBuf::from(self.winner().path)))
+        } else {
+            None
+        }
+    }
+}
@@ -1,17 +1,38 @@
-use crate::{effects::EffectKind, message::Message, rg3d::core::math::Vector3Ext, GameTime};
-use rg3d::engine::resource_manager::MaterialSearchOptions;
+use crate::{
+    assets,
+    effects::EffectKind,
+    localization::{tr, tr_format},
+    message::Message,
+    rg3d::core::math::Vector3Ext,
+    saves,
+    weapon::WeaponKind,
+    GameTime,
+};
 use rg3d::sound::pool::PoolIteratorMut;
 use rg3d::{
     core::{
-        algebra::Vector3,
+        algebra::{UnitQuaternion, Vector3},
+        color::Color,
         pool::{Handle, Pool, PoolIterator, PoolPairIterator},
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::ResourceManager,
-    scene::{base::BaseBuilder, graph::Graph, node::Node, transform::TransformBuilder, Scene},
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        light::{point::PointLightBuilder, BaseLightBuilder},
+        node::Node,
+        transform::TransformBuilder,
+        Scene,
+    },
 };
-use std::path::PathBuf;
-use std::{path::Path, sync::mpsc::Sender};
+use std::{collections::HashMap, sync::mpsc::Sender};
+
+/// Amount of health medkit restores.
+pub const MEDKIT_HEALTH: f32 = 20.0;
+
+/// Amount of ammo that ammo items and duplicate weapons give.
+pub const AMMO_AMOUNT: u32 = 200;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum ItemKind {
@@ -27,10 +48,16 @@ pub enum ItemKind {
     Ak47,
     M4,
     RocketLauncher,
+    Flamethrower,
+    LightningGun,
+    MineLayer,
+    SentryKit,
+
+    Shield,
 }
 
 impl ItemKind {
-    fn from_id(id: u32) -> Result<ItemKind, String> {
+    pub fn from_id(id: u32) -> Result<ItemKind, String> {
         match id {
             0 => Ok(ItemKind::Medkit),
             1 => Ok(ItemKind::Plasma),
@@ -40,11 +67,106 @@ impl ItemKind {
             5 => Ok(ItemKind::Ak47),
             6 => Ok(ItemKind::M4),
             7 => Ok(ItemKind::RocketLauncher),
+            8 => Ok(ItemKind::Flamethrower),
+            9 => Ok(ItemKind::LightningGun),
+            10 => Ok(ItemKind::MineLayer),
+            11 => Ok(ItemKind::SentryKit),
+            12 => Ok(ItemKind::Shield),
             _ => Err(format!("Unknown item kind {}", id)),
         }
     }
 
-    fn id(self) -> u32 {
+    /// Returns kind of item that represents given weapon in the world.
+    pub fn from_weapon(kind: WeaponKind) -> ItemKind {
+        match kind {
+            WeaponKind::M4 => ItemKind::M4,
+            WeaponKind::Ak47 => ItemKind::Ak47,
+            WeaponKind::PlasmaRifle => ItemKind::PlasmaGun,
+            WeaponKind::RocketLauncher => ItemKind::RocketLauncher,
+            WeaponKind::Flamethrower => ItemKind::Flamethrower,
+            WeaponKind::LightningGun => ItemKind::LightningGun,
+            WeaponKind::MineLayer => ItemKind::MineLayer,
+            WeaponKind::SentryKit => ItemKind::SentryKit,
+        }
+    }
+
+    /// Returns kind of ammo item for given weapon, if there is any.
+    pub fn ammo_for(kind: WeaponKind) -> Option<ItemKind> {
+        match kind {
+            WeaponKind::M4 => Some(ItemKind::M4Ammo),
+            WeaponKind::Ak47 => Some(ItemKind::Ak47Ammo),
+            WeaponKind::PlasmaRifle => Some(ItemKind::Plasma),
+            WeaponKind::RocketLauncher
+            | WeaponKind::Flamethrower
+            | WeaponKind::LightningGun
+            | WeaponKind::MineLayer
+            | WeaponKind::SentryKit => None,
+        }
+    }
+
+    /// Returns kind of weapon that given item gives, if any.
+    pub fn weapon(self) -> Option<WeaponKind> {
+        match self {
+            ItemKind::PlasmaGun => Some(WeaponKind::PlasmaRifle),
+            ItemKind::Ak47 => Some(WeaponKind::Ak47),
+            ItemKind::M4 => Some(WeaponKind::M4),
+            ItemKind::RocketLauncher => Some(WeaponKind::RocketLauncher),
+            ItemKind::Flamethrower => Some(WeaponKind::Flamethrower),
+            ItemKind::LightningGun => Some(WeaponKind::LightningGun),
+            ItemKind::MineLayer => Some(WeaponKind::MineLayer),
+            ItemKind::SentryKit => Some(WeaponKind::SentryKit),
+            ItemKind::Medkit
+            | ItemKind::Plasma
+            | ItemKind::Ak47Ammo
+            | ItemKind::M4Ammo
+            | ItemKind::Shield => None,
+        }
+    }
+
+    /// Short text that is shown on HUD when player picks up item of given kind.
+    pub fn toast(self) -> String {
+        match self {
+            ItemKind::Medkit => tr_format("+{} Health", &[&MEDKIT_HEALTH]),
+            ItemKind::Shield => tr_format("+{} Shield", &[&crate::character::SHIELD_CAPACITY]),
+            ItemKind::Plasma => tr_format("+{} Plasma Rifle ammo", &[&AMMO_AMOUNT]),
+            ItemKind::Ak47Ammo => tr_format("+{} AK-47 ammo", &[&AMMO_AMOUNT]),
+            ItemKind::M4Ammo => tr_format("+{} M4 ammo", &[&AMMO_AMOUNT]),
+            _ => tr_format("{} acquired", &[&tr(self.weapon().unwrap().name())]),
+        }
+    }
+
+    /// Description that is shown along with toast when player picks up item of given kind
+    /// for the first time.
+    pub fn description(self) -> &'static str {
+        match self {
+            ItemKind::Medkit => "Restores health, up to 150.",
+            ItemKind::Shield => {
+                "Energy barrier that takes damage first and recharges out of combat."
+            }
+            ItemKind::Plasma | ItemKind::Ak47Ammo | ItemKind::M4Ammo => {
+                "Ammo for the weapon if you have it."
+            }
+            ItemKind::PlasmaGun => "Fast energy bolts, good at medium range.",
+            ItemKind::Ak47 => "Hard hitting automatic rifle.",
+            ItemKind::M4 => "Accurate automatic rifle with burst and single fire.",
+            ItemKind::RocketLauncher => "Rockets explode on impact, mind the splash.",
+            ItemKind::Flamethrower => "Sets targets on fire at short range.",
+            ItemKind::LightningGun => "Continuous beam, keep it on target.",
+            ItemKind::MineLayer => "Places proximity mines on surfaces.",
+            ItemKind::SentryKit => "Deploys an automatic turret.",
+        }
+    }
+
+    /// Path to HUD icon of given kind.
+    pub fn icon(self) -> &'static str {
+        match self {
+            ItemKind::Medkit => "data/ui/health_icon.png",
+            ItemKind::Shield => "data/ui/shield_icon.png",
+            _ => "data/ui/ammo_icon.png",
+        }
+    }
+
+    pub fn id(self) -> u32 {
         match self {
             ItemKind::Medkit => 0,
             ItemKind::Plasma => 1,
@@ -54,6 +176,11 @@ impl ItemKind {
             ItemKind::Ak47 => 5,
             ItemKind::M4 => 6,
             ItemKind::RocketLauncher => 7,
+            ItemKind::Flamethrower => 8,
+            ItemKind::LightningGun => 9,
+            ItemKind::MineLayer => 10,
+            ItemKind::SentryKit => 11,
+            ItemKind::Shield => 12,
         }
     }
 }
@@ -62,6 +189,7 @@ pub struct Item {
     kind: ItemKind,
     pivot: Handle<Node>,
     model: Handle<Node>,
+    glow: Handle<Node>,
     offset: Vector3<f32>,
     dest_offset: Vector3<f32>,
     offset_factor: f32,
@@ -78,6 +206,7 @@ impl Default for Item {
             kind: ItemKind::Medkit,
             pivot: Default::default(),
             model: Default::default(),
+            glow: Default::default(),
             offset: Default::default(),
             dest_offset: Default::default(),
             offset_factor: 0.0,
@@ -90,10 +219,24 @@ impl Default for Item {
     }
 }
 
+/// Parameters of procedural animation of item lying in the world.
+pub struct ItemAnimation {
+    /// Height (in meters) of hovering above spawn point.
+    hover_height: f32,
+    /// Angular speeds are in rad/s.
+    hover_speed: f32,
+    spin_speed: f32,
+    glow_color: Color,
+    glow_radius: f32,
+    /// Zero for steady glow.
+    pulse_speed: f32,
+}
+
 pub struct ItemDefinition {
-    model: &'static str,
+    pub model: &'static str,
     scale: f32,
     reactivation_interval: f32,
+    animation: ItemAnimation,
 }
 
 impl Item {
@@ -104,6 +247,14 @@ impl Item {
                     model: "data/models/medkit.fbx",
                     scale: 1.0,
                     reactivation_interval: 20.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.1,
+                        hover_speed: 1.2,
+                        spin_speed: 0.8,
+                        glow_color: Color::opaque(255, 60, 60),
+                        glow_radius: 1.0,
+                        pulse_speed: 3.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -112,6 +263,14 @@ impl Item {
                     model: "data/models/yellow_box.FBX",
                     scale: 0.25,
                     reactivation_interval: 15.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.08,
+                        hover_speed: 1.5,
+                        spin_speed: 1.2,
+                        glow_color: Color::opaque(0, 160, 255),
+                        glow_radius: 0.8,
+                        pulse_speed: 4.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -120,6 +279,14 @@ impl Item {
                     model: "data/models/box_medium.FBX",
                     scale: 0.30,
                     reactivation_interval: 14.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.08,
+                        hover_speed: 1.5,
+                        spin_speed: 1.2,
+                        glow_color: Color::opaque(255, 200, 0),
+                        glow_radius: 0.8,
+                        pulse_speed: 4.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -128,6 +295,14 @@ impl Item {
                     model: "data/models/box_small.FBX",
                     scale: 0.30,
                     reactivation_interval: 13.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.08,
+                        hover_speed: 1.5,
+                        spin_speed: 1.2,
+                        glow_color: Color::opaque(255, 200, 0),
+                        glow_radius: 0.8,
+                        pulse_speed: 4.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -136,6 +311,14 @@ impl Item {
                     model: "data/models/plasma_rifle.FBX",
                     scale: 3.0,
                     reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(0, 160, 255),
+                        glow_radius: 1.5,
+                        pulse_speed: 2.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -144,6 +327,14 @@ impl Item {
                     model: "data/models/ak47.FBX",
                     scale: 3.0,
                     reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(255, 255, 255),
+                        glow_radius: 1.5,
+                        pulse_speed: 2.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -152,6 +343,14 @@ impl Item {
                     model: "data/models/m4.FBX",
                     scale: 3.0,
                     reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(255, 255, 255),
+                        glow_radius: 1.5,
+                        pulse_speed: 2.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -160,6 +359,94 @@ impl Item {
                     model: "data/models/Rpg7.FBX",
                     scale: 3.0,
                     reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(255, 127, 0),
+                        glow_radius: 1.5,
+                        pulse_speed: 2.0,
+                    },
+                };
+                &DEFINITION
+            }
+            ItemKind::Flamethrower => {
+                static DEFINITION: ItemDefinition = ItemDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    scale: 3.0,
+                    reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(255, 80, 0),
+                        glow_radius: 1.5,
+                        pulse_speed: 2.0,
+                    },
+                };
+                &DEFINITION
+            }
+            ItemKind::LightningGun => {
+                static DEFINITION: ItemDefinition = ItemDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    scale: 3.0,
+                    reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(140, 180, 255),
+                        glow_radius: 1.5,
+                        pulse_speed: 2.0,
+                    },
+                };
+                &DEFINITION
+            }
+            ItemKind::MineLayer => {
+                static DEFINITION: ItemDefinition = ItemDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    scale: 3.0,
+                    reactivation_interval: 30.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.2,
+                        spin_speed: 1.5,
+                        glow_color: Color::opaque(255, 0, 0),
+                        glow_radius: 1.5,
+                        pulse_speed: 6.0,
+                    },
+                };
+                &DEFINITION
+            }
+            ItemKind::SentryKit => {
+                static DEFINITION: ItemDefinition = ItemDefinition {
+                    model: "data/models/box_medium.FBX",
+                    scale: 0.30,
+                    reactivation_interval: 45.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.1,
+                        hover_speed: 1.2,
+                        spin_speed: 1.0,
+                        glow_color: Color::opaque(255, 220, 120),
+                        glow_radius: 1.2,
+                        pulse_speed: 2.0,
+                    },
+                };
+                &DEFINITION
+            }
+            ItemKind::Shield => {
+                static DEFINITION: ItemDefinition = ItemDefinition {
+                    model: "data/models/yellow_box.FBX",
+                    scale: 0.3,
+                    reactivation_interval: 40.0,
+                    animation: ItemAnimation {
+                        hover_height: 0.15,
+                        hover_speed: 1.0,
+                        spin_speed: 2.0,
+                        glow_color: Color::opaque(0, 220, 255),
+                        glow_radius: 1.5,
+                        pulse_speed: 5.0,
+                    },
                 };
                 &DEFINITION
             }
@@ -175,14 +462,7 @@ impl Item {
     ) -> Self {
         let definition = Self::get_definition(kind);
 
-        let model = resource_manager
-            .request_model(
-                Path::new(definition.model),
-                MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures")),
-            )
-            .await
-            .unwrap()
-            .instantiate_geometry(scene);
+        let model = assets::instantiate_model(&resource_manager, definition.model, scene).await;
 
         let pivot = BaseBuilder::new()
             .with_local_transform(
@@ -199,10 +479,21 @@ impl Item {
 
         scene.graph.link_nodes(model, pivot);
 
+        let glow = PointLightBuilder::new(
+            BaseLightBuilder::new(BaseBuilder::new())
+                .with_color(definition.animation.glow_color)
+                .with_scatter_enabled(false)
+                .cast_shadows(false),
+        )
+        .with_radius(definition.animation.glow_radius)
+        .build(&mut scene.graph);
+        scene.graph.link_nodes(glow, pivot);
+
         Self {
             pivot,
             kind,
             model,
+            glow,
             sender: Some(sender),
             ..Default::default()
         }
@@ -217,18 +508,53 @@ impl Item {
     }
 
     pub fn update(&mut self, graph: &mut Graph, time: GameTime) {
-        self.offset_factor += 1.2 * time.delta;
+        let animation = &self.definition.animation;
+
+        // Offset factor is time since spawn, all animations are driven by it.
+        self.offset_factor += time.delta;
 
-        let amp = 0.085;
-        self.dest_offset = Vector3::new(0.0, amp + amp * self.offset_factor.sin(), 0.0);
-        self.offset.follow(&self.dest_offset, 0.2);
+        // Model is a child of scaled pivot, so hover height is scaled back to meters.
+        let amp = 0.5 * animation.hover_height / self.definition.scale;
+        self.dest_offset = Vector3::new(
+            0.0,
+            amp + amp * (self.offset_factor * animation.hover_speed).sin(),
+            0.0,
+        );
+        self.offset.follow(&self.dest_offset, time.smoothing(0.2));
 
         let position = graph[self.pivot].global_position();
 
+        // Temporary items blink for a while before disappearing.
+        let mut visible = !self.is_picked_up();
+        if let Some(lifetime) = self.lifetime.as_mut() {
+            *lifetime -= time.delta;
+            if *lifetime < 3.0 {
+                visible &= (*lifetime * 16.0).sin() > 0.0;
+            }
+        }
+
         graph[self.model]
-            .set_visibility(!self.is_picked_up())
+            .set_visibility(visible)
             .local_transform_mut()
-            .set_position(self.offset);
+            .set_position(self.offset)
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                self.offset_factor * animation.spin_speed,
+            ));
+
+        if self.glow.is_some() {
+            let pulse = 0.75 + 0.25 * (self.offset_factor * animation.pulse_speed).sin();
+            let color = animation.glow_color;
+            let glow = &mut graph[self.glow];
+            glow.set_visibility(visible);
+            if let Node::Light(light) = glow {
+                light.set_color(Color::opaque(
+                    (color.r as f32 * pulse) as u8,
+                    (color.g as f32 * pulse) as u8,
+                    (color.b as f32 * pulse) as u8,
+                ));
+            }
+        }
 
         if !self.active {
             self.reactivation_timer -= time.delta;
@@ -251,8 +577,9 @@ impl Item {
         self.kind
     }
 
-    pub fn pick_up(&mut self) {
-        self.reactivation_timer = self.definition.reactivation_interval;
+    /// `reactivation_scale` stretches or shrinks time until item appears again.
+    pub fn pick_up(&mut self, reactivation_scale: f32) {
+        self.reactivation_timer = self.definition.reactivation_interval * reactivation_scale;
         self.active = false;
     }
 
@@ -260,6 +587,25 @@ impl Item {
         !self.active
     }
 
+    /// Returns time until item appears again, None if item is not picked up.
+    pub fn reactivation_time(&self) -> Option<f32> {
+        if self.active {
+            None
+        } else {
+            Some(self.reactivation_timer)
+        }
+    }
+
+    pub fn set_reactivation_time(&mut self, time: Option<f32>) {
+        match time {
+            Some(time) => {
+                self.reactivation_timer = time;
+                self.active = false;
+            }
+            None => self.active = true,
+        }
+    }
+
     fn cleanup(&self, graph: &mut Graph) {
         graph.remove_node(self.pivot)
     }
@@ -274,6 +620,11 @@ impl Item {
     pub fn set_lifetime(&mut self, lifetime: Option<f32>) {
         self.lifetime = lifetime;
     }
+
+    /// Returns true if item was dropped by someone and will despawn after some time.
+    pub fn is_dropped(&self) -> bool {
+        self.lifetime.is_some()
+    }
 }
 
 impl Visit for Item {
@@ -289,6 +640,9 @@ impl Visit for Item {
         self.definition = Self::get_definition(self.kind);
         self.model.visit("Model", visitor)?;
         self.pivot.visit("Pivot", visitor)?;
+        if saves::read_version(visitor) >= 3 {
+            self.glow.visit("Glow", visitor)?;
+        }
         self.offset.visit("Offset", visitor)?;
         self.offset_factor.visit("OffsetFactor", visitor)?;
         self.dest_offset.visit("DestOffset", visitor)?;
@@ -301,8 +655,15 @@ impl Visit for Item {
     }
 }
 
+/// Size of a cell of item grid, should be larger than pick up radius so actors
+/// have to check only few cells.
+const GRID_CELL_SIZE: f32 = 4.0;
+
 pub struct ItemContainer {
     pool: Pool<Item>,
+    // Uniform grid over items on XZ plane, used as broad phase for pick up checks.
+    // Rebuilt every frame, so it is not serialized.
+    grid: HashMap<(i32, i32), Vec<Handle<Item>>>,
 }
 
 impl Default for ItemContainer {
@@ -323,7 +684,10 @@ impl Visit for ItemContainer {
 
 impl ItemContainer {
     pub fn new() -> Self {
-        Self { pool: Pool::new() }
+        Self {
+            pool: Pool::new(),
+            grid: Default::default(),
+        }
     }
 
     pub fn add(&mut self, item: Item) -> Handle<Item> {
@@ -334,6 +698,10 @@ impl ItemContainer {
         self.pool.borrow_mut(item)
     }
 
+    pub fn get(&self, item: Handle<Item>) -> &Item {
+        self.pool.borrow(item)
+    }
+
     pub fn contains(&self, item: Handle<Item>) -> bool {
         self.pool.is_valid_handle(item)
     }
@@ -361,6 +729,40 @@ impl ItemContainer {
                 item.cleanup(&mut scene.graph);
             }
         }
-        self.pool.retain(|i| !i.can_be_removed())
+        self.pool.retain(|i| !i.can_be_removed());
+
+        self.rebuild_grid(&scene.graph);
+    }
+
+    fn cell_of(position: Vector3<f32>) -> (i32, i32) {
+        (
+            (position.x / GRID_CELL_SIZE).floor() as i32,
+            (position.z / GRID_CELL_SIZE).floor() as i32,
+        )
+    }
+
+    fn rebuild_grid(&mut self, graph: &Graph) {
+        for cell in self.grid.values_mut() {
+            cell.clear();
+        }
+        for (handle, item) in self.pool.pair_iter() {
+            let cell = Self::cell_of(graph[item.pivot].global_position());
+            self.grid.entry(cell).or_default().push(handle);
+        }
+    }
+
+    /// Returns handles of items that *may* be within given radius around the point,
+    /// exact distance must be checked by caller.
+    pub fn query(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = Handle<Item>> + '_ {
+        let (min_x, min_z) = Self::cell_of(position - Vector3::new(radius, 0.0, radius));
+        let (max_x, max_z) = Self::cell_of(position + Vector3::new(radius, 0.0, radius));
+        (min_x..=max_x)
+            .flat_map(move |x| (min_z..=max_z).map(move |z| (x, z)))
+            .filter_map(move |cell| self.grid.get(&cell))
+            .flat_map(|cell| cell.iter().cloned())
     }
 }
@@ -1,11 +1,12 @@
 use rg3d::{
     core::{
         algebra::Vector3,
-        pool::{Handle, Pool, PoolIterator},
+        pool::{Handle, Pool, PoolIterator, PoolPairIterator},
         visitor::{Visit, VisitResult, Visitor},
     },
     physics3d::RigidBodyHandle,
 };
+use std::collections::HashMap;
 
 pub struct JumpPad {
     force: Vector3<f32>,
@@ -48,6 +49,9 @@ impl Visit for JumpPad {
 
 pub struct JumpPadContainer {
     pool: Pool<JumpPad>,
+    // Maps rigid body of a jump pad to the jump pad, this allows to resolve contacts
+    // without iterating over every jump pad.
+    body_map: HashMap<RigidBodyHandle, Handle<JumpPad>>,
 }
 
 impl Default for JumpPadContainer {
@@ -58,16 +62,32 @@ impl Default for JumpPadContainer {
 
 impl JumpPadContainer {
     pub fn new() -> Self {
-        Self { pool: Pool::new() }
+        Self {
+            pool: Pool::new(),
+            body_map: Default::default(),
+        }
     }
 
     pub fn add(&mut self, jump_pad: JumpPad) -> Handle<JumpPad> {
-        self.pool.spawn(jump_pad)
+        let body = jump_pad.body;
+        let handle = self.pool.spawn(jump_pad);
+        self.body_map.insert(body, handle);
+        handle
+    }
+
+    pub fn find_by_body(&self, body: RigidBodyHandle) -> Option<&JumpPad> {
+        self.body_map
+            .get(&body)
+            .map(|&handle| self.pool.borrow(handle))
     }
 
     pub fn iter(&self) -> PoolIterator<JumpPad> {
         self.pool.iter()
     }
+
+    pub fn pair_iter(&self) -> PoolPairIterator<JumpPad> {
+        self.pool.pair_iter()
+    }
 }
 
 impl Visit for JumpPadContainer {
@@ -76,6 +96,14 @@ impl Visit for JumpPadContainer {
 
         self.pool.visit("Pool", visitor)?;
 
+        if visitor.is_reading() {
+            self.body_map = self
+                .pool
+                .pair_iter()
+                .map(|(handle, jump_pad)| (jump_pad.body, handle))
+                .collect();
+        }
+
         visitor.leave_region()
     }
 }
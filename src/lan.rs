@@ -0,0 +1,172 @@
+//! LAN game discovery. Host of a match periodically broadcasts a small beacon with match info
+//! over UDP, clients listen on well-known port and collect beacons into a list of LAN games.
+//! No master server is involved, so discovery works in local network only.
+//!
+//! Beacon is a UTF-8 text, one field per line: magic string, protocol version, host name,
+//! map name, mode name and player count.
+
+use crate::logger::{self, Category};
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+/// Port clients listen on for beacons.
+pub const DISCOVERY_PORT: u16 = 27960;
+
+const MAGIC: &str = "RUSTY-SHOOTER";
+
+const PROTOCOL_VERSION: u32 = 1;
+
+/// How often (in seconds) host broadcasts beacon.
+const BEACON_INTERVAL: f32 = 1.0;
+
+/// Game is removed from the list if no beacon was received from it for this time.
+const GAME_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Beacon {
+    pub host: String,
+    pub map: String,
+    pub mode: String,
+    pub players: u32,
+}
+
+impl Beacon {
+    fn encode(&self) -> String {
+        // Line breaks would break the format, so they are stripped from text fields.
+        let clean = |s: &str| s.replace(|c| c == '\n' || c == '\r', " ");
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            MAGIC,
+            PROTOCOL_VERSION,
+            clean(&self.host),
+            clean(&self.map),
+            clean(&self.mode),
+            self.players
+        )
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(data).ok()?;
+        let mut lines = text.lines();
+        if lines.next()? != MAGIC || lines.next()?.parse::<u32>().ok()? != PROTOCOL_VERSION {
+            return None;
+        }
+        Some(Self {
+            host: lines.next()?.to_owned(),
+            map: lines.next()?.to_owned(),
+            mode: lines.next()?.to_owned(),
+            players: lines.next()?.parse().ok()?,
+        })
+    }
+}
+
+/// Broadcasts beacon of the match that is being played.
+pub struct LanBroadcaster {
+    socket: Option<UdpSocket>,
+    timer: f32,
+    broadcasting: bool,
+}
+
+impl Default for LanBroadcaster {
+    fn default() -> Self {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .and_then(|socket| socket.set_broadcast(true).map(|_| socket));
+        let socket = match socket {
+            Ok(socket) => Some(socket),
+            Err(e) => {
+                logger::warning(
+                    Category::Net,
+                    format!("Unable to create LAN broadcast socket. Reason: {:?}", e),
+                );
+                None
+            }
+        };
+        Self {
+            socket,
+            timer: 0.0,
+            broadcasting: false,
+        }
+    }
+}
+
+impl LanBroadcaster {
+    /// Sends beacon once in a while, `None` means that nothing is hosted right now.
+    pub fn update(&mut self, beacon: Option<Beacon>, dt: f32) {
+        self.timer -= dt;
+        self.broadcasting = self.socket.is_some() && beacon.is_some();
+        if let (Some(socket), Some(beacon)) = (self.socket.as_ref(), beacon) {
+            if self.timer <= 0.0 {
+                self.timer = BEACON_INTERVAL;
+                // Broadcast may fail when there is no network, it is fine to skip a beacon.
+                let _ = socket.send_to(
+                    beacon.encode().as_bytes(),
+                    (Ipv4Addr::BROADCAST, DISCOVERY_PORT),
+                );
+            }
+        }
+    }
+
+    /// Whether the match is announced in LAN right now, so others may be looking for it.
+    pub fn is_broadcasting(&self) -> bool {
+        self.broadcasting
+    }
+}
+
+pub struct LanGame {
+    pub address: SocketAddr,
+    pub beacon: Beacon,
+    last_seen: Instant,
+}
+
+/// Listens for beacons and keeps list of games that are currently broadcasting.
+pub struct LanListener {
+    socket: UdpSocket,
+    games: Vec<LanGame>,
+}
+
+impl LanListener {
+    pub fn new() -> Result<Self, String> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT))
+            .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+            .map_err(|e| format!("Unable to listen for LAN games. Reason: {:?}", e))?;
+        Ok(Self {
+            socket,
+            games: Default::default(),
+        })
+    }
+
+    /// Reads every pending beacon and forgets games that went silent. Returns true if list
+    /// of games has changed.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        let mut buffer = [0u8; 512];
+        while let Ok((size, address)) = self.socket.recv_from(&mut buffer) {
+            if let Some(beacon) = Beacon::decode(&buffer[..size]) {
+                let now = Instant::now();
+                if let Some(game) = self.games.iter_mut().find(|game| game.address == address) {
+                    changed |= game.beacon != beacon;
+                    game.beacon = beacon;
+                    game.last_seen = now;
+                } else {
+                    self.games.push(LanGame {
+                        address,
+                        beacon,
+                        last_seen: now,
+                    });
+                    changed = true;
+                }
+            }
+        }
+
+        let count = self.games.len();
+        self.games
+            .retain(|game| game.last_seen.elapsed() < GAME_TIMEOUT);
+        changed || count != self.games.len()
+    }
+
+    pub fn games(&self) -> &[LanGame] {
+        &self.games
+    }
+}
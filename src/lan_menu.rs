@@ -0,0 +1,127 @@
+use crate::{
+    lan::LanListener,
+    localization::{tr, tr_format},
+    logger::{self, Category},
+};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        Thickness, UiNode, UserInterface,
+    },
+};
+
+pub struct LanMenu {
+    pub window: Handle<UiNode>,
+    list: Handle<UiNode>,
+    status: Handle<UiNode>,
+    items: Vec<Handle<UiNode>>,
+    // Listens only while the window is open, so the port is not held for nothing.
+    listener: Option<LanListener>,
+}
+
+impl LanMenu {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let status =
+            TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(4.0))).build(ctx);
+        let list;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(500.0))
+            .with_title(WindowTitle::text(tr("LAN Games")))
+            .open(false)
+            .with_content({
+                list = StackPanelBuilder::new(WidgetBuilder::new().with_child(status)).build(ctx);
+                list
+            })
+            .build(ctx);
+
+        Self {
+            window,
+            list,
+            status,
+            items: Default::default(),
+            listener: None,
+        }
+    }
+
+    pub fn open(&mut self, ui: &mut UserInterface) {
+        let status = match LanListener::new() {
+            Ok(listener) => {
+                self.listener = Some(listener);
+                "Searching for games..."
+            }
+            Err(e) => {
+                self.listener = None;
+                logger::warning(Category::Net, e);
+                "Unable to search for LAN games"
+            }
+        };
+        ui.send_message(TextMessage::text(
+            self.status,
+            MessageDirection::ToWidget,
+            tr(status),
+        ));
+        self.rebuild(ui);
+
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    fn rebuild(&mut self, ui: &mut UserInterface) {
+        for item in self.items.drain(..) {
+            ui.send_message(WidgetMessage::remove(item, MessageDirection::ToWidget));
+        }
+
+        let games = self
+            .listener
+            .as_ref()
+            .map_or(&[][..], |listener| listener.games());
+        ui.send_message(WidgetMessage::visibility(
+            self.status,
+            MessageDirection::ToWidget,
+            games.is_empty(),
+        ));
+        for game in games {
+            let text = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(4.0)))
+                .with_text(&tr_format(
+                    "{} - {} - {} - {} players ({})",
+                    &[
+                        &game.beacon.host,
+                        &game.beacon.map,
+                        &tr(&game.beacon.mode),
+                        &game.beacon.players,
+                        &game.address.ip(),
+                    ],
+                ))
+                .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                text,
+                MessageDirection::ToWidget,
+                self.list,
+            ));
+            self.items.push(text);
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface) {
+        if !ui.node(self.window).visibility() {
+            // Window was closed.
+            self.listener = None;
+            return;
+        }
+
+        if let Some(listener) = self.listener.as_mut() {
+            if listener.poll() {
+                self.rebuild(ui);
+            }
+        }
+    }
+}
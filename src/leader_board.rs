@@ -1,4 +1,13 @@
-use crate::{character::Team, message::Message, MatchOptions};
+use crate::{
+    announcer::Announcement,
+    character::Team,
+    localization::{tr, tr_format},
+    message::Message,
+    saves,
+    score::ScoreEvent,
+    theme::Palette,
+    MatchOptions,
+};
 use rg3d::{
     core::{
         color::Color,
@@ -6,14 +15,15 @@ use rg3d::{
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::Engine,
-    event::{ElementState, Event, VirtualKeyCode, WindowEvent},
+    event::{Event, WindowEvent},
     gui::{
+        border::BorderBuilder,
         brush::Brush,
         grid::{Column, GridBuilder, Row},
         message::MessageDirection,
         text::TextBuilder,
         widget::{WidgetBuilder, WidgetMessage},
-        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+        BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
 };
 use std::collections::HashMap;
@@ -22,6 +32,13 @@ use std::collections::HashMap;
 pub struct PersonalScore {
     pub kills: u32,
     pub deaths: u32,
+    pub assists: u32,
+    /// Sum of points of score events, may be negative because of suicides.
+    pub score: i32,
+    /// Color of name on leader board.
+    pub color: Color,
+    /// Team of actor, rows are grouped by team in team modes.
+    pub team: Team,
 }
 
 impl Default for PersonalScore {
@@ -29,6 +46,10 @@ impl Default for PersonalScore {
         Self {
             kills: 0,
             deaths: 0,
+            assists: 0,
+            score: 0,
+            color: Color::WHITE,
+            team: Team::None,
         }
     }
 }
@@ -39,11 +60,20 @@ impl Visit for PersonalScore {
 
         self.kills.visit("Kills", visitor)?;
         self.deaths.visit("Deaths", visitor)?;
+        self.color.visit("Color", visitor)?;
+        if saves::read_version(visitor) >= 2 {
+            self.team.visit("Team", visitor)?;
+        }
+        if saves::read_version(visitor) >= 15 {
+            self.assists.visit("Assists", visitor)?;
+            self.score.visit("Score", visitor)?;
+        }
 
         visitor.leave_region()
     }
 }
 
+#[derive(Clone)]
 pub struct LeaderBoard {
     personal_score: HashMap<String, PersonalScore>,
     team_score: HashMap<Team, u32>,
@@ -64,6 +94,29 @@ impl LeaderBoard {
         self.get_or_add_actor(actor_name).deaths += 1;
     }
 
+    pub fn add_score<P: AsRef<str>>(&mut self, actor_name: P, event: ScoreEvent, points: i32) {
+        let score = self.get_or_add_actor(actor_name);
+        if event == ScoreEvent::Assist {
+            score.assists += 1;
+        }
+        score.score += points;
+    }
+
+    /// Resets scores of everyone, names and colors are kept.
+    pub fn reset(&mut self) {
+        for score in self.personal_score.values_mut() {
+            score.kills = 0;
+            score.deaths = 0;
+            score.assists = 0;
+            score.score = 0;
+        }
+        self.team_score.clear();
+    }
+
+    pub fn add_team_score(&mut self, team: Team) {
+        *self.team_score.entry(team).or_insert(0) += 1;
+    }
+
     pub fn team_score(&self, team: Team) -> u32 {
         match self.team_score.get(&team) {
             None => 0,
@@ -124,7 +177,27 @@ impl LeaderBoard {
                     }
                 }
                 false
+            } // Survival ends when player dies, level takes care of it.
+            MatchOptions::Survival(_) => false,
+        }
+    }
+
+    /// Returns true if there is no single leader: top two characters have same amount of
+    /// frags in death match, or both teams have same score in team modes.
+    pub fn is_tied(&self, options: &MatchOptions) -> bool {
+        match options {
+            MatchOptions::DeathMatch(_) => {
+                if let Some((leader, highest_score)) = self.highest_personal_score(None) {
+                    if let Some((_, second_score)) = self.highest_personal_score(Some(leader)) {
+                        return highest_score == second_score;
+                    }
+                }
+                false
             }
+            MatchOptions::TeamDeathMatch(_) | MatchOptions::CaptureTheFlag(_) => {
+                self.team_score(Team::Red) == self.team_score(Team::Blue)
+            }
+            MatchOptions::Survival(_) => false,
         }
     }
 }
@@ -151,6 +224,40 @@ impl Visit for LeaderBoard {
 
 pub struct LeaderBoardUI {
     root: Handle<UiNode>,
+    palette: Palette,
+    // Name of local player, its row is highlighted.
+    local_player: String,
+}
+
+/// Builds a cell of the table, cells of local player's row have highlighted background.
+fn make_cell(
+    ctx: &mut BuildContext,
+    row: usize,
+    column: usize,
+    text: String,
+    color: Color,
+    highlight: bool,
+) -> Handle<UiNode> {
+    let text = TextBuilder::new(
+        WidgetBuilder::new()
+            .with_margin(Thickness::uniform(3.0))
+            .with_foreground(Brush::Solid(color)),
+    )
+    .with_text(text)
+    .build(ctx);
+    BorderBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(column)
+            .with_background(Brush::Solid(if highlight {
+                Color::opaque(70, 70, 30)
+            } else {
+                Color::TRANSPARENT
+            }))
+            .with_foreground(Brush::Solid(Color::TRANSPARENT))
+            .with_child(text),
+    )
+    .build(ctx)
 }
 
 impl LeaderBoardUI {
@@ -169,10 +276,23 @@ impl LeaderBoardUI {
         .add_row(Row::strict(600.0))
         .add_row(Row::stretch())
         .add_column(Column::stretch())
-        .add_column(Column::strict(500.0))
+        .add_column(Column::strict(600.0))
         .add_column(Column::stretch())
         .build(&mut ui.build_ctx());
-        Self { root }
+        Self {
+            root,
+            palette: Default::default(),
+            local_player: Default::default(),
+        }
+    }
+
+    pub fn set_local_player(&mut self, name: &str) {
+        self.local_player = name.to_owned();
+    }
+
+    /// Palette is used next time table is rebuilt.
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
     }
 
     fn sync_to_model(
@@ -190,60 +310,115 @@ impl LeaderBoardUI {
 
         let row_template = Row::strict(30.0);
 
+        let team_mode = !matches!(
+            match_options,
+            MatchOptions::DeathMatch(_) | MatchOptions::Survival(_)
+        );
+
+        let mut scores = leader_board.values().iter().collect::<Vec<_>>();
+        scores.sort_by(|(a_name, a), (b_name, b)| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.kills.cmp(&a.kills))
+                .then_with(|| a_name.cmp(b_name))
+        });
+
+        // Team modes have a header row before players of each team.
+        let mut groups = Vec::new();
+        if team_mode {
+            for &team in &[Team::Red, Team::Blue, Team::None] {
+                let members = scores
+                    .iter()
+                    .filter(|(_, score)| score.team == team)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !members.is_empty() {
+                    groups.push((Some(team), members));
+                }
+            }
+        } else {
+            groups.push((None, scores));
+        }
+
         let mut children = Vec::new();
+        let mut row = 1;
 
-        for (i, (name, score)) in leader_board.values().iter().enumerate() {
-            let row = i + 1;
+        for (team, members) in groups {
+            if let Some(team) = team {
+                let header = if team == Team::None {
+                    tr("No team")
+                } else {
+                    tr_format(
+                        "{} team - {}",
+                        &[&tr(&format!("{:?}", team)), &leader_board.team_score(team)],
+                    )
+                };
+                children.push(make_cell(
+                    ctx,
+                    row,
+                    0,
+                    header,
+                    self.palette.theme().team_color(team),
+                    false,
+                ));
+                row += 1;
+            }
 
-            children.push(
-                TextBuilder::new(
-                    WidgetBuilder::new()
-                        .with_margin(Thickness::uniform(3.0))
-                        .on_row(row)
-                        .on_column(0),
-                )
-                .with_text(name)
-                .build(ctx),
-            );
-
-            children.push(
-                TextBuilder::new(
-                    WidgetBuilder::new()
-                        .with_margin(Thickness::uniform(3.0))
-                        .on_row(row)
-                        .on_column(1),
-                )
-                .with_text(format!("{}", score.kills))
-                .build(ctx),
-            );
-
-            children.push(
-                TextBuilder::new(
-                    WidgetBuilder::new()
-                        .with_margin(Thickness::uniform(3.0))
-                        .on_row(row)
-                        .on_column(2),
-                )
-                .with_text(format!("{}", score.deaths))
-                .build(ctx),
-            );
+            for (name, score) in members {
+                let highlight = *name == self.local_player;
 
-            let kd = if score.deaths != 0 {
-                format!("{}", score.kills as f32 / score.deaths as f32)
-            } else {
-                "N/A".to_owned()
-            };
-
-            children.push(
-                TextBuilder::new(
-                    WidgetBuilder::new()
-                        .with_margin(Thickness::uniform(3.0))
-                        .on_row(row)
-                        .on_column(3),
-                )
-                .with_text(kd)
-                .build(ctx),
-            );
+                let kd = if score.deaths != 0 {
+                    format!("{}", score.kills as f32 / score.deaths as f32)
+                } else {
+                    "N/A".to_owned()
+                };
+
+                children.push(make_cell(ctx, row, 0, name.clone(), score.color, highlight));
+                children.push(make_cell(
+                    ctx,
+                    row,
+                    1,
+                    format!("{}", score.score),
+                    Color::WHITE,
+                    highlight,
+                ));
+                children.push(make_cell(
+                    ctx,
+                    row,
+                    2,
+                    format!("{}", score.kills),
+                    Color::WHITE,
+                    highlight,
+                ));
+                children.push(make_cell(
+                    ctx,
+                    row,
+                    3,
+                    format!("{}", score.assists),
+                    Color::WHITE,
+                    highlight,
+                ));
+                children.push(make_cell(
+                    ctx,
+                    row,
+                    4,
+                    format!("{}", score.deaths),
+                    Color::WHITE,
+                    highlight,
+                ));
+                children.push(make_cell(ctx, row, 5, kd, Color::WHITE, highlight));
+                // There is no network play yet, so there is no ping to show.
+                children.push(make_cell(
+                    ctx,
+                    row,
+                    6,
+                    "-".to_owned(),
+                    Color::WHITE,
+                    highlight,
+                ));
+
+                row += 1;
+            }
         }
 
         let table = GridBuilder::new(
@@ -259,29 +434,25 @@ impl LeaderBoardUI {
                             .with_horizontal_alignment(HorizontalAlignment::Center),
                     )
                     .with_text({
-                        let time_limit_secs = match match_options {
-                            MatchOptions::DeathMatch(dm) => dm.time_limit_secs,
-                            MatchOptions::TeamDeathMatch(tdm) => tdm.time_limit_secs,
-                            MatchOptions::CaptureTheFlag(ctf) => ctf.time_limit_secs,
-                        };
+                        let time_limit_secs = match_options.time_limit_secs();
 
                         let seconds = (time_limit_secs % 60.0) as u32;
                         let minutes = (time_limit_secs / 60.0) as u32;
                         let hours = (time_limit_secs / 3600.0) as u32;
 
-                        match match_options {
-                            MatchOptions::DeathMatch(_) => format!(
-                                "Death Match - Time Limit {:02}:{:02}:{:02}",
-                                hours, minutes, seconds
-                            ),
-                            MatchOptions::TeamDeathMatch(_) => format!(
-                                "Team Death Match - Time Limit {:02}:{:02}:{:02}",
-                                hours, minutes, seconds
-                            ),
-                            MatchOptions::CaptureTheFlag(_) => format!(
-                                "Capture The Flag - Time Limit {:02}:{:02}:{:02}",
-                                hours, minutes, seconds
-                            ),
+                        let time_limit = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
+
+                        let mode = match match_options {
+                            MatchOptions::DeathMatch(_) => Some("Death Match"),
+                            MatchOptions::TeamDeathMatch(_) => Some("Team Death Match"),
+                            MatchOptions::CaptureTheFlag(_) => Some("Capture The Flag"),
+                            MatchOptions::Survival(_) => None,
+                        };
+                        match mode {
+                            Some(mode) => {
+                                tr_format("{} - Time Limit {}", &[&tr(mode), &time_limit])
+                            }
+                            None => tr("Survival"),
                         }
                     })
                     .build(ctx),
@@ -292,13 +463,15 @@ impl LeaderBoardUI {
                             let text = if let Some((name, kills)) =
                                 leader_board.highest_personal_score(None)
                             {
-                                format!(
-                                    "{} leads with {} frags\nPlaying until {} frags",
-                                    name, kills, dm.frag_limit
-                                )
+                                tr_format("{} leads with {} frags", &[&name, &kills])
                             } else {
-                                format!("Draw\nPlaying until {} frags", dm.frag_limit)
+                                tr("Draw")
                             };
+                            let text = format!(
+                                "{}\n{}",
+                                text,
+                                tr_format("Playing until {} frags", &[&dm.frag_limit])
+                            );
                             TextBuilder::new(
                                 WidgetBuilder::new()
                                     .with_margin(Thickness::uniform(5.0))
@@ -312,42 +485,66 @@ impl LeaderBoardUI {
                         MatchOptions::TeamDeathMatch(tdm) => {
                             let red_score = leader_board.team_score(Team::Red);
                             let blue_score = leader_board.team_score(Team::Blue);
+                            let leader = if red_score > blue_score {
+                                Team::Red
+                            } else {
+                                Team::Blue
+                            };
 
                             TextBuilder::new(
                                 WidgetBuilder::new()
+                                    .with_foreground(Brush::Solid(
+                                        self.palette.theme().team_color(leader),
+                                    ))
                                     .with_margin(Thickness::uniform(5.0))
                                     .with_horizontal_alignment(HorizontalAlignment::Center)
                                     .on_column(0)
                                     .on_row(1),
                             )
                             .with_text(format!(
-                                "{} team leads\nRed {} - {} Blue\nPlaying until {} frags",
-                                if red_score > blue_score {
-                                    "Red"
-                                } else {
-                                    "Blue"
-                                },
-                                red_score,
-                                blue_score,
-                                tdm.team_frag_limit
+                                "{}\n{}\n{}",
+                                tr_format("{} team leads", &[&tr(&format!("{:?}", leader))]),
+                                tr_format("Red {} - {} Blue", &[&red_score, &blue_score]),
+                                tr_format("Playing until {} frags", &[&tdm.team_frag_limit])
                             ))
                             .build(ctx)
                         }
                         MatchOptions::CaptureTheFlag(ctf) => {
-                            // TODO - implement when CTF mode implemented
+                            let red_score = leader_board.team_score(Team::Red);
+                            let blue_score = leader_board.team_score(Team::Blue);
+                            let leader = if red_score > blue_score {
+                                Team::Red
+                            } else {
+                                Team::Blue
+                            };
+
                             TextBuilder::new(
                                 WidgetBuilder::new()
+                                    .with_foreground(Brush::Solid(
+                                        self.palette.theme().team_color(leader),
+                                    ))
                                     .with_margin(Thickness::uniform(5.0))
                                     .with_horizontal_alignment(HorizontalAlignment::Center)
                                     .on_column(0)
                                     .on_row(1),
                             )
                             .with_text(format!(
-                                "Red team leads\nRed 0 - 0 Blue\nPlaying until {} flags",
-                                ctf.flag_limit
+                                "{}\n{}\n{}",
+                                tr_format("{} team leads", &[&tr(&format!("{:?}", leader))]),
+                                tr_format("Red {} - {} Blue", &[&red_score, &blue_score]),
+                                tr_format("Playing until {} flags", &[&ctf.flag_limit])
                             ))
                             .build(ctx)
                         }
+                        MatchOptions::Survival(_) => TextBuilder::new(
+                            WidgetBuilder::new()
+                                .with_margin(Thickness::uniform(5.0))
+                                .with_horizontal_alignment(HorizontalAlignment::Center)
+                                .on_column(0)
+                                .on_row(1),
+                        )
+                        .with_text(tr("Survive as many waves as you can"))
+                        .build(ctx),
                     }
                 })
                 .with_child(
@@ -364,7 +561,7 @@ impl LeaderBoardUI {
                                         .on_column(0)
                                         .on_row(0),
                                 )
-                                .with_text("Name")
+                                .with_text(tr("Name"))
                                 .build(ctx),
                             )
                             .with_child(
@@ -375,7 +572,7 @@ impl LeaderBoardUI {
                                         .on_column(1)
                                         .on_row(0),
                                 )
-                                .with_text("Kills")
+                                .with_text(tr("Score"))
                                 .build(ctx),
                             )
                             .with_child(
@@ -386,7 +583,7 @@ impl LeaderBoardUI {
                                         .on_column(2)
                                         .on_row(0),
                                 )
-                                .with_text("Deaths")
+                                .with_text(tr("Kills"))
                                 .build(ctx),
                             )
                             .with_child(
@@ -397,23 +594,55 @@ impl LeaderBoardUI {
                                         .on_column(3)
                                         .on_row(0),
                                 )
-                                .with_text("K/D")
+                                .with_text(tr("Assists"))
+                                .build(ctx),
+                            )
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                                        .with_vertical_alignment(VerticalAlignment::Center)
+                                        .on_column(4)
+                                        .on_row(0),
+                                )
+                                .with_text(tr("Deaths"))
+                                .build(ctx),
+                            )
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                                        .with_vertical_alignment(VerticalAlignment::Center)
+                                        .on_column(5)
+                                        .on_row(0),
+                                )
+                                .with_text(tr("K/D"))
+                                .build(ctx),
+                            )
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                                        .with_vertical_alignment(VerticalAlignment::Center)
+                                        .on_column(6)
+                                        .on_row(0),
+                                )
+                                .with_text(tr("Ping"))
                                 .build(ctx),
                             )
                             .with_children(children),
                     )
                     .with_border_thickness(2.0)
                     .add_row(Row::strict(30.0))
-                    .add_rows(
-                        (0..leader_board.values().len())
-                            .map(|_| row_template)
-                            .collect(),
-                    )
+                    .add_rows((1..row).map(|_| row_template).collect())
                     .add_row(Row::stretch())
                     .add_column(Column::stretch())
                     .add_column(Column::stretch())
                     .add_column(Column::stretch())
                     .add_column(Column::stretch())
+                    .add_column(Column::stretch())
+                    .add_column(Column::stretch())
+                    .add_column(Column::stretch())
                     .draw_border(true)
                     .build(ctx),
                 ),
@@ -444,34 +673,21 @@ impl LeaderBoardUI {
     }
 
     pub fn process_input_event(&mut self, engine: &mut Engine, event: &Event<()>) {
-        if let Event::WindowEvent { event, .. } = event {
-            match event {
-                WindowEvent::Resized(new_size) => {
-                    engine.user_interface.send_message(WidgetMessage::width(
-                        self.root,
-                        MessageDirection::ToWidget,
-                        new_size.width as f32,
-                    ));
-                    engine.user_interface.send_message(WidgetMessage::height(
-                        self.root,
-                        MessageDirection::ToWidget,
-                        new_size.height as f32,
-                    ));
-                }
-                WindowEvent::KeyboardInput { input, .. } => {
-                    if let Some(vk) = input.virtual_keycode {
-                        if vk == VirtualKeyCode::Tab {
-                            let visible = match input.state {
-                                ElementState::Pressed => true,
-                                ElementState::Released => false,
-                            };
-
-                            self.set_visible(visible, &mut engine.user_interface);
-                        }
-                    }
-                }
-                _ => {}
-            }
+        if let Event::WindowEvent {
+            event: WindowEvent::Resized(new_size),
+            ..
+        } = event
+        {
+            engine.user_interface.send_message(WidgetMessage::width(
+                self.root,
+                MessageDirection::ToWidget,
+                new_size.width as f32,
+            ));
+            engine.user_interface.send_message(WidgetMessage::height(
+                self.root,
+                MessageDirection::ToWidget,
+                new_size.height as f32,
+            ));
         }
     }
 
@@ -488,6 +704,10 @@ impl LeaderBoardUI {
             Message::SpawnBot { .. } => self.sync_to_model(ui, leader_board, match_options),
             Message::SpawnPlayer => self.sync_to_model(ui, leader_board, match_options),
             Message::RespawnActor { .. } => self.sync_to_model(ui, leader_board, match_options),
+            Message::Announce {
+                announcement: Announcement::FlagCaptured { .. },
+            } => self.sync_to_model(ui, leader_board, match_options),
+            Message::ShowScorePopup { .. } => self.sync_to_model(ui, leader_board, match_options),
             _ => (),
         }
     }
@@ -1,49 +1,82 @@
 use crate::{
     actor::{Actor, ActorContainer},
+    announcer::Announcement,
+    assets,
     bot::{Bot, BotKind},
+    campaign::Loadout,
+    character::{Team, BURN_DAMAGE, SPAWN_PROTECTION_TIME},
+    checkpoint::{self, BotState, Checkpoint, Snapshot},
     control_scheme::ControlScheme,
-    effects,
-    item::{Item, ItemContainer, ItemKind},
+    damage_effects,
+    debug_overlay::{DebugOverlay, DebugOverlays},
+    director::{Director, DirectorEvent, PlayerCondition},
+    effects::{EffectKind, EffectPool},
+    flag::{self, Flag, FlagContainer, FlagEvent, FlagState},
+    heatmap::{Heatmap, HeatmapKind},
+    hitbox::HitZone,
+    inspector::{Entity, EntityInfo},
+    item::{Item, ItemContainer, ItemKind, AMMO_AMOUNT, MEDKIT_HEALTH},
     jump_pad::{JumpPad, JumpPadContainer},
     leader_board::LeaderBoard,
+    localization::{tr, tr_format},
+    logger::{self, Category},
+    map_layout::{MapLayout, PlacementKind},
+    match_recorder::{self, MatchEvent, MatchRecorder},
     message::Message,
+    mine::{Mine, MineContainer},
+    names, navmesh_builder,
+    observer::{self, Observer},
+    pak,
+    ping::{self, PingKind, Pings},
     player::Player,
+    practice::Practice,
     projectile::{Projectile, ProjectileContainer, ProjectileKind},
-    weapon::{Weapon, WeaponContainer, WeaponKind},
-    GameTime, MatchOptions,
+    saves,
+    score::{AssistTracker, ScoreEvent},
+    settings::{Profile, Settings},
+    spawn::{SpawnPoint, SpawnPoints, SPAWN_HEIGHT},
+    squad::{Member, Order, SquadCoordinator},
+    teleporter::{self, Teleporter},
+    theme::{Palette, Theme},
+    training::{Objective, PlayerState, Training},
+    turret::{Turret, TurretContainer},
+    voice::{VoiceBank, VoiceEvent},
+    vote::{Vote, VoteResult, VoteSubject},
+    waypoints::{View, Waypoint},
+    weapon::{Shot, Weapon, WeaponContainer, WeaponKind},
+    weapon_stats::WeaponStats,
+    Difficulty, GameTime, HealthDensity, ItemEconomy, MatchOptions, Mutators, Overtime,
 };
 use rg3d::core::algebra::Point3;
 use rg3d::engine::Engine;
 use rg3d::{
     core::{
-        algebra::{Matrix3, Vector3},
+        algebra::{Isometry3, Matrix3, UnitQuaternion, Vector3},
         color::Color,
         math::{aabb::AxisAlignedBoundingBox, ray::Ray, PositionProvider, Vector3Ext},
         pool::Handle,
         rand::Rng,
         visitor::{Visit, VisitResult, Visitor},
     },
-    engine::resource_manager::{MaterialSearchOptions, ResourceManager},
-    event::Event,
+    engine::resource_manager::ResourceManager,
+    event::{DeviceEvent, Event, WindowEvent},
     physics3d::{
         rapier::{
-            geometry::{ContactEvent, InteractionGroups, IntersectionEvent},
+            dynamics::{RigidBodyBuilder, RigidBodyType},
+            geometry::{ColliderBuilder, ContactEvent, InteractionGroups, IntersectionEvent},
             pipeline::ChannelEventCollector,
         },
         RayCastOptions,
     },
     rand,
-    scene::{self, base::BaseBuilder, camera::CameraBuilder, node::Node, Scene},
+    scene::{self, base::BaseBuilder, camera::CameraBuilder, node::Node, physics::Physics, Scene},
     sound::{
         context,
         context::SoundContext,
         effects::{BaseEffect, Effect, EffectInput},
         source::{generic::GenericSourceBuilder, spatial::SpatialSourceBuilder, Status},
     },
-    utils::{
-        log::{Log, MessageKind},
-        navmesh::Navmesh,
-    },
+    utils::navmesh::Navmesh,
 };
 use std::{
     path::{Path, PathBuf},
@@ -53,10 +86,300 @@ use std::{
 
 pub const RESPAWN_TIME: f32 = 4.0;
 
+/// Time (in seconds) after which items dropped by dead actors disappear.
+pub const DROPPED_ITEM_LIFETIME: f32 = 20.0;
+
+/// Time (in seconds) of warm-up before match, scores are not counted during warm-up.
+pub const WARM_UP_TIME: f32 = 20.0;
+
+/// Duration of 3-2-1 countdown between warm-up and match start.
+pub const COUNTDOWN_TIME: f32 = 3.0;
+
+const COUNTDOWN_SOUND: &str = "data/sounds/item_pickup.ogg";
+
+/// Remaining time (in minutes) that is called out by announcer.
+const TIME_CALLOUTS: [u32; 2] = [5, 1];
+
+/// Fraction of normal gravity when low gravity mutator is active.
+const LOW_GRAVITY_SCALE: f32 = 0.35;
+
+/// Fraction of dealt damage that attacker gets back as health with vampire mutator.
+const VAMPIRE_FACTOR: f32 = 0.5;
+
+/// Bots closer than this distance to player are considered a threat, they keep combat
+/// music playing.
+const THREAT_RADIUS: f32 = 15.0;
+
+/// Max distance (in meters) of a hit of beam weapons.
+const BEAM_RANGE: f32 = 25.0;
+
+/// Damage of each tick of beam.
+const BEAM_DAMAGE: f32 = 5.0;
+
+const BEAM_COLOR: Color = Color::opaque(140, 180, 255);
+
+/// Beam jumps from the victim to an enemy within this radius.
+const CHAIN_RADIUS: f32 = 6.0;
+
+/// Fraction of beam damage that is dealt by the chained part of beam.
+const CHAIN_FACTOR: f32 = 0.5;
+
+/// Max distance (in meters) from shooter to a surface on which mine or turret can be placed.
+const DEPLOY_RANGE: f32 = 3.0;
+
+/// Y component of normal of surfaces that are flat enough to put a turret on.
+const MIN_FLOOR_NORMAL_Y: f32 = 0.7;
+
+/// Death camera orbits place of player's death at this distance and height.
+const DEATH_CAMERA_RADIUS: f32 = 3.0;
+
+const DEATH_CAMERA_HEIGHT: f32 = 1.5;
+
+/// Angular speed (rad/s) of death camera.
+const DEATH_CAMERA_SPEED: f32 = 0.4;
+
+/// Actor closer than this distance to a spot where another actor spawns is telefragged.
+const TELEFRAG_RADIUS: f32 = 1.0;
+
+/// Enough to kill anyone regardless of armor and damage scale of difficulty.
+const TELEFRAG_DAMAGE: f32 = 10_000.0;
+
+/// How many times per second spawn protected actor blinks.
+const SHIMMER_RATE: f32 = 8.0;
+
+/// Half of size of box that outlines an actor in observer mode.
+const OUTLINE_HALF_SIZE: Vector3<f32> = Vector3::new(0.4, 1.0, 0.4);
+
+const HEARTBEAT_SOUND: &str = "data/sounds/heartbeat.wav";
+
+/// Heartbeat is this slow right below low health and twice as fast near death.
+const HEARTBEAT_INTERVAL: f32 = 1.0;
+
+/// Idle player is warned this many seconds before being moved to observers.
+const AFK_WARNING_TIME: f32 = 10.0;
+
+/// Standing of player (or leading team in team modes), used to detect lead changes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Lead {
+    Ahead,
+    Tied,
+    Behind,
+    Team(Option<Team>),
+}
+
+/// Camera that orbits place of player's death until player respawns.
+#[derive(Copy, Clone, Debug)]
+struct DeathCamera {
+    focus: Vector3<f32>,
+    angle: f32,
+}
+
+impl DeathCamera {
+    /// Camera looks along its Z axis, so it is turned around to face the focus.
+    fn rotation(&self) -> UnitQuaternion<f32> {
+        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.angle + std::f32::consts::PI)
+            * UnitQuaternion::from_axis_angle(
+                &Vector3::x_axis(),
+                DEATH_CAMERA_HEIGHT.atan2(DEATH_CAMERA_RADIUS),
+            )
+    }
+
+    fn position(&self) -> Vector3<f32> {
+        self.focus
+            + Vector3::new(
+                self.angle.sin() * DEATH_CAMERA_RADIUS,
+                DEATH_CAMERA_HEIGHT,
+                self.angle.cos() * DEATH_CAMERA_RADIUS,
+            )
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MatchPhase {
+    /// Actors can move and fight, but scores are not counted.
+    WarmUp {
+        time_left: f32,
+    },
+    Countdown {
+        time_left: f32,
+    },
+    Live,
+}
+
+impl Default for MatchPhase {
+    fn default() -> Self {
+        MatchPhase::Live
+    }
+}
+
+impl MatchPhase {
+    fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(MatchPhase::WarmUp { time_left: 0.0 }),
+            1 => Ok(MatchPhase::Countdown { time_left: 0.0 }),
+            2 => Ok(MatchPhase::Live),
+            _ => Err(format!("Invalid match phase {}", id)),
+        }
+    }
+
+    fn id(&self) -> u32 {
+        match self {
+            MatchPhase::WarmUp { .. } => 0,
+            MatchPhase::Countdown { .. } => 1,
+            MatchPhase::Live => 2,
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        *self == MatchPhase::Live
+    }
+}
+
+/// State of the match after its regular time ran out with tied scores.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OvertimeState {
+    Regular,
+    /// Time limit was extended by `Overtime::ExtraTime`.
+    Extra,
+    /// First score change ends the match.
+    SuddenDeath,
+}
+
+impl Default for OvertimeState {
+    fn default() -> Self {
+        OvertimeState::Regular
+    }
+}
+
+impl OvertimeState {
+    fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(OvertimeState::Regular),
+            1 => Ok(OvertimeState::Extra),
+            2 => Ok(OvertimeState::SuddenDeath),
+            _ => Err(format!("Invalid overtime state {}", id)),
+        }
+    }
+
+    fn id(&self) -> u32 {
+        match self {
+            OvertimeState::Regular => 0,
+            OvertimeState::Extra => 1,
+            OvertimeState::SuddenDeath => 2,
+        }
+    }
+}
+
+impl Visit for OvertimeState {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        let mut id = self.id();
+        id.visit("Id", visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+
+        visitor.leave_region()
+    }
+}
+
+impl Visit for MatchPhase {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        let mut id = self.id();
+        id.visit("Id", visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        match self {
+            MatchPhase::WarmUp { time_left } | MatchPhase::Countdown { time_left } => {
+                time_left.visit("TimeLeft", visitor)?
+            }
+            MatchPhase::Live => (),
+        }
+
+        visitor.leave_region()
+    }
+}
+
+/// Acoustics of an area of a level. Areas are marked by meshes named `ReverbZone_<Preset>`,
+/// for example `ReverbZone_Tunnel`, everything outside of zones sounds like a hall.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ReverbPreset {
+    Outdoor,
+    Room,
+    Hall,
+    Tunnel,
+}
+
+impl Default for ReverbPreset {
+    fn default() -> Self {
+        ReverbPreset::Hall
+    }
+}
+
+impl ReverbPreset {
+    pub fn id(self) -> u32 {
+        match self {
+            ReverbPreset::Outdoor => 0,
+            ReverbPreset::Room => 1,
+            ReverbPreset::Hall => 2,
+            ReverbPreset::Tunnel => 3,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(ReverbPreset::Outdoor),
+            1 => Ok(ReverbPreset::Room),
+            2 => Ok(ReverbPreset::Hall),
+            3 => Ok(ReverbPreset::Tunnel),
+            _ => Err(format!("Invalid reverb preset id {}!", id)),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Outdoor" => Some(ReverbPreset::Outdoor),
+            "Room" => Some(ReverbPreset::Room),
+            "Hall" => Some(ReverbPreset::Hall),
+            "Tunnel" => Some(ReverbPreset::Tunnel),
+            _ => None,
+        }
+    }
+
+    /// Returns dry and wet parts and decay time (in seconds) of reverb.
+    fn parameters(self) -> (f32, f32, f32) {
+        match self {
+            ReverbPreset::Outdoor => (0.9, 0.1, 0.6),
+            ReverbPreset::Room => (0.7, 0.3, 1.0),
+            ReverbPreset::Hall => (0.5, 0.5, 3.0),
+            ReverbPreset::Tunnel => (0.4, 0.6, 2.0),
+        }
+    }
+}
+
+impl Visit for ReverbPreset {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut id = self.id();
+        id.visit(name, visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fraction of gain left to sounds which are blocked by level geometry from listener.
+const OCCLUDED_GAIN: f32 = 0.35;
+
 #[derive(Default)]
 pub struct SoundManager {
     context: SoundContext,
     reverb: Handle<Effect>,
+    preset: ReverbPreset,
 }
 
 impl SoundManager {
@@ -64,9 +387,10 @@ impl SoundManager {
         let mut base_effect = BaseEffect::default();
         base_effect.set_gain(0.7);
         let mut reverb = rg3d::sound::effects::reverb::Reverb::new(base_effect);
-        reverb.set_dry(0.5);
-        reverb.set_wet(0.5);
-        reverb.set_decay_time(Duration::from_secs_f32(3.0));
+        let (dry, wet, decay_time) = ReverbPreset::default().parameters();
+        reverb.set_dry(dry);
+        reverb.set_wet(wet);
+        reverb.set_decay_time(Duration::from_secs_f32(decay_time));
         let reverb = context
             .state()
             .add_effect(rg3d::sound::effects::Effect::Reverb(reverb));
@@ -85,7 +409,62 @@ impl SoundManager {
         Self { context, reverb }
     }
 
-    pub async fn handle_message(&mut self, resource_manager: ResourceManager, message: &Message) {
+    /// Switches reverb to preset of a zone which contains listener.
+    pub fn update(&mut self, zones: &[ReverbZone]) {
+        let mut state = self.context.state();
+        let listener = state.listener().position();
+        let preset = zones
+            .iter()
+            .find(|zone| zone.bounds.is_contains_point(listener))
+            .map_or_else(ReverbPreset::default, |zone| zone.preset);
+        if preset != self.preset {
+            self.preset = preset;
+            if let Effect::Reverb(reverb) = state.effect_mut(self.reverb) {
+                let (dry, wet, decay_time) = preset.parameters();
+                reverb.set_dry(dry);
+                reverb.set_wet(wet);
+                reverb.set_decay_time(Duration::from_secs_f32(decay_time));
+            }
+        }
+    }
+
+    fn set_pitch(&self, pitch: f64) {
+        let mut state = self.context.state();
+        for source in state.sources_mut().iter_mut() {
+            source.generic_mut().set_pitch(pitch);
+        }
+    }
+
+    /// Checks whether level geometry blocks line of sight between listener and a point.
+    fn is_occluded(listener: Vector3<f32>, position: Vector3<f32>, physics: &Physics) -> bool {
+        let ray = Ray::from_two_points(listener, position);
+        let mut query_buffer = Vec::default();
+        physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(ray.origin),
+                ray_direction: ray.dir,
+                max_len: ray.dir.norm(),
+                groups: InteractionGroups::all(),
+                sort_results: false,
+            },
+            &mut query_buffer,
+        );
+        // Only static geometry blocks sounds, actors and other moving bodies do not.
+        query_buffer.iter().any(|hit| {
+            physics
+                .collider_parent(&hit.collider)
+                .and_then(|body| physics.bodies.get(body))
+                .map_or(false, |body| body.is_static())
+        })
+    }
+
+    pub async fn handle_message(
+        &mut self,
+        resource_manager: ResourceManager,
+        message: &Message,
+        time_scale: f32,
+        physics: &Physics,
+    ) {
         let mut state = self.context.state();
 
         match message {
@@ -96,16 +475,30 @@ impl SoundManager {
                 rolloff_factor,
                 radius,
             } => {
-                let shot_buffer = resource_manager
-                    .request_sound_buffer(path, false)
+                // Missing sound is not played at all.
+                let shot_buffer = match resource_manager
+                    .request_sound_buffer(pak::resolve(path), false)
                     .await
-                    .unwrap();
+                {
+                    Ok(buffer) => buffer,
+                    Err(e) => {
+                        assets::report_missing(path, &format!("{:?}", e));
+                        return;
+                    }
+                };
+                let gain = if Self::is_occluded(state.listener().position(), *position, physics) {
+                    *gain * OCCLUDED_GAIN
+                } else {
+                    *gain
+                };
                 let shot_sound = SpatialSourceBuilder::new(
                     GenericSourceBuilder::new()
                         .with_buffer(shot_buffer.into())
                         .with_status(Status::Playing)
                         .with_play_once(true)
-                        .with_gain(*gain)
+                        .with_gain(gain)
+                        // Sounds are pitched along with game speed.
+                        .with_pitch(time_scale as f64)
                         .build()
                         .unwrap(),
                 )
@@ -129,27 +522,51 @@ impl Visit for SoundManager {
 
         self.context.visit("Context", visitor)?;
         self.reverb.visit("Reverb", visitor)?;
+        self.preset.visit("Preset", visitor)?;
 
         visitor.leave_region()
     }
 }
 
 pub struct Level {
+    map: PathBuf,
     map_root: Handle<Node>,
     pub scene: Handle<Scene>,
     player: Handle<Actor>,
+    // Profile of player at the moment when level was created.
+    profile: Profile,
     projectiles: ProjectileContainer,
+    effects: EffectPool,
     pub actors: ActorContainer,
     weapons: WeaponContainer,
     jump_pads: JumpPadContainer,
     items: ItemContainer,
-    spawn_points: Vec<SpawnPoint>,
+    mines: MineContainer,
+    turrets: TurretContainer,
+    spawn_points: SpawnPoints,
     sender: Option<Sender<Message>>,
     pub navmesh: Handle<Navmesh>,
     pub control_scheme: Option<Arc<RwLock<ControlScheme>>>,
+    pub settings: Option<Arc<RwLock<Settings>>>,
     death_zones: Vec<DeathZone>,
+    reverb_zones: Vec<ReverbZone>,
+    teleporters: Vec<Teleporter>,
+    checkpoints: Vec<Checkpoint>,
+    flags: FlagContainer,
     pub options: MatchOptions,
+    // Some if level is the training level.
+    training: Option<Training>,
+    // Some if match is played in survival mode.
+    director: Option<Director>,
+    /// Index of campaign level in `campaign::LEVELS`, None if level is not part of campaign.
+    pub campaign_level: Option<u32>,
     time: f32,
+    phase: MatchPhase,
+    // Level time at which match went live, match time limit is counted from it.
+    match_start_time: f32,
+    overtime: OvertimeState,
+    // Extra seconds added to time limit by overtime.
+    time_limit_extension: f32,
     pub leader_board: LeaderBoard,
     respawn_list: Vec<RespawnEntry>,
     spectator_camera: Handle<Node>,
@@ -157,26 +574,73 @@ pub struct Level {
     sound_manager: SoundManager,
     proximity_events_receiver: Option<crossbeam::channel::Receiver<IntersectionEvent>>,
     contact_events_receiver: Option<crossbeam::channel::Receiver<ContactEvent>>,
+    // Not serialized, log of loaded match starts from the moment of loading.
+    pub match_recorder: MatchRecorder,
+    // Not serialized, voice sets are loaded when level is created.
+    voices: VoiceBank,
+    // Not serialized, it is None until first update so loaded game won't announce anything.
+    lead: Option<Lead>,
+    // Not serialized, death camera of loaded game just stays where it is.
+    death_camera: Option<DeathCamera>,
+    // Not serialized, who killed player and with what.
+    death_description: Option<String>,
+    // Not serialized, vote in progress is cancelled by loading.
+    vote: Option<Vote>,
+    // Some if player left the match to observe it.
+    observer: Option<Observer>,
+    // Not serialized, telemetry of loaded match starts from the moment of loading.
+    pub weapon_stats: WeaponStats,
+    // Not serialized, readout of practice mode starts over after loading.
+    practice: Practice,
+    // Not serialized, time until next heartbeat of player with low health.
+    heartbeat_timer: f32,
+    // Not serialized, pings are short-lived.
+    pings: Pings,
+    // Not serialized, orders of loaded game are cancelled.
+    squad: SquadCoordinator,
+    // Not serialized, damage dealt before loading does not count towards assists.
+    assists: AssistTracker,
+    // Not serialized, seconds since last input of player.
+    idle_time: f32,
+    // Not serialized, loaded game restarts from the next checkpoint player reaches.
+    checkpoint: Option<Snapshot>,
 }
 
 impl Default for Level {
     fn default() -> Self {
         Self {
+            map: Default::default(),
             map_root: Default::default(),
             projectiles: ProjectileContainer::new(),
+            effects: Default::default(),
             actors: ActorContainer::new(),
             scene: Default::default(),
             player: Handle::NONE,
+            profile: Default::default(),
             weapons: WeaponContainer::new(),
             jump_pads: JumpPadContainer::new(),
             items: ItemContainer::new(),
+            mines: MineContainer::new(),
+            turrets: TurretContainer::new(),
             spawn_points: Default::default(),
             sender: None,
             navmesh: Default::default(),
             control_scheme: None,
+            settings: None,
             death_zones: Default::default(),
+            reverb_zones: Default::default(),
+            teleporters: Default::default(),
+            checkpoints: Default::default(),
+            flags: Default::default(),
             options: Default::default(),
+            training: None,
+            director: None,
+            campaign_level: None,
             time: 0.0,
+            phase: Default::default(),
+            match_start_time: 0.0,
+            overtime: Default::default(),
+            time_limit_extension: 0.0,
             leader_board: Default::default(),
             respawn_list: Default::default(),
             spectator_camera: Default::default(),
@@ -184,6 +648,21 @@ impl Default for Level {
             sound_manager: Default::default(),
             proximity_events_receiver: None,
             contact_events_receiver: None,
+            match_recorder: Default::default(),
+            voices: VoiceBank::new(),
+            lead: None,
+            death_camera: None,
+            death_description: None,
+            vote: None,
+            observer: None,
+            weapon_stats: Default::default(),
+            practice: Default::default(),
+            heartbeat_timer: 0.0,
+            pings: Default::default(),
+            squad: Default::default(),
+            assists: Default::default(),
+            idle_time: 0.0,
+            checkpoint: None,
         }
     }
 }
@@ -193,16 +672,30 @@ impl Visit for Level {
         visitor.enter_region(name)?;
 
         self.scene.visit("Scene", visitor)?;
+        self.map.visit("Map", visitor)?;
         self.map_root.visit("MapRoot", visitor)?;
         self.player.visit("Player", visitor)?;
+        self.profile.visit("Profile", visitor)?;
         self.actors.visit("Actors", visitor)?;
         self.projectiles.visit("Projectiles", visitor)?;
+        self.effects.visit("Effects", visitor)?;
         self.weapons.visit("Weapons", visitor)?;
         self.jump_pads.visit("JumpPads", visitor)?;
         self.spawn_points.visit("SpawnPoints", visitor)?;
         self.death_zones.visit("DeathZones", visitor)?;
+        self.reverb_zones.visit("ReverbZones", visitor)?;
         self.options.visit("Options", visitor)?;
+        // Saves made before versioning have neither training nor campaign state.
+        if saves::read_version(visitor) >= 1 {
+            self.training.visit("Training", visitor)?;
+            self.campaign_level.visit("CampaignLevel", visitor)?;
+        }
         self.time.visit("Time", visitor)?;
+        self.phase.visit("Phase", visitor)?;
+        self.match_start_time.visit("MatchStartTime", visitor)?;
+        self.overtime.visit("Overtime", visitor)?;
+        self.time_limit_extension
+            .visit("TimeLimitExtension", visitor)?;
         self.leader_board.visit("LeaderBoard", visitor)?;
         self.respawn_list.visit("RespawnList", visitor)?;
         self.spectator_camera.visit("SpectatorCamera", visitor)?;
@@ -211,6 +704,27 @@ impl Visit for Level {
         self.sound_manager.visit("SoundManager", visitor)?;
         self.items.visit("Items", visitor)?;
         self.navmesh.visit("Navmesh", visitor)?;
+        if saves::read_version(visitor) >= 5 {
+            self.mines.visit("Mines", visitor)?;
+        }
+        if saves::read_version(visitor) >= 6 {
+            self.turrets.visit("Turrets", visitor)?;
+        }
+        if saves::read_version(visitor) >= 7 {
+            self.director.visit("Director", visitor)?;
+        }
+        if saves::read_version(visitor) >= 8 {
+            self.teleporters.visit("Teleporters", visitor)?;
+        }
+        if saves::read_version(visitor) >= 11 {
+            self.observer.visit("Observer", visitor)?;
+        }
+        if saves::read_version(visitor) >= 14 {
+            self.flags.visit("Flags", visitor)?;
+        }
+        if saves::read_version(visitor) >= 16 {
+            self.checkpoints.visit("Checkpoints", visitor)?;
+        }
 
         visitor.leave_region()
     }
@@ -230,6 +744,23 @@ impl Visit for DeathZone {
     }
 }
 
+#[derive(Default)]
+pub struct ReverbZone {
+    bounds: AxisAlignedBoundingBox,
+    preset: ReverbPreset,
+}
+
+impl Visit for ReverbZone {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.bounds.visit("Bounds", visitor)?;
+        self.preset.visit("Preset", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
 impl Default for DeathZone {
     fn default() -> Self {
         Self {
@@ -245,6 +776,16 @@ pub struct UpdateContext<'a> {
     pub jump_pads: &'a JumpPadContainer,
     pub navmesh: Handle<Navmesh>,
     pub weapons: &'a WeaponContainer,
+    /// Position of the camera that is used to render the level, used for distance culling.
+    pub observer_position: Vector3<f32>,
+    pub lod_near_distance: f32,
+    pub lod_far_distance: f32,
+    /// Scale of player's camera effects, zero if reduced motion is enabled.
+    pub camera_effects: f32,
+    /// Disables any procedural camera motion, including head bob.
+    pub reduced_motion: bool,
+    pub difficulty: Difficulty,
+    pub economy: ItemEconomy,
 }
 
 struct PlayerRespawnEntry {
@@ -345,7 +886,45 @@ impl Visit for RespawnEntry {
     }
 }
 
-fn build_navmesh(scene: &mut Scene) -> Handle<Navmesh> {
+/// Returns position and normal of closest hit of level geometry along given ray.
+pub fn cast_to_static_geometry(
+    physics: &Physics,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_len: f32,
+) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    let mut query_buffer = Vec::default();
+    physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(origin),
+            ray_direction: direction,
+            max_len,
+            groups: InteractionGroups::all(),
+            sort_results: true,
+        },
+        &mut query_buffer,
+    );
+    query_buffer
+        .iter()
+        .find(|hit| {
+            physics
+                .colliders
+                .get(&hit.collider)
+                .unwrap()
+                .shape()
+                .as_trimesh()
+                .is_some()
+        })
+        .map(|hit| (hit.position.coords, hit.normal))
+}
+
+/// Hand-made navmesh of the map is used if the map has one, otherwise navmesh is generated
+/// from collision geometry.
+async fn build_navmesh(
+    scene: &mut Scene,
+    map: &Path,
+    polygon_handle: Handle<Node>,
+) -> Handle<Navmesh> {
     let navmesh_handle = scene.graph.find_by_name(scene.graph.get_root(), "Navmesh");
     if navmesh_handle.is_some() {
         let navmesh_node = &mut scene.graph[navmesh_handle];
@@ -354,10 +933,15 @@ fn build_navmesh(scene: &mut Scene) -> Handle<Navmesh> {
         scene
             .navmeshes
             .add(Navmesh::from_mesh(navmesh_node.as_mesh()))
+    } else if polygon_handle.is_some() {
+        let navmesh =
+            navmesh_builder::load_or_generate(map, &scene.graph[polygon_handle], &scene.physics)
+                .await;
+        scene.navmeshes.add(navmesh)
     } else {
-        Log::writeln(
-            MessageKind::Warning,
-            "Unable to find Navmesh node to build navmesh!".to_owned(),
+        logger::warning(
+            Category::Level,
+            "Unable to find Navmesh or Polygon node to build navmesh!".to_owned(),
         );
         Handle::NONE
     }
@@ -367,20 +951,42 @@ fn build_navmesh(scene: &mut Scene) -> Handle<Navmesh> {
 pub struct AnalysisResult {
     jump_pads: JumpPadContainer,
     items: ItemContainer,
+    turrets: TurretContainer,
     death_zones: Vec<DeathZone>,
+    reverb_zones: Vec<ReverbZone>,
+    teleporters: Vec<Teleporter>,
+    checkpoints: Vec<Checkpoint>,
     spawn_points: Vec<SpawnPoint>,
+    flag_stands: Vec<(Vector3<f32>, Team)>,
+}
+
+/// Half-size (in meters) of collision box of a jump pad placed by level editor.
+const LAYOUT_JUMP_PAD_SIZE: Vector3<f32> = Vector3::new(1.0, 0.1, 1.0);
+
+const LAYOUT_JUMP_PAD_COLOR: Color = Color::opaque(255, 160, 40);
+
+fn jump_pad_force(begin: Vector3<f32>, end: Vector3<f32>) -> Vector3<f32> {
+    let d = end - begin;
+    let len = d.norm();
+    let force = d.try_normalize(std::f32::EPSILON);
+    force.unwrap_or(Vector3::y()).scale(len * 3.0)
 }
 
+/// Item and player spawns come from placement layer of the map, which is made of scene markers
+/// if the map has no layer of its own. Medkits are thinned out by health density of the match.
 pub async fn analyze(
     scene: &mut Scene,
+    layout: &MapLayout,
     resource_manager: ResourceManager,
     sender: Sender<Message>,
+    health_density: HealthDensity,
 ) -> AnalysisResult {
     let mut result = AnalysisResult::default();
 
-    let mut items = Vec::new();
-    let mut spawn_points = Vec::new();
+    let mut turrets = Vec::new();
     let mut death_zones = Vec::new();
+    let mut checkpoints = Vec::new();
+    let mut reverb_zones = Vec::new();
     for (handle, node) in scene.graph.pair_iter() {
         let position = node.global_position();
         let name = node.name();
@@ -394,42 +1000,121 @@ pub async fn analyze(
             if begin.is_some() && end.is_some() {
                 let begin = scene.graph[begin].global_position();
                 let end = scene.graph[end].global_position();
-                let d = end - begin;
-                let len = d.norm();
-                let force = d.try_normalize(std::f32::EPSILON);
-                let force = force.unwrap_or(Vector3::y()).scale(len * 3.0);
+                let force = jump_pad_force(begin, end);
                 let shape = scene.physics.mesh_to_trimesh(handle, &scene.graph);
                 scene.physics_binder.bind(handle, shape);
                 result.jump_pads.add(JumpPad::new(shape, force));
             };
-        } else if name.starts_with("Medkit") {
-            items.push((ItemKind::Medkit, position));
-        } else if name.starts_with("Ammo_Ak47") {
-            items.push((ItemKind::Ak47Ammo, position));
-        } else if name.starts_with("Ammo_M4") {
-            items.push((ItemKind::M4Ammo, position));
-        } else if name.starts_with("Ammo_Plasma") {
-            items.push((ItemKind::Plasma, position));
-        } else if name.starts_with("SpawnPoint") {
-            spawn_points.push(node.global_position())
+        } else if name.starts_with("Turret") {
+            let team = if name.ends_with("_Red") {
+                Team::Red
+            } else if name.ends_with("_Blue") {
+                Team::Blue
+            } else {
+                Team::None
+            };
+            turrets.push((position, team));
+        } else if name.starts_with("FlagStand") {
+            if name.ends_with("_Red") {
+                result.flag_stands.push((position, Team::Red));
+            } else if name.ends_with("_Blue") {
+                result.flag_stands.push((position, Team::Blue));
+            }
         } else if name.starts_with("DeathZone") {
             if let Node::Mesh(_) = node {
                 death_zones.push(handle);
             }
+        } else if name.starts_with("Checkpoint") {
+            if let Node::Mesh(_) = node {
+                checkpoints.push(handle);
+            }
+        } else if let Some(preset) = name.strip_prefix("ReverbZone_") {
+            if let Node::Mesh(_) = node {
+                match ReverbPreset::from_name(preset) {
+                    Some(preset) => reverb_zones.push((handle, preset)),
+                    None => logger::warning(
+                        Category::Level,
+                        format!("Unknown reverb preset {} of zone {}!", preset, name),
+                    ),
+                }
+            }
         }
     }
 
-    for (kind, position) in items {
-        result.items.add(
-            Item::new(
-                kind,
-                position,
-                scene,
-                resource_manager.clone(),
-                sender.clone(),
-            )
-            .await,
-        );
+    let mut medkits = 0;
+    for placement in layout.placements.iter() {
+        match placement.kind {
+            PlacementKind::SpawnPoint => result.spawn_points.push(SpawnPoint {
+                position: placement.position,
+                team: placement.team,
+                modes: placement.modes,
+            }),
+            PlacementKind::Item(kind) => {
+                if kind == ItemKind::Medkit {
+                    medkits += 1;
+                    if !health_density.keeps_medkit(medkits - 1) {
+                        continue;
+                    }
+                }
+                result.items.add(
+                    Item::new(
+                        kind,
+                        placement.position,
+                        scene,
+                        resource_manager.clone(),
+                        sender.clone(),
+                    )
+                    .await,
+                );
+            }
+            PlacementKind::JumpPad => {
+                let body = scene.physics.add_body(
+                    RigidBodyBuilder::new(RigidBodyType::Static)
+                        .translation(placement.position)
+                        .build(),
+                );
+                scene.physics.add_collider(
+                    ColliderBuilder::cuboid(
+                        LAYOUT_JUMP_PAD_SIZE.x,
+                        LAYOUT_JUMP_PAD_SIZE.y,
+                        LAYOUT_JUMP_PAD_SIZE.z,
+                    )
+                    .build(),
+                    &body,
+                );
+                let model = teleporter::make_pad(
+                    &mut scene.graph,
+                    placement.position,
+                    LAYOUT_JUMP_PAD_SIZE.x,
+                    LAYOUT_JUMP_PAD_SIZE.y,
+                    LAYOUT_JUMP_PAD_COLOR,
+                );
+                scene.physics_binder.bind(model, body);
+                result.jump_pads.add(JumpPad::new(
+                    body,
+                    jump_pad_force(placement.position, placement.target),
+                ));
+            }
+            PlacementKind::Teleporter => result.teleporters.push(Teleporter::new(
+                &mut scene.graph,
+                placement.position,
+                placement.target,
+            )),
+            PlacementKind::Checkpoint => result
+                .checkpoints
+                .push(Checkpoint::around(placement.position)),
+        }
+    }
+    for (position, team) in turrets {
+        let turret = Turret::new(scene, position, Handle::NONE, team);
+        result.turrets.add(turret, scene);
+    }
+    for handle in checkpoints {
+        let node = &mut scene.graph[handle];
+        node.set_visibility(false);
+        result
+            .checkpoints
+            .push(Checkpoint::new(node.as_mesh().world_bounding_box()));
     }
     for handle in death_zones {
         let node = &mut scene.graph[handle];
@@ -438,40 +1123,88 @@ pub async fn analyze(
             bounds: node.as_mesh().world_bounding_box(),
         });
     }
-    result.spawn_points = spawn_points
-        .into_iter()
-        .map(|p| SpawnPoint { position: p })
-        .collect();
-
+    for (handle, preset) in reverb_zones {
+        let node = &mut scene.graph[handle];
+        node.set_visibility(false);
+        result.reverb_zones.push(ReverbZone {
+            bounds: node.as_mesh().world_bounding_box(),
+            preset,
+        });
+    }
     result
 }
 
+/// Team of an actor that joins the match. Actor that has played the match already returns to
+/// its team, newcomers of team modes join the smaller team.
+fn join_team(options: &MatchOptions, leader_board: &LeaderBoard, name: Option<&str>) -> Team {
+    match options {
+        MatchOptions::TeamDeathMatch(_) | MatchOptions::CaptureTheFlag(_) => {
+            if let Some(score) = name.and_then(|name| leader_board.values().get(name)) {
+                if score.team != Team::None {
+                    return score.team;
+                }
+            }
+            let size = |team| {
+                leader_board
+                    .values()
+                    .values()
+                    .filter(|score| score.team == team)
+                    .count()
+            };
+            if size(Team::Blue) < size(Team::Red) {
+                Team::Blue
+            } else {
+                Team::Red
+            }
+        }
+        _ => Team::None,
+    }
+}
+
 async fn spawn_player(
-    spawn_points: &[SpawnPoint],
+    team: Team,
+    spawn_points: &mut SpawnPoints,
+    options: &MatchOptions,
     actors: &mut ActorContainer,
     weapons: &mut WeaponContainer,
     sender: Sender<Message>,
     resource_manager: ResourceManager,
     control_scheme: Arc<RwLock<ControlScheme>>,
+    profile: &Profile,
+    leader_board: &mut LeaderBoard,
     scene: &mut Scene,
+    armed: bool,
 ) -> Handle<Actor> {
-    let index = find_suitable_spawn_point(spawn_points, actors, scene);
-    let spawn_position = spawn_points.get(index).map_or(Vector3::default(), |pt| {
-        pt.position + Vector3::new(0.0, 1.5, 0.0)
-    });
+    let spawn_position = spawn_points
+        .select(team, options, actors, &scene.physics)
+        .unwrap_or_default()
+        + Vector3::new(0.0, SPAWN_HEIGHT, 0.0);
     let mut player = Player::new(scene, sender.clone());
     player.set_control_scheme(control_scheme);
+    player.name = profile.name.clone();
+    player.set_team(team);
+    let score = leader_board.get_or_add_actor(&profile.name);
+    score.color = profile.color();
+    score.team = player.team();
     let player = actors.add(Actor::Player(player));
     actors
         .get_mut(player)
         .set_position(&mut scene.physics, spawn_position);
 
-    let weapons_to_give = [
-        WeaponKind::M4,
-        WeaponKind::Ak47,
-        WeaponKind::PlasmaRifle,
-        WeaponKind::RocketLauncher,
-    ];
+    let weapons_to_give: &[WeaponKind] = if armed {
+        &[
+            WeaponKind::M4,
+            WeaponKind::Ak47,
+            WeaponKind::PlasmaRifle,
+            WeaponKind::Flamethrower,
+            WeaponKind::LightningGun,
+            WeaponKind::MineLayer,
+            WeaponKind::SentryKit,
+            WeaponKind::RocketLauncher,
+        ]
+    } else {
+        &[]
+    };
     for (i, &weapon) in weapons_to_give.iter().enumerate() {
         give_new_weapon(
             weapon,
@@ -479,6 +1212,7 @@ async fn spawn_player(
             sender.clone(),
             resource_manager.clone(),
             i == weapons_to_give.len() - 1,
+            &options.economy(),
             weapons,
             actors,
             scene,
@@ -495,6 +1229,7 @@ async fn give_new_weapon(
     sender: Sender<Message>,
     resource_manager: ResourceManager,
     visible: bool,
+    economy: &ItemEconomy,
     weapons: &mut WeaponContainer,
     actors: &mut ActorContainer,
     scene: &mut Scene,
@@ -502,6 +1237,8 @@ async fn give_new_weapon(
     if actors.contains(actor) {
         let mut weapon = Weapon::new(kind, resource_manager, scene, sender.clone()).await;
         weapon.set_owner(actor);
+        let ammo = economy.scale_ammo(weapon.ammo());
+        weapon.set_ammo(ammo);
         let weapon_model = weapon.get_model();
         scene.graph[weapon_model].set_visibility(visible);
         let actor = actors.get_mut(actor);
@@ -517,32 +1254,12 @@ async fn give_new_weapon(
     }
 }
 
-fn find_suitable_spawn_point(
-    spawn_points: &[SpawnPoint],
-    actors: &ActorContainer,
-    scene: &Scene,
-) -> usize {
-    // Find spawn point with least amount of enemies nearby.
-    let mut index = rand::thread_rng().gen_range(0..spawn_points.len());
-    let mut max_distance = -std::f32::MAX;
-    for (i, pt) in spawn_points.iter().enumerate() {
-        let mut sum_distance = 0.0;
-        for actor in actors.iter() {
-            let position = actor.position(&scene.physics);
-            sum_distance += pt.position.metric_distance(&position);
-        }
-        if sum_distance > max_distance {
-            max_distance = sum_distance;
-            index = i;
-        }
-    }
-    index
-}
-
 async fn spawn_bot(
     kind: BotKind,
     name: Option<String>,
-    spawn_points: &[SpawnPoint],
+    team: Team,
+    spawn_points: &mut SpawnPoints,
+    options: &MatchOptions,
     actors: &mut ActorContainer,
     weapons: &mut WeaponContainer,
     resource_manager: ResourceManager,
@@ -550,10 +1267,9 @@ async fn spawn_bot(
     leader_board: &mut LeaderBoard,
     scene: &mut Scene,
 ) -> Handle<Actor> {
-    let index = find_suitable_spawn_point(spawn_points, actors, scene);
     let spawn_position = spawn_points
-        .get(index)
-        .map_or(Vector3::default(), |pt| pt.position);
+        .select(team, options, actors, &scene.physics)
+        .unwrap_or_default();
 
     let bot = add_bot(
         kind,
@@ -565,12 +1281,23 @@ async fn spawn_bot(
         sender,
         leader_board,
         scene,
+        options.time_scale(),
     )
     .await;
 
+    if team != Team::None {
+        let bot = actors.get_mut(bot);
+        bot.set_team(team);
+        leader_board.get_or_add_actor(&bot.name).team = team;
+    }
+
     bot
 }
 
+fn is_name_taken(name: &str, actors: &ActorContainer, leader_board: &LeaderBoard) -> bool {
+    leader_board.values().contains_key(name) || actors.iter().any(|actor| actor.name == name)
+}
+
 async fn add_bot(
     kind: BotKind,
     position: Vector3<f32>,
@@ -581,8 +1308,9 @@ async fn add_bot(
     sender: Sender<Message>,
     leader_board: &mut LeaderBoard,
     scene: &mut Scene,
+    time_scale: f32,
 ) -> Handle<Actor> {
-    let bot = Bot::new(
+    let mut bot = Bot::new(
         kind,
         resource_manager.clone(),
         scene,
@@ -590,8 +1318,12 @@ async fn add_bot(
         sender.clone(),
     )
     .await;
-    let name = name.unwrap_or_else(|| format!("Bot {:?} {}", kind, actors.count()));
-    leader_board.get_or_add_actor(&name);
+    bot.set_time_scale(scene, time_scale);
+    let name = name.unwrap_or_else(|| {
+        names::generate_bot_name(kind, |name| is_name_taken(name, actors, leader_board))
+    });
+    leader_board.get_or_add_actor(&name).team = bot.team();
+    bot.name = name;
     let bot = actors.add(Actor::Bot(bot));
     give_new_weapon(
         WeaponKind::Ak47,
@@ -599,6 +1331,7 @@ async fn add_bot(
         sender.clone(),
         resource_manager,
         true,
+        &options.economy(),
         weapons,
         actors,
         scene,
@@ -611,8 +1344,11 @@ impl Level {
     pub async fn new(
         resource_manager: ResourceManager,
         control_scheme: Arc<RwLock<ControlScheme>>,
+        settings: Arc<RwLock<Settings>>,
         sender: Sender<Message>,
         options: MatchOptions,
+        map: PathBuf,
+        training: bool,
     ) -> (Level, Scene) {
         let mut scene = Scene::new();
 
@@ -635,43 +1371,67 @@ impl Level {
             .enabled(false)
             .build(&mut scene.graph);
 
-        let map_model = resource_manager
-            .request_model(
-                Path::new("data/models/dm6.fbx"),
-                MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures")),
-            )
-            .await
-            .unwrap();
+        assets::validate(map.to_str());
 
-        // Instantiate map
-        let map_root = map_model.instantiate_geometry(&mut scene);
+        // Instantiate map, placeholder map has no collision geometry but level still loads
+        // and player is told what's wrong.
+        let map_root = assets::instantiate_model(&resource_manager, &map, &mut scene).await;
 
         // Create collision geometry
         let polygon_handle = scene.graph.find_by_name(map_root, "Polygon");
         if polygon_handle.is_some() {
             scene.physics.mesh_to_trimesh(polygon_handle, &scene.graph);
         } else {
-            Log::writeln(
-                MessageKind::Warning,
+            logger::warning(
+                Category::Level,
                 "Unable to find Polygon node to build collision shape for level!".to_owned(),
             );
         }
 
+        let navmesh = build_navmesh(&mut scene, &map, polygon_handle).await;
+
+        let layout = MapLayout::load(&map).unwrap_or_else(|| MapLayout::from_scene(&scene.graph));
         let AnalysisResult {
             jump_pads,
             items,
+            turrets,
             death_zones,
+            reverb_zones,
+            teleporters,
+            checkpoints,
             spawn_points,
-        } = analyze(&mut scene, resource_manager.clone(), sender.clone()).await;
+            flag_stands,
+        } = analyze(
+            &mut scene,
+            &layout,
+            resource_manager.clone(),
+            sender.clone(),
+            options.economy().health_density,
+        )
+        .await;
         let mut actors = ActorContainer::new();
         let mut weapons = WeaponContainer::new();
         let mut leader_board = LeaderBoard::default();
 
-        for &kind in &[BotKind::Maw, BotKind::Mutant, BotKind::Parasite] {
+        // Name of the player is reserved before bots are named, so no bot takes it.
+        let profile = settings.read().unwrap().profile.clone();
+        leader_board.get_or_add_actor(&profile.name);
+
+        // Bots of survival are brought by director.
+        let survival = matches!(options, MatchOptions::Survival(_));
+        let initial_bots: &[BotKind] = if survival {
+            &[]
+        } else {
+            &[BotKind::Maw, BotKind::Mutant, BotKind::Parasite]
+        };
+        let mut spawn_points = SpawnPoints::new(spawn_points);
+        for &kind in initial_bots {
             spawn_bot(
                 kind,
-                Some(kind.description().to_owned()),
-                &spawn_points,
+                None,
+                join_team(&options, &leader_board, None),
+                &mut spawn_points,
+                &options,
                 &mut actors,
                 &mut weapons,
                 resource_manager.clone(),
@@ -682,40 +1442,148 @@ impl Level {
             .await;
         }
 
-        let level = Level {
+        // Flags stand at markers of the map, or at team bases if the map has no markers.
+        let mut flags = FlagContainer::default();
+        if let MatchOptions::CaptureTheFlag(_) = options {
+            let theme = settings.read().unwrap().palette.theme();
+            for &team in &[Team::Red, Team::Blue] {
+                let home = flag_stands
+                    .iter()
+                    .find(|(_, stand_team)| *stand_team == team)
+                    .map(|(position, _)| *position)
+                    .or_else(|| spawn_points.base(team));
+                match home {
+                    Some(home) => flags.add(Flag::new(
+                        &mut scene.graph,
+                        team,
+                        home,
+                        theme.team_color(team),
+                    )),
+                    None => logger::warning(
+                        Category::Level,
+                        format!("Map has no base of {:?} team to place its flag!", team),
+                    ),
+                }
+            }
+        }
+
+        let mut level = Level {
+            map,
             player: spawn_player(
-                &spawn_points,
+                join_team(&options, &leader_board, Some(&profile.name)),
+                &mut spawn_points,
+                &options,
                 &mut actors,
                 &mut weapons,
                 sender.clone(),
                 resource_manager.clone(),
                 control_scheme.clone(),
+                &profile,
+                &mut leader_board,
                 &mut scene,
+                !training,
             )
             .await,
+            profile,
             map_root,
             options,
+            training: if training {
+                Some(Default::default())
+            } else {
+                None
+            },
+            director: if survival {
+                Some(Default::default())
+            } else {
+                None
+            },
+            campaign_level: None,
             spectator_camera,
             actors,
             weapons,
             jump_pads,
             items,
+            mines: MineContainer::new(),
+            turrets,
             death_zones,
+            reverb_zones,
+            teleporters,
+            checkpoints,
+            flags,
             spawn_points,
             leader_board,
-            navmesh: build_navmesh(&mut scene),
+            navmesh,
             scene: Handle::NONE, // Filled when scene will be moved to engine.
             sender: Some(sender),
             control_scheme: Some(control_scheme),
+            settings: Some(settings),
             time: 0.0,
+            // There is no match in training, so no warm-up either. Survival starts with a
+            // pause before first wave instead.
+            phase: if training || survival {
+                MatchPhase::Live
+            } else {
+                MatchPhase::WarmUp {
+                    time_left: WARM_UP_TIME,
+                }
+            },
+            match_start_time: 0.0,
+            overtime: Default::default(),
+            time_limit_extension: 0.0,
             respawn_list: Default::default(),
             contact_events_receiver: Some(contact_events_receiver),
             proximity_events_receiver: Some(proximity_events_receiver),
             projectiles: ProjectileContainer::new(),
+            effects: Default::default(),
             target_spectator_position: Default::default(),
             sound_manager,
+            match_recorder: Default::default(),
+            voices: VoiceBank::new(),
+            lead: None,
+            death_camera: None,
+            death_description: None,
+            vote: None,
+            observer: None,
+            weapon_stats: Default::default(),
+            practice: Default::default(),
+            heartbeat_timer: 0.0,
+            pings: Default::default(),
+            squad: Default::default(),
+            assists: Default::default(),
+            idle_time: 0.0,
+            checkpoint: None,
         };
 
+        if training {
+            for actor in level.actors.iter_mut() {
+                if let Actor::Bot(bot) = actor {
+                    bot.dummy = true;
+                }
+            }
+        }
+
+        // Start of match and initial spawns are recorded directly, so they always open the log.
+        level.match_recorder.record(
+            0.0,
+            MatchEvent::MatchStarted {
+                mode: match_recorder::mode_id(&options),
+                map: level
+                    .map
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            },
+        );
+        for actor in level.actors.iter() {
+            level.match_recorder.record(
+                0.0,
+                MatchEvent::Spawned {
+                    actor: actor.name.clone(),
+                    position: actor.position(&scene.physics),
+                },
+            );
+        }
+
         (level, scene)
     }
 
@@ -735,6 +1603,7 @@ impl Level {
             self.sender.clone().unwrap(),
             engine.resource_manager.clone(),
             true,
+            &self.options.economy(),
             &mut self.weapons,
             &mut self.actors,
             &mut engine.scenes[self.scene],
@@ -746,28 +1615,342 @@ impl Level {
         let scene = &mut engine.scenes[self.scene];
 
         let player = spawn_player(
-            &self.spawn_points,
+            join_team(&self.options, &self.leader_board, Some(&self.profile.name)),
+            &mut self.spawn_points,
+            &self.options,
             &mut self.actors,
             &mut self.weapons,
             self.sender.clone().unwrap(),
             engine.resource_manager.clone(),
             self.control_scheme.clone().unwrap(),
+            &self.profile,
+            &mut self.leader_board,
             scene,
+            self.training
+                .as_ref()
+                .map_or(true, |training| training.is_player_armed()),
         )
         .await;
 
         if let Node::Camera(spectator_camera) = &mut scene.graph[self.spectator_camera] {
             spectator_camera.set_enabled(false);
         }
+        self.death_camera = None;
+        self.death_description = None;
+
+        self.actors.get_mut(player).protect(SPAWN_PROTECTION_TIME);
+        self.telefrag(&engine.scenes[self.scene], player);
+        self.record_spawn(engine, player);
 
         player
     }
 
+    /// Kills every other actor standing in the spot where given actor has just appeared.
+    fn telefrag(&mut self, scene: &Scene, actor: Handle<Actor>) {
+        let position = self.actors.get(actor).position(&scene.physics);
+        let victims = self
+            .actors
+            .pair_iter()
+            .filter(|(handle, victim)| {
+                *handle != actor
+                    && !victim.is_dead()
+                    && victim.position(&scene.physics).metric_distance(&position) < TELEFRAG_RADIUS
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for victim in victims {
+            // Spawn protection does not save from telefrag, otherwise both actors would be
+            // stuck in each other.
+            self.actors.get_mut(victim).cancel_protection();
+            self.notify(tr_format(
+                "{} telefragged {}!",
+                &[&self.actors.get(actor).name, &self.actors.get(victim).name],
+            ));
+            self.sender
+                .as_ref()
+                .unwrap()
+                .send(Message::DamageActor {
+                    actor: victim,
+                    who: actor,
+                    amount: TELEFRAG_DAMAGE,
+                    direction: None,
+                    zone: None,
+                })
+                .unwrap();
+        }
+    }
+
+    /// Match events go through message queue, so recorder gets them in the order they happen
+    /// together with other events of the frame.
+    fn record_event(&self, event: MatchEvent) {
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::RecordMatchEvent {
+                time: self.time,
+                event,
+            })
+            .unwrap();
+    }
+
+    fn record_spawn(&mut self, engine: &Engine, actor: Handle<Actor>) {
+        let actor = self.actors.get(actor);
+        self.record_event(MatchEvent::Spawned {
+            actor: actor.name.clone(),
+            position: actor.position(&engine.scenes[self.scene].physics),
+        });
+    }
+
+    /// Finalizes match log and writes it into given directory.
+    pub fn export_match_log(&mut self, dir: &Path) -> std::io::Result<PathBuf> {
+        self.match_recorder
+            .record_match_end(self.time, &self.leader_board);
+        let path = self.match_recorder.export(dir)?;
+        self.weapon_stats
+            .write_report(&path.with_extension("weapons.txt"))?;
+        Ok(path)
+    }
+
+    fn observer_position(&self, scene: &Scene) -> Vector3<f32> {
+        if self.actors.contains(self.player) {
+            if let Actor::Player(player) = self.actors.get(self.player) {
+                return scene.graph[player.camera()].global_position();
+            }
+        }
+        scene.graph[self.spectator_camera].global_position()
+    }
+
+    /// Returns amount of alive bots near player.
+    pub fn threats_near_player(&self, engine: &Engine) -> usize {
+        if !self.actors.contains(self.player) {
+            return 0;
+        }
+        let physics = &engine.scenes[self.scene].physics;
+        let player_position = self.actors.get(self.player).position(physics);
+        self.actors
+            .iter()
+            .filter(|actor| {
+                matches!(actor, Actor::Bot(_))
+                    && !actor.is_dead()
+                    && actor.position(physics).metric_distance(&player_position) < THREAT_RADIUS
+            })
+            .count()
+    }
+
+    /// Returns points of interest that must be marked on HUD right now.
+    pub fn waypoints(&self, engine: &Engine) -> Vec<Waypoint> {
+        let scene = &engine.scenes[self.scene];
+        let color = Color::opaque(255, 200, 0);
+        let mut waypoints = match self.training.as_ref().map(|training| training.objective()) {
+            Some(Objective::PickUpWeapon) => self
+                .items
+                .iter()
+                .filter(|item| !item.is_picked_up())
+                .map(|item| Waypoint {
+                    label: "Weapon".to_owned(),
+                    position: item.position(&scene.graph),
+                    color,
+                })
+                .collect(),
+            Some(Objective::HitTargets) => self
+                .actors
+                .iter()
+                .filter(|actor| matches!(actor, Actor::Bot(bot) if bot.dummy && !bot.is_dead()))
+                .map(|actor| Waypoint {
+                    label: "Target".to_owned(),
+                    position: actor.position(&scene.physics) + Vector3::new(0.0, 1.0, 0.0),
+                    color,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let theme = self.theme();
+        if self.actors.contains(self.player) {
+            waypoints.extend(
+                self.pings
+                    .visible_to(self.actors.get(self.player).team())
+                    .map(|ping| Waypoint {
+                        label: ping.kind.label().to_owned(),
+                        position: ping.position,
+                        color: ping.kind.color(&theme),
+                    }),
+            );
+        }
+        waypoints.extend(self.flags.iter().map(|flag| Waypoint {
+            label: match flag.state() {
+                FlagState::Dropped { .. } => format!("Dropped {:?} Flag", flag.team()),
+                _ => format!("{:?} Flag", flag.team()),
+            },
+            position: flag.position() + Vector3::new(0.0, 1.0, 0.0),
+            color: theme.team_color(flag.team()),
+        }));
+        waypoints
+    }
+
+    fn theme(&self) -> Theme {
+        self.settings
+            .as_ref()
+            .map_or_else(Palette::default, |settings| {
+                settings.read().unwrap().palette
+            })
+            .theme()
+    }
+
+    /// Returns camera through which level is seen right now - spectator camera while player
+    /// is dead and player's camera otherwise.
+    pub fn view(&self, engine: &Engine) -> Option<View> {
+        let graph = &engine.scenes[self.scene].graph;
+        let camera = match &graph[self.spectator_camera] {
+            Node::Camera(camera) if camera.is_enabled() => self.spectator_camera,
+            _ if self.actors.contains(self.player) => match self.actors.get(self.player) {
+                Actor::Player(player) => player.camera(),
+                Actor::Bot(_) => return None,
+            },
+            _ => return None,
+        };
+        match &graph[camera] {
+            Node::Camera(camera) => Some(View {
+                view_projection: camera.view_projection_matrix(),
+                eye: camera.global_position(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Name of local player, it is taken from profile when level is created.
+    pub fn player_name(&self) -> &str {
+        &self.profile.name
+    }
+
     pub fn get_player(&self) -> Handle<Actor> {
         self.player
     }
 
+    /// Tells who killed player and with what, it is None if player is alive or killed by
+    /// environment.
+    pub fn death_description(&self) -> Option<&str> {
+        self.death_description.as_deref()
+    }
+
+    /// Text of wave counter for HUD, None if match is not a survival.
+    pub fn wave_status(&self) -> Option<String> {
+        let director = self.director.as_ref()?;
+        Some(match director.rest_time_left() {
+            Some(time_left) => tr_format(
+                "Wave {} in {}",
+                &[&(director.wave() + 1), &time_left.ceil()],
+            ),
+            None => tr_format(
+                "Wave {} - {} left",
+                &[
+                    &director.wave(),
+                    &(director.pending() + self.alive_bot_count() as u32),
+                ],
+            ),
+        })
+    }
+
+    /// Amount of cleared waves, None if match is not a survival.
+    pub fn waves_cleared(&self) -> Option<u32> {
+        self.director
+            .as_ref()
+            .map(|director| director.waves_cleared())
+    }
+
+    /// Kills and deaths of player in current match.
+    pub fn player_score(&self) -> (u32, u32) {
+        self.leader_board
+            .values()
+            .get(&self.profile.name)
+            .map_or((0, 0), |score| (score.kills, score.deaths))
+    }
+
+    pub fn is_player_leading(&self) -> bool {
+        self.current_lead() == Lead::Ahead
+    }
+
+    /// Returns None if player is dead.
+    pub fn player_loadout(&self) -> Option<Loadout> {
+        if !self.actors.contains(self.player) {
+            return None;
+        }
+
+        let player = self.actors.get(self.player);
+        Some(Loadout {
+            health: player.get_health(),
+            armor: player.get_armor(),
+            weapons: player
+                .weapons()
+                .iter()
+                .map(|&weapon| {
+                    let weapon = &self.weapons[weapon];
+                    (weapon.get_kind(), weapon.ammo())
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn apply_loadout(&mut self, engine: &mut Engine, loadout: &Loadout) {
+        if !self.actors.contains(self.player) {
+            return;
+        }
+
+        for &(kind, ammo) in loadout.weapons.iter() {
+            let weapons = &self.weapons;
+            let owned = self
+                .actors
+                .get(self.player)
+                .weapons()
+                .iter()
+                .cloned()
+                .find(|&weapon| weapons[weapon].get_kind() == kind);
+            let weapon = match owned {
+                Some(weapon) => weapon,
+                None => {
+                    self.give_new_weapon(engine, self.player, kind).await;
+                    *self.actors.get(self.player).weapons().last().unwrap()
+                }
+            };
+            self.weapons[weapon].set_ammo(ammo);
+        }
+
+        let player = self.actors.get_mut(self.player);
+        player.set_health(loadout.health);
+        player.set_armor(loadout.armor);
+    }
+
     pub fn process_input_event(&mut self, event: &Event<()>) -> bool {
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { .. },
+                ..
+            }
+            | Event::WindowEvent {
+                event: WindowEvent::MouseInput { .. },
+                ..
+            }
+            | Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { .. },
+                ..
+            } => self.idle_time = 0.0,
+            _ => (),
+        }
+        if let Some(observer) = self.observer.as_mut() {
+            let control_scheme = self.control_scheme.as_ref().unwrap().read().unwrap();
+            observer.process_input_event(event, &control_scheme);
+            return true;
+        }
+        if self.checkpoint.is_some() && !self.actors.contains(self.player) {
+            let control_scheme = self.control_scheme.as_ref().unwrap().read().unwrap();
+            if checkpoint::is_restart_event(event, &control_scheme) {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::RestartFromCheckpoint)
+                    .unwrap();
+                return true;
+            }
+        }
         if self.player.is_some() {
             if let Actor::Player(player) = self.actors.get_mut(self.player) {
                 return player.process_input_event(event);
@@ -825,7 +2008,13 @@ impl Level {
         position: Vector3<f32>,
         name: Option<String>,
     ) -> Handle<Actor> {
-        add_bot(
+        // Requested name could be already in use.
+        let name = name.map(|name| {
+            names::make_unique(&name, |n| {
+                is_name_taken(n, &self.actors, &self.leader_board)
+            })
+        });
+        let bot = add_bot(
             kind,
             position,
             name,
@@ -835,8 +2024,41 @@ impl Level {
             self.sender.clone().unwrap(),
             &mut self.leader_board,
             &mut engine.scenes[self.scene],
+            self.options.time_scale(),
         )
-        .await
+        .await;
+        self.make_target_dummy(&engine.scenes[self.scene], bot);
+        bot
+    }
+
+    /// Every bot of training level or practice match is a target dummy. Every other dummy of
+    /// practice match walks back and forth around its spawn point.
+    fn make_target_dummy(&mut self, scene: &Scene, actor: Handle<Actor>) {
+        let practice = self.is_practice();
+        if self.training.is_none() && !practice {
+            return;
+        }
+        let (moving, standing) = self
+            .actors
+            .iter()
+            .filter_map(|actor| match actor {
+                Actor::Bot(bot) if bot.dummy => Some(bot.patrol_origin.is_some()),
+                _ => None,
+            })
+            .fold((0, 0), |(moving, standing), is_moving| {
+                if is_moving {
+                    (moving + 1, standing)
+                } else {
+                    (moving, standing + 1)
+                }
+            });
+        let position = self.actors.get(actor).position(&scene.physics);
+        if let Actor::Bot(bot) = self.actors.get_mut(actor) {
+            bot.dummy = true;
+            if practice && moving < standing {
+                bot.patrol_origin = Some(position);
+            }
+        }
     }
 
     async fn remove_actor(&mut self, engine: &mut Engine, actor: Handle<Actor>) {
@@ -844,22 +2066,48 @@ impl Level {
             let scene = &mut engine.scenes[self.scene];
             let character = self.actors.get(actor);
 
-            // Make sure to remove weapons and drop appropriate items (items will be temporary).
+            // Drop current weapon and some ammo for it so others can scavenge it, dropped
+            // items are temporary. Rest of the weapons are just removed.
             let drop_position = character.position(&scene.physics);
+            let current_weapon = character.current_weapon();
             let weapons = character
                 .weapons()
                 .iter()
                 .copied()
                 .collect::<Vec<Handle<Weapon>>>();
+            // Items never despawn in practice.
+            let dropped_item_lifetime = if self.is_practice() {
+                None
+            } else {
+                Some(DROPPED_ITEM_LIFETIME)
+            };
             for weapon in weapons {
-                let item_kind = match self.weapons[weapon].get_kind() {
-                    WeaponKind::M4 => ItemKind::M4,
-                    WeaponKind::Ak47 => ItemKind::Ak47,
-                    WeaponKind::PlasmaRifle => ItemKind::PlasmaGun,
-                    WeaponKind::RocketLauncher => ItemKind::RocketLauncher,
-                };
-                self.spawn_item(engine, item_kind, drop_position, true, Some(20.0))
+                if weapon == current_weapon {
+                    let weapon_kind = self.weapons[weapon].get_kind();
+                    self.spawn_item(
+                        engine,
+                        ItemKind::from_weapon(weapon_kind),
+                        drop_position,
+                        true,
+                        dropped_item_lifetime,
+                    )
                     .await;
+                    if let Some(ammo_kind) = ItemKind::ammo_for(weapon_kind) {
+                        let offset = Vector3::new(
+                            rand::thread_rng().gen_range(-0.6..0.6),
+                            0.0,
+                            rand::thread_rng().gen_range(-0.6..0.6),
+                        );
+                        self.spawn_item(
+                            engine,
+                            ammo_kind,
+                            drop_position + offset,
+                            true,
+                            dropped_item_lifetime,
+                        )
+                        .await;
+                    }
+                }
                 self.remove_weapon(engine, weapon);
             }
 
@@ -874,18 +2122,21 @@ impl Level {
     }
 
     async fn give_item(&mut self, engine: &mut Engine, actor: Handle<Actor>, kind: ItemKind) {
+        let ammo_amount = self.options.economy().scale_ammo(AMMO_AMOUNT);
         if self.actors.contains(actor) {
             let character = self.actors.get_mut(actor);
             match kind {
-                ItemKind::Medkit => character.heal(20.0),
-                ItemKind::Ak47 | ItemKind::PlasmaGun | ItemKind::M4 | ItemKind::RocketLauncher => {
-                    let weapon_kind = match kind {
-                        ItemKind::Ak47 => WeaponKind::Ak47,
-                        ItemKind::PlasmaGun => WeaponKind::PlasmaRifle,
-                        ItemKind::M4 => WeaponKind::M4,
-                        ItemKind::RocketLauncher => WeaponKind::RocketLauncher,
-                        _ => unreachable!(),
-                    };
+                ItemKind::Medkit => character.heal(MEDKIT_HEALTH),
+                ItemKind::Shield => character.give_shield(),
+                ItemKind::Ak47
+                | ItemKind::PlasmaGun
+                | ItemKind::M4
+                | ItemKind::RocketLauncher
+                | ItemKind::Flamethrower
+                | ItemKind::LightningGun
+                | ItemKind::MineLayer
+                | ItemKind::SentryKit => {
+                    let weapon_kind = kind.weapon().unwrap();
 
                     let mut found = false;
                     for weapon_handle in character.weapons() {
@@ -893,7 +2144,7 @@ impl Level {
                         // If actor already has weapon of given kind, then just add ammo to it.
                         if weapon.get_kind() == weapon_kind {
                             found = true;
-                            weapon.add_ammo(200);
+                            weapon.add_ammo(ammo_amount);
                             break;
                         }
                     }
@@ -906,9 +2157,9 @@ impl Level {
                     for weapon in character.weapons() {
                         let weapon = &mut self.weapons[*weapon];
                         let (weapon_kind, ammo) = match kind {
-                            ItemKind::Plasma => (WeaponKind::PlasmaRifle, 200),
-                            ItemKind::Ak47Ammo => (WeaponKind::Ak47, 200),
-                            ItemKind::M4Ammo => (WeaponKind::M4, 200),
+                            ItemKind::Plasma => (WeaponKind::PlasmaRifle, ammo_amount),
+                            ItemKind::Ak47Ammo => (WeaponKind::Ak47, ammo_amount),
+                            ItemKind::M4Ammo => (WeaponKind::M4, ammo_amount),
                             _ => continue,
                         };
                         if weapon.get_kind() == weapon_kind {
@@ -923,6 +2174,24 @@ impl Level {
 
     async fn pickup_item(&mut self, engine: &mut Engine, actor: Handle<Actor>, item: Handle<Item>) {
         if self.actors.contains(actor) && self.items.contains(item) {
+            let economy = self.options.economy();
+            let weapon_kind = self.items.get(item).get_kind().weapon();
+            let stays =
+                economy.weapon_stay && weapon_kind.is_some() && !self.items.get(item).is_dropped();
+            // Weapon that stays can't be picked up again just for its ammo.
+            if stays {
+                let weapons = &self.weapons;
+                if self
+                    .actors
+                    .get(actor)
+                    .weapons()
+                    .iter()
+                    .any(|&weapon| Some(weapons[weapon].get_kind()) == weapon_kind)
+                {
+                    return;
+                }
+            }
+
             let item = self.items.get_mut(item);
 
             self.sender
@@ -935,8 +2204,23 @@ impl Level {
 
             let scene = &mut engine.scenes[self.scene];
             let position = item.position(&scene.graph);
-            item.pick_up();
             let kind = item.get_kind();
+            let reactivation = if self.options.mutators().contains(Mutators::PRACTICE) {
+                0.0
+            } else if kind == ItemKind::Medkit {
+                self.options.difficulty().item_reactivation()
+                    * economy.health_density.medkit_reactivation()
+            } else {
+                self.options.difficulty().item_reactivation()
+            };
+            if !stays {
+                item.pick_up(reactivation);
+            }
+            self.record_event(MatchEvent::PickedUp {
+                actor: self.actors.get(actor).name.clone(),
+                item: format!("{:?}", kind),
+                position,
+            });
             self.sender
                 .as_ref()
                 .unwrap()
@@ -948,6 +2232,13 @@ impl Level {
                     radius: 2.0,
                 })
                 .unwrap();
+            if actor == self.player {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::ShowPickupToast { kind })
+                    .unwrap();
+            }
             self.give_item(engine, actor, kind).await;
         }
     }
@@ -963,18 +2254,39 @@ impl Level {
         basis: Matrix3<f32>,
     ) {
         let scene = &mut engine.scenes[self.scene];
-        let projectile = Projectile::new(
-            kind,
-            engine.resource_manager.clone(),
-            scene,
-            direction,
-            position,
-            owner,
-            initial_velocity,
-            self.sender.as_ref().unwrap().clone(),
-            basis,
-        )
-        .await;
+        let sender = self.sender.as_ref().unwrap().clone();
+        let mut projectile = match self.projectiles.take_free(kind) {
+            Some(mut projectile) => {
+                projectile.respawn(
+                    scene,
+                    direction,
+                    position,
+                    owner,
+                    initial_velocity,
+                    sender,
+                    basis,
+                );
+                projectile
+            }
+            None => {
+                Projectile::new(
+                    kind,
+                    engine.resource_manager.clone(),
+                    scene,
+                    direction,
+                    position,
+                    owner,
+                    initial_velocity,
+                    sender,
+                    basis,
+                )
+                .await
+            }
+        };
+        if let Some(settings) = self.settings.as_ref() {
+            let density = settings.read().unwrap().tracer_density;
+            projectile.set_trail_enabled(rand::thread_rng().gen::<f32>() < density);
+        }
         self.projectiles.add(projectile);
     }
 
@@ -988,25 +2300,262 @@ impl Level {
     ) {
         if self.weapons.contains(weapon_handle) {
             let scene = &mut engine.scenes[self.scene];
+            let infinite_ammo = self.is_practice();
             let weapon = &mut self.weapons[weapon_handle];
+            if infinite_ammo {
+                let ammo = weapon.ammo().max(weapon.definition.ammo);
+                weapon.set_ammo(ammo);
+            }
             if weapon.try_shoot(scene, time) {
-                let kind = weapon.definition.projectile;
+                let owner = weapon.owner();
+                if self.actors.contains(owner) && self.actors.get(owner).is_protected() {
+                    self.actors.get_mut(owner).cancel_protection();
+                    self.set_shimmer(scene, owner, true);
+                }
+                let weapon = &self.weapons[weapon_handle];
+                self.weapon_stats.record_shot(weapon.get_kind());
+                if self.actors.contains(owner) {
+                    self.weapon_stats
+                        .record_actor_shot(&self.actors.get(owner).name);
+                }
+                let shot = weapon.definition.shot;
                 let position = weapon.get_shot_position(&scene.graph);
                 let direction = direction
                     .unwrap_or_else(|| weapon.get_shot_direction(&scene.graph))
                     .try_normalize(std::f32::EPSILON)
                     .unwrap_or_else(|| Vector3::z());
+                let direction = weapon.apply_spread(direction);
                 let basis = weapon.world_basis(&scene.graph);
-                self.create_projectile(
-                    engine,
-                    kind,
-                    position,
-                    direction,
-                    initial_velocity,
-                    weapon_handle,
-                    basis,
-                )
-                .await;
+                match shot {
+                    Shot::Projectile(kind) => {
+                        self.create_projectile(
+                            engine,
+                            kind,
+                            position,
+                            direction,
+                            initial_velocity,
+                            weapon_handle,
+                            basis,
+                        )
+                        .await
+                    }
+                    Shot::Beam => self.fire_beam(engine, weapon_handle, position, direction),
+                    Shot::Mine => self.place_mine(engine, weapon_handle, position, direction),
+                    Shot::Turret => self.place_turret(engine, weapon_handle, position, direction),
+                }
+            }
+        }
+    }
+
+    /// Beam hits first actor on the line of fire instantly and jumps to one more enemy next
+    /// to the victim with reduced damage.
+    fn fire_beam(
+        &mut self,
+        engine: &mut Engine,
+        weapon: Handle<Weapon>,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+    ) {
+        let scene = &mut engine.scenes[self.scene];
+        let owner = self.weapons[weapon].owner();
+
+        let mut query_buffer = Vec::default();
+        scene.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(origin),
+                ray_direction: direction,
+                max_len: BEAM_RANGE,
+                groups: InteractionGroups::all(),
+                sort_results: true,
+            },
+            &mut query_buffer,
+        );
+
+        let mut end = origin + direction.scale(BEAM_RANGE);
+        let mut victim = Handle::NONE;
+        let mut zone = None;
+        for hit in query_buffer.iter() {
+            let collider = scene.physics.colliders.get(&hit.collider).unwrap();
+            if collider.shape().as_trimesh().is_some() {
+                end = hit.position.coords;
+                break;
+            }
+            let body = scene
+                .physics
+                .bodies
+                .handle_map()
+                .key_of(&collider.parent().unwrap())
+                .cloned()
+                .unwrap();
+            if let Some((handle, actor)) = self
+                .actors
+                .pair_iter()
+                .find(|(handle, actor)| actor.get_body() == body && *handle != owner)
+            {
+                victim = handle;
+                end = hit.position.coords;
+                zone = Some(HitZone::resolve(actor.position(&scene.physics), end));
+                break;
+            }
+            if let Some(turret) = self.turrets.find_by_body(body) {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::DamageTurret {
+                        turret,
+                        amount: BEAM_DAMAGE,
+                    })
+                    .unwrap();
+                end = hit.position.coords;
+                break;
+            }
+        }
+
+        let sender = self.sender.as_ref().unwrap();
+
+        // Mines have no colliders, so beam hits a mine only if it is in front of the hit.
+        if let Some((mine, t)) = self
+            .mines
+            .hit_test(&Ray::from_two_points(origin, end), &scene.graph)
+        {
+            end = origin + (end - origin).scale(t);
+            self.effects
+                .spawn_lightning(&mut scene.graph, origin, end, BEAM_COLOR);
+            self.mines.detonate(mine, scene, &self.actors, sender);
+            return;
+        }
+
+        self.effects
+            .spawn_lightning(&mut scene.graph, origin, end, BEAM_COLOR);
+
+        if victim.is_none() {
+            return;
+        }
+
+        sender
+            .send(Message::DamageActor {
+                actor: victim,
+                who: owner,
+                amount: BEAM_DAMAGE,
+                direction: Some(direction),
+                zone,
+            })
+            .unwrap();
+
+        let team = self.team_of(owner);
+        let victim_position = self.actors.get(victim).position(&scene.physics);
+        let chained = self
+            .actors
+            .pair_iter()
+            .filter(|(handle, actor)| {
+                *handle != victim
+                    && *handle != owner
+                    && !actor.is_dead()
+                    && (team == Team::None || actor.team() != team)
+            })
+            .map(|(handle, actor)| {
+                let position = actor.position(&scene.physics);
+                (handle, position, position.metric_distance(&victim_position))
+            })
+            .filter(|&(_, position, distance)| {
+                distance < CHAIN_RADIUS
+                    && !SoundManager::is_occluded(victim_position, position, &scene.physics)
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+        if let Some((handle, position, _)) = chained {
+            self.effects
+                .spawn_lightning(&mut scene.graph, victim_position, position, BEAM_COLOR);
+            sender
+                .send(Message::DamageActor {
+                    actor: handle,
+                    who: owner,
+                    amount: BEAM_DAMAGE * CHAIN_FACTOR,
+                    direction: Some(position - victim_position),
+                    zone: None,
+                })
+                .unwrap();
+        }
+    }
+
+    fn team_of(&self, actor: Handle<Actor>) -> Team {
+        if self.actors.contains(actor) {
+            self.actors.get(actor).team()
+        } else {
+            Team::None
+        }
+    }
+
+    /// Deployables are placed on a surface at which shooter aims, or on the floor in front of
+    /// shooter if there is no suitable surface in range. Returns position and normal of the
+    /// surface, ammo is given back if there is nowhere to place the deployable.
+    fn find_deploy_surface(
+        &mut self,
+        scene: &Scene,
+        weapon: Handle<Weapon>,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        floor_only: bool,
+    ) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        let reach = origin + direction.scale(DEPLOY_RANGE);
+        let surface = cast_to_static_geometry(&scene.physics, origin, direction, DEPLOY_RANGE)
+            .filter(|&(_, normal)| !floor_only || normal.y > MIN_FLOOR_NORMAL_Y)
+            .or_else(|| cast_to_static_geometry(&scene.physics, reach, -Vector3::y(), 10.0));
+        if surface.is_none() {
+            self.weapons[weapon].add_ammo(1);
+        }
+        surface
+    }
+
+    fn place_mine(
+        &mut self,
+        engine: &mut Engine,
+        weapon: Handle<Weapon>,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+    ) {
+        let scene = &mut engine.scenes[self.scene];
+        if let Some((position, normal)) =
+            self.find_deploy_surface(scene, weapon, origin, direction, false)
+        {
+            let owner = self.weapons[weapon].owner();
+            let mine = Mine::new(
+                &mut scene.graph,
+                position,
+                normal,
+                owner,
+                self.team_of(owner),
+            );
+            self.mines.add(mine, &mut scene.graph);
+        }
+    }
+
+    fn place_turret(
+        &mut self,
+        engine: &mut Engine,
+        weapon: Handle<Weapon>,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+    ) {
+        let scene = &mut engine.scenes[self.scene];
+        if let Some((position, _)) =
+            self.find_deploy_surface(scene, weapon, origin, direction, true)
+        {
+            let owner = self.weapons[weapon].owner();
+            let turret = Turret::new(scene, position, owner, self.team_of(owner));
+            self.turrets.add(turret, scene);
+        }
+    }
+
+    fn shake_camera(&mut self, engine: &Engine, position: Vector3<f32>, radius: f32) {
+        if self.actors.contains(self.player) {
+            let scene = &engine.scenes[self.scene];
+            if let Actor::Player(player) = self.actors.get_mut(self.player) {
+                let distance = scene.graph[player.camera()]
+                    .global_position()
+                    .metric_distance(&position);
+                if distance < radius {
+                    player.shake_camera(1.0 - distance / radius);
+                }
             }
         }
     }
@@ -1021,10 +2570,18 @@ impl Level {
         kind: BotKind,
         name: Option<String>,
     ) -> Handle<Actor> {
+        // Bots of survival fight the player together.
+        let team = if self.director.is_some() {
+            Team::Red
+        } else {
+            join_team(&self.options, &self.leader_board, name.as_deref())
+        };
         let bot = spawn_bot(
             kind,
             name,
-            &self.spawn_points,
+            team,
+            &mut self.spawn_points,
+            &self.options,
             &mut self.actors,
             &mut self.weapons,
             engine.resource_manager.clone(),
@@ -1033,166 +2590,1438 @@ impl Level {
             &mut engine.scenes[self.scene],
         )
         .await;
+        self.make_target_dummy(&engine.scenes[self.scene], bot);
 
         self.sender
             .as_ref()
             .unwrap()
             .send(Message::AddNotification {
-                text: format!("Bot {} spawned!", self.actors.get(bot).name),
+                text: tr_format("Bot {} spawned!", &[&self.actors.get(bot).name]),
             })
             .unwrap();
 
+        self.actors.get_mut(bot).protect(SPAWN_PROTECTION_TIME);
+        self.telefrag(&engine.scenes[self.scene], bot);
+        self.record_spawn(engine, bot);
+
         bot
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn damage_actor(
         &mut self,
         engine: &Engine,
         actor: Handle<Actor>,
         who: Handle<Actor>,
         amount: f32,
+        direction: Option<Vector3<f32>>,
+        zone: Option<HitZone>,
         time: GameTime,
     ) {
         if self.actors.contains(actor)
             && (who.is_none() || who.is_some() && self.actors.contains(who))
         {
+            // Dead actors can still be hit by splash or projectiles already in flight,
+            // such hits must not count in stats, damage events or assists.
+            if self.actors.get(actor).is_protected() || self.actors.get(actor).is_dead() {
+                return;
+            }
+
+            let amount = amount * zone.map_or(1.0, HitZone::damage_multiplier);
+            let headshot = zone == Some(HitZone::Head);
+            let mutators = self.options.mutators();
+            let amount = if who.is_some() && mutators.contains(Mutators::INSTAGIB) {
+                let victim = self.actors.get(actor);
+                victim.get_health() + victim.get_armor() + victim.shield
+            } else if who.is_some() {
+                let difficulty = self.options.difficulty();
+                match (self.actors.get(who), self.actors.get(actor)) {
+                    (Actor::Bot(_), Actor::Player(_)) => {
+                        amount
+                            * difficulty.bot_damage_dealt()
+                            * self
+                                .director
+                                .as_ref()
+                                .map_or(1.0, |director| director.damage_scale())
+                    }
+                    (Actor::Player(_), Actor::Bot(_)) => amount * difficulty.bot_damage_received(),
+                    _ => amount,
+                }
+            } else {
+                amount
+            };
+
             let mut who_name = Default::default();
             let message = if who.is_some() {
                 who_name = self.actors.get(who).name.clone();
-                format!(
+                tr_format(
                     "{} dealt {} damage to {}!",
-                    who_name,
-                    amount,
-                    self.actors.get(actor).name
+                    &[&who_name, &amount, &self.actors.get(actor).name],
+                )
+            } else {
+                tr_format(
+                    "{} took {} damage!",
+                    &[&self.actors.get(actor).name, &amount],
                 )
+            };
+
+            self.sender
+                .as_ref()
+                .unwrap()
+                .send(Message::AddNotification { text: message })
+                .unwrap();
+
+            let scene = &engine.scenes[self.scene];
+            let who_position = if who.is_some() {
+                Some(self.actors.get(who).position(&scene.physics))
+            } else {
+                None
+            };
+            if who.is_some() && who != actor && mutators.contains(Mutators::VAMPIRE) {
+                self.actors.get_mut(who).heal(amount * VAMPIRE_FACTOR);
+            }
+            if who.is_some() && who == self.player {
+                if let (Some(training), Actor::Bot(_)) =
+                    (self.training.as_mut(), self.actors.get(actor))
+                {
+                    training.on_target_hit();
+                }
+            }
+            let victim_handle = actor;
+            let victim_is_player = actor == self.player;
+            let self_damage = who == actor;
+            let weapon = if who.is_some() {
+                let weapon = self.actors.get(who).current_weapon();
+                if self.weapons.contains(weapon) {
+                    Some(self.weapons[weapon].get_kind())
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+            let actor = self.actors.get_mut(actor);
+            match actor {
+                Actor::Bot(bot) => bot.on_damaged(who_position, direction, &scene.physics, time),
+                Actor::Player(player) => player.flinch(amount, direction),
+            }
+            let had_shield = actor.shield > 0.0;
+            actor.damage(amount);
+            let killed = actor.is_dead();
+
+            let victim = actor.name.clone();
+            let victim_voice = actor.voice();
+            let position = actor.position(&scene.physics);
+            if had_shield {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::CreateEffect {
+                        kind: EffectKind::ShieldSpark,
+                        position,
+                    })
+                    .unwrap();
+            }
+            if let Some(observer) = self.observer.as_mut() {
+                observer.add_action(position);
+            }
+            if let (Some(weapon), false) = (weapon, self_damage) {
+                self.weapon_stats.record_hit(weapon, amount, killed);
+            }
+            if who.is_some() && !self_damage {
+                self.assists.record(victim_handle, who, amount, self.time);
+                self.weapon_stats
+                    .record_actor_hit(&who_name, amount, killed);
+            }
+            if who == self.player && !self_damage && self.is_practice() {
+                self.practice.record_hit(self.time, amount);
+            }
+            if who.is_some() && who == self.player && !self_damage {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::HitConfirmed { killed, headshot })
+                    .unwrap();
+            }
+            let attacker = if who.is_some() {
+                Some(who_name.clone())
+            } else {
+                None
+            };
+            self.record_event(MatchEvent::Damaged {
+                victim: victim.clone(),
+                attacker: attacker.clone(),
+                amount,
+                position,
+            });
+
+            if killed {
+                self.say(victim_voice, &victim, position, VoiceEvent::Death);
+
+                if victim_is_player {
+                    self.death_description = if who == self.player {
+                        Some(tr("You killed yourself"))
+                    } else if let Some(weapon) = weapon {
+                        let template = if headshot {
+                            "Killed by {} with {} (headshot)"
+                        } else {
+                            "Killed by {} with {}"
+                        };
+                        Some(tr_format(template, &[&who_name, &tr(weapon.name())]))
+                    } else if who.is_some() {
+                        Some(tr_format("Killed by {}", &[&who_name]))
+                    } else {
+                        None
+                    };
+                    if self.checkpoint.is_some() {
+                        let control_scheme = self.control_scheme.as_ref().unwrap().read().unwrap();
+                        let prompt = format!(
+                            "Press {} to restart from checkpoint",
+                            control_scheme.jump.button.name()
+                        );
+                        self.death_description = Some(match self.death_description.take() {
+                            Some(text) => format!("{}\n{}", text, prompt),
+                            None => prompt,
+                        });
+                    }
+                }
+
+                if headshot && who.is_some() && !self_damage {
+                    self.sender
+                        .as_ref()
+                        .unwrap()
+                        .send(Message::AddNotification {
+                            text: tr_format("{} killed {} with a headshot!", &[&who_name, &victim]),
+                        })
+                        .unwrap();
+                }
+
+                self.record_event(MatchEvent::Killed {
+                    victim,
+                    killer: attacker,
+                    position,
+                });
+
+                if who.is_some() {
+                    let killer = self.actors.get(who);
+                    let killer_voice = killer.voice();
+                    let killer_position = killer.position(&scene.physics);
+                    self.say(killer_voice, &who_name, killer_position, VoiceEvent::Kill);
+
+                    if self.phase.is_live() {
+                        self.leader_board.add_frag(who_name)
+                    }
+                }
+
+                // Deaths from own weapon or from environment are suicides.
+                if who.is_none() || self_damage {
+                    self.award(victim_handle, ScoreEvent::Suicide);
+                } else {
+                    self.award(who, ScoreEvent::Kill);
+                }
+                for assistant in self.assists.take_assists(victim_handle, who, self.time) {
+                    self.award(assistant, ScoreEvent::Assist);
+                }
+            }
+        }
+    }
+
+    /// Plays random voice line of a character for given event at its position, subtitle of
+    /// the line is shown on HUD if subtitles are enabled.
+    fn say(
+        &mut self,
+        voice: &'static str,
+        speaker: &str,
+        position: Vector3<f32>,
+        event: VoiceEvent,
+    ) {
+        let (volume, subtitles) = self.settings.as_ref().map_or((1.0, true), |settings| {
+            let settings = settings.read().unwrap();
+            (settings.voice_volume, settings.subtitles)
+        });
+
+        if let Some(line) = self.voices.pick(voice, event) {
+            let sender = self.sender.as_ref().unwrap();
+            if let Some(sound) = line.sound.as_ref() {
+                sender
+                    .send(Message::PlaySound {
+                        path: sound.clone(),
+                        position,
+                        gain: volume,
+                        rolloff_factor: 2.0,
+                        radius: 5.0,
+                    })
+                    .unwrap();
+            }
+            if subtitles && !line.subtitle.is_empty() {
+                sender
+                    .send(Message::ShowSubtitle {
+                        speaker: speaker.to_owned(),
+                        text: tr(&line.subtitle),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    async fn spawn_item(
+        &mut self,
+        engine: &mut Engine,
+        kind: ItemKind,
+        position: Vector3<f32>,
+        adjust_height: bool,
+        lifetime: Option<f32>,
+    ) {
+        let position = if adjust_height {
+            self.pick(engine, position, position - Vector3::new(0.0, 1000.0, 0.0))
+        } else {
+            position
+        };
+        let scene = &mut engine.scenes[self.scene];
+        let mut item = Item::new(
+            kind,
+            position,
+            scene,
+            engine.resource_manager.clone(),
+            self.sender.as_ref().unwrap().clone(),
+        )
+        .await;
+        item.set_lifetime(lifetime);
+        self.items.add(item);
+    }
+
+    /// Returns time since match went live, it is zero during warm-up.
+    pub fn time(&self) -> f32 {
+        if self.phase.is_live() {
+            self.time - self.match_start_time
+        } else {
+            0.0
+        }
+    }
+
+    pub fn phase(&self) -> MatchPhase {
+        self.phase
+    }
+
+    pub fn overtime(&self) -> OvertimeState {
+        self.overtime
+    }
+
+    fn play_countdown_sound(&self, scene: &Scene) {
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::PlaySound {
+                path: PathBuf::from(COUNTDOWN_SOUND),
+                position: self.observer_position(scene),
+                gain: 1.0,
+                rolloff_factor: 1.0,
+                radius: 10.0,
+            })
+            .unwrap();
+    }
+
+    fn update_phase(&mut self, scene: &mut Scene, dt: f32) {
+        match self.phase {
+            MatchPhase::WarmUp { time_left } => {
+                let time_left = time_left - dt;
+                if time_left <= 0.0 {
+                    self.phase = MatchPhase::Countdown {
+                        time_left: COUNTDOWN_TIME,
+                    };
+                    self.play_countdown_sound(scene);
+                } else {
+                    self.phase = MatchPhase::WarmUp { time_left };
+                }
+            }
+            MatchPhase::Countdown { time_left } => {
+                let new_time_left = time_left - dt;
+                if new_time_left <= 0.0 {
+                    self.start_match(scene);
+                } else {
+                    self.phase = MatchPhase::Countdown {
+                        time_left: new_time_left,
+                    };
+                    // Tick on every whole second.
+                    if new_time_left.ceil() < time_left.ceil() {
+                        self.play_countdown_sound(scene);
+                    }
+                }
+            }
+            MatchPhase::Live => (),
+        }
+    }
+
+    /// Makes match live: every actor is moved to its own spawn point and scores of warm-up
+    /// are discarded so everyone starts at the same time.
+    fn start_match(&mut self, scene: &mut Scene) {
+        self.phase = MatchPhase::Live;
+        self.match_start_time = self.time;
+        self.leader_board.reset();
+
+        // Actors are placed one by one, so every next actor avoids ones placed before it.
+        let handles = self
+            .actors
+            .pair_iter()
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in handles {
+            self.actors.get_mut(handle).protect(SPAWN_PROTECTION_TIME);
+            let team = self.actors.get(handle).team();
+            if let Some(position) =
+                self.spawn_points
+                    .select(team, &self.options, &self.actors, &scene.physics)
+            {
+                let body = self.actors.get(handle).get_body();
+                if let Some(body) = scene.physics.bodies.get_mut(&body) {
+                    body.set_position(
+                        Isometry3::translation(position.x, position.y, position.z),
+                        true,
+                    );
+                    body.set_linvel(Default::default(), true);
+                }
+            }
+        }
+
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::AddNotification { text: tr("Fight!") })
+            .unwrap();
+    }
+
+    pub fn map(&self) -> &Path {
+        &self.map
+    }
+
+    pub fn is_training(&self) -> bool {
+        self.training.is_some()
+    }
+
+    /// Training and campaign are played alone, regular matches are announced in LAN.
+    pub fn is_single_player(&self) -> bool {
+        self.is_training() || self.campaign_level.is_some()
+    }
+
+    pub fn is_weapon_wheel_open(&self) -> bool {
+        if self.player.is_some() {
+            if let Actor::Player(player) = self.actors.get(self.player) {
+                return player.weapon_wheel().is_some();
+            }
+        }
+        false
+    }
+
+    /// Pitches every sound of the level by match speed multiplied by given debug time scale.
+    pub fn set_sound_pitch(&self, time_scale: f32) {
+        self.sound_manager
+            .set_pitch((self.options.time_scale() * time_scale) as f64);
+    }
+
+    pub fn is_practice(&self) -> bool {
+        self.options.mutators().contains(Mutators::PRACTICE)
+    }
+
+    pub fn practice_readout(&mut self) -> Option<String> {
+        if self.is_practice() {
+            Some(self.practice.readout(self.time))
+        } else {
+            None
+        }
+    }
+
+    /// Converts real frame time into match time. Every timer of the level (weapon cooldowns,
+    /// bot AI, respawns, etc.) must use match time so they stay balanced when game speed
+    /// mutator is active.
+    pub fn scale_time(&self, time: GameTime) -> GameTime {
+        GameTime {
+            clock: time.clock,
+            elapsed: self.time as f64,
+            delta: time.delta * self.options.time_scale(),
+        }
+    }
+
+    fn update_respawn(&mut self, time: GameTime) {
+        // Respawn is done in deferred manner: we just gather all info needed
+        // for respawn, wait some time and then re-create actor. Actor is spawned
+        // by sending a message: this is needed because there are some other
+        // systems that catches such messages and updates their own state.
+        for respawn_entry in self.respawn_list.iter_mut() {
+            match respawn_entry {
+                RespawnEntry::Bot(v) => {
+                    v.time_left -= time.delta;
+                    if v.time_left <= 0.0 {
+                        self.sender
+                            .as_mut()
+                            .unwrap()
+                            .send(Message::SpawnBot {
+                                kind: v.kind,
+                                name: v.name.clone(),
+                            })
+                            .unwrap();
+                    }
+                }
+                RespawnEntry::Player(v) => {
+                    v.time_left -= time.delta;
+                    if v.time_left <= 0.0 {
+                        // Survival is over once player dies, death camera is shown meanwhile.
+                        let message = if self.director.is_some() {
+                            Message::EndMatch
+                        } else {
+                            Message::SpawnPlayer
+                        };
+                        self.sender.as_mut().unwrap().send(message).unwrap();
+                    }
+                }
+            }
+        }
+
+        self.respawn_list.retain(|entry| match entry {
+            RespawnEntry::Bot(v) => v.time_left >= 0.0,
+            RespawnEntry::Player(v) => v.time_left >= 0.0,
+        });
+    }
+
+    fn update_spectator_camera(&mut self, scene: &mut Scene, time: GameTime) {
+        if let Some(observer) = self.observer.as_mut() {
+            if let Node::Camera(camera) = &mut scene.graph[self.spectator_camera] {
+                observer.update(camera, time.delta);
+            }
+            return;
+        }
+
+        let reduced_motion = self
+            .settings
+            .as_ref()
+            .map_or(false, |settings| settings.read().unwrap().reduced_motion);
+        if let Some(death_camera) = self.death_camera.as_mut() {
+            // Camera smoothly moves from player's eyes to the orbit.
+            if !reduced_motion {
+                death_camera.angle += DEATH_CAMERA_SPEED * time.delta;
+            }
+            self.target_spectator_position = death_camera.position();
+        }
+
+        if let Node::Camera(spectator_camera) = &mut scene.graph[self.spectator_camera] {
+            let mut position = spectator_camera.global_position();
+            position.follow(&self.target_spectator_position, time.smoothing(0.1));
+            let transform = spectator_camera.local_transform_mut();
+            transform.set_position(position);
+            if let Some(death_camera) = self.death_camera.as_ref() {
+                transform.set_rotation(death_camera.rotation());
+            }
+        }
+    }
+
+    /// Turns observer mode on or off. Player leaves the match while observing it and joins
+    /// back at a spawn point.
+    async fn toggle_observer(&mut self, engine: &mut Engine) {
+        if self.observer.take().is_some() {
+            self.notify(tr("Observer mode is off"));
+            self.sender
+                .as_ref()
+                .unwrap()
+                .send(Message::SpawnPlayer)
+                .unwrap();
+            return;
+        }
+        if self.training.is_some() || self.campaign_level.is_some() {
+            self.notify(tr("Observer mode is disabled in single-player levels"));
+            return;
+        }
+
+        let position = self.observer_position(&engine.scenes[self.scene]);
+        let mut yaw = 0.0;
+        if self.actors.contains(self.player) {
+            if let Actor::Player(player) = self.actors.get(self.player) {
+                yaw = player.yaw();
+            }
+            self.remove_actor(engine, self.player).await;
+        }
+        self.player = Handle::NONE;
+        self.respawn_list
+            .retain(|entry| !matches!(entry, RespawnEntry::Player(_)));
+        self.death_camera = None;
+        self.death_description = None;
+        if let Node::Camera(spectator_camera) =
+            &mut engine.scenes[self.scene].graph[self.spectator_camera]
+        {
+            spectator_camera.set_enabled(true);
+        }
+        self.observer = Some(Observer::new(position, yaw));
+        self.notify(tr("Observer mode is on"));
+    }
+
+    pub fn is_observing(&self) -> bool {
+        self.observer.is_some()
+    }
+
+    /// Returns text of top bar of observer mode, None if player is not observing.
+    pub fn observer_summary(&self) -> Option<String> {
+        self.observer.as_ref()?;
+
+        let team_summary = |team: Team| {
+            let alive = self
+                .actors
+                .iter()
+                .filter(|actor| actor.team() == team && !actor.is_dead())
+                .collect::<Vec<_>>();
+            let health = alive.iter().map(|actor| actor.get_health()).sum::<f32>();
+            format!(
+                "{:?} {} - {} alive, {} HP",
+                team,
+                self.leader_board.team_score(team),
+                alive.len(),
+                health as u32
+            )
+        };
+        let text = match self.options {
+            MatchOptions::TeamDeathMatch(_) | MatchOptions::CaptureTheFlag(_) => format!(
+                "{}        {}",
+                team_summary(Team::Red),
+                team_summary(Team::Blue)
+            ),
+            _ => {
+                let mut scores = self.leader_board.values().iter().collect::<Vec<_>>();
+                scores.sort_by(|a, b| b.1.kills.cmp(&a.1.kills));
+                scores
+                    .iter()
+                    .take(3)
+                    .map(|(name, score)| {
+                        let state = self
+                            .actors
+                            .iter()
+                            .find(|actor| actor.name == **name && !actor.is_dead())
+                            .map_or_else(
+                                || "dead".to_owned(),
+                                |actor| format!("{} HP", actor.get_health() as u32),
+                            );
+                        format!("{} {} ({})", name, score.kills, state)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("    ")
+            }
+        };
+        Some(format!("OBSERVER   {}\n{}", text, observer::HELP))
+    }
+
+    fn call_vote(&mut self, caller: &str, subject: &VoteSubject) {
+        let error = if self.vote.is_some() {
+            Some(tr("Another vote is in progress"))
+        } else if self.training.is_some() || self.campaign_level.is_some() {
+            Some(tr("Votes are disabled in single-player levels"))
+        } else if let VoteSubject::Kick(name) = subject {
+            let in_respawn = self
+                .respawn_list
+                .iter()
+                .any(|entry| matches!(entry, RespawnEntry::Bot(bot) if bot.name == *name));
+            match self.actors.iter().find(|actor| actor.name == *name) {
+                Some(Actor::Player(_)) => Some(tr("Host can't be kicked")),
+                None if !in_respawn => Some(tr_format("There is no player named {}", &[&name])),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            self.notify(error);
+            return;
+        }
+
+        // Dead players and bots vote as well.
+        let mut players = 0;
+        let mut bots = Vec::new();
+        for actor in self.actors.iter() {
+            match actor {
+                Actor::Bot(bot) => bots.push(bot.name.clone()),
+                Actor::Player(_) => players += 1,
+            }
+        }
+        for entry in self.respawn_list.iter() {
+            match entry {
+                RespawnEntry::Bot(bot) => bots.push(bot.name.clone()),
+                RespawnEntry::Player(_) => players += 1,
+            }
+        }
+        self.notify(tr_format(
+            "{} called a vote to {}",
+            &[&caller, &subject.describe()],
+        ));
+        self.vote = Some(Vote::new(subject.clone(), caller.to_owned(), players, bots));
+    }
+
+    /// Counts down vote in progress and enforces its result once it is known.
+    fn update_vote(&mut self, dt: f32) {
+        let vote = match self.vote.as_mut() {
+            Some(vote) => vote,
+            None => return,
+        };
+        vote.update(dt);
+        let result = vote.result();
+        let sender = self.sender.clone().unwrap();
+        if result == VoteResult::Pending {
+            if let Some(text) = vote.poll_prompt() {
+                sender.send(Message::ShowVote { text: Some(text) }).unwrap();
+            }
+            return;
+        }
+
+        let subject = vote.subject().clone();
+        self.vote = None;
+        sender.send(Message::ShowVote { text: None }).unwrap();
+        if result == VoteResult::Failed {
+            self.notify(tr_format("Vote to {} failed", &[&subject.describe()]));
+            return;
+        }
+
+        self.notify(tr_format("Vote to {} passed", &[&subject.describe()]));
+        match subject {
+            VoteSubject::Kick(name) => {
+                self.respawn_list
+                    .retain(|entry| !matches!(entry, RespawnEntry::Bot(bot) if bot.name == name));
+                if let Some((actor, _)) = self
+                    .actors
+                    .pair_iter()
+                    .find(|(_, actor)| actor.name == name)
+                {
+                    sender.send(Message::RemoveActor { actor }).unwrap();
+                }
+            }
+            VoteSubject::ChangeMap(map) => sender
+                .send(Message::StartNewGame {
+                    options: self.options,
+                    map,
+                })
+                .unwrap(),
+            VoteSubject::ChangeMode(options) => sender
+                .send(Message::StartNewGame {
+                    options,
+                    map: self.map.clone(),
+                })
+                .unwrap(),
+            VoteSubject::EndMatch => sender.send(Message::EndMatch).unwrap(),
+        }
+    }
+
+    fn update_death_zones(&mut self, scene: &Scene) {
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            for death_zone in self.death_zones.iter() {
+                if death_zone
+                    .bounds
+                    .is_contains_point(actor.position(&scene.physics))
+                {
+                    self.sender
+                        .as_ref()
+                        .unwrap()
+                        .send(Message::RespawnActor { actor: handle })
+                        .unwrap();
+                }
+            }
+        }
+    }
+
+    fn update_teleporters(&mut self, scene: &mut Scene) {
+        let sender = self.sender.as_ref().unwrap();
+        let mut teleported = Vec::new();
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            let position = actor.position(&scene.physics);
+            if let Some(teleporter) = self
+                .teleporters
+                .iter()
+                .find(|teleporter| teleporter.is_triggered_by(position))
+            {
+                let exit = teleporter.exit();
+                actor.set_position(&mut scene.physics, exit);
+                for &position in &[teleporter.position(), exit] {
+                    sender
+                        .send(Message::CreateEffect {
+                            kind: EffectKind::ItemAppear,
+                            position,
+                        })
+                        .unwrap();
+                }
+                teleported.push(handle);
+            }
+        }
+        for actor in teleported {
+            self.telefrag(scene, actor);
+        }
+    }
+
+    /// Remembers state of campaign level when player reaches a checkpoint.
+    fn update_checkpoints(&mut self, scene: &Scene) {
+        if self.campaign_level.is_none() || !self.actors.contains(self.player) {
+            return;
+        }
+
+        let position = self.actors.get(self.player).position(&scene.physics);
+        if let Some(index) = self
+            .checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.is_triggered_by(position))
+        {
+            self.checkpoints[index].set_reached();
+            let restart_position = self.checkpoints[index].restart_position();
+            self.checkpoint = self.take_snapshot(scene, restart_position);
+            self.notify(tr("Checkpoint reached"));
+        }
+    }
+
+    /// Returns None if player is dead.
+    fn take_snapshot(&self, scene: &Scene, position: Vector3<f32>) -> Option<Snapshot> {
+        Some(Snapshot {
+            position,
+            loadout: self.player_loadout()?,
+            bots: self
+                .actors
+                .iter()
+                .filter(|actor| matches!(actor, Actor::Bot(_)) && !actor.is_dead())
+                .map(|actor| BotState {
+                    name: actor.name.clone(),
+                    position: actor.position(&scene.physics),
+                    health: actor.get_health(),
+                    armor: actor.get_armor(),
+                })
+                .collect(),
+            items: self
+                .items
+                .pair_iter()
+                .filter(|(_, item)| !item.is_dropped())
+                .map(|(handle, item)| (handle, item.reactivation_time()))
+                .collect(),
+            leader_board: self.leader_board.clone(),
+        })
+    }
+
+    /// Brings dead player back to the latest checkpoint, bots, items and scores are restored
+    /// to the moment player reached it, so the death does not count.
+    async fn restart_from_checkpoint(&mut self, engine: &mut Engine) {
+        if self.actors.contains(self.player) || self.observer.is_some() {
+            return;
+        }
+        let snapshot = match self.checkpoint.take() {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+
+        self.respawn_list
+            .retain(|entry| !matches!(entry, RespawnEntry::Player(_)));
+        self.leader_board = snapshot.leader_board.clone();
+        self.player = self.spawn_player(engine).await;
+        self.apply_loadout(engine, &snapshot.loadout).await;
+
+        let scene = &mut engine.scenes[self.scene];
+        self.actors
+            .get_mut(self.player)
+            .set_position(&mut scene.physics, snapshot.position);
+        for state in snapshot.bots.iter() {
+            // Bots that died since then respawn as usual.
+            if let Some(bot) = self.actors.iter_mut().find(|actor| {
+                matches!(actor, Actor::Bot(_)) && actor.name == state.name && !actor.is_dead()
+            }) {
+                bot.set_position(&mut scene.physics, state.position);
+                bot.set_health(state.health);
+                bot.set_armor(state.armor);
+            }
+        }
+        for &(item, reactivation_time) in snapshot.items.iter() {
+            if self.items.contains(item) {
+                self.items
+                    .get_mut(item)
+                    .set_reactivation_time(reactivation_time);
+            }
+        }
+
+        self.checkpoint = Some(snapshot);
+        self.notify(tr("Restarted from checkpoint"));
+    }
+
+    /// Shows or hides protected actor for shimmer effect - model of a bot, weapon of the player
+    /// since player does not see their own model.
+    fn set_shimmer(&self, scene: &mut Scene, actor: Handle<Actor>, visible: bool) {
+        match self.actors.get(actor) {
+            Actor::Bot(bot) => bot.set_model_visibility(&mut scene.graph, visible),
+            Actor::Player(player) => {
+                let weapon = player.current_weapon();
+                if self.weapons.contains(weapon) {
+                    self.weapons[weapon].set_visibility(visible, &mut scene.graph);
+                }
+            }
+        }
+    }
+
+    fn update_spawn_protection(&mut self, scene: &mut Scene, dt: f32) {
+        let mut shimmers = Vec::new();
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            if actor.is_protected() {
+                actor.update_protection(dt);
+                let visible = !actor.is_protected()
+                    || (actor.protection_time() * SHIMMER_RATE) as u32 % 2 == 0;
+                shimmers.push((handle, visible));
+            }
+        }
+        for (actor, visible) in shimmers {
+            self.set_shimmer(scene, actor, visible);
+        }
+    }
+
+    fn update_heartbeat(&mut self, scene: &Scene, dt: f32) {
+        let enabled = self.settings.as_ref().map_or(true, |settings| {
+            settings.read().unwrap().damage_effects > 0.0
+        });
+        let health = if enabled && self.player.is_some() {
+            self.actors.get(self.player).get_health()
+        } else {
+            0.0
+        };
+        if health <= 0.0 || health >= damage_effects::LOW_HEALTH {
+            self.heartbeat_timer = 0.0;
+            return;
+        }
+        self.heartbeat_timer -= dt;
+        if self.heartbeat_timer <= 0.0 {
+            self.heartbeat_timer =
+                HEARTBEAT_INTERVAL * (0.5 + 0.5 * health / damage_effects::LOW_HEALTH);
+            self.sender
+                .as_ref()
+                .unwrap()
+                .send(Message::PlaySound {
+                    path: PathBuf::from(HEARTBEAT_SOUND),
+                    position: self.actors.get(self.player).position(&scene.physics),
+                    gain: 1.0,
+                    rolloff_factor: 1.0,
+                    radius: 10.0,
+                })
+                .unwrap();
+        }
+    }
+
+    /// Marks point player looks at. Enemy near the line of sight or a mine on it are marked
+    /// as such, otherwise player asks teammates to move to the point.
+    fn ping(&mut self, engine: &mut Engine, danger: bool) {
+        if !self.pings.can_ping() || !self.actors.contains(self.player) {
+            return;
+        }
+        let scene = &engine.scenes[self.scene];
+        let player = match self.actors.get(self.player) {
+            Actor::Player(player) if !player.is_dead() => player,
+            _ => return,
+        };
+        let team = player.team();
+        let camera = &scene.graph[player.camera()];
+        let origin = camera.global_position();
+        let direction = camera
+            .look_vector()
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+        // Pinging the sky means nothing.
+        let end =
+            match cast_to_static_geometry(&scene.physics, origin, direction, ping::MAX_DISTANCE) {
+                Some((end, _)) => end,
+                None => return,
+            };
+        let max_t = (end - origin).norm();
+
+        let mut enemy = None;
+        let mut closest_t = max_t;
+        for (handle, actor) in self.actors.pair_iter() {
+            if handle == self.player
+                || actor.is_dead()
+                || (team != Team::None && actor.team() == team)
+            {
+                continue;
+            }
+            let position = actor.position(&scene.physics);
+            let t = (position - origin).dot(&direction);
+            if t > 0.0
+                && t < closest_t
+                && (origin + direction.scale(t)).metric_distance(&position) <= ping::ENEMY_RADIUS
+            {
+                closest_t = t;
+                enemy = Some(position);
+            }
+        }
+
+        let ray = Ray::from_two_points(origin, end);
+        let (kind, position) = if let Some(position) = enemy {
+            (PingKind::Enemy, position)
+        } else if let Some((_, t)) = self.mines.hit_test(&ray, &scene.graph) {
+            (PingKind::Danger, ray.origin + ray.dir.scale(t))
+        } else if danger {
+            (PingKind::Danger, end)
+        } else {
+            (PingKind::Move, end)
+        };
+        self.pings.add(kind, position, team);
+    }
+
+    fn update_flags(&mut self, scene: &mut Scene, time: GameTime) {
+        let events = self
+            .flags
+            .update(time.delta, &self.actors, &scene.physics, &mut scene.graph);
+        // Player may be dead, but stays in the team.
+        let own_team = self
+            .leader_board
+            .values()
+            .get(&self.profile.name)
+            .map_or(Team::None, |score| score.team);
+        for event in events {
+            let announcement = match event {
+                FlagEvent::Taken { team, carrier } => {
+                    let actor = self.actors.get(carrier);
+                    let name = actor.name.clone();
+                    let voice = actor.voice();
+                    let position = actor.position(&scene.physics);
+                    self.notify(tr_format(
+                        "{} took {} flag",
+                        &[&name, &tr(&format!("{:?}", team))],
+                    ));
+                    self.say(voice, &name, position, VoiceEvent::FlagTaken);
+                    self.record_event(MatchEvent::FlagTaken {
+                        team,
+                        actor: name,
+                        position,
+                    });
+                    Announcement::FlagTaken {
+                        team,
+                        own: team == own_team,
+                    }
+                }
+                FlagEvent::Dropped { team } => Announcement::FlagDropped {
+                    team,
+                    own: team == own_team,
+                },
+                FlagEvent::Returned { team, returner } => {
+                    if returner.is_some() {
+                        self.award(returner, ScoreEvent::FlagReturn);
+                    }
+                    self.record_event(MatchEvent::FlagReturned {
+                        team,
+                        actor: if self.actors.contains(returner) {
+                            Some(self.actors.get(returner).name.clone())
+                        } else {
+                            None
+                        },
+                        position: self
+                            .flags
+                            .get(team)
+                            .map_or_else(Vector3::default, |f| f.home()),
+                    });
+                    Announcement::FlagReturned {
+                        team,
+                        own: team == own_team,
+                    }
+                }
+                FlagEvent::Captured { team, carrier } => {
+                    self.notify(tr_format(
+                        "{} captured {} flag",
+                        &[&self.actors.get(carrier).name, &tr(&format!("{:?}", team))],
+                    ));
+                    self.award(carrier, ScoreEvent::FlagCapture);
+                    self.weapon_stats
+                        .record_capture(&self.actors.get(carrier).name);
+                    self.record_event(MatchEvent::FlagCaptured {
+                        team,
+                        actor: self.actors.get(carrier).name.clone(),
+                        position: self.actors.get(carrier).position(&scene.physics),
+                    });
+                    let team = team.opponent();
+                    self.leader_board.add_team_score(team);
+                    Announcement::FlagCaptured {
+                        team,
+                        own: team == own_team,
+                    }
+                }
+            };
+            self.announce(announcement);
+        }
+
+        let heavy_flag = self.options.mutators().contains(Mutators::HEAVY_FLAG);
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            let carrier = self.flags.carried_by(handle).is_some();
+            actor.speed_scale = if carrier && heavy_flag {
+                flag::CARRIER_SPEED_SCALE
+            } else {
+                1.0
+            };
+            // Bots bring taken flag home right away, fighting on the way.
+            if let Actor::Bot(bot) = actor {
+                match self.flags.get(bot.team()) {
+                    Some(own_flag) if carrier => bot.set_point_of_interest(own_flag.home(), time),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn afk_timeout(&self) -> f32 {
+        self.settings
+            .as_ref()
+            .map_or(0.0, |settings| settings.read().unwrap().afk_timeout)
+    }
+
+    /// Moves idle player to observers. Only alive player in regular matches is watched, there
+    /// is nothing to observe in single-player levels.
+    fn update_idle(&mut self, dt: f32) {
+        let timeout = self.afk_timeout();
+        if timeout <= 0.0
+            || self.player.is_none()
+            || self.is_single_player()
+            || !self.phase.is_live()
+        {
+            self.idle_time = 0.0;
+            return;
+        }
+        self.idle_time += dt;
+        if self.idle_time >= timeout {
+            self.idle_time = 0.0;
+            self.notify(tr_format("{} is away from keyboard", &[&self.profile.name]));
+            self.sender
+                .as_ref()
+                .unwrap()
+                .send(Message::ToggleObserver)
+                .unwrap();
+        }
+    }
+
+    /// Text of warning for idle player, None if player is not about to be moved to observers.
+    pub fn afk_warning(&self) -> Option<String> {
+        let timeout = self.afk_timeout();
+        if timeout <= 0.0 || self.idle_time <= 0.0 || self.idle_time < timeout - AFK_WARNING_TIME {
+            return None;
+        }
+        Some(tr_format(
+            "No input - moving to observers in {} s",
+            &[&(timeout - self.idle_time).max(0.0).ceil()],
+        ))
+    }
+
+    /// Text of flag states for HUD, None if match has no flags.
+    pub fn flag_status(&self) -> Option<String> {
+        let status = self
+            .flags
+            .iter()
+            .map(|flag| {
+                let state = match flag.state() {
+                    FlagState::Home => tr("home"),
+                    FlagState::Carried { carrier } if self.actors.contains(carrier) => {
+                        tr_format("taken by {}", &[&self.actors.get(carrier).name])
+                    }
+                    FlagState::Carried { .. } => tr("taken"),
+                    FlagState::Dropped { time_left } => {
+                        tr_format("dropped, returns in {}", &[&time_left.ceil()])
+                    }
+                };
+                tr_format("{} flag: {}", &[&tr(&format!("{:?}", flag.team())), &state])
+            })
+            .collect::<Vec<_>>();
+        if status.is_empty() {
+            None
+        } else {
+            Some(status.join("        "))
+        }
+    }
+
+    fn issue_order(&mut self, order: Order) {
+        if !self.actors.contains(self.player) {
+            return;
+        }
+        let team = self.actors.get(self.player).team();
+        if team == Team::None {
+            return;
+        }
+        // Repeated order cancels it.
+        let (order, text) = if self.squad.order(team) == Some(order) {
+            (None, "As you were.")
+        } else {
+            (Some(order), order.callout())
+        };
+        self.squad.issue(team, order);
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::ChatMessage {
+                sender: self.profile.name.clone(),
+                text: text.to_owned(),
+                team: true,
+            })
+            .unwrap();
+    }
+
+    /// Player leads the squad, so follow and cover orders are about player. Maps without flags
+    /// still have team bases to attack and defend.
+    fn update_squad(&mut self, scene: &Scene, time: GameTime) {
+        let members = self
+            .actors
+            .pair_iter()
+            .filter(|(_, actor)| matches!(actor, Actor::Bot(bot) if !bot.is_dead()))
+            .map(|(handle, actor)| Member {
+                handle,
+                team: actor.team(),
+                position: actor.position(&scene.physics),
+            })
+            .collect::<Vec<_>>();
+        let leader = if self.actors.contains(self.player) {
+            match self.actors.get(self.player) {
+                Actor::Player(player) if !player.is_dead() => {
+                    let look = scene.graph[player.camera()].look_vector();
+                    Some((
+                        player.position(&scene.physics),
+                        Vector3::new(look.x, 0.0, look.z),
+                    ))
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let spawn_points = &self.spawn_points;
+        let flags = &self.flags;
+        self.squad
+            .update(time.delta, &members, |team, order| match order {
+                Order::FollowMe => leader.map(|(position, _)| (position, None)),
+                Order::CoverMe => leader.map(|(position, look)| (position, Some(look))),
+                Order::AttackFlag => flags
+                    .get(team.opponent())
+                    .map(|flag| flag.position())
+                    .or_else(|| spawn_points.base(team.opponent()))
+                    .map(|base| (base, None)),
+                Order::DefendBase => flags
+                    .get(team)
+                    .map(|flag| flag.home())
+                    .or_else(|| spawn_points.base(team))
+                    .map(|base| (base, None)),
+            });
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            if let Actor::Bot(bot) = actor {
+                if let Some(role) = self.squad.role(handle) {
+                    if !bot.is_dead() {
+                        bot.follow_order(role, &scene.physics, time);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Friendly bots follow newest ping of their team, unless it warns about danger.
+    fn update_pings(&mut self, physics: &Physics, time: GameTime) {
+        self.pings.update(time.delta);
+        for actor in self.actors.iter_mut() {
+            let team = actor.team();
+            if team == Team::None || actor.is_dead() {
+                continue;
+            }
+            if let Actor::Bot(bot) = actor {
+                if let Some(ping) = self
+                    .pings
+                    .visible_to(team)
+                    .filter(|ping| ping.kind != PingKind::Danger)
+                    .last()
+                {
+                    bot.on_ping(ping.kind, ping.position, physics, time);
+                }
+            }
+        }
+    }
+
+    fn update_recovery(&mut self, dt: f32) {
+        let shield_delay = self.options.shield_regen_delay();
+        let regenerate_health = self.options.mutators().contains(Mutators::REGENERATION);
+        for actor in self.actors.iter_mut() {
+            if !actor.is_dead() {
+                actor.update_recovery(dt, shield_delay, regenerate_health);
+            }
+        }
+    }
+
+    fn update_burning(&mut self, scene: &Scene, time: GameTime) {
+        let mut burns = Vec::new();
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            if !actor.is_dead() {
+                if let Some(who) = actor.update_burning(time.delta) {
+                    burns.push((handle, who, actor.position(&scene.physics)));
+                }
+            }
+        }
+
+        let sender = self.sender.as_ref().unwrap();
+        for (actor, who, position) in burns {
+            // Burner may be gone already, then fire is credited to nobody.
+            let who = if self.actors.contains(who) {
+                who
             } else {
-                format!("{} took {} damage!", self.actors.get(actor).name, amount)
+                Handle::NONE
             };
+            sender
+                .send(Message::DamageActor {
+                    actor,
+                    who,
+                    amount: BURN_DAMAGE,
+                    direction: None,
+                    zone: None,
+                })
+                .unwrap();
+            sender
+                .send(Message::CreateEffect {
+                    kind: EffectKind::Smoke,
+                    position,
+                })
+                .unwrap();
+        }
+    }
 
+    fn notify(&self, text: String) {
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::AddNotification { text })
+            .unwrap();
+    }
+
+    /// Gives points of score event to given actor, popup is shown if the actor is player.
+    fn award(&mut self, actor: Handle<Actor>, event: ScoreEvent) {
+        let points = self.options.points(event);
+        if points == 0 || !self.phase.is_live() || !self.actors.contains(actor) {
+            return;
+        }
+        self.leader_board
+            .add_score(&self.actors.get(actor).name, event, points);
+        if actor == self.player {
             self.sender
                 .as_ref()
                 .unwrap()
-                .send(Message::AddNotification { text: message })
+                .send(Message::ShowScorePopup { event, points })
                 .unwrap();
+        }
+    }
 
-            let who_position = if who.is_some() {
-                let scene = &engine.scenes[self.scene];
-                Some(self.actors.get(who).position(&scene.physics))
-            } else {
-                None
-            };
-            let actor = self.actors.get_mut(actor);
-            if let Actor::Bot(bot) = actor {
-                if let Some(who_position) = who_position {
-                    bot.set_point_of_interest(who_position, time);
+    fn announce(&self, announcement: Announcement) {
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::Announce { announcement })
+            .unwrap();
+    }
+
+    fn current_lead(&self) -> Lead {
+        match self.options {
+            MatchOptions::DeathMatch(_) => {
+                let own = self
+                    .leader_board
+                    .values()
+                    .get(&self.profile.name)
+                    .map_or(0, |score| score.kills);
+                let best_other = self
+                    .leader_board
+                    .highest_personal_score(Some(&self.profile.name))
+                    .map_or(0, |(_, kills)| kills);
+                if own > best_other {
+                    Lead::Ahead
+                } else if own == best_other {
+                    Lead::Tied
+                } else {
+                    Lead::Behind
                 }
             }
-            let was_dead = actor.is_dead();
-            actor.damage(amount);
-            if !was_dead && actor.is_dead() && who.is_some() {
-                self.leader_board.add_frag(who_name)
+            MatchOptions::TeamDeathMatch(_) | MatchOptions::CaptureTheFlag(_) => {
+                let red = self.leader_board.team_score(Team::Red);
+                let blue = self.leader_board.team_score(Team::Blue);
+                Lead::Team(if red > blue {
+                    Some(Team::Red)
+                } else if blue > red {
+                    Some(Team::Blue)
+                } else {
+                    None
+                })
             }
+            // Lead never changes in co-op, so there is nothing to announce.
+            MatchOptions::Survival(_) => Lead::Team(None),
         }
     }
 
-    async fn spawn_item(
-        &mut self,
-        engine: &mut Engine,
-        kind: ItemKind,
-        position: Vector3<f32>,
-        adjust_height: bool,
-        lifetime: Option<f32>,
-    ) {
-        let position = if adjust_height {
-            self.pick(engine, position, position - Vector3::new(0.0, 1000.0, 0.0))
-        } else {
-            position
-        };
-        let scene = &mut engine.scenes[self.scene];
-        let mut item = Item::new(
-            kind,
-            position,
-            scene,
-            engine.resource_manager.clone(),
-            self.sender.as_ref().unwrap().clone(),
-        )
-        .await;
-        item.set_lifetime(lifetime);
-        self.items.add(item);
-    }
-
-    pub fn time(&self) -> f32 {
-        self.time
-    }
+    fn update_announcements(&mut self, dt: f32) {
+        if !self.phase.is_live() {
+            return;
+        }
 
-    fn update_respawn(&mut self, time: GameTime) {
-        // Respawn is done in deferred manner: we just gather all info needed
-        // for respawn, wait some time and then re-create actor. Actor is spawned
-        // by sending a message: this is needed because there are some other
-        // systems that catches such messages and updates their own state.
-        for respawn_entry in self.respawn_list.iter_mut() {
-            match respawn_entry {
-                RespawnEntry::Bot(v) => {
-                    v.time_left -= time.delta;
-                    if v.time_left <= 0.0 {
-                        self.sender
-                            .as_mut()
-                            .unwrap()
-                            .send(Message::SpawnBot {
-                                kind: v.kind,
-                                name: v.name.clone(),
-                            })
-                            .unwrap();
-                    }
+        let lead = self.current_lead();
+        if let Some(previous) = self.lead {
+            if previous != lead {
+                let player_team = if self.actors.contains(self.player) {
+                    self.actors.get(self.player).team()
+                } else {
+                    Team::None
+                };
+                let announcement = match (previous, lead) {
+                    (_, Lead::Ahead) => Some(Announcement::LeadTaken),
+                    (Lead::Ahead, Lead::Tied) => Some(Announcement::TiedForLead),
+                    (Lead::Ahead, Lead::Behind) => Some(Announcement::LeadLost),
+                    (_, Lead::Team(Some(team))) => Some(Announcement::TeamLeads {
+                        team,
+                        own: team == player_team,
+                    }),
+                    _ => None,
+                };
+                if let Some(announcement) = announcement {
+                    self.announce(announcement);
                 }
-                RespawnEntry::Player(v) => {
-                    v.time_left -= time.delta;
-                    if v.time_left <= 0.0 {
-                        self.sender
-                            .as_mut()
-                            .unwrap()
-                            .send(Message::SpawnPlayer)
-                            .unwrap();
-                    }
+            }
+        }
+        self.lead = Some(lead);
+
+        let time_limit = self.options.time_limit_secs() + self.time_limit_extension;
+        if time_limit > 0.0 && self.overtime != OvertimeState::SuddenDeath {
+            let time_left = time_limit - self.time();
+            let previous_time_left = time_left + dt;
+            for &minutes in TIME_CALLOUTS.iter() {
+                let mark = minutes as f32 * 60.0;
+                if previous_time_left > mark && time_left <= mark {
+                    self.announce(Announcement::TimeRemaining { minutes });
                 }
             }
         }
-
-        self.respawn_list.retain(|entry| match entry {
-            RespawnEntry::Bot(v) => v.time_left >= 0.0,
-            RespawnEntry::Player(v) => v.time_left >= 0.0,
-        });
     }
 
-    fn update_spectator_camera(&mut self, scene: &mut Scene) {
-        if let Node::Camera(spectator_camera) = &mut scene.graph[self.spectator_camera] {
-            let mut position = spectator_camera.global_position();
-            position.follow(&self.target_spectator_position, 0.1);
-            spectator_camera
-                .local_transform_mut()
-                .set_position(position);
+    fn update_game_ending(&mut self) {
+        if !self.phase.is_live() {
+            return;
         }
-    }
 
-    fn update_death_zones(&mut self, scene: &Scene) {
-        for (handle, actor) in self.actors.pair_iter_mut() {
-            for death_zone in self.death_zones.iter() {
-                if death_zone
-                    .bounds
-                    .is_contains_point(actor.position(&scene.physics))
-                {
-                    self.sender
-                        .as_ref()
-                        .unwrap()
-                        .send(Message::RespawnActor { actor: handle })
-                        .unwrap();
+        let tied = self.leader_board.is_tied(&self.options);
+
+        let mut match_over = self.leader_board.is_match_over(&self.options)
+            || (self.overtime == OvertimeState::SuddenDeath && !tied);
+
+        let time_limit = self.options.time_limit_secs();
+        if !match_over
+            && self.overtime != OvertimeState::SuddenDeath
+            && time_limit > 0.0
+            && self.time() >= time_limit + self.time_limit_extension
+        {
+            if !tied {
+                match_over = true;
+            } else {
+                match self.options.overtime() {
+                    Overtime::None => match_over = true,
+                    Overtime::ExtraTime { secs }
+                        if self.overtime == OvertimeState::Regular && secs > 0.0 =>
+                    {
+                        self.overtime = OvertimeState::Extra;
+                        self.time_limit_extension += secs;
+                        self.notify(tr_format(
+                            "Overtime! +{} min",
+                            &[&((secs / 60.0).ceil() as u32)],
+                        ));
+                    }
+                    _ => {
+                        self.overtime = OvertimeState::SuddenDeath;
+                        self.notify(tr("Sudden death! Next score wins"));
+                    }
                 }
             }
         }
-    }
 
-    fn update_game_ending(&self) {
-        if self.leader_board.is_match_over(&self.options) {
+        if match_over {
             self.sender
                 .as_ref()
                 .unwrap()
@@ -1202,21 +4031,79 @@ impl Level {
     }
 
     pub fn update(&mut self, engine: &mut Engine, time: GameTime) {
+        // Idle time is real time, slow motion mutators must not stretch it.
+        self.update_idle(time.delta);
+        let time = self.scale_time(time);
         self.time += time.delta;
         self.update_respawn(time);
+        self.update_vote(time.delta);
         let scene = &mut engine.scenes[self.scene];
+        scene.physics.integration_parameters.dt = time.delta;
+        scene.physics.gravity = Vector3::new(
+            0.0,
+            -9.81
+                * if self.options.mutators().contains(Mutators::LOW_GRAVITY) {
+                    LOW_GRAVITY_SCALE
+                } else {
+                    1.0
+                },
+            0.0,
+        );
+        self.update_phase(scene, time.delta);
         while let Ok(proximity_event) = self.proximity_events_receiver.as_ref().unwrap().try_recv()
         {
             for proj in self.projectiles.iter_mut() {
                 proj.handle_proximity(&proximity_event, scene, &self.actors, &self.weapons);
             }
         }
-        self.update_spectator_camera(scene);
+        self.update_spectator_camera(scene, time);
         self.update_death_zones(scene);
-        self.weapons.update(scene, &self.actors);
-        self.projectiles
-            .update(scene, &self.actors, &self.weapons, time);
+        self.update_teleporters(scene);
+        self.update_checkpoints(scene);
+        self.update_spawn_protection(scene, time.delta);
+        self.update_recovery(time.delta);
+        self.update_heartbeat(scene, time.delta);
+        self.update_flags(scene, time);
+        self.update_squad(scene, time);
+        self.update_pings(&scene.physics, time);
+        self.update_burning(scene, time);
+        self.sound_manager.update(&self.reverb_zones);
+        self.weapons.update(scene, &self.actors, time);
+        self.effects.update(&mut scene.graph, time.delta);
+        self.projectiles.update(
+            scene,
+            &self.actors,
+            &self.weapons,
+            &self.mines,
+            &self.turrets,
+            &mut self.effects,
+            self.options.mutators(),
+            time,
+        );
         self.items.update(scene, time);
+        self.mines.update(
+            scene,
+            &self.actors,
+            time.delta,
+            self.sender.as_ref().unwrap(),
+        );
+        let observer_position = self.observer_position(scene);
+        let (lod_near_distance, lod_far_distance) =
+            self.settings
+                .as_ref()
+                .map_or((f32::MAX, f32::MAX), |settings| {
+                    let settings = settings.read().unwrap();
+                    (settings.lod_near_distance, settings.lod_far_distance)
+                });
+        let (camera_effects, reduced_motion) =
+            self.settings.as_ref().map_or((1.0, false), |settings| {
+                let settings = settings.read().unwrap();
+                if settings.reduced_motion {
+                    (0.0, true)
+                } else {
+                    (settings.camera_effects, false)
+                }
+            });
         let mut ctx = UpdateContext {
             time,
             scene,
@@ -1224,40 +4111,161 @@ impl Level {
             jump_pads: &self.jump_pads,
             navmesh: self.navmesh,
             weapons: &self.weapons,
+            observer_position,
+            lod_near_distance,
+            lod_far_distance,
+            camera_effects,
+            reduced_motion,
+            difficulty: self.options.difficulty(),
+            economy: self.options.economy(),
         };
         self.actors.update(&mut ctx);
         while let Ok(contact_event) = self.contact_events_receiver.as_ref().unwrap().try_recv() {
             self.actors.handle_event(&contact_event, &mut ctx);
         }
-        self.update_game_ending();
+        self.turrets.update(
+            scene,
+            self.actors.target_descriptors(),
+            &mut self.effects,
+            time.delta,
+            self.sender.as_ref().unwrap(),
+        );
+        if self.training.is_some() {
+            self.update_training(engine);
+        } else if self.director.is_some() {
+            self.update_director(time.delta);
+        } else {
+            self.update_announcements(time.delta);
+            self.update_game_ending();
+        }
+    }
+
+    fn alive_bot_count(&self) -> usize {
+        self.actors
+            .iter()
+            .filter(|actor| matches!(actor, Actor::Bot(_)) && !actor.is_dead())
+            .count()
+    }
+
+    fn update_director(&mut self, dt: f32) {
+        if !self.actors.contains(self.player) {
+            return;
+        }
+
+        let player = self.actors.get(self.player);
+        let weapon = player.current_weapon();
+        let ammo = if self.weapons.contains(weapon) {
+            let weapon = &self.weapons[weapon];
+            let max_ammo = Weapon::get_definition(weapon.get_kind()).ammo;
+            (weapon.ammo() as f32 / max_ammo as f32).min(1.0)
+        } else {
+            0.0
+        };
+        let condition = PlayerCondition {
+            health: (player.get_health() / 100.0).min(1.0),
+            ammo,
+        };
+        let alive_bots = self.alive_bot_count();
+
+        let director = self.director.as_mut().unwrap();
+        match director.update(alive_bots, &condition, dt) {
+            Some(DirectorEvent::WaveStarted(wave)) => self.notify(tr_format("Wave {}", &[&wave])),
+            Some(DirectorEvent::WaveCleared(wave)) => {
+                self.notify(tr_format("Wave {} cleared!", &[&wave]))
+            }
+            Some(DirectorEvent::Spawn(kind)) => {
+                let name = names::generate_bot_name(kind, |name| {
+                    is_name_taken(name, &self.actors, &self.leader_board)
+                });
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::SpawnBot { kind, name })
+                    .unwrap();
+            }
+            None => (),
+        }
+    }
+
+    fn update_training(&mut self, engine: &Engine) {
+        let training = self.training.as_mut().unwrap();
+
+        if self.actors.contains(self.player) {
+            let scene = &engine.scenes[self.scene];
+            let player = self.actors.get(self.player);
+            let state = PlayerState {
+                position: player.position(&scene.physics),
+                has_ground_contact: player.has_ground_contact(&scene.physics),
+                armed: !player.weapons().is_empty(),
+            };
+            if training.update(&state) && training.objective() == Objective::PickUpWeapon {
+                // Put a rifle in front of player.
+                let look = match player {
+                    Actor::Player(player) => scene.graph[player.camera()].look_vector(),
+                    Actor::Bot(_) => Vector3::z(),
+                };
+                let forward = Vector3::new(look.x, 0.0, look.z)
+                    .try_normalize(std::f32::EPSILON)
+                    .unwrap_or_else(Vector3::z);
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::SpawnItem {
+                        kind: ItemKind::Ak47,
+                        position: state.position + forward.scale(3.0),
+                        adjust_height: true,
+                        lifetime: None,
+                    })
+                    .unwrap();
+            }
+        }
+
+        if let Some(control_scheme) = self.control_scheme.as_ref() {
+            if let Some(text) = training.poll_prompt(&control_scheme.read().unwrap()) {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::ShowTrainingPrompt { text })
+                    .unwrap();
+            }
+        }
     }
 
     pub async fn respawn_actor(&mut self, engine: &mut Engine, actor: Handle<Actor>) {
         if self.actors.contains(actor) {
             let name = self.actors.get(actor).name.clone();
 
-            self.leader_board.add_death(&name);
+            if self.phase.is_live() {
+                self.leader_board.add_death(&name);
+            }
 
             let entry = match self.actors.get(actor) {
                 Actor::Bot(bot) => RespawnEntry::Bot(BotRespawnEntry {
                     name,
                     kind: bot.definition.kind,
-                    time_left: RESPAWN_TIME,
+                    time_left: RESPAWN_TIME / self.options.difficulty().player_respawn(),
                 }),
                 Actor::Player(player) => {
                     // Turn on spectator camera and prepare its target position. Spectator
                     // camera will be used to render world until player is despawned.
                     let scene = &mut engine.scenes[self.scene];
                     let position = scene.graph[player.camera()].global_position();
+                    // Death camera starts behind the place of death and looks in the same
+                    // direction as player did.
+                    let mut death_camera = DeathCamera {
+                        focus: position,
+                        angle: player.yaw().to_radians() - std::f32::consts::PI,
+                    };
                     if let Node::Camera(spectator_camera) = &mut scene.graph[self.spectator_camera]
                     {
                         spectator_camera
                             .set_enabled(true)
                             .local_transform_mut()
-                            .set_position(position);
+                            .set_position(position)
+                            .set_rotation(death_camera.rotation());
                     }
-                    // Use ray casting to get target position for spectator camera, it is used to
-                    // create "dropping head" effect.
+                    // Use ray casting to find the floor under player, death camera orbits this
+                    // point.
                     let ray =
                         Ray::from_two_points(position, position - Vector3::new(0.0, 1000.0, 0.0));
                     let options = RayCastOptions {
@@ -1277,22 +4285,32 @@ impl Level {
                     } else {
                         self.target_spectator_position = position;
                     }
+                    death_camera.focus = self.target_spectator_position;
+                    self.death_camera = Some(death_camera);
 
                     RespawnEntry::Player(PlayerRespawnEntry {
-                        time_left: RESPAWN_TIME,
+                        time_left: RESPAWN_TIME * self.options.difficulty().player_respawn(),
                     })
                 }
             };
 
             self.remove_actor(engine, actor).await;
 
-            self.respawn_list.push(entry);
+            // Killed bots of survival are gone for good, director brings new ones.
+            if !(self.director.is_some() && matches!(entry, RespawnEntry::Bot(_))) {
+                self.respawn_list.push(entry);
+            }
         }
     }
 
     pub async fn handle_message(&mut self, engine: &mut Engine, message: &Message, time: GameTime) {
         self.sound_manager
-            .handle_message(engine.resource_manager.clone(), &message)
+            .handle_message(
+                engine.resource_manager.clone(),
+                &message,
+                self.options.time_scale(),
+                &engine.scenes[self.scene].physics,
+            )
             .await;
 
         match message {
@@ -1307,6 +4325,11 @@ impl Level {
                 self.add_bot(engine, *kind, *position, name.clone()).await;
             }
             &Message::RemoveActor { actor } => self.remove_actor(engine, actor).await,
+            Message::CallVote { caller, subject } => self.call_vote(caller, subject),
+            &Message::CastVote { ref voter, yes } => match self.vote.as_mut() {
+                Some(vote) => vote.cast(voter, yes),
+                None => self.notify(tr("There is no vote in progress")),
+            },
             &Message::GiveItem { actor, kind } => {
                 self.give_item(engine, actor, kind).await;
             }
@@ -1321,6 +4344,22 @@ impl Level {
                 self.shoot_weapon(engine, weapon, initial_velocity, time, direction)
                     .await
             }
+            &Message::ReleaseTrigger { weapon } => {
+                if self.weapons.contains(weapon) {
+                    self.weapons[weapon].release_trigger();
+                }
+            }
+            &Message::CycleFireMode { weapon } => {
+                if self.weapons.contains(weapon) {
+                    self.weapons[weapon].cycle_fire_mode();
+                }
+            }
+            &Message::IssueOrder { order } => {
+                self.issue_order(order);
+            }
+            &Message::Ping { danger } => {
+                self.ping(engine, danger);
+            }
             &Message::CreateProjectile {
                 kind,
                 position,
@@ -1344,20 +4383,70 @@ impl Level {
             Message::SpawnBot { kind, name } => {
                 self.spawn_bot(engine, *kind, Some(name.clone())).await;
             }
-            &Message::DamageActor { actor, who, amount } => {
-                self.damage_actor(engine, actor, who, amount, time);
+            &Message::DamageActor {
+                actor,
+                who,
+                amount,
+                direction,
+                zone,
+            } => {
+                self.damage_actor(engine, actor, who, amount, direction, zone, time);
+            }
+            &Message::IgniteActor { actor, who } => {
+                if self.actors.contains(actor) {
+                    self.actors.get_mut(actor).ignite(who);
+                }
+            }
+            &Message::DamageTurret { turret, amount } => {
+                self.turrets.damage(
+                    turret,
+                    amount,
+                    &mut engine.scenes[self.scene],
+                    self.sender.as_ref().unwrap(),
+                );
+            }
+            &Message::ProjectileThreat {
+                actor,
+                position,
+                direction,
+            } => {
+                if self.actors.contains(actor) {
+                    if let Actor::Bot(bot) = self.actors.get_mut(actor) {
+                        bot.on_projectile_threat(
+                            position,
+                            direction,
+                            &engine.scenes[self.scene].physics,
+                        );
+                    }
+                }
+            }
+            &Message::DetonateMine { mine } => {
+                self.mines.detonate(
+                    mine,
+                    &mut engine.scenes[self.scene],
+                    &self.actors,
+                    self.sender.as_ref().unwrap(),
+                );
             }
             &Message::CreateEffect { kind, position } => {
-                effects::create(
+                self.effects.spawn(
                     kind,
                     &mut engine.scenes[self.scene].graph,
                     engine.resource_manager.clone(),
                     position,
                 );
             }
+            &Message::ShakeCamera { position, radius } => {
+                self.shake_camera(engine, position, radius)
+            }
             Message::SpawnPlayer => {
                 self.player = self.spawn_player(engine).await;
             }
+            Message::ToggleObserver => self.toggle_observer(engine).await,
+            Message::RestartFromCheckpoint => self.restart_from_checkpoint(engine).await,
+            Message::RecordMatchEvent { time, event } => {
+                self.match_recorder.record(*time, event.clone())
+            }
             &Message::SpawnItem {
                 kind,
                 position,
@@ -1401,16 +4490,40 @@ impl Level {
         ));
     }
 
-    pub fn debug_draw(&self, engine: &mut Engine) {
+    pub fn debug_draw(&self, engine: &mut Engine, overlays: &DebugOverlays) {
         let scene = &mut engine.scenes[self.scene];
 
         let drawing_context = &mut scene.drawing_context;
 
         drawing_context.clear_lines();
 
-        scene.physics.draw(drawing_context);
+        if self.observer.is_some() {
+            let theme = self.theme();
+            for actor in self.actors.iter().filter(|actor| !actor.is_dead()) {
+                let center = actor.position(&scene.physics);
+                drawing_context.draw_aabb(
+                    &AxisAlignedBoundingBox::from_min_max(
+                        center - OUTLINE_HALF_SIZE,
+                        center + OUTLINE_HALF_SIZE,
+                    ),
+                    theme.team_color(actor.team()),
+                );
+            }
+        }
+
+        if !overlays.any() {
+            return;
+        }
+
+        if overlays.is_enabled(DebugOverlay::Physics) {
+            scene.physics.draw(drawing_context);
+
+            for death_zone in self.death_zones.iter() {
+                drawing_context.draw_aabb(&death_zone.bounds, Color::opaque(0, 0, 200));
+            }
+        }
 
-        if self.navmesh.is_some() {
+        if self.navmesh.is_some() && overlays.is_enabled(DebugOverlay::Navmesh) {
             let navmesh = &scene.navmeshes[self.navmesh];
 
             for pt in navmesh.vertices() {
@@ -1422,38 +4535,119 @@ impl Level {
                     });
                 }
             }
+        }
 
-            for actor in self.actors.iter() {
-                if let Actor::Bot(bot) = actor {
-                    bot.debug_draw(drawing_context);
-                }
+        for &(overlay, kind) in &[
+            (DebugOverlay::Deaths, HeatmapKind::Deaths),
+            (DebugOverlay::Pickups, HeatmapKind::Pickups),
+        ] {
+            if overlays.is_enabled(overlay) {
+                Heatmap::new(kind, self.match_recorder.records()).draw(drawing_context);
             }
         }
 
-        for death_zone in self.death_zones.iter() {
-            drawing_context.draw_aabb(&death_zone.bounds, Color::opaque(0, 0, 200));
+        for actor in self.actors.iter() {
+            if let Actor::Bot(bot) = actor {
+                if !bot.is_dead() {
+                    bot.debug_draw(drawing_context, overlays, &scene.physics);
+                }
+            }
         }
     }
-}
 
-pub struct SpawnPoint {
-    position: Vector3<f32>,
-}
+    /// Describes every entity of the level for inspector.
+    pub fn inspect(&self, engine: &Engine) -> Vec<EntityInfo> {
+        let scene = &engine.scenes[self.scene];
+        let mut entities = Vec::new();
 
-impl Default for SpawnPoint {
-    fn default() -> Self {
-        Self {
-            position: Default::default(),
+        for (handle, actor) in self.actors.pair_iter() {
+            let (name, state) = match actor {
+                Actor::Bot(bot) => (format!("Bot {}", bot.name), bot.ai_state()),
+                Actor::Player(player) => (format!("Player {}", player.name), "Playing"),
+            };
+            let position = actor.position(&scene.physics);
+            entities.push(EntityInfo {
+                entity: Entity::Actor(handle),
+                name,
+                details: format!(
+                    "Health: {:.0}\nArmor: {:.0}\nTeam: {:?}\nState: {}\nPosition: {:.1} {:.1} {:.1}",
+                    actor.health,
+                    actor.armor,
+                    actor.team(),
+                    if actor.is_dead() { "Dead" } else { state },
+                    position.x,
+                    position.y,
+                    position.z
+                ),
+                position,
+            });
         }
-    }
-}
 
-impl Visit for SpawnPoint {
-    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
-        visitor.enter_region(name)?;
+        for (handle, item) in self.items.pair_iter() {
+            let position = item.position(&scene.graph);
+            entities.push(EntityInfo {
+                entity: Entity::Item(handle),
+                name: format!("Item {:?}", item.get_kind()),
+                details: format!(
+                    "Picked up: {}\nDropped: {}\nPosition: {:.1} {:.1} {:.1}",
+                    item.is_picked_up(),
+                    item.is_dropped(),
+                    position.x,
+                    position.y,
+                    position.z
+                ),
+                position,
+            });
+        }
 
-        self.position.visit("Position", visitor)?;
+        for (handle, projectile) in self.projectiles.pair_iter() {
+            let position = projectile.get_position(&scene.graph);
+            entities.push(EntityInfo {
+                entity: Entity::Projectile(handle),
+                name: format!("Projectile {:?}", projectile.kind()),
+                details: format!(
+                    "Position: {:.1} {:.1} {:.1}",
+                    position.x, position.y, position.z
+                ),
+                position,
+            });
+        }
 
-        visitor.leave_region()
+        for (handle, jump_pad) in self.jump_pads.pair_iter() {
+            if let Some(body) = scene.physics.bodies.get(&jump_pad.rigid_body()) {
+                let position = body.position().translation.vector;
+                let force = jump_pad.get_force();
+                entities.push(EntityInfo {
+                    entity: Entity::JumpPad(handle),
+                    name: "Jump Pad".to_owned(),
+                    details: format!(
+                        "Force: {:.1} {:.1} {:.1}\nPosition: {:.1} {:.1} {:.1}",
+                        force.x, force.y, force.z, position.x, position.y, position.z
+                    ),
+                    position,
+                });
+            }
+        }
+
+        entities
+    }
+
+    /// Returns labels with AI state that are shown above heads of bots.
+    pub fn debug_labels(&self, engine: &Engine, overlays: &DebugOverlays) -> Vec<Waypoint> {
+        if !overlays.is_enabled(DebugOverlay::States) {
+            return Vec::new();
+        }
+        let physics = &engine.scenes[self.scene].physics;
+        self.actors
+            .iter()
+            .filter_map(|actor| match actor {
+                Actor::Bot(bot) if !bot.is_dead() => Some(Waypoint {
+                    label: format!("{}: {}", bot.name, bot.ai_state()),
+                    position: bot.position(physics) + Vector3::new(0.0, 1.2, 0.0),
+                    color: Color::opaque(0, 255, 255),
+                }),
+                _ => None,
+            })
+            .collect()
     }
 }
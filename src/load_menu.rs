@@ -0,0 +1,156 @@
+use crate::{
+    localization::{tr, tr_format},
+    message::Message,
+    saves,
+};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        brush::Brush,
+        button::{ButtonBuilder, ButtonMessage},
+        message::{MessageDirection, UiMessage},
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        Thickness, UiNode, UserInterface,
+    },
+};
+use std::{path::PathBuf, sync::mpsc::Sender, time::SystemTime};
+
+pub struct LoadMenu {
+    sender: Sender<Message>,
+    pub window: Handle<UiNode>,
+    list: Handle<UiNode>,
+    error: Handle<UiNode>,
+    items: Vec<Handle<UiNode>>,
+    // Button of every listed save along with path to save file.
+    saves: Vec<(Handle<UiNode>, PathBuf)>,
+}
+
+fn describe_age(modified: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(modified)
+        .map_or(0, |age| age.as_secs());
+    if secs < 60 {
+        tr("just now")
+    } else if secs < 3600 {
+        tr_format("{} min ago", &[&(secs / 60)])
+    } else if secs < 86400 {
+        tr_format("{} h ago", &[&(secs / 3600)])
+    } else {
+        tr_format("{} days ago", &[&(secs / 86400)])
+    }
+}
+
+impl LoadMenu {
+    pub fn new(ui: &mut UserInterface, sender: Sender<Message>) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let error = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_margin(Thickness::uniform(4.0))
+                .with_foreground(Brush::Solid(Color::opaque(255, 60, 60))),
+        )
+        .build(ctx);
+        let list;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0))
+            .with_title(WindowTitle::text(tr("Load Game")))
+            .open(false)
+            .with_content({
+                list = StackPanelBuilder::new(WidgetBuilder::new().with_child(error)).build(ctx);
+                list
+            })
+            .build(ctx);
+
+        Self {
+            sender,
+            window,
+            list,
+            error,
+            items: Default::default(),
+            saves: Default::default(),
+        }
+    }
+
+    /// Opens the window, list of saves is rebuilt every time since saves could be made
+    /// since last time.
+    pub fn open(&mut self, ui: &mut UserInterface) {
+        for item in self.items.drain(..) {
+            ui.send_message(WidgetMessage::remove(item, MessageDirection::ToWidget));
+        }
+        self.saves.clear();
+        ui.send_message(WidgetMessage::visibility(
+            self.error,
+            MessageDirection::ToWidget,
+            false,
+        ));
+
+        let saves = saves::list_saves();
+        if saves.is_empty() {
+            let ctx = &mut ui.build_ctx();
+            let text = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(4.0)))
+                .with_text(tr("No saved games"))
+                .build(ctx);
+            ui.send_message(WidgetMessage::link(
+                text,
+                MessageDirection::ToWidget,
+                self.list,
+            ));
+            self.items.push(text);
+        }
+
+        for save in saves {
+            let ctx = &mut ui.build_ctx();
+            let button = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .with_height(36.0)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(&format!("{} - {}", save.name, describe_age(save.modified)))
+            .build(ctx);
+            ui.send_message(WidgetMessage::link(
+                button,
+                MessageDirection::ToWidget,
+                self.list,
+            ));
+            self.items.push(button);
+            self.saves.push((button, save.path));
+        }
+
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    /// Shows why save could not be loaded, window stays open so player can pick another save.
+    pub fn show_error(&self, ui: &mut UserInterface, error: &str) {
+        ui.send_message(TextMessage::text(
+            self.error,
+            MessageDirection::ToWidget,
+            tr(error),
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.error,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    pub fn handle_ui_event(&mut self, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if let Some((_, path)) = self
+                .saves
+                .iter()
+                .find(|(button, _)| *button == message.destination())
+            {
+                self.sender
+                    .send(Message::LoadGame { path: path.clone() })
+                    .unwrap();
+            }
+        }
+    }
+}
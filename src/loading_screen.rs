@@ -0,0 +1,248 @@
+//! Screen shown while level is loading. It shows name and preview of the map from the map
+//! registry and a random tip from `data/tips.txt`.
+
+use crate::{
+    gui::UiScale,
+    localization::tr,
+    logger::{self, Category},
+    maps,
+};
+use rg3d::{
+    core::{pool::Handle, rand::seq::SliceRandom},
+    engine::Engine,
+    gui::{
+        grid::{Column, GridBuilder, Row},
+        image::{ImageBuilder, ImageMessage},
+        message::MessageDirection,
+        progress_bar::ProgressBarBuilder,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        VerticalAlignment,
+    },
+    rand, utils,
+};
+use std::path::Path;
+
+const TIPS_PATH: &str = "data/tips.txt";
+
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+
+/// Time (in seconds) each frame of spinner is shown.
+const SPINNER_FRAME_TIME: f32 = 0.1;
+
+pub struct LoadingScreen {
+    pub root: Handle<UiNode>,
+    pub progress_bar: Handle<UiNode>,
+    preview: Handle<UiNode>,
+    map_name: Handle<UiNode>,
+    tip_panel: Handle<UiNode>,
+    tip: Handle<UiNode>,
+    spinner: Handle<UiNode>,
+    spinner_time: f32,
+    tips: Vec<String>,
+}
+
+fn load_tips() -> Vec<String> {
+    match std::fs::read_to_string(TIPS_PATH) {
+        Ok(content) => content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_owned())
+            .collect(),
+        Err(e) => {
+            logger::warning(
+                Category::General,
+                format!("Unable to load tips from {}. Reason: {:?}", TIPS_PATH, e),
+            );
+            Vec::new()
+        }
+    }
+}
+
+impl LoadingScreen {
+    pub fn new(ctx: &mut BuildContext, width: f32, height: f32, ui_scale: &mut UiScale) -> Self {
+        let progress_bar;
+        let content;
+        let preview;
+        let map_name;
+        let tip_panel;
+        let spinner;
+        let root = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_width(width)
+                .with_height(height)
+                .with_visibility(false)
+                .with_child({
+                    content = GridBuilder::new(
+                        WidgetBuilder::new()
+                            .with_width(400.0)
+                            .with_height(380.0)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_child({
+                                preview = ImageBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(0)
+                                        .with_visibility(false)
+                                        .with_margin(Thickness::bottom(4.0)),
+                                )
+                                .build(ctx);
+                                preview
+                            })
+                            .with_child({
+                                map_name = TextBuilder::new(WidgetBuilder::new().on_row(1))
+                                    .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                                    .build(ctx);
+                                map_name
+                            })
+                            .with_child({
+                                tip_panel = StackPanelBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(2)
+                                        .with_margin(Thickness::uniform(4.0)),
+                                )
+                                .build(ctx);
+                                tip_panel
+                            })
+                            .with_child(
+                                StackPanelBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(3)
+                                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                                        .with_child(
+                                            TextBuilder::new(WidgetBuilder::new())
+                                                .with_vertical_text_alignment(
+                                                    VerticalAlignment::Center,
+                                                )
+                                                .with_text(tr("Loading... Please wait."))
+                                                .build(ctx),
+                                        )
+                                        .with_child({
+                                            spinner = TextBuilder::new(
+                                                WidgetBuilder::new()
+                                                    .with_width(16.0)
+                                                    .with_margin(Thickness::left(6.0)),
+                                            )
+                                            .with_horizontal_text_alignment(
+                                                HorizontalAlignment::Center,
+                                            )
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .with_text(SPINNER_FRAMES[0])
+                                            .build(ctx);
+                                            spinner
+                                        }),
+                                )
+                                .with_orientation(Orientation::Horizontal)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                progress_bar = ProgressBarBuilder::new(
+                                    WidgetBuilder::new().on_row(4).with_height(32.0),
+                                )
+                                .build(ctx);
+                                progress_bar
+                            }),
+                    )
+                    .add_row(Row::stretch())
+                    .add_row(Row::strict(30.0))
+                    .add_row(Row::strict(60.0))
+                    .add_row(Row::strict(30.0))
+                    .add_row(Row::auto())
+                    .add_column(Column::stretch())
+                    .build(ctx);
+                    content
+                }),
+        )
+        .add_column(Column::stretch())
+        .add_row(Row::stretch())
+        .build(ctx);
+        ui_scale.register(content, 400.0, 380.0);
+        ui_scale.register(progress_bar, 0.0, 32.0);
+        Self {
+            root,
+            progress_bar,
+            preview,
+            map_name,
+            tip_panel,
+            tip: Handle::NONE,
+            spinner,
+            spinner_time: 0.0,
+            tips: load_tips(),
+        }
+    }
+
+    /// Shows the screen for given map with a new random tip.
+    pub fn show(&mut self, engine: &mut Engine, map: &Path) {
+        let definition = maps::find(map);
+
+        let resource_manager = engine.resource_manager.clone();
+        let ui = &mut engine.user_interface;
+        let name = definition.map_or_else(
+            || {
+                map.file_stem()
+                    .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+            },
+            |definition| definition.name.to_owned(),
+        );
+        ui.send_message(TextMessage::text(
+            self.map_name,
+            MessageDirection::ToWidget,
+            name,
+        ));
+
+        let preview = definition.and_then(|definition| definition.preview);
+        ui.send_message(ImageMessage::texture(
+            self.preview,
+            MessageDirection::ToWidget,
+            preview
+                .map(|path| utils::into_gui_texture(resource_manager.request_texture(path, None))),
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.preview,
+            MessageDirection::ToWidget,
+            preview.is_some(),
+        ));
+
+        // Tip is created anew every time, so it is shown in current language.
+        if self.tip.is_some() {
+            ui.send_message(WidgetMessage::remove(self.tip, MessageDirection::ToWidget));
+            self.tip = Handle::NONE;
+        }
+        if let Some(tip) = self.tips.choose(&mut rand::thread_rng()) {
+            self.tip = TextBuilder::new(WidgetBuilder::new())
+                .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                .with_wrap(true)
+                .with_text(tr(tip))
+                .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                self.tip,
+                MessageDirection::ToWidget,
+                self.tip_panel,
+            ));
+        }
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        let frame = (self.spinner_time / SPINNER_FRAME_TIME) as usize;
+        self.spinner_time =
+            (self.spinner_time + dt) % (SPINNER_FRAME_TIME * SPINNER_FRAMES.len() as f32);
+        let next_frame = (self.spinner_time / SPINNER_FRAME_TIME) as usize;
+        if frame != next_frame {
+            ui.send_message(TextMessage::text(
+                self.spinner,
+                MessageDirection::ToWidget,
+                SPINNER_FRAMES[next_frame % SPINNER_FRAMES.len()].to_owned(),
+            ));
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Translations of UI text. Text in code is written in English and English text itself is
+//! used as a key, every other language has a file `data/languages/<code>.txt` where each line
+//! has the form:
+//!
+//! `<English text> = <translation>`
+//!
+//! Empty lines and lines starting with `#` are ignored. Text is translated by [`tr`] when a
+//! widget is created or its text is set, text that is formatted at runtime is translated by
+//! [`tr_format`] with its template as a key, for example `Warm-up {} = Aufwärmphase {}`.
+//! When language changes, text of existing widgets is translated again once.
+
+use crate::logger::{self, Category};
+use rg3d::gui::{
+    message::MessageDirection,
+    text::{Text, TextMessage},
+    UserInterface,
+};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::RwLock,
+};
+
+pub struct Language {
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+pub static LANGUAGES: [Language; 3] = [
+    Language {
+        code: "en",
+        name: "English",
+    },
+    Language {
+        code: "de",
+        name: "Deutsch",
+    },
+    Language {
+        code: "es",
+        name: "Español",
+    },
+];
+
+/// Language of text in code, it has no file.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// Strings of current language, text is translated wherever widgets are built, so the table is
+// shared instead of being passed to every menu.
+static STRINGS: RwLock<Option<HashMap<String, String>>> = RwLock::new(None);
+
+/// Returns translation of English text to current language, or the text itself if there is no
+/// translation.
+pub fn tr(text: &str) -> String {
+    STRINGS
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|strings| strings.get(text))
+        .map_or_else(|| text.to_owned(), |translation| translation.clone())
+}
+
+/// Translates format template and substitutes every `{}` in it with next argument.
+pub fn tr_format(template: &str, args: &[&dyn Display]) -> String {
+    let template = tr(template);
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    let mut out = parts.next().unwrap_or_default().to_owned();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out += &arg.to_string();
+        }
+        out += part;
+    }
+    out
+}
+
+fn load_strings(language: &str) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    if language == DEFAULT_LANGUAGE {
+        return strings;
+    }
+
+    let path = Path::new("data/languages")
+        .join(language)
+        .with_extension("txt");
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            logger::warning(
+                Category::General,
+                format!("Unable to load language {:?}. Reason: {:?}", path, e),
+            );
+            return strings;
+        }
+    };
+
+    for line in BufReader::new(file).lines().filter_map(|line| line.ok()) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(source), Some(translation)) => {
+                strings.insert(source.trim().to_owned(), translation.trim().to_owned());
+            }
+            _ => logger::warning(
+                Category::General,
+                format!("Invalid translation {:?} in {:?}", line, path),
+            ),
+        }
+    }
+
+    strings
+}
+
+pub struct Localization {
+    language: String,
+}
+
+impl Localization {
+    /// Loads strings of a language, must be done before any UI is built.
+    pub fn new(language: &str) -> Self {
+        *STRINGS.write().unwrap() = Some(load_strings(language));
+        Self {
+            language: language.to_owned(),
+        }
+    }
+
+    /// Switches language and translates text of existing widgets again. Widgets keep no
+    /// English text, so translation is looked up back by its text in previous language.
+    /// Text that is formatted at runtime is updated by its owner.
+    pub fn set_language(&mut self, ui: &mut UserInterface, language: &str) {
+        if self.language == language {
+            return;
+        }
+        self.language = language.to_owned();
+
+        let strings = load_strings(language);
+        let mut previous = STRINGS.write().unwrap();
+        let sources = previous
+            .iter()
+            .flatten()
+            .map(|(source, translation)| (translation.clone(), source.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let mut stack = vec![ui.root()];
+        let mut changes = Vec::new();
+        while let Some(handle) = stack.pop() {
+            let node = ui.node(handle);
+            stack.extend_from_slice(node.children());
+
+            if let Some(text) = node.cast::<Text>() {
+                let text = text.text();
+                let source = sources.get(&text).unwrap_or(&text);
+                let translation = strings.get(source).unwrap_or(source);
+                if *translation != text {
+                    changes.push((handle, translation.clone()));
+                }
+            }
+        }
+
+        *previous = Some(strings);
+        drop(previous);
+
+        for (handle, text) in changes {
+            ui.send_message(TextMessage::text(handle, MessageDirection::ToWidget, text));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! In-game log viewer, it shows recent lines of game log and can filter them by category.
+//! Viewer is opened by `/debug log` chat command.
+
+use crate::logger::{self, Category, LogLevel};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        brush::Brush,
+        button::{ButtonBuilder, ButtonMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        scroll_viewer::ScrollViewerBuilder,
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        Orientation, Thickness, UiNode, UserInterface,
+    },
+};
+
+/// Amount of most recent lines that are shown.
+const MAX_LINES: usize = 50;
+
+fn level_color(level: LogLevel) -> Color {
+    match level {
+        LogLevel::Debug => Color::opaque(150, 150, 150),
+        LogLevel::Info => Color::WHITE,
+        LogLevel::Warning => Color::opaque(255, 200, 0),
+        LogLevel::Error => Color::opaque(255, 60, 60),
+    }
+}
+
+pub struct LogViewer {
+    pub window: Handle<UiNode>,
+    lines_panel: Handle<UiNode>,
+    lines: Vec<Handle<UiNode>>,
+    // Filter buttons, None is for all categories.
+    filters: Vec<(Handle<UiNode>, Option<Category>)>,
+    category: Option<Category>,
+    // Generation of log that is shown, lines are rebuilt when it changes.
+    generation: Option<u64>,
+    open: bool,
+}
+
+impl LogViewer {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let filters = std::iter::once(None)
+            .chain(Category::ALL.iter().copied().map(Some))
+            .map(|category| {
+                let button = ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .with_width(60.0)
+                        .with_margin(Thickness::uniform(1.0)),
+                )
+                .with_text(category.map_or("All", |category| category.name()))
+                .build(ctx);
+                (button, category)
+            })
+            .collect::<Vec<_>>();
+
+        let lines_panel;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(600.0).with_height(400.0))
+            .with_title(WindowTitle::text("Log"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .with_children(filters.iter().map(|(button, _)| *button)),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        )
+                        .with_child(
+                            ScrollViewerBuilder::new(WidgetBuilder::new().on_row(1))
+                                .with_content({
+                                    lines_panel =
+                                        StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+                                    lines_panel
+                                })
+                                .build(ctx),
+                        ),
+                )
+                .add_column(Column::stretch())
+                .add_row(Row::strict(28.0))
+                .add_row(Row::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            lines_panel,
+            lines: Default::default(),
+            filters,
+            category: None,
+            generation: None,
+            open: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_open(&mut self, ui: &mut UserInterface, open: bool) {
+        self.open = open;
+        if open {
+            // Log could change while viewer was closed.
+            self.generation = None;
+            ui.send_message(WindowMessage::open(
+                self.window,
+                MessageDirection::ToWidget,
+                false,
+            ));
+        } else {
+            ui.send_message(WindowMessage::close(
+                self.window,
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface) {
+        if !self.open || self.generation == Some(logger::generation()) {
+            return;
+        }
+
+        let (generation, entries) = logger::recent(self.category);
+        self.generation = Some(generation);
+
+        for line in self.lines.drain(..) {
+            ui.send_message(WidgetMessage::remove(line, MessageDirection::ToWidget));
+        }
+        let skip = entries.len().saturating_sub(MAX_LINES);
+        for entry in entries.iter().skip(skip) {
+            let line = TextBuilder::new(
+                WidgetBuilder::new().with_foreground(Brush::Solid(level_color(entry.level))),
+            )
+            .with_text(entry.format())
+            .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                line,
+                MessageDirection::ToWidget,
+                self.lines_panel,
+            ));
+            self.lines.push(line);
+        }
+    }
+
+    pub fn handle_ui_event(&mut self, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if let Some((_, category)) = self
+                .filters
+                .iter()
+                .find(|(button, _)| *button == message.destination())
+            {
+                self.category = *category;
+                self.generation = None;
+            }
+        } else if let Some(WindowMessage::Close) = message.data() {
+            if message.destination() == self.window {
+                self.open = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Logging facade of the game. Every line has a level and a category, lines are passed to engine
+//! log as before and also written to a log file in user data directory. Log files are rotated
+//! on start and when current file grows too big, a few previous files are kept. Recent lines
+//! are kept in memory for in-game log viewer.
+
+use crate::paths;
+use rg3d::utils::log::{Log, MessageKind};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Amount of previous log files kept along with the current one.
+const MAX_OLD_FILES: usize = 4;
+
+/// Log file is rotated when it grows bigger than this (in bytes).
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Amount of recent lines available for log viewer.
+const MAX_RECENT: usize = 200;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    pub fn name(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Category {
+    General,
+    Ai,
+    Net,
+    Save,
+    Audio,
+    Level,
+}
+
+impl Category {
+    pub const ALL: [Category; 6] = [
+        Category::General,
+        Category::Ai,
+        Category::Net,
+        Category::Save,
+        Category::Audio,
+        Category::Level,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::General => "General",
+            Category::Ai => "AI",
+            Category::Net => "Net",
+            Category::Save => "Save",
+            Category::Audio => "Audio",
+            Category::Level => "Level",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LogEntry {
+    pub level: LogLevel,
+    pub category: Category,
+    pub text: String,
+}
+
+impl LogEntry {
+    pub fn format(&self) -> String {
+        format!(
+            "[{}] [{}] {}",
+            self.level.name(),
+            self.category.name(),
+            self.text
+        )
+    }
+}
+
+struct Logger {
+    path: Option<PathBuf>,
+    file: Option<File>,
+    written: u64,
+    min_level: LogLevel,
+    recent: Vec<LogEntry>,
+    // Increased with every line, viewer uses it to find out that log has changed.
+    generation: u64,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    path: None,
+    file: None,
+    written: 0,
+    min_level: LogLevel::Info,
+    recent: Vec::new(),
+    generation: 0,
+});
+
+fn old_file_path(path: &Path, index: usize) -> PathBuf {
+    path.with_extension(format!("{}.log", index))
+}
+
+/// Shifts `game.log` to `game.1.log`, `game.1.log` to `game.2.log` and so on, the oldest file
+/// is overwritten.
+fn rotate(path: &Path) {
+    for index in (1..MAX_OLD_FILES).rev() {
+        let _ = std::fs::rename(old_file_path(path, index), old_file_path(path, index + 1));
+    }
+    let _ = std::fs::rename(path, old_file_path(path, 1));
+}
+
+fn open(path: &Path) -> Option<File> {
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
+        .ok()
+}
+
+/// Opens new log file, must be called once on start. Lines logged before are kept in memory
+/// only, debug lines are written only in debug builds.
+pub fn init() {
+    // Paths may log on their own, so they must be resolved before the logger is locked.
+    let path = paths::logs_dir().join("game.log");
+    rotate(&path);
+    let file = open(&path);
+
+    let mut logger = LOGGER.lock().unwrap();
+    if cfg!(debug_assertions) {
+        logger.min_level = LogLevel::Debug;
+    }
+    if let Some(mut file) = file {
+        for entry in logger.recent.iter() {
+            let _ = writeln!(file, "{}", entry.format());
+        }
+        logger.file = Some(file);
+    }
+    logger.path = Some(path);
+}
+
+pub fn set_min_level(level: LogLevel) {
+    LOGGER.lock().unwrap().min_level = level;
+}
+
+pub fn writeln(level: LogLevel, category: Category, text: String) {
+    let mut logger = LOGGER.lock().unwrap();
+    if level < logger.min_level {
+        return;
+    }
+
+    let entry = LogEntry {
+        level,
+        category,
+        text,
+    };
+    let line = entry.format();
+
+    let kind = match level {
+        LogLevel::Debug | LogLevel::Info => MessageKind::Information,
+        LogLevel::Warning => MessageKind::Warning,
+        LogLevel::Error => MessageKind::Error,
+    };
+    Log::writeln(kind, format!("[{}] {}", category.name(), entry.text));
+
+    if logger.written > MAX_FILE_SIZE {
+        if let Some(path) = logger.path.clone() {
+            logger.file = None;
+            rotate(&path);
+            logger.file = open(&path);
+            logger.written = 0;
+        }
+    }
+    if let Some(file) = logger.file.as_mut() {
+        if writeln!(file, "{}", line).is_ok() {
+            logger.written += line.len() as u64 + 1;
+        }
+    }
+
+    if logger.recent.len() >= MAX_RECENT {
+        logger.recent.remove(0);
+    }
+    logger.recent.push(entry);
+    logger.generation += 1;
+}
+
+pub fn debug(category: Category, text: String) {
+    writeln(LogLevel::Debug, category, text)
+}
+
+pub fn info(category: Category, text: String) {
+    writeln(LogLevel::Info, category, text)
+}
+
+pub fn warning(category: Category, text: String) {
+    writeln(LogLevel::Warning, category, text)
+}
+
+pub fn error(category: Category, text: String) {
+    writeln(LogLevel::Error, category, text)
+}
+
+/// Returns generation of log along with recent lines of given category, all categories if
+/// None, oldest first.
+pub fn recent(category: Option<Category>) -> (u64, Vec<LogEntry>) {
+    let logger = LOGGER.lock().unwrap();
+    let entries = logger
+        .recent
+        .iter()
+        .filter(|entry| category.map_or(true, |category| entry.category == category))
+        .cloned()
+        .collect();
+    (logger.generation, entries)
+}
+
+pub fn generation() -> u64 {
+    LOGGER.lock().unwrap().generation
+}
@@ -5,132 +5,211 @@ extern crate crossbeam;
 extern crate rg3d;
 
 mod actor;
+mod announcer;
+mod assets;
+mod awards;
 mod bot;
+mod camera_effects;
+mod campaign;
+mod campaign_menu;
+mod captions;
 mod character;
+mod chat;
+mod checkpoint;
 mod control_scheme;
+mod damage_effects;
+mod debug_overlay;
+mod director;
+mod display;
+mod editor;
 mod effects;
+mod flag;
+mod frame_graph;
 mod gui;
+mod heatmap;
+mod hitbox;
 mod hud;
+mod inspector;
+mod intermission;
 mod item;
 mod jump_pad;
+mod lan;
+mod lan_menu;
 mod leader_board;
 mod level;
+mod load_menu;
+mod loading_screen;
+mod localization;
+mod log_viewer;
+mod map_install;
+mod map_layout;
+mod maps;
 mod match_menu;
+mod match_recorder;
 mod menu;
+mod menu_scene;
 mod message;
+mod mine;
+mod missing_assets;
+mod mods;
+mod mods_menu;
+mod music;
+mod names;
+mod navmesh_builder;
+mod observer;
 mod options_menu;
+mod pak;
+mod paths;
+mod pickup_toasts;
+mod ping;
 mod player;
+mod practice;
+mod profile_menu;
 mod projectile;
+mod saves;
+mod score;
+mod score_popups;
+mod settings;
+mod spawn;
+mod squad;
+mod startup;
+mod teleporter;
+mod theme;
+mod training;
+mod turret;
+mod viewmodel;
+mod voice;
+mod vote;
+mod waypoints;
 mod weapon;
+mod weapon_stats;
+mod weapon_wheel;
 
 use crate::{
-    actor::Actor, control_scheme::ControlScheme, hud::Hud, level::Level, menu::Menu,
+    actor::Actor,
+    campaign::Loadout,
+    control_scheme::{ControlButton, ControlScheme},
+    debug_overlay::{DebugOverlay, DebugOverlays},
+    display::DisplayMode,
+    editor::Editor,
+    frame_graph::{FrameGraph, FrameTimes},
+    gui::UiScale,
+    hud::Hud,
+    inspector::Inspector,
+    intermission::Intermission,
+    lan::{Beacon, LanBroadcaster},
+    level::Level,
+    loading_screen::LoadingScreen,
+    localization::{tr, tr_format, Localization},
+    log_viewer::LogViewer,
+    logger::{self, Category},
+    menu::Menu,
+    menu_scene::MenuScene,
     message::Message,
+    missing_assets::MissingAssetsDialog,
+    music::MusicManager,
+    score::ScoreEvent,
+    settings::Settings,
+    squad::Order,
 };
 use rg3d::{
-    core::{
-        pool::Handle,
-        visitor::{Visit, VisitResult, Visitor},
-    },
+    core::visitor::{Visit, VisitResult, Visitor},
     engine::Engine,
     event::{ElementState, Event, VirtualKeyCode, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
-    gui::{
-        grid::{Column, GridBuilder, Row},
-        message::MessageDirection,
-        progress_bar::{ProgressBarBuilder, ProgressBarMessage},
-        text::{TextBuilder, TextMessage},
-        widget::{WidgetBuilder, WidgetMessage},
-        BuildContext, HorizontalAlignment, UiNode, VerticalAlignment,
-    },
+    gui::{message::MessageDirection, progress_bar::ProgressBarMessage, widget::WidgetMessage},
     scene::Scene,
-    sound::{
-        context::SoundContext,
-        source::{generic::GenericSourceBuilder, SoundSource, Status},
-    },
-    utils::{
-        log::{Log, MessageKind},
-        translate_event,
-    },
+    utils::translate_event,
 };
 use std::{
     fs::File,
     io::Write,
-    path::Path,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
     sync::{
         mpsc::{self, Receiver, Sender},
         Arc, Mutex, RwLock,
     },
-    time::{self, Instant},
+    time::{self, Duration, Instant},
 };
 
+/// Default tick rate, every per-frame constant of the game is tuned for it.
 const FIXED_FPS: f32 = 60.0;
+/// Max amount of simulation ticks per frame, excess time is dropped after long stalls so
+/// simulation won't spiral down trying to catch up.
+const MAX_CATCH_UP_TICKS: u32 = 8;
+pub const MIN_TIME_SCALE: f32 = 0.5;
+pub const MAX_TIME_SCALE: f32 = 2.0;
+pub const MIN_AMMO_SCALE: f32 = 0.25;
+pub const MAX_AMMO_SCALE: f32 = 3.0;
+/// Limits of debug time scale of the whole game, it is not limited by match settings.
+const MIN_DEBUG_TIME_SCALE: f32 = 0.05;
+const MAX_DEBUG_TIME_SCALE: f32 = 4.0;
+/// Debug time scale that is toggled by bullet time key.
+const BULLET_TIME_SCALE: f32 = 0.25;
+/// Sounds are this much quieter while window is not focused.
+const UNFOCUSED_GAIN_SCALE: f32 = 0.2;
+
+/// Flow of matches, every match ends with intermission which then starts next match.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MatchFlow {
+    /// No match is running, only menu is shown.
+    Idle,
+    Playing,
+    /// Match is over, final scoreboard is shown and players vote for next map.
+    Intermission,
+}
 
 pub struct Game {
     menu: Menu,
+    menu_scene: MenuScene,
     hud: Hud,
+    intermission: Intermission,
+    flow: MatchFlow,
     engine: Engine,
     level: Option<Level>,
-    debug_text: Handle<UiNode>,
-    debug_string: String,
+    // Some while level editor is open, there is no level then.
+    editor: Option<Editor>,
+    frame_graph: FrameGraph,
+    // Time (in milliseconds) spent on updates since last rendered frame.
+    update_time: f32,
+    debug_overlays: DebugOverlays,
+    inspector: Inspector,
+    log_viewer: LogViewer,
+    missing_assets: MissingAssetsDialog,
     running: bool,
     control_scheme: Arc<RwLock<ControlScheme>>,
+    settings: Arc<RwLock<Settings>>,
     time: GameTime,
     events_receiver: Receiver<Message>,
     events_sender: Sender<Message>,
     load_context: Option<Arc<Mutex<LoadContext>>>,
+    // Campaign level that is being loaded and the loadout player brings into it.
+    campaign_start: Option<(u32, Option<Loadout>)>,
+    // Time since last autosave or since level was loaded.
+    autosave_timer: f32,
+    lan_broadcaster: LanBroadcaster,
     loading_screen: LoadingScreen,
-    menu_sound_context: SoundContext,
-    music: Handle<SoundSource>,
-}
-
-struct LoadingScreen {
-    root: Handle<UiNode>,
-    progress_bar: Handle<UiNode>,
-}
-
-impl LoadingScreen {
-    fn new(ctx: &mut BuildContext, width: f32, height: f32) -> Self {
-        let progress_bar;
-        let root = GridBuilder::new(
-            WidgetBuilder::new()
-                .with_width(width)
-                .with_height(height)
-                .with_visibility(false)
-                .with_child(
-                    GridBuilder::new(
-                        WidgetBuilder::new()
-                            .on_row(1)
-                            .on_column(1)
-                            .with_child({
-                                progress_bar =
-                                    ProgressBarBuilder::new(WidgetBuilder::new().on_row(1))
-                                        .build(ctx);
-                                progress_bar
-                            })
-                            .with_child(
-                                TextBuilder::new(WidgetBuilder::new().on_row(0))
-                                    .with_horizontal_text_alignment(HorizontalAlignment::Center)
-                                    .with_vertical_text_alignment(VerticalAlignment::Center)
-                                    .with_text("Loading... Please wait.")
-                                    .build(ctx),
-                            ),
-                    )
-                    .add_row(Row::stretch())
-                    .add_row(Row::strict(32.0))
-                    .add_column(Column::stretch())
-                    .build(ctx),
-                ),
-        )
-        .add_column(Column::stretch())
-        .add_column(Column::strict(400.0))
-        .add_column(Column::stretch())
-        .add_row(Row::stretch())
-        .add_row(Row::strict(100.0))
-        .add_row(Row::stretch())
-        .build(ctx);
-        Self { root, progress_bar }
-    }
+    music: MusicManager,
+    ui_scale: UiScale,
+    localization: Localization,
+    // Directory to write match logs to, given on command line. Overrides the setting.
+    match_log_dir: Option<PathBuf>,
+    // Debug speed of the whole game, fixed updates are accumulated this much faster than real
+    // time.
+    time_scale: f32,
+    // Real time of previous frame and scaled time that was not simulated yet.
+    frame_clock: f64,
+    time_lag: f64,
+    // Game is slowed down while weapon wheel is open in local match.
+    wheel_slowdown: bool,
+    focused: bool,
+    // Master gain before window lost focus, it is restored when focus returns.
+    focused_gain: f32,
+    // Local match stands still while menu opened by losing focus is shown.
+    focus_pause: bool,
+    // Time when last frame was rendered, frame rate limit counts from it.
+    last_frame: Instant,
 }
 
 #[derive(Copy, Clone)]
@@ -140,6 +219,19 @@ pub struct GameTime {
     delta: f32,
 }
 
+impl GameTime {
+    /// Converts per-frame increment tuned for [`FIXED_FPS`] into increment for current time step.
+    pub fn per_frame(&self, value: f32) -> f32 {
+        value * self.delta * FIXED_FPS
+    }
+
+    /// Converts per-frame smoothing factor tuned for [`FIXED_FPS`] into a factor for current
+    /// time step, so smoothing looks the same for any tick rate.
+    pub fn smoothing(&self, k: f32) -> f32 {
+        1.0 - (1.0 - k).powf(self.delta * FIXED_FPS)
+    }
+}
+
 // Disable false-positive lint, isize *is* portable.
 #[allow(clippy::enum_clike_unportable_variant)]
 pub enum CollisionGroups {
@@ -149,10 +241,354 @@ pub enum CollisionGroups {
     All = std::isize::MAX,
 }
 
+/// What happens when time is up and scores are tied.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Overtime {
+    /// Match ends in a draw.
+    None,
+    /// Next frag (or flag) wins.
+    SuddenDeath,
+    /// Time limit is extended once, if scores are still tied after it, sudden death begins.
+    ExtraTime { secs: f32 },
+}
+
+impl Default for Overtime {
+    fn default() -> Self {
+        Overtime::SuddenDeath
+    }
+}
+
+impl Overtime {
+    fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(Overtime::None),
+            1 => Ok(Overtime::SuddenDeath),
+            2 => Ok(Overtime::ExtraTime { secs: 0.0 }),
+            _ => Err(format!("Invalid overtime {}", id)),
+        }
+    }
+
+    fn id(&self) -> u32 {
+        match self {
+            Overtime::None => 0,
+            Overtime::SuddenDeath => 1,
+            Overtime::ExtraTime { .. } => 2,
+        }
+    }
+}
+
+impl Visit for Overtime {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        let mut id = self.id();
+        id.visit("Id", visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        if let Overtime::ExtraTime { secs } = self {
+            secs.visit("Secs", visitor)?;
+        }
+
+        visitor.leave_region()
+    }
+}
+
+/// Single-player difficulty, it changes balance between player and bots.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Normal
+    }
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(Difficulty::Easy),
+            1 => Ok(Difficulty::Normal),
+            2 => Ok(Difficulty::Hard),
+            _ => Err(format!("Invalid difficulty {}", id)),
+        }
+    }
+
+    fn id(self) -> u32 {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    /// Multiplier of damage that bots deal.
+    pub fn bot_damage_dealt(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier of damage that bots receive from player.
+    pub fn bot_damage_received(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.75,
+        }
+    }
+
+    /// Multiplier of time it takes for picked up item to appear again.
+    pub fn item_reactivation(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier of respawn time of player, bots respawn at inverse rate.
+    pub fn player_respawn(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Time (in seconds) it takes bot to open fire after it has spotted a target.
+    pub fn bot_reaction_time(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.6,
+            Difficulty::Normal => 0.35,
+            Difficulty::Hard => 0.2,
+        }
+    }
+
+    /// Max speed (in rad/s) at which bot turns its aim after a moving target.
+    pub fn bot_tracking_speed(self) -> f32 {
+        match self {
+            Difficulty::Easy => 90.0f32.to_radians(),
+            Difficulty::Normal => 180.0f32.to_radians(),
+            Difficulty::Hard => 360.0f32.to_radians(),
+        }
+    }
+
+    /// Max deviation (in radians) of bot's shots from its aim at close range.
+    pub fn bot_aim_error(self) -> f32 {
+        match self {
+            Difficulty::Easy => 6.0f32.to_radians(),
+            Difficulty::Normal => 3.0f32.to_radians(),
+            Difficulty::Hard => 1.5f32.to_radians(),
+        }
+    }
+}
+
+impl Visit for Difficulty {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut id = self.id();
+        id.visit(name, visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Set of rule modifiers of a match, every mutator is a single bit.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Mutators(u32);
+
+impl Mutators {
+    pub const NONE: Mutators = Mutators(0);
+    /// Gravity is reduced to a fraction of normal.
+    pub const LOW_GRAVITY: Mutators = Mutators(1);
+    /// Any hit of one actor by another is lethal.
+    pub const INSTAGIB: Mutators = Mutators(1 << 1);
+    /// Attackers restore part of damage they deal as health.
+    pub const VAMPIRE: Mutators = Mutators(1 << 2);
+    /// Hit boxes of heads are much larger.
+    pub const BIG_HEADS: Mutators = Mutators(1 << 3);
+    /// Bots are target dummies, ammo is infinite and items never despawn.
+    pub const PRACTICE: Mutators = Mutators(1 << 4);
+    /// Health slowly restores after a while without damage.
+    pub const REGENERATION: Mutators = Mutators(1 << 5);
+    /// Flag carriers of capture the flag move slower.
+    pub const HEAVY_FLAG: Mutators = Mutators(1 << 6);
+
+    pub fn contains(self, other: Mutators) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn set(&mut self, other: Mutators, state: bool) {
+        if state {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl Visit for Mutators {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        self.0.visit(name, visitor)
+    }
+}
+
+/// How many medkits of the map are placed.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HealthDensity {
+    /// Every second medkit is removed.
+    Scarce,
+    Normal,
+    /// Every medkit appears again twice as fast.
+    Plenty,
+}
+
+impl Default for HealthDensity {
+    fn default() -> Self {
+        HealthDensity::Normal
+    }
+}
+
+impl HealthDensity {
+    pub const ALL: [HealthDensity; 3] = [
+        HealthDensity::Scarce,
+        HealthDensity::Normal,
+        HealthDensity::Plenty,
+    ];
+
+    fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(HealthDensity::Scarce),
+            1 => Ok(HealthDensity::Normal),
+            2 => Ok(HealthDensity::Plenty),
+            _ => Err(format!("Invalid health density {}", id)),
+        }
+    }
+
+    fn id(self) -> u32 {
+        match self {
+            HealthDensity::Scarce => 0,
+            HealthDensity::Normal => 1,
+            HealthDensity::Plenty => 2,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HealthDensity::Scarce => "Scarce",
+            HealthDensity::Normal => "Normal",
+            HealthDensity::Plenty => "Plenty",
+        }
+    }
+
+    /// Whether medkit with given index (in order of placement) is placed.
+    pub fn keeps_medkit(self, index: usize) -> bool {
+        self != HealthDensity::Scarce || index % 2 == 0
+    }
+
+    /// Multiplier of time it takes for picked up medkit to appear again.
+    pub fn medkit_reactivation(self) -> f32 {
+        match self {
+            HealthDensity::Plenty => 0.5,
+            _ => 1.0,
+        }
+    }
+}
+
+impl Visit for HealthDensity {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut id = self.id();
+        id.visit(name, visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Item scarcity of a match. Ammo scale applies to ammo of weapons given at spawn and to ammo
+/// given by items, weapon that stays remains on its spot after pick up, but actor that has such
+/// weapon can't pick it up again.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ItemEconomy {
+    pub ammo_scale: f32,
+    pub health_density: HealthDensity,
+    pub weapon_stay: bool,
+}
+
+impl Default for ItemEconomy {
+    fn default() -> Self {
+        Self {
+            ammo_scale: 1.0,
+            health_density: Default::default(),
+            weapon_stay: false,
+        }
+    }
+}
+
+impl ItemEconomy {
+    /// Defaults of match menu for given difficulty, harder matches have fewer items.
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => Self {
+                ammo_scale: 1.5,
+                health_density: HealthDensity::Plenty,
+                weapon_stay: true,
+            },
+            Difficulty::Normal => Default::default(),
+            Difficulty::Hard => Self {
+                ammo_scale: 0.75,
+                health_density: HealthDensity::Scarce,
+                weapon_stay: false,
+            },
+        }
+    }
+
+    pub fn scale_ammo(&self, ammo: u32) -> u32 {
+        let scale = self.ammo_scale.max(MIN_AMMO_SCALE).min(MAX_AMMO_SCALE);
+        ((ammo as f32 * scale).round() as u32).max(1)
+    }
+}
+
+impl Visit for ItemEconomy {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.ammo_scale.visit("AmmoScale", visitor)?;
+        self.health_density.visit("HealthDensity", visitor)?;
+        self.weapon_stay.visit("WeaponStay", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct DeathMatch {
     pub time_limit_secs: f32,
     pub frag_limit: u32,
+    pub time_scale: f32,
+    pub overtime: Overtime,
+    pub mutators: Mutators,
+    pub difficulty: Difficulty,
+    pub economy: ItemEconomy,
 }
 
 impl Default for DeathMatch {
@@ -160,6 +596,11 @@ impl Default for DeathMatch {
         Self {
             time_limit_secs: Default::default(),
             frag_limit: 0,
+            time_scale: 1.0,
+            overtime: Default::default(),
+            mutators: Default::default(),
+            difficulty: Default::default(),
+            economy: Default::default(),
         }
     }
 }
@@ -170,6 +611,13 @@ impl Visit for DeathMatch {
 
         self.time_limit_secs.visit("TimeLimit", visitor)?;
         self.frag_limit.visit("FragLimit", visitor)?;
+        self.time_scale.visit("TimeScale", visitor)?;
+        self.overtime.visit("Overtime", visitor)?;
+        self.mutators.visit("Mutators", visitor)?;
+        self.difficulty.visit("Difficulty", visitor)?;
+        if saves::read_version(visitor) >= 17 {
+            self.economy.visit("Economy", visitor)?;
+        }
 
         visitor.leave_region()
     }
@@ -179,6 +627,11 @@ impl Visit for DeathMatch {
 pub struct TeamDeathMatch {
     pub time_limit_secs: f32,
     pub team_frag_limit: u32,
+    pub time_scale: f32,
+    pub overtime: Overtime,
+    pub mutators: Mutators,
+    pub difficulty: Difficulty,
+    pub economy: ItemEconomy,
 }
 
 impl Default for TeamDeathMatch {
@@ -186,6 +639,11 @@ impl Default for TeamDeathMatch {
         Self {
             time_limit_secs: Default::default(),
             team_frag_limit: 0,
+            time_scale: 1.0,
+            overtime: Default::default(),
+            mutators: Default::default(),
+            difficulty: Default::default(),
+            economy: Default::default(),
         }
     }
 }
@@ -196,6 +654,13 @@ impl Visit for TeamDeathMatch {
 
         self.time_limit_secs.visit("TimeLimit", visitor)?;
         self.team_frag_limit.visit("TeamFragLimit", visitor)?;
+        self.time_scale.visit("TimeScale", visitor)?;
+        self.overtime.visit("Overtime", visitor)?;
+        self.mutators.visit("Mutators", visitor)?;
+        self.difficulty.visit("Difficulty", visitor)?;
+        if saves::read_version(visitor) >= 17 {
+            self.economy.visit("Economy", visitor)?;
+        }
 
         visitor.leave_region()
     }
@@ -205,6 +670,11 @@ impl Visit for TeamDeathMatch {
 pub struct CaptureTheFlag {
     pub time_limit_secs: f32,
     pub flag_limit: u32,
+    pub time_scale: f32,
+    pub overtime: Overtime,
+    pub mutators: Mutators,
+    pub difficulty: Difficulty,
+    pub economy: ItemEconomy,
 }
 
 impl Default for CaptureTheFlag {
@@ -212,6 +682,11 @@ impl Default for CaptureTheFlag {
         Self {
             time_limit_secs: Default::default(),
             flag_limit: 0,
+            time_scale: 1.0,
+            overtime: Default::default(),
+            mutators: Default::default(),
+            difficulty: Default::default(),
+            economy: Default::default(),
         }
     }
 }
@@ -222,6 +697,48 @@ impl Visit for CaptureTheFlag {
 
         self.time_limit_secs.visit("TimeLimit", visitor)?;
         self.flag_limit.visit("FlagLimit", visitor)?;
+        self.time_scale.visit("TimeScale", visitor)?;
+        self.overtime.visit("Overtime", visitor)?;
+        self.mutators.visit("Mutators", visitor)?;
+        self.difficulty.visit("Difficulty", visitor)?;
+        if saves::read_version(visitor) >= 17 {
+            self.economy.visit("Economy", visitor)?;
+        }
+
+        visitor.leave_region()
+    }
+}
+
+/// Co-op against waves of bots, match ends when player dies. There is no time limit.
+#[derive(Copy, Clone, Debug)]
+pub struct Survival {
+    pub time_scale: f32,
+    pub mutators: Mutators,
+    pub difficulty: Difficulty,
+    pub economy: ItemEconomy,
+}
+
+impl Default for Survival {
+    fn default() -> Self {
+        Self {
+            time_scale: 1.0,
+            mutators: Default::default(),
+            difficulty: Default::default(),
+            economy: Default::default(),
+        }
+    }
+}
+
+impl Visit for Survival {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.time_scale.visit("TimeScale", visitor)?;
+        self.mutators.visit("Mutators", visitor)?;
+        self.difficulty.visit("Difficulty", visitor)?;
+        if saves::read_version(visitor) >= 17 {
+            self.economy.visit("Economy", visitor)?;
+        }
 
         visitor.leave_region()
     }
@@ -232,6 +749,7 @@ pub enum MatchOptions {
     DeathMatch(DeathMatch),
     TeamDeathMatch(TeamDeathMatch),
     CaptureTheFlag(CaptureTheFlag),
+    Survival(Survival),
 }
 
 impl MatchOptions {
@@ -240,6 +758,7 @@ impl MatchOptions {
             0 => Ok(MatchOptions::DeathMatch(Default::default())),
             1 => Ok(MatchOptions::TeamDeathMatch(Default::default())),
             2 => Ok(MatchOptions::CaptureTheFlag(Default::default())),
+            3 => Ok(MatchOptions::Survival(Default::default())),
             _ => Err(format!("Invalid match options {}", id)),
         }
     }
@@ -249,6 +768,103 @@ impl MatchOptions {
             MatchOptions::DeathMatch(_) => 0,
             MatchOptions::TeamDeathMatch(_) => 1,
             MatchOptions::CaptureTheFlag(_) => 2,
+            MatchOptions::Survival(_) => 3,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatchOptions::DeathMatch(_) => "Deathmatch",
+            MatchOptions::TeamDeathMatch(_) => "Team Deathmatch",
+            MatchOptions::CaptureTheFlag(_) => "Capture The Flag",
+            MatchOptions::Survival(_) => "Survival",
+        }
+    }
+
+    /// Returns how long (in seconds) shield barrier waits after last damage before it starts
+    /// to recharge, None means that shields never recharge in this mode.
+    pub fn shield_regen_delay(&self) -> Option<f32> {
+        match self {
+            MatchOptions::DeathMatch(_) | MatchOptions::TeamDeathMatch(_) => Some(4.0),
+            // Flag carriers must not outlast defenders by hiding for a moment.
+            MatchOptions::CaptureTheFlag(_) => Some(6.0),
+            // Waves keep coming, shield is a one-time buffer there.
+            MatchOptions::Survival(_) => None,
+        }
+    }
+
+    /// Returns amount of points that given score event is worth in the match.
+    pub fn points(&self, event: ScoreEvent) -> i32 {
+        match (self, event) {
+            (_, ScoreEvent::Suicide) => -10,
+            (MatchOptions::CaptureTheFlag(_), ScoreEvent::Kill) => 5,
+            (MatchOptions::CaptureTheFlag(_), ScoreEvent::Assist) => 3,
+            (MatchOptions::CaptureTheFlag(_), ScoreEvent::FlagCapture) => 50,
+            (MatchOptions::CaptureTheFlag(_), ScoreEvent::FlagReturn) => 10,
+            (_, ScoreEvent::Kill) => 10,
+            (_, ScoreEvent::Assist) => 5,
+            // Point capture is meant for team modes with control points, there are none yet.
+            (MatchOptions::TeamDeathMatch(_), ScoreEvent::PointCapture) => 20,
+            (_, ScoreEvent::FlagCapture)
+            | (_, ScoreEvent::FlagReturn)
+            | (_, ScoreEvent::PointCapture) => 0,
+        }
+    }
+
+    /// Returns game speed multiplier of the match, clamped to supported range.
+    pub fn time_scale(&self) -> f32 {
+        let time_scale = match self {
+            MatchOptions::DeathMatch(dm) => dm.time_scale,
+            MatchOptions::TeamDeathMatch(tdm) => tdm.time_scale,
+            MatchOptions::CaptureTheFlag(ctf) => ctf.time_scale,
+            MatchOptions::Survival(survival) => survival.time_scale,
+        };
+        time_scale.max(MIN_TIME_SCALE).min(MAX_TIME_SCALE)
+    }
+
+    /// Returns time limit of the match in seconds, zero means no limit.
+    pub fn time_limit_secs(&self) -> f32 {
+        match self {
+            MatchOptions::DeathMatch(dm) => dm.time_limit_secs,
+            MatchOptions::TeamDeathMatch(tdm) => tdm.time_limit_secs,
+            MatchOptions::CaptureTheFlag(ctf) => ctf.time_limit_secs,
+            MatchOptions::Survival(_) => 0.0,
+        }
+    }
+
+    pub fn overtime(&self) -> Overtime {
+        match self {
+            MatchOptions::DeathMatch(dm) => dm.overtime,
+            MatchOptions::TeamDeathMatch(tdm) => tdm.overtime,
+            MatchOptions::CaptureTheFlag(ctf) => ctf.overtime,
+            MatchOptions::Survival(_) => Overtime::None,
+        }
+    }
+
+    pub fn mutators(&self) -> Mutators {
+        match self {
+            MatchOptions::DeathMatch(dm) => dm.mutators,
+            MatchOptions::TeamDeathMatch(tdm) => tdm.mutators,
+            MatchOptions::CaptureTheFlag(ctf) => ctf.mutators,
+            MatchOptions::Survival(survival) => survival.mutators,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            MatchOptions::DeathMatch(dm) => dm.difficulty,
+            MatchOptions::TeamDeathMatch(tdm) => tdm.difficulty,
+            MatchOptions::CaptureTheFlag(ctf) => ctf.difficulty,
+            MatchOptions::Survival(survival) => survival.difficulty,
+        }
+    }
+
+    pub fn economy(&self) -> ItemEconomy {
+        match self {
+            MatchOptions::DeathMatch(dm) => dm.economy,
+            MatchOptions::TeamDeathMatch(tdm) => tdm.economy,
+            MatchOptions::CaptureTheFlag(ctf) => ctf.economy,
+            MatchOptions::Survival(survival) => survival.economy,
         }
     }
 }
@@ -272,6 +888,7 @@ impl Visit for MatchOptions {
             MatchOptions::DeathMatch(o) => o.visit("Data", visitor)?,
             MatchOptions::TeamDeathMatch(o) => o.visit("Data", visitor)?,
             MatchOptions::CaptureTheFlag(o) => o.visit("Data", visitor)?,
+            MatchOptions::Survival(o) => o.visit("Data", visitor)?,
         }
 
         visitor.leave_region()
@@ -283,88 +900,175 @@ pub struct LoadContext {
 }
 
 impl Game {
-    pub fn run() {
+    /// Returns only if the game can't start, error is meant to be shown to player.
+    pub fn run() -> Result<(), String> {
+        logger::init();
+
+        startup::check_data()?;
+
         let events_loop = EventLoop::<()>::new();
 
-        let primary_monitor = events_loop.primary_monitor().unwrap();
-        let mut monitor_dimensions = primary_monitor.size();
-        monitor_dimensions.height = (monitor_dimensions.height as f32 * 0.7) as u32;
-        monitor_dimensions.width = (monitor_dimensions.width as f32 * 0.7) as u32;
-        let inner_size = monitor_dimensions.to_logical::<f32>(primary_monitor.scale_factor());
+        // Settings are needed before window is created, they tell where and how to show it.
+        let settings = Arc::new(RwLock::new(Settings::load()));
+
+        // Window of default size is created if there are no monitors at all, for example over
+        // remote desktop.
+        let (display_mode, monitor_index) = {
+            let settings = settings.read().unwrap();
+            (settings.display_mode, settings.monitor)
+        };
+        let monitor = display::pick_monitor(
+            events_loop.available_monitors(),
+            events_loop.primary_monitor(),
+            monitor_index,
+        );
+        let inner_size = display::windowed_size(monitor.as_ref());
 
         let window_builder = rg3d::window::WindowBuilder::new()
             .with_title("Rusty Shooter")
             .with_inner_size(inner_size)
+            .with_fullscreen(display_mode.fullscreen(monitor, None))
             .with_resizable(true);
 
-        let mut engine = Engine::new(window_builder, &events_loop, false).unwrap();
+        // Sound engine panics if there is no audio device, it is caught to tell player what's
+        // wrong instead of crashing.
+        let mut engine = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            Engine::new(window_builder, &events_loop, false)
+        }))
+        .map_err(|payload| {
+            format!(
+                "Unable to initialize audio: {}.\n\
+                 Make sure an audio output device is connected and enabled.",
+                startup::panic_message(payload)
+            )
+        })?
+        .map_err(|e| {
+            format!(
+                "Unable to initialize graphics: {:?}.\n\
+                 Make sure your video drivers are up to date and support OpenGL 3.3.",
+                e
+            )
+        })?;
+
+        // Windowed window is placed by the system, it is moved to the chosen monitor.
+        if display_mode == DisplayMode::Windowed {
+            display::apply(engine.get_window(), display_mode, monitor_index, None);
+        }
 
         let control_scheme = Arc::new(RwLock::new(ControlScheme::default()));
 
-        let fixed_timestep = 1.0 / FIXED_FPS;
+        {
+            let mut settings = settings.write().unwrap();
+            if mods::scan(&mut settings.mods) {
+                settings.save();
+            }
+            mods::mount(&settings.mods);
+        }
+        maps::load_user_maps();
+
+        // Missing assets are shown to player on first update.
+        assets::validate_definitions();
 
         let time = GameTime {
             clock: Instant::now(),
             elapsed: 0.0,
-            delta: fixed_timestep,
+            delta: settings.read().unwrap().fixed_timestep(),
         };
 
         let (tx, rx) = mpsc::channel();
 
-        let menu_sound_context = SoundContext::new();
-
-        let buffer = rg3d::core::futures::executor::block_on(
-            engine
-                .resource_manager
-                .request_sound_buffer("data/sounds/Antonio_Bizarro_Berzerker.ogg", true),
-        )
-        .unwrap();
-        let music = menu_sound_context.state().add_source(
-            GenericSourceBuilder::new()
-                .with_buffer(buffer.into())
-                .with_looping(true)
-                .with_status(Status::Playing)
-                .with_gain(0.25)
-                .build_source()
-                .unwrap(),
-        );
+        let match_log_dir = std::env::args()
+            .skip_while(|arg| arg != "--match-log-dir")
+            .nth(1)
+            .map(PathBuf::from);
+
+        let music = MusicManager::new(&mut engine);
+
+        let localization = Localization::new(&settings.read().unwrap().language);
 
-        engine
-            .sound_engine
-            .lock()
-            .unwrap()
-            .add_context(menu_sound_context.clone());
+        let mut ui_scale = UiScale::default();
+        let loading_screen = LoadingScreen::new(
+            &mut engine.user_interface.build_ctx(),
+            inner_size.width,
+            inner_size.height,
+            &mut ui_scale,
+        );
+        let hud = Hud::new(&mut engine, &mut ui_scale);
+        let intermission = Intermission::new(&mut engine.user_interface);
+        ui_scale.register(intermission.window, 600.0, 0.0);
+        let inspector = Inspector::new(&mut engine.user_interface);
+        ui_scale.register(inspector.window, 360.0, 500.0);
+        let log_viewer = LogViewer::new(&mut engine.user_interface);
+        ui_scale.register(log_viewer.window, 600.0, 400.0);
+        let missing_assets = MissingAssetsDialog::new(&mut engine.user_interface);
+        ui_scale.register(missing_assets.window, 500.0, 300.0);
+        let frame_graph = FrameGraph::new(&mut engine.user_interface);
+        let menu = Menu::new(
+            &mut engine,
+            control_scheme.clone(),
+            settings.clone(),
+            tx.clone(),
+            &mut ui_scale,
+        );
+        let menu_scene = MenuScene::new(&engine);
 
         let mut game = Game {
-            loading_screen: LoadingScreen::new(
-                &mut engine.user_interface.build_ctx(),
-                inner_size.width,
-                inner_size.height,
-            ),
-            menu_sound_context,
+            loading_screen,
             music,
-            hud: Hud::new(&mut engine),
+            ui_scale,
+            localization,
+            hud,
+            intermission,
+            flow: MatchFlow::Idle,
             running: true,
-            menu: Menu::new(&mut engine, control_scheme.clone(), tx.clone()),
+            menu,
+            menu_scene,
             control_scheme,
-            debug_text: Handle::NONE,
+            settings,
             engine,
             level: None,
-            debug_string: String::new(),
+            editor: None,
+            frame_graph,
+            update_time: 0.0,
+            debug_overlays: Default::default(),
+            inspector,
+            log_viewer,
+            missing_assets,
             time,
             events_receiver: rx,
             events_sender: tx,
             load_context: None,
+            campaign_start: None,
+            autosave_timer: 0.0,
+            lan_broadcaster: Default::default(),
+            match_log_dir,
+            time_scale: 1.0,
+            frame_clock: 0.0,
+            time_lag: 0.0,
+            wheel_slowdown: false,
+            focused: true,
+            focused_gain: 1.0,
+            focus_pause: false,
+            last_frame: Instant::now(),
         };
 
-        game.create_debug_ui();
-
         events_loop.run(move |event, _, control_flow| {
             game.process_input_event(&event);
 
             match event {
                 Event::MainEventsCleared => {
-                    let mut dt = game.time.clock.elapsed().as_secs_f64() - game.time.elapsed;
+                    let fixed_timestep = game.settings.read().unwrap().fixed_timestep();
+                    game.time.delta = fixed_timestep;
+
+                    let now = game.time.clock.elapsed().as_secs_f64();
+                    let mut dt = game.time_lag
+                        + (now - game.frame_clock) * game.effective_time_scale() as f64;
+                    game.frame_clock = now;
+                    let max_dt = fixed_timestep as f64 * MAX_CATCH_UP_TICKS as f64;
+                    if dt > max_dt {
+                        dt = max_dt;
+                    }
+                    let update_start = Instant::now();
                     while dt >= fixed_timestep as f64 {
                         dt -= fixed_timestep as f64;
                         game.time.elapsed += fixed_timestep as f64;
@@ -373,37 +1077,73 @@ impl Game {
 
                         while let Some(ui_event) = game.engine.user_interface.poll_message() {
                             game.menu.handle_ui_event(&mut game.engine, &ui_event);
+                            game.intermission
+                                .handle_ui_event(&mut game.engine.user_interface, &ui_event);
+                            game.inspector.handle_ui_event(&ui_event);
+                            game.log_viewer.handle_ui_event(&ui_event);
+                            game.missing_assets
+                                .handle_ui_event(&mut game.engine.user_interface, &ui_event);
+                            if let Some(editor) = game.editor.as_mut() {
+                                editor.handle_ui_event(&mut game.engine, &ui_event);
+                            }
                         }
                     }
+                    game.time_lag = dt;
+                    game.update_time += update_start.elapsed().as_secs_f32() * 1000.0;
                     if !game.running {
                         *control_flow = ControlFlow::Exit;
                     }
-                    game.engine.get_window().request_redraw();
+                    let frame_due = game
+                        .frame_time()
+                        .map_or(true, |frame_time| game.last_frame.elapsed() >= frame_time);
+                    if frame_due {
+                        game.engine.get_window().request_redraw();
+                    }
                 }
                 Event::RedrawRequested(_) => {
-                    game.update_statistics(game.time.elapsed);
-
-                    // <<<<< ENABLE THIS TO SHOW DEBUG GEOMETRY >>>>>
-                    if false {
-                        game.debug_render();
-                    }
+                    game.debug_render();
 
-                    // Render at max speed
+                    // Render at max speed unless frame rate is limited.
+                    game.last_frame = Instant::now();
+                    let render_start = Instant::now();
                     game.engine.render().unwrap();
+                    let render_time = render_start.elapsed().as_secs_f32() * 1000.0;
+
+                    game.update_statistics(game.time.elapsed, render_time);
                 }
                 Event::WindowEvent { event, .. } => match event {
                     WindowEvent::CloseRequested => {
                         game.destroy_level();
                         *control_flow = ControlFlow::Exit
                     }
+                    WindowEvent::Focused(focused) => game.set_focused(focused),
                     WindowEvent::Resized(new_size) => {
                         game.engine.set_frame_size(new_size.into()).unwrap();
+                        let ui = &mut game.engine.user_interface;
+                        ui.send_message(WidgetMessage::width(
+                            game.loading_screen.root,
+                            MessageDirection::ToWidget,
+                            new_size.width as f32,
+                        ));
+                        ui.send_message(WidgetMessage::height(
+                            game.loading_screen.root,
+                            MessageDirection::ToWidget,
+                            new_size.height as f32,
+                        ));
                     }
                     _ => (),
                 },
+                // Loop sleeps until next frame when frame rate is limited, instead of spinning.
+                Event::RedrawEventsCleared => {
+                    *control_flow = match game.frame_time() {
+                        Some(frame_time) => ControlFlow::WaitUntil(game.last_frame + frame_time),
+                        None => ControlFlow::Poll,
+                    }
+                }
                 Event::LoopDestroyed => {
                     if let Ok(profiling_results) = rg3d::core::profiler::print() {
-                        if let Ok(mut file) = File::create("profiling.log") {
+                        if let Ok(mut file) = File::create(paths::data_dir().join("profiling.log"))
+                        {
                             let _ = writeln!(file, "{}", profiling_results);
                         }
                     }
@@ -415,116 +1155,323 @@ impl Game {
 
     fn debug_render(&mut self) {
         if let Some(level) = self.level.as_mut() {
-            level.debug_draw(&mut self.engine);
+            level.debug_draw(&mut self.engine, &self.debug_overlays);
+            if self.inspector.is_open() {
+                let entities = level.inspect(&self.engine);
+                self.inspector
+                    .update(&mut self.engine.user_interface, &entities);
+                self.inspector.draw_highlight(
+                    &mut self.engine.scenes[level.scene].drawing_context,
+                    &entities,
+                );
+            }
         }
     }
 
-    pub fn create_debug_ui(&mut self) {
-        self.debug_text = TextBuilder::new(WidgetBuilder::new().with_width(400.0))
-            .build(&mut self.engine.user_interface.build_ctx());
-    }
-
-    pub fn save_game(&mut self) -> VisitResult {
+    pub fn save_game(&mut self, path: &Path) -> VisitResult {
         let mut visitor = Visitor::new();
 
+        saves::write_header(&mut visitor)?;
         // Visit engine state first.
         self.engine.visit("Engine", &mut visitor)?;
         self.level.visit("Level", &mut visitor)?;
-        self.menu_sound_context
-            .visit("MenuSoundContext", &mut visitor)?;
-        self.music.visit("Music", &mut visitor)?;
 
-        // Debug output
-        if let Ok(mut file) = File::create(Path::new("save.txt")) {
-            file.write_all(visitor.save_text().as_bytes()).unwrap();
+        // Debug output, text dump is many times larger than the save itself.
+        if cfg!(debug_assertions) {
+            if let Ok(mut file) = File::create(path.with_extension("txt")) {
+                file.write_all(visitor.save_text().as_bytes()).unwrap();
+            }
         }
 
-        visitor.save_binary(Path::new("save.bin"))
+        saves::save_compressed(&mut visitor, path)
     }
 
-    pub fn load_game(&mut self) -> VisitResult {
-        Log::writeln(
-            MessageKind::Information,
-            "Attempting load a save...".to_owned(),
-        );
+    /// Returns error that can be shown to player if save can't be loaded, details are logged.
+    pub fn load_game(&mut self, path: &Path) -> Result<(), String> {
+        logger::info(Category::Save, "Attempting load a save...".to_owned());
 
         let mut visitor =
-            rg3d::core::futures::executor::block_on(Visitor::load_binary(Path::new("save.bin")))?;
+            rg3d::core::futures::executor::block_on(saves::load(path)).map_err(|e| {
+                logger::error(
+                    Category::Save,
+                    format!("Unable to read save {}. Reason: {:?}", path.display(), e),
+                );
+                "Save file is missing or damaged".to_owned()
+            })?;
+
+        // Version is checked before clean up, so incompatible save does not break current game.
+        let version = saves::read_header(&mut visitor)?;
 
         // Clean up.
         self.destroy_level();
 
         // Load engine state first
-        Log::writeln(
-            MessageKind::Information,
-            "Trying to load a save file...".to_owned(),
+        logger::info(
+            Category::Save,
+            format!("Trying to load a save file of version {}...", version),
         );
-        self.engine.visit("Engine", &mut visitor)?;
-        self.level.visit("Level", &mut visitor)?;
-        self.menu_sound_context
-            .visit("MenuSoundContext", &mut visitor)?;
-        self.music.visit("Music", &mut visitor)?;
+        self.engine
+            .visit("Engine", &mut visitor)
+            .and_then(|_| self.level.visit("Level", &mut visitor))
+            .map_err(|e| {
+                logger::error(
+                    Category::Save,
+                    format!("Unable to load save {}. Reason: {:?}", path.display(), e),
+                );
+                "Save is damaged or incompatible with this version of the game".to_owned()
+            })?;
 
-        Log::writeln(
-            MessageKind::Information,
-            "Game state successfully loaded!".to_owned(),
-        );
+        logger::info(Category::Save, "Game state successfully loaded!".to_owned());
 
         // Hide menu only of we successfully loaded a save.
         self.set_menu_visible(false);
 
+        if self.flow == MatchFlow::Intermission {
+            self.intermission.close(&mut self.engine.user_interface);
+        }
+        self.flow = MatchFlow::Playing;
+        self.autosave_timer = 0.0;
+
         // Set control scheme for player.
         if let Some(level) = &mut self.level {
             level.set_message_sender(self.events_sender.clone(), &mut self.engine);
             level.control_scheme = Some(self.control_scheme.clone());
+            level.settings = Some(self.settings.clone());
+            self.music
+                .set_playlist(maps::find(level.map()).map(|map| &map.music));
             let player = level.get_player();
             if let Actor::Player(player) = level.actors_mut().get_mut(player) {
                 player.set_control_scheme(self.control_scheme.clone());
             }
+            self.hud
+                .leader_board_mut()
+                .set_local_player(level.player_name());
         }
 
-        self.time.elapsed = self.time.clock.elapsed().as_secs_f64();
+        // Time spent on loading must not be caught up.
+        self.frame_clock = self.time.clock.elapsed().as_secs_f64();
+        self.time_lag = 0.0;
 
         Ok(())
     }
 
+    /// Min time between frames, None if frame rate is not limited. Unfocused window has its
+    /// own limit, so game in background does not burn battery.
+    fn frame_time(&self) -> Option<Duration> {
+        let settings = self.settings.read().unwrap();
+        let limit = if self.focused {
+            settings.fps_limit
+        } else {
+            settings.background_fps_limit
+        };
+        if limit > 0 {
+            Some(Duration::from_secs_f64(1.0 / limit as f64))
+        } else {
+            None
+        }
+    }
+
+    fn effective_time_scale(&self) -> f32 {
+        if self.wheel_slowdown {
+            self.time_scale * weapon_wheel::TIME_SCALE
+        } else {
+            self.time_scale
+        }
+    }
+
+    fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(MIN_DEBUG_TIME_SCALE).min(MAX_DEBUG_TIME_SCALE);
+        let time_scale = self.effective_time_scale();
+        if let Some(level) = self.level.as_mut() {
+            level.set_sound_pitch(time_scale);
+        }
+        self.hud.chat_mut().add_system_line(
+            &mut self.engine.user_interface,
+            format!("Time scale is {}", self.time_scale),
+        );
+    }
+
     fn destroy_level(&mut self) {
         if let Some(ref mut level) = self.level.take() {
             level.destroy(&mut self.engine);
-            Log::writeln(
-                MessageKind::Information,
-                "Current level destroyed!".to_owned(),
-            );
+            self.music.set_playlist(None);
+            self.hud
+                .hide_training_prompt(&mut self.engine.user_interface);
+            self.hud.hide_vote_prompt(&mut self.engine.user_interface);
+            self.hud
+                .update_waypoints(&mut self.engine.user_interface, &[], None);
+            self.hud.set_wave(&mut self.engine.user_interface, None);
+            self.hud
+                .set_flag_status(&mut self.engine.user_interface, None);
+            self.hud
+                .set_afk_warning(&mut self.engine.user_interface, None);
+            self.hud
+                .set_observer_bar(&mut self.engine.user_interface, None);
+            self.hud
+                .set_practice_readout(&mut self.engine.user_interface, None);
+            self.hud
+                .set_shield(&mut self.engine.user_interface, 0.0, 0.0);
+            self.hud
+                .set_regenerating(&mut self.engine.user_interface, false);
+            self.hud
+                .clear_pickup_toasts(&mut self.engine.user_interface);
+            self.hud.clear_score_popups(&mut self.engine.user_interface);
+            self.hud
+                .set_weapon_wheel(&mut self.engine.user_interface, None);
+            self.hud
+                .set_command_rose(&mut self.engine.user_interface, None);
+            self.hud
+                .reset_damage_effects(&mut self.engine.user_interface);
+            self.wheel_slowdown = false;
+            if self.hud.chat().is_typing() {
+                self.hud.chat_mut().close(&mut self.engine.user_interface);
+            }
+            logger::info(Category::Level, "Current level destroyed!".to_owned());
         }
     }
 
-    pub fn start_new_game(&mut self, options: MatchOptions) {
+    fn start_editor(&mut self, map: PathBuf) {
+        self.stop_editor();
         self.destroy_level();
+        if self.flow == MatchFlow::Intermission {
+            self.intermission.close(&mut self.engine.user_interface);
+        }
+        self.flow = MatchFlow::Idle;
+
+        let ui = &mut self.engine.user_interface;
+        self.menu.set_visible(ui, false);
+        self.hud.set_visible(ui, false);
+
+        logger::info(
+            Category::Level,
+            format!("Opening editor on {}.", map.display()),
+        );
+        self.editor = Some(Editor::new(
+            &mut self.engine,
+            map,
+            self.control_scheme.clone(),
+            self.events_sender.clone(),
+        ));
+    }
+
+    fn stop_editor(&mut self) {
+        if let Some(mut editor) = self.editor.take() {
+            editor.destroy(&mut self.engine);
+            self.set_menu_visible(true);
+        }
+    }
+
+    pub fn start_new_game(&mut self, options: MatchOptions, map: PathBuf) {
+        self.load_level(options, map, false);
+    }
+
+    pub fn start_training(&mut self) {
+        self.load_level(
+            MatchOptions::DeathMatch(Default::default()),
+            maps::default_map().path.into(),
+            true,
+        );
+    }
+
+    /// Starts campaign level, player gets default weapons if there is no loadout.
+    pub fn start_campaign_level(&mut self, index: u32, loadout: Option<Loadout>) {
+        let level = &campaign::LEVELS[index as usize];
+        let difficulty = self.settings.read().unwrap().difficulty;
+        self.load_level(level.options(difficulty), level.map.into(), false);
+        self.campaign_start = Some((index, loadout));
+    }
+
+    /// Survival score is stored in profile if it beats previous record.
+    fn finish_survival(&mut self, waves: u32) {
+        let mut settings = self.settings.write().unwrap();
+        let text = if waves > settings.profile.best_survival_wave {
+            settings.profile.best_survival_wave = waves;
+            settings.save();
+            tr_format("New record! {} waves survived", &[&waves])
+        } else {
+            tr_format(
+                "{} waves survived, best is {}",
+                &[&waves, &settings.profile.best_survival_wave],
+            )
+        };
+        self.events_sender
+            .send(Message::AddNotification { text })
+            .unwrap();
+    }
+
+    fn finish_campaign_level(&mut self, index: u32) {
+        let level = self.level.as_ref().unwrap();
+        let definition = &campaign::LEVELS[index as usize];
+        if definition.objective.is_complete(level) {
+            let loadout = level.player_loadout();
+            let next = index + 1;
+            {
+                let mut settings = self.settings.write().unwrap();
+                if settings.profile.campaign_progress < next {
+                    settings.profile.campaign_progress = next;
+                    settings.save();
+                }
+            }
+            if (next as usize) < campaign::LEVELS.len() {
+                self.start_campaign_level(next, loadout);
+            } else {
+                self.destroy_level();
+                self.set_menu_visible(true);
+            }
+            self.events_sender
+                .send(Message::AddNotification {
+                    text: tr_format("{} complete!", &[&definition.name]),
+                })
+                .unwrap();
+        } else {
+            // Failed level is restarted from scratch.
+            self.start_campaign_level(index, None);
+            self.events_sender
+                .send(Message::AddNotification {
+                    text: tr_format(
+                        "Objective failed: {}",
+                        &[&definition.objective.description()],
+                    ),
+                })
+                .unwrap();
+        }
+    }
+
+    fn load_level(&mut self, options: MatchOptions, map: PathBuf, training: bool) {
+        self.destroy_level();
+        self.campaign_start = None;
+        self.autosave_timer = 0.0;
+
+        if self.flow == MatchFlow::Intermission {
+            self.intermission.close(&mut self.engine.user_interface);
+            self.hud
+                .leader_board()
+                .set_visible(false, &mut self.engine.user_interface);
+        }
+        self.flow = MatchFlow::Playing;
 
         let ctx = Arc::new(Mutex::new(LoadContext { level: None }));
 
         self.load_context = Some(ctx.clone());
 
-        self.engine
-            .user_interface
-            .send_message(WidgetMessage::visibility(
-                self.loading_screen.root,
-                MessageDirection::ToWidget,
-                true,
-            ));
+        self.loading_screen.show(&mut self.engine, &map);
         self.menu
             .set_visible(&mut self.engine.user_interface, false);
 
         let resource_manager = self.engine.resource_manager.clone();
         let control_scheme = self.control_scheme.clone();
+        let settings = self.settings.clone();
         let sender = self.events_sender.clone();
 
         std::thread::spawn(move || {
             let level = rg3d::core::futures::executor::block_on(Level::new(
                 resource_manager,
                 control_scheme,
+                settings,
                 sender,
                 options,
+                map,
+                training,
             ));
 
             ctx.lock().unwrap().level = Some(level);
@@ -535,6 +1482,40 @@ impl Game {
         let ui = &mut self.engine.user_interface;
         self.menu.set_visible(ui, visible);
         self.hud.set_visible(ui, !visible);
+        if !visible {
+            self.focus_pause = false;
+        }
+    }
+
+    /// Match is local unless it is announced in LAN. Every match runs on this machine only, so
+    /// it can be paused or slowed down as long as nobody else may be looking for it.
+    fn is_local_match(&self) -> bool {
+        self.level.is_some() && !self.lan_broadcaster.is_broadcasting()
+    }
+
+    /// Window that lost focus releases cursor and ducks sounds, local match is paused with menu.
+    /// Time that passed while window was unfocused is not simulated.
+    fn set_focused(&mut self, focused: bool) {
+        if focused == self.focused {
+            return;
+        }
+        self.focused = focused;
+
+        let mut sound_engine = self.engine.sound_engine.lock().unwrap();
+        if focused {
+            sound_engine.set_master_gain(self.focused_gain);
+            drop(sound_engine);
+            self.frame_clock = self.time.clock.elapsed().as_secs_f64();
+            self.time_lag = 0.0;
+        } else {
+            self.focused_gain = sound_engine.master_gain();
+            sound_engine.set_master_gain(self.focused_gain * UNFOCUSED_GAIN_SCALE);
+            drop(sound_engine);
+            if self.is_local_match() && self.editor.is_none() && !self.is_menu_visible() {
+                self.set_menu_visible(true);
+                self.focus_pause = true;
+            }
+        }
     }
 
     pub fn is_menu_visible(&self) -> bool {
@@ -543,13 +1524,43 @@ impl Game {
 
     pub fn update(&mut self, time: GameTime) {
         let window = self.engine.get_window();
-        window.set_cursor_visible(self.is_menu_visible());
-        let _ = window.set_cursor_grab(!self.is_menu_visible());
+        let cursor_visible = !self.focused
+            || self.is_menu_visible()
+            || self.flow == MatchFlow::Intermission
+            || self.inspector.is_open()
+            || self.log_viewer.is_open()
+            || self.missing_assets.is_open()
+            || self.editor.is_some();
+        window.set_cursor_visible(cursor_visible);
+        let _ = window.set_cursor_grab(!cursor_visible);
 
         if let Some(ctx) = self.load_context.clone() {
             if let Ok(mut ctx) = ctx.try_lock() {
                 if let Some((mut level, scene)) = ctx.level.take() {
                     level.scene = self.engine.scenes.add(scene);
+                    if let Some((index, loadout)) = self.campaign_start.take() {
+                        level.campaign_level = Some(index);
+                        if let Some(loadout) = loadout {
+                            rg3d::core::futures::executor::block_on(
+                                level.apply_loadout(&mut self.engine, &loadout),
+                            );
+                        }
+                        let definition = &campaign::LEVELS[index as usize];
+                        self.events_sender
+                            .send(Message::AddNotification {
+                                text: format!(
+                                    "{}: {}",
+                                    definition.name,
+                                    definition.objective.description()
+                                ),
+                            })
+                            .unwrap();
+                    }
+                    self.music
+                        .set_playlist(maps::find(level.map()).map(|map| &map.music));
+                    self.hud
+                        .leader_board_mut()
+                        .set_local_player(level.player_name());
                     self.level = Some(level);
                     self.load_context = None;
                     self.set_menu_visible(false);
@@ -561,6 +1572,8 @@ impl Game {
                             false,
                         ));
                 } else {
+                    self.loading_screen
+                        .update(&mut self.engine.user_interface, time.delta);
                     self.engine
                         .user_interface
                         .send_message(ProgressBarMessage::progress(
@@ -572,81 +1585,356 @@ impl Game {
             }
         }
 
+        let background_visible =
+            self.level.is_none() && self.load_context.is_none() && self.editor.is_none();
+        self.menu_scene
+            .update(&mut self.engine, background_visible, time.delta);
+
+        if let Some(editor) = self.editor.as_mut() {
+            editor.update(&mut self.engine, time.delta);
+        }
+
         self.engine.update(time.delta);
+        self.log_viewer.update(&mut self.engine.user_interface);
+
+        let missing = assets::take_missing();
+        if !missing.is_empty() {
+            self.missing_assets
+                .show(&mut self.engine.user_interface, missing);
+        }
+
+        let threats = self
+            .level
+            .as_ref()
+            .map_or(0, |level| level.threats_near_player(&self.engine));
+        self.music.update(time.delta, threats);
 
+        self.menu.update(&mut self.engine.user_interface);
+        self.update_lan_beacon(time.delta);
+
+        let local_match = self.is_local_match();
         if let Some(ref mut level) = self.level {
-            level.update(&mut self.engine, time);
+            if !self.focus_pause {
+                level.update(&mut self.engine, time);
+            }
+            let wheel_slowdown = local_match && level.is_weapon_wheel_open();
+            let time_scale = if wheel_slowdown {
+                self.time_scale * weapon_wheel::TIME_SCALE
+            } else {
+                self.time_scale
+            };
+            // New sounds are pitched by match speed only.
+            if time_scale != 1.0 || wheel_slowdown != self.wheel_slowdown {
+                level.set_sound_pitch(time_scale);
+            }
+            self.wheel_slowdown = wheel_slowdown;
+            {
+                let state = self.engine.scenes[level.scene].sound_context.state();
+                let listener = state.listener();
+                self.hud
+                    .set_listener(listener.position(), *listener.basis());
+            }
+            let mut waypoints = level.waypoints(&self.engine);
+            waypoints.extend(level.debug_labels(&self.engine, &self.debug_overlays));
+            let view = if self.menu.is_visible(&self.engine.user_interface) {
+                None
+            } else {
+                level.view(&self.engine)
+            };
             let ui = &mut self.engine.user_interface;
+            self.hud.update_waypoints(ui, &waypoints, view);
             self.hud.set_time(ui, level.time());
+            self.hud
+                .set_match_phase(ui, level.phase(), level.overtime());
+            self.hud.set_wave(ui, level.wave_status());
+            self.hud.set_flag_status(ui, level.flag_status());
+            self.hud.set_observer_bar(ui, level.observer_summary());
+            self.hud.set_practice_readout(ui, level.practice_readout());
+            self.hud.set_afk_warning(ui, level.afk_warning());
             let player = level.get_player();
             if player.is_some() {
                 // Sync hud with player state.
                 let player = level.actors().get(player);
                 self.hud.set_health(ui, player.get_health());
+                self.hud.set_regenerating(ui, player.is_regenerating());
                 self.hud.set_armor(ui, player.get_armor());
+                self.hud
+                    .set_shield(ui, player.shield, player.shield_capacity());
                 let current_weapon = player.current_weapon();
                 if current_weapon.is_some() {
-                    self.hud
-                        .set_ammo(ui, level.weapons()[current_weapon].ammo());
+                    let weapon = &level.weapons()[current_weapon];
+                    self.hud.set_ammo(ui, weapon.ammo());
+                    self.hud.set_fire_mode(ui, weapon.fire_mode());
                 }
+                let arsenal = player
+                    .weapons()
+                    .iter()
+                    .map(|weapon| level.weapons()[*weapon].get_kind())
+                    .collect::<Vec<_>>();
+                self.hud
+                    .set_arsenal(ui, &arsenal, player.current_weapon as usize);
+                let wheel = match player {
+                    Actor::Player(player) => player
+                        .weapon_wheel()
+                        .map(|wheel| wheel.selection(arsenal.len())),
+                    Actor::Bot(_) => None,
+                };
+                self.hud.set_weapon_wheel(ui, wheel);
+                let rose = match player {
+                    Actor::Player(player) => player
+                        .command_rose()
+                        .map(|rose| rose.selection(Order::ALL.len())),
+                    Actor::Bot(_) => None,
+                };
+                self.hud.set_command_rose(ui, rose);
                 self.hud.set_is_died(ui, false);
+                self.hud.set_killed_by(ui, None);
             } else {
-                self.hud.set_is_died(ui, true);
+                // Observer has not died, they left the match on their own.
+                self.hud.set_is_died(ui, !level.is_observing());
+                self.hud.set_killed_by(ui, level.death_description());
             }
         }
 
         self.handle_messages(time);
+        self.update_autosave(time.delta);
+
+        if self.flow == MatchFlow::Intermission {
+            if let Some((options, map)) = self
+                .intermission
+                .update(&mut self.engine.user_interface, time.delta)
+            {
+                self.start_new_game(options, map);
+            }
+        }
 
+        self.hud
+            .set_minimal_mode(self.settings.read().unwrap().minimal_hud);
+        self.hud
+            .set_damage_effects(self.settings.read().unwrap().damage_effects);
+        {
+            let settings = self.settings.read().unwrap();
+            self.hud
+                .set_hit_markers(settings.hit_markers, settings.kill_marker);
+        }
+        self.hud
+            .set_captions_enabled(self.settings.read().unwrap().captions);
+        self.hud.set_palette(
+            &mut self.engine.user_interface,
+            self.settings.read().unwrap().palette,
+        );
         self.hud.update(&mut self.engine.user_interface, &self.time);
+
+        // Scale factor is queried every frame so moving window to a display with different
+        // DPI rescales UI as well.
+        let ui_scale =
+            self.settings.read().unwrap().ui_scale * self.engine.get_window().scale_factor() as f32;
+        self.ui_scale
+            .set_scale(&mut self.engine.user_interface, ui_scale);
+    }
+
+    // Only regular matches are announced in LAN, and only if player chose to host on LAN.
+    fn update_lan_beacon(&mut self, dt: f32) {
+        let host_on_lan = self.settings.read().unwrap().host_on_lan;
+        let beacon = self
+            .level
+            .as_ref()
+            .filter(|level| host_on_lan && !level.is_single_player())
+            .map(|level| Beacon {
+                host: self.settings.read().unwrap().profile.name.clone(),
+                map: maps::find(level.map()).map_or_else(
+                    || level.map().to_string_lossy().into_owned(),
+                    |map| map.name.to_owned(),
+                ),
+                mode: level.options.name().to_owned(),
+                players: level.actors().count(),
+            });
+        self.lan_broadcaster.update(beacon, dt);
+    }
+
+    /// Game is autosaved periodically while match is played, menu pauses the timer.
+    fn update_autosave(&mut self, dt: f32) {
+        if self.level.is_none() || self.flow != MatchFlow::Playing || self.is_menu_visible() {
+            return;
+        }
+
+        self.autosave_timer += dt;
+        if self.autosave_timer >= saves::AUTOSAVE_INTERVAL {
+            self.autosave_timer = 0.0;
+            self.events_sender
+                .send(Message::SaveGame {
+                    path: saves::next_autosave_path(),
+                })
+                .unwrap();
+        }
     }
 
     fn handle_messages(&mut self, time: GameTime) {
         while let Ok(message) = self.events_receiver.try_recv() {
             match &message {
-                Message::StartNewGame { options } => {
-                    self.start_new_game(*options);
+                Message::StartNewGame { options, map } => {
+                    self.start_new_game(*options, map.clone());
                 }
-                Message::SaveGame => match self.save_game() {
-                    Ok(_) => {
-                        Log::writeln(MessageKind::Information, "Successfully saved".to_owned())
-                    }
-                    Err(e) => Log::writeln(
-                        MessageKind::Error,
+                Message::StartTraining => self.start_training(),
+                Message::StartEditor { map } => self.start_editor(map.clone()),
+                Message::StopEditor => self.stop_editor(),
+                Message::StartCampaign { level } => self.start_campaign_level(*level, None),
+                Message::SaveGame { path } => match self.save_game(path) {
+                    Ok(_) => logger::info(Category::Save, "Successfully saved".to_owned()),
+                    Err(e) => logger::error(
+                        Category::Save,
                         format!("Failed to make a save, reason: {}", e),
                     ),
                 },
-                Message::LoadGame => {
-                    if let Err(e) = self.load_game() {
-                        Log::writeln(
-                            MessageKind::Error,
-                            format!("Failed to load saved game. Reason: {:?}", e),
-                        );
+                Message::LoadGame { path } => {
+                    if let Err(e) = self.load_game(path) {
+                        self.menu
+                            .show_load_error(&mut self.engine.user_interface, &e);
+                        self.events_sender
+                            .send(Message::AddNotification {
+                                text: tr_format("Unable to load game: {}", &[&tr(e)]),
+                            })
+                            .unwrap();
                     }
                 }
+                Message::MapInstalled { result } => {
+                    self.menu
+                        .show_install_result(&mut self.engine.user_interface, result);
+                }
+                Message::ToggleDebugOverlay { overlay } => {
+                    let text = match overlay {
+                        Some(DebugOverlay::Inspector) => {
+                            let open = !self.inspector.is_open();
+                            self.inspector
+                                .set_open(&mut self.engine.user_interface, open);
+                            format!("Inspector is {}", if open { "open" } else { "closed" })
+                        }
+                        Some(DebugOverlay::Log) => {
+                            let open = !self.log_viewer.is_open();
+                            self.log_viewer
+                                .set_open(&mut self.engine.user_interface, open);
+                            format!("Log is {}", if open { "open" } else { "closed" })
+                        }
+                        Some(overlay) => {
+                            let enabled = self.debug_overlays.toggle(*overlay);
+                            format!(
+                                "Debug overlay {} is {}",
+                                overlay.name(),
+                                if enabled { "on" } else { "off" }
+                            )
+                        }
+                        None => {
+                            self.debug_overlays.disable_all();
+                            self.inspector
+                                .set_open(&mut self.engine.user_interface, false);
+                            self.log_viewer
+                                .set_open(&mut self.engine.user_interface, false);
+                            "Debug overlays are off".to_owned()
+                        }
+                    };
+                    self.hud
+                        .chat_mut()
+                        .add_system_line(&mut self.engine.user_interface, text);
+                }
+                Message::SetTimeScale { scale } => {
+                    self.set_time_scale(*scale);
+                }
                 Message::QuitGame => {
+                    self.settings.write().unwrap().save();
                     self.destroy_level();
+                    self.flow = MatchFlow::Idle;
                     self.running = false;
                 }
                 Message::EndMatch => {
-                    self.destroy_level();
-                    self.hud
-                        .leader_board()
-                        .set_visible(true, &mut self.engine.user_interface);
+                    if let Some(level) = self.level.as_mut() {
+                        // Match logs are opt-in, either by command line or by the setting.
+                        let match_log_dir = self.match_log_dir.clone().or_else(|| {
+                            if self.settings.read().unwrap().match_logs {
+                                Some(paths::matches_dir())
+                            } else {
+                                None
+                            }
+                        });
+                        if let Some(dir) = match_log_dir {
+                            match level.export_match_log(&dir) {
+                                Ok(path) => logger::info(
+                                    Category::General,
+                                    format!("Match timeline was written to {}", path.display()),
+                                ),
+                                Err(e) => logger::error(
+                                    Category::General,
+                                    format!("Failed to write match log, reason: {}", e),
+                                ),
+                            }
+                        }
+                        logger::info(
+                            Category::General,
+                            format!("Weapon balance report:\n{}", level.weapon_stats.report()),
+                        );
+                        if self.settings.read().unwrap().weapon_telemetry {
+                            let path = paths::data_dir().join("weapon_stats.csv");
+                            if let Err(e) = level.weapon_stats.merge_into(&path) {
+                                logger::error(
+                                    Category::General,
+                                    format!("Failed to update weapon telemetry, reason: {}", e),
+                                );
+                            }
+                        }
+                    }
+                    if let Some(waves) = self.level.as_ref().and_then(|level| level.waves_cleared())
+                    {
+                        self.finish_survival(waves);
+                    }
+                    // Campaign levels are chained without intermission.
+                    if let Some(index) = self.level.as_ref().and_then(|level| level.campaign_level)
+                    {
+                        self.finish_campaign_level(index);
+                    } else {
+                        if let Some(level) = self.level.as_ref() {
+                            let bot_count = level
+                                .actors()
+                                .iter()
+                                .filter(|actor| matches!(actor, Actor::Bot(_)))
+                                .count();
+                            let awards = awards::compute(&level.weapon_stats, &level.leader_board);
+                            self.intermission.begin(
+                                &mut self.engine.user_interface,
+                                level.options,
+                                bot_count,
+                                &awards,
+                            );
+                            self.flow = MatchFlow::Intermission;
+                        }
+                        self.destroy_level();
+                        self.hud
+                            .leader_board()
+                            .set_visible(true, &mut self.engine.user_interface);
+                    }
                 }
                 Message::SetMusicVolume { volume } => {
-                    self.menu_sound_context
-                        .state()
-                        .source_mut(self.music)
-                        .set_gain(*volume);
+                    self.music.set_volume(*volume);
+                }
+                Message::SetLanguage { language } => {
+                    self.localization
+                        .set_language(&mut self.engine.user_interface, language);
+                }
+                Message::DamageActor { actor, who, .. } => {
+                    if let Some(level) = self.level.as_ref() {
+                        let player = level.get_player();
+                        if player.is_some() && (*actor == player || *who == player) {
+                            self.music.on_combat_event();
+                        }
+                    }
                 }
                 _ => (),
             }
 
             if let Some(ref mut level) = self.level {
+                let level_time = level.scale_time(time);
                 rg3d::core::futures::executor::block_on(level.handle_message(
                     &mut self.engine,
                     &message,
-                    time,
+                    level_time,
                 ));
 
                 self.hud.handle_message(
@@ -659,34 +1947,29 @@ impl Game {
         }
     }
 
-    pub fn update_statistics(&mut self, elapsed: f64) {
-        self.debug_string.clear();
-        use std::fmt::Write;
+    pub fn update_statistics(&mut self, elapsed: f64, render_time: f32) {
         let statistics = self.engine.renderer.get_statistics();
-        write!(
-            self.debug_string,
-            "Pure frame time: {:.2} ms\n\
-               Capped frame time: {:.2} ms\n\
-               FPS: {}\n\
+        let extra = format!(
+            "FPS: {}\n\
                Triangles: {}\n\
                Draw calls: {}\n\
-               Uptime: {:.2} s\n\
-               UI time: {:?}",
-            statistics.pure_frame_time * 1000.0,
-            statistics.capped_frame_time * 1000.0,
+               Uptime: {:.2} s",
             statistics.frames_per_second,
             statistics.geometry.triangles_rendered,
             statistics.geometry.draw_calls,
             elapsed,
-            self.engine.ui_time
-        )
-        .unwrap();
-
-        self.engine.user_interface.send_message(TextMessage::text(
-            self.debug_text,
-            MessageDirection::ToWidget,
-            self.debug_string.clone(),
-        ));
+        );
+
+        // UI is updated by engine within update of the game.
+        let ui = self.engine.ui_time.as_secs_f32() * 1000.0;
+        let times = FrameTimes {
+            update: (self.update_time - ui).max(0.0),
+            render: render_time,
+            ui,
+        };
+        self.update_time = 0.0;
+        self.frame_graph
+            .push(&mut self.engine.user_interface, times, &extra);
     }
 
     fn process_dispatched_event(&mut self, event: &Event<()>) {
@@ -696,10 +1979,13 @@ impl Game {
             }
         }
 
-        if !self.is_menu_visible() {
+        if !self.is_menu_visible() && !self.hud.chat().is_typing() {
             if let Some(ref mut level) = self.level {
                 level.process_input_event(event);
             }
+            if let Some(editor) = self.editor.as_mut() {
+                editor.process_input_event(&mut self.engine, event);
+            }
         }
     }
 
@@ -708,10 +1994,74 @@ impl Game {
 
         if let Event::WindowEvent { event, .. } = event {
             if let WindowEvent::KeyboardInput { input, .. } = event {
+                // Scoreboard is shown while its button is held, intermission shows it on its own.
+                if let Some(key) = input.virtual_keycode {
+                    let scoreboard = self.control_scheme.read().unwrap().scoreboard.button;
+                    if ControlButton::Key(key) == scoreboard && self.flow == MatchFlow::Playing {
+                        let visible = input.state == ElementState::Pressed
+                            && !self.is_menu_visible()
+                            && !self.hud.chat().is_typing();
+                        self.hud
+                            .leader_board()
+                            .set_visible(visible, &mut self.engine.user_interface);
+                    }
+                }
+
                 if let ElementState::Pressed = input.state {
                     if let Some(key) = input.virtual_keycode {
-                        if key == VirtualKeyCode::Escape {
+                        if self.hud.chat().is_typing() {
+                            let ui = &mut self.engine.user_interface;
+                            if key == VirtualKeyCode::Escape {
+                                self.hud.chat_mut().close(ui);
+                            } else if key == VirtualKeyCode::Return {
+                                let name = self.settings.read().unwrap().profile.name.clone();
+                                if let Some(message) = self.hud.chat_mut().submit(ui, &name) {
+                                    self.events_sender.send(message).unwrap();
+                                }
+                            }
+                        } else if key == VirtualKeyCode::Escape && self.editor.is_some() {
+                            self.events_sender.send(Message::StopEditor).unwrap();
+                        } else if key == VirtualKeyCode::Escape {
                             self.set_menu_visible(!self.is_menu_visible());
+                        } else if !self.is_menu_visible() && self.editor.is_none() {
+                            let button = ControlButton::Key(key);
+                            let control_scheme = self.control_scheme.read().unwrap();
+                            if (button == control_scheme.chat.button
+                                || button == control_scheme.team_chat.button)
+                                && self.level.is_some()
+                            {
+                                let team = button == control_scheme.team_chat.button;
+                                self.hud
+                                    .chat_mut()
+                                    .open(&mut self.engine.user_interface, team);
+                            } else if button == control_scheme.quick_save.button
+                                && self.level.is_some()
+                            {
+                                self.events_sender
+                                    .send(Message::SaveGame {
+                                        path: saves::quicksave_path(),
+                                    })
+                                    .unwrap();
+                            } else if button == control_scheme.frame_graph.button {
+                                let visible = !self.frame_graph.is_visible();
+                                self.frame_graph
+                                    .set_visible(&mut self.engine.user_interface, visible);
+                            } else if button == control_scheme.bullet_time.button {
+                                let scale = if self.time_scale == 1.0 {
+                                    BULLET_TIME_SCALE
+                                } else {
+                                    1.0
+                                };
+                                self.events_sender
+                                    .send(Message::SetTimeScale { scale })
+                                    .unwrap();
+                            } else if button == control_scheme.quick_load.button {
+                                self.events_sender
+                                    .send(Message::LoadGame {
+                                        path: saves::quicksave_path(),
+                                    })
+                                    .unwrap();
+                            }
                         }
                     }
                 }
@@ -724,5 +2074,8 @@ impl Game {
 }
 
 fn main() {
-    Game::run();
+    if let Err(e) = Game::run() {
+        startup::report(&e);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,193 @@
+//! Installation of user maps. Map package is a zip archive or a folder with `map.txt` descriptor
+//! (see `maps::MapDescriptor`), scene of the map and optionally its navmesh and preview image.
+//! Textures of the scene are searched in `textures` folder of the package. Package can be
+//! installed from a file or a folder or downloaded by URL. Installed maps are kept in `maps`
+//! folder of user data directory and join map rotation right away.
+
+use crate::{
+    logger::{self, Category},
+    maps::{self, MapDefinition},
+    navmesh_builder, pak, paths,
+};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Downloads larger than this (in bytes) are rejected.
+const MAX_DOWNLOAD_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Package is prepared here before it is moved into place, so a failed installation never
+/// leaves a broken map behind.
+const STAGING_DIR: &str = ".staging";
+
+/// Installed map is moved here while it is replaced, and moved back if replacement fails.
+const REPLACED_DIR: &str = ".replaced";
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+fn download(url: &str) -> Result<PathBuf, String> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Unable to download {}: {}", url, e))?;
+
+    let dir = paths::data_dir().join("downloads");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join("map_package.zip");
+    let mut file = File::create(&path).map_err(|e| e.to_string())?;
+    let size = std::io::copy(
+        &mut response.into_reader().take(MAX_DOWNLOAD_SIZE + 1),
+        &mut file,
+    )
+    .map_err(|e| format!("Unable to download {}: {}", url, e))?;
+    if size > MAX_DOWNLOAD_SIZE {
+        return Err(format!(
+            "Package is larger than {} MB",
+            MAX_DOWNLOAD_SIZE / (1024 * 1024)
+        ));
+    }
+    Ok(path)
+}
+
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(target)?;
+    for entry in std::fs::read_dir(source)? {
+        let path = entry?.path();
+        if let Some(name) = path.file_name() {
+            if path.is_dir() {
+                copy_dir(&path, &target.join(name))?;
+            } else {
+                std::fs::copy(&path, target.join(name))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Packages often wrap their files into a folder, so descriptor is searched in the root and in
+// the only folder of the root.
+fn find_root(dir: &Path) -> Result<PathBuf, String> {
+    if dir.join(maps::DESCRIPTOR_FILE).is_file() {
+        return Ok(dir.to_owned());
+    }
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    match entries.as_slice() {
+        [single] if single.join(maps::DESCRIPTOR_FILE).is_file() => Ok(single.clone()),
+        _ => Err(format!(
+            "Package has no {}, it is not a map package",
+            maps::DESCRIPTOR_FILE
+        )),
+    }
+}
+
+// Copies or extracts package into staging folder, returns folder with descriptor.
+fn stage(source: &str, staging: &Path) -> Result<PathBuf, String> {
+    let path = if is_url(source) {
+        download(source)?
+    } else {
+        PathBuf::from(source)
+    };
+
+    if path.is_dir() {
+        copy_dir(&path, staging).map_err(|e| format!("Unable to copy package: {}", e))?;
+    } else if path.is_file() {
+        pak::extract(&path, staging).map_err(|e| format!("Unable to unpack package: {}", e))?;
+    } else {
+        return Err(format!("{} does not exist", path.display()));
+    }
+
+    find_root(staging)
+}
+
+fn folder_name(name: &str) -> String {
+    let folder = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if folder.trim_matches('_').is_empty() {
+        "map".to_owned()
+    } else {
+        folder
+    }
+}
+
+fn install_staged(root: &Path) -> Result<&'static MapDefinition, String> {
+    let descriptor = maps::read_descriptor(root)?;
+
+    // Navmesh is put where navmesh cache is expected and written after the scene, so it is
+    // newer than the scene and is not rebuilt on first load.
+    if let Some(navmesh) = descriptor.navmesh.as_ref() {
+        let cache = navmesh_builder::cache_path(&root.join(&descriptor.scene));
+        let data = std::fs::read(root.join(navmesh)).map_err(|e| e.to_string())?;
+        std::fs::write(&cache, data).map_err(|e| e.to_string())?;
+    }
+
+    let target = paths::maps_dir().join(folder_name(&descriptor.name));
+    // Different names may map to the same folder, only map with the same name is replaced.
+    if let Ok(installed) = maps::read_descriptor(&target) {
+        if installed.name != descriptor.name {
+            return Err(format!(
+                "Map {} is installed in {}, remove it to install {}",
+                installed.name,
+                target.display(),
+                descriptor.name
+            ));
+        }
+    }
+
+    let replaced = paths::maps_dir().join(REPLACED_DIR);
+    let _ = std::fs::remove_dir_all(&replaced);
+    if target.exists() {
+        std::fs::rename(&target, &replaced)
+            .map_err(|e| format!("Unable to replace installed map: {}", e))?;
+    }
+    if let Err(e) = std::fs::rename(root, &target) {
+        if replaced.exists() {
+            let _ = std::fs::rename(&replaced, &target);
+        }
+        return Err(format!("Unable to install map: {}", e));
+    }
+    let _ = std::fs::remove_dir_all(&replaced);
+
+    maps::register_user_map(&target)
+}
+
+/// Installs map package from a path or URL, map with the same name is replaced. Blocks until
+/// done, so it must be called from a separate thread.
+pub fn install(source: &str) -> Result<&'static MapDefinition, String> {
+    let source = source.trim();
+    if source.is_empty() {
+        return Err("Enter path or URL of a map package".to_owned());
+    }
+
+    let staging = paths::maps_dir().join(STAGING_DIR);
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+
+    let result = stage(source, &staging).and_then(|root| install_staged(&root));
+    let _ = std::fs::remove_dir_all(&staging);
+
+    match result {
+        Ok(map) => logger::info(
+            Category::Level,
+            format!("Installed map {} from {}.", map.name, source),
+        ),
+        Err(ref e) => logger::error(
+            Category::Level,
+            format!("Unable to install map from {}: {}.", source, e),
+        ),
+    }
+    result
+}
@@ -0,0 +1,289 @@
+//! Placement layer of a map - item spawns, player spawns, jump pads, teleporters and
+//! checkpoints placed by level editor on top of map scene. Layer is a text file, one placement per line:
+//!
+//! ```text
+//! spawn_point 1.0 0.0 -4.5
+//! item 0 3.0 0.2 7.0
+//! jump_pad 10.0 0.0 2.0 10.0 6.0 12.0
+//! teleporter -3.0 0.0 8.0 25.0 4.0 -6.0
+//! checkpoint 12.0 0.0 -3.0
+//! ```
+//!
+//! Item is given by id of its kind. Jump pads and teleporters have target position after the
+//! position: landing point of a jump pad and destination of a teleporter. Spawn point can be
+//! followed by its team and by match modes it is used in, see `spawn` module:
+//!
+//! ```text
+//! spawn_point 1.0 0.0 -4.5 red modes=tdm,ctf
+//! ```
+//! Layer of user map is
+//! `layout.txt` in folder of the map, so it is shipped with map package; layers of built-in
+//! maps are kept in `layouts` folder of user data directory.
+//!
+//! If a map has a layer, item spawns and player spawns of the layer replace markers of scene.
+//! Jump pads, teleporters and checkpoints of the layer are added to ones of scene.
+
+use crate::{
+    character::Team,
+    item::ItemKind,
+    logger::{self, Category},
+    paths,
+    spawn::SpawnModes,
+};
+use rg3d::{core::algebra::Vector3, scene::graph::Graph};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+pub const LAYOUT_FILE: &str = "layout.txt";
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PlacementKind {
+    SpawnPoint,
+    Item(ItemKind),
+    JumpPad,
+    Teleporter,
+    Checkpoint,
+}
+
+impl PlacementKind {
+    /// Whether placement has target position besides its own position.
+    pub fn has_target(self) -> bool {
+        matches!(self, PlacementKind::JumpPad | PlacementKind::Teleporter)
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            PlacementKind::SpawnPoint => "Spawn Point".to_owned(),
+            PlacementKind::Item(kind) => format!("{:?}", kind),
+            PlacementKind::JumpPad => "Jump Pad".to_owned(),
+            PlacementKind::Teleporter => "Teleporter".to_owned(),
+            PlacementKind::Checkpoint => "Checkpoint".to_owned(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Placement {
+    pub kind: PlacementKind,
+    pub position: Vector3<f32>,
+    /// Unused by placements without target.
+    pub target: Vector3<f32>,
+    /// Tags of spawn point, unused by other placements.
+    pub team: Team,
+    pub modes: SpawnModes,
+}
+
+impl Placement {
+    pub fn new(kind: PlacementKind, position: Vector3<f32>) -> Self {
+        Self {
+            kind,
+            position,
+            target: Default::default(),
+            team: Team::None,
+            modes: SpawnModes::ALL,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MapLayout {
+    pub placements: Vec<Placement>,
+}
+
+/// Returns path of placement layer of a map, the file may not exist.
+pub fn path(map: &Path) -> PathBuf {
+    let maps_dir = paths::maps_dir();
+    match map.parent() {
+        Some(dir) if map.starts_with(&maps_dir) => dir.join(LAYOUT_FILE),
+        _ => {
+            let dir = paths::data_dir().join("layouts");
+            let _ = std::fs::create_dir_all(&dir);
+            let stem = map.file_stem().map_or_else(
+                || "map".to_owned(),
+                |stem| stem.to_string_lossy().into_owned(),
+            );
+            dir.join(format!("{}.txt", stem))
+        }
+    }
+}
+
+fn parse_vector(values: &[f32]) -> Vector3<f32> {
+    Vector3::new(values[0], values[1], values[2])
+}
+
+fn parse_line(line: &str) -> Result<Placement, String> {
+    let mut tokens = line.split_whitespace();
+    let kind = match tokens.next() {
+        Some("spawn_point") => PlacementKind::SpawnPoint,
+        Some("jump_pad") => PlacementKind::JumpPad,
+        Some("teleporter") => PlacementKind::Teleporter,
+        Some("checkpoint") => PlacementKind::Checkpoint,
+        Some("item") => {
+            let id = tokens
+                .next()
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| "item has no kind".to_owned())?;
+            PlacementKind::Item(ItemKind::from_id(id)?)
+        }
+        Some(other) => return Err(format!("unknown placement \"{}\"", other)),
+        None => return Err("empty line".to_owned()),
+    };
+
+    let tokens = tokens.collect::<Vec<_>>();
+    let expected = if kind.has_target() { 6 } else { 3 };
+    if tokens.len() < expected {
+        return Err(format!(
+            "expected {} coordinates, got {}",
+            expected,
+            tokens.len()
+        ));
+    }
+    let values = tokens[..expected]
+        .iter()
+        .map(|token| token.parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut placement = Placement::new(kind, parse_vector(&values[0..3]));
+    if kind.has_target() {
+        placement.target = parse_vector(&values[3..6]);
+    }
+    for &tag in &tokens[expected..] {
+        match (kind, tag) {
+            (PlacementKind::SpawnPoint, "red") => placement.team = Team::Red,
+            (PlacementKind::SpawnPoint, "blue") => placement.team = Team::Blue,
+            (PlacementKind::SpawnPoint, _) if tag.starts_with("modes=") => {
+                placement.modes = SpawnModes::parse(&tag["modes=".len()..])?
+            }
+            _ => return Err(format!("unexpected \"{}\"", tag)),
+        }
+    }
+    Ok(placement)
+}
+
+impl MapLayout {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut placements = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            placements.push(parse_line(line).map_err(|e| format!("line {}: {}", number + 1, e))?);
+        }
+        Ok(Self { placements })
+    }
+
+    /// Loads placement layer of a map, None if map has no layer or it is malformed.
+    pub fn load(map: &Path) -> Option<Self> {
+        let path = path(map);
+        let text = std::fs::read_to_string(&path).ok()?;
+        match Self::parse(&text) {
+            Ok(layout) => Some(layout),
+            Err(e) => {
+                logger::warning(
+                    Category::Level,
+                    format!(
+                        "Placement layer {} is ignored, it is malformed: {}.",
+                        path.display(),
+                        e
+                    ),
+                );
+                None
+            }
+        }
+    }
+
+    /// Collects item and player spawns from markers of map scene, layer of a map that has none
+    /// starts from them.
+    pub fn from_scene(graph: &Graph) -> Self {
+        let mut placements = Vec::new();
+        for node in graph.linear_iter() {
+            let name = node.name();
+            let mut team = Team::None;
+            let kind = if name.starts_with("SpawnPoint") {
+                if name.ends_with("_Red") {
+                    team = Team::Red;
+                } else if name.ends_with("_Blue") {
+                    team = Team::Blue;
+                }
+                PlacementKind::SpawnPoint
+            } else if name.starts_with("Medkit") {
+                PlacementKind::Item(ItemKind::Medkit)
+            } else if name.starts_with("Ammo_Ak47") {
+                PlacementKind::Item(ItemKind::Ak47Ammo)
+            } else if name.starts_with("Ammo_M4") {
+                PlacementKind::Item(ItemKind::M4Ammo)
+            } else if name.starts_with("Ammo_Plasma") {
+                PlacementKind::Item(ItemKind::Plasma)
+            } else if name.starts_with("Shield") {
+                PlacementKind::Item(ItemKind::Shield)
+            } else {
+                continue;
+            };
+            let mut placement = Placement::new(kind, node.global_position());
+            placement.team = team;
+            placements.push(placement);
+        }
+        Self { placements }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = "# Placement layer written by level editor.\n".to_owned();
+        for placement in self.placements.iter() {
+            let p = placement.position;
+            let t = placement.target;
+            match placement.kind {
+                PlacementKind::SpawnPoint => {
+                    let mut line = format!("spawn_point {} {} {}", p.x, p.y, p.z);
+                    match placement.team {
+                        Team::Red => line += " red",
+                        Team::Blue => line += " blue",
+                        Team::None => (),
+                    }
+                    if placement.modes != SpawnModes::ALL {
+                        line += &format!(" modes={}", placement.modes.names());
+                    }
+                    writeln!(text, "{}", line)
+                }
+                PlacementKind::Item(kind) => {
+                    writeln!(text, "item {} {} {} {}", kind.id(), p.x, p.y, p.z)
+                }
+                PlacementKind::JumpPad => writeln!(
+                    text,
+                    "jump_pad {} {} {} {} {} {}",
+                    p.x, p.y, p.z, t.x, t.y, t.z
+                ),
+                PlacementKind::Teleporter => writeln!(
+                    text,
+                    "teleporter {} {} {} {} {} {}",
+                    p.x, p.y, p.z, t.x, t.y, t.z
+                ),
+                PlacementKind::Checkpoint => {
+                    writeln!(text, "checkpoint {} {} {}", p.x, p.y, p.z)
+                }
+            }
+            .unwrap();
+        }
+        text
+    }
+
+    pub fn save(&self, map: &Path) -> Result<(), String> {
+        let path = path(map);
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, self.to_text())
+            .and_then(|_| std::fs::rename(&temp_path, &path))
+            .map_err(|e| format!("Unable to write {}: {}", path.display(), e))?;
+        logger::info(
+            Category::Level,
+            format!(
+                "Placement layer of {} saved to {}.",
+                map.display(),
+                path.display()
+            ),
+        );
+        Ok(())
+    }
+}
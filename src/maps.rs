@@ -0,0 +1,210 @@
+//! Registry of maps that can be played. Matches started from menu use default map, next
+//! maps are voted for during intermission. Besides built-in maps there are user maps which
+//! are installed into `maps` folder of user data directory, see `map_install` module.
+
+use crate::{
+    logger::{self, Category},
+    music::Playlist,
+    paths,
+};
+use rg3d::{core::rand::seq::SliceRandom, rand};
+use std::{
+    path::{Component, Path},
+    sync::Mutex,
+};
+
+/// Descriptor of user map in root of its folder.
+pub const DESCRIPTOR_FILE: &str = "map.txt";
+
+const USER_MAP_MUSIC: &[&str] = &["data/sounds/Antonio_Bizarro_Berzerker.ogg"];
+
+pub struct MapDefinition {
+    pub name: &'static str,
+    pub path: &'static str,
+    /// Image shown on loading screen while the map is loaded.
+    pub preview: Option<&'static str>,
+    pub music: Playlist,
+}
+
+pub static MAPS: [MapDefinition; 2] = [
+    MapDefinition {
+        name: "DM6",
+        path: "data/models/dm6.fbx",
+        preview: None,
+        music: Playlist {
+            ambient: &[],
+            combat: &["data/sounds/Antonio_Bizarro_Berzerker.ogg"],
+        },
+    },
+    MapDefinition {
+        name: "Outpost",
+        path: "data/models/map.FBX",
+        preview: None,
+        music: Playlist {
+            ambient: &[],
+            combat: &["data/sounds/Antonio_Bizarro_Berzerker.ogg"],
+        },
+    },
+];
+
+// User maps live until exit, so their definitions are leaked to have the same lifetime as
+// built-in ones.
+static USER_MAPS: Mutex<Vec<&'static MapDefinition>> = Mutex::new(Vec::new());
+
+pub fn default_map() -> &'static MapDefinition {
+    &MAPS[0]
+}
+
+/// Returns built-in maps followed by user maps.
+pub fn all() -> Vec<&'static MapDefinition> {
+    MAPS.iter()
+        .chain(USER_MAPS.lock().unwrap().iter().copied())
+        .collect()
+}
+
+pub fn find(path: &Path) -> Option<&'static MapDefinition> {
+    all().into_iter().find(|map| Path::new(map.path) == path)
+}
+
+/// Picks up to `count` distinct random maps.
+pub fn random_maps(count: usize) -> Vec<&'static MapDefinition> {
+    all()
+        .choose_multiple(&mut rand::thread_rng(), count)
+        .copied()
+        .collect()
+}
+
+/// Contents of `map.txt`, file paths are relative to folder of the map:
+///
+/// ```text
+/// name = Canyon
+/// scene = canyon.fbx
+/// navmesh = canyon.navmesh
+/// preview = preview.png
+/// ```
+///
+/// Navmesh and preview are optional. Folder can also have placement layer made by level editor,
+/// see `map_layout` module.
+pub struct MapDescriptor {
+    pub name: String,
+    pub scene: String,
+    pub navmesh: Option<String>,
+    pub preview: Option<String>,
+}
+
+/// Reads descriptor of a map folder and checks that files it refers to are in the folder.
+pub fn read_descriptor(dir: &Path) -> Result<MapDescriptor, String> {
+    let text = std::fs::read_to_string(dir.join(DESCRIPTOR_FILE))
+        .map_err(|e| format!("{} is missing or unreadable ({})", DESCRIPTOR_FILE, e))?;
+
+    let mut name = None;
+    let mut scene = None;
+    let mut navmesh = None;
+    let mut preview = None;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            format!(
+                "{} line {}: expected \"key = value\"",
+                DESCRIPTOR_FILE,
+                number + 1
+            )
+        })?;
+        let value = Some(value.trim().to_owned()).filter(|value| !value.is_empty());
+        match key.trim() {
+            "name" => name = value,
+            "scene" => scene = value,
+            "navmesh" => navmesh = value,
+            "preview" => preview = value,
+            key => {
+                return Err(format!(
+                    "{} line {}: unknown key \"{}\"",
+                    DESCRIPTOR_FILE,
+                    number + 1,
+                    key
+                ))
+            }
+        }
+    }
+
+    let descriptor = MapDescriptor {
+        name: name.ok_or_else(|| format!("{} has no name", DESCRIPTOR_FILE))?,
+        scene: scene.ok_or_else(|| format!("{} has no scene", DESCRIPTOR_FILE))?,
+        navmesh,
+        preview,
+    };
+
+    for file in std::iter::once(&descriptor.scene)
+        .chain(descriptor.navmesh.iter())
+        .chain(descriptor.preview.iter())
+    {
+        let path = Path::new(file);
+        if path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+            return Err(format!("{} must be inside map folder", file));
+        }
+        if !dir.join(path).is_file() {
+            return Err(format!(
+                "{} refers to missing file {}",
+                DESCRIPTOR_FILE, file
+            ));
+        }
+    }
+
+    Ok(descriptor)
+}
+
+fn leak(text: String) -> &'static str {
+    Box::leak(text.into_boxed_str())
+}
+
+/// Adds map of a folder to the registry, map with the same name is replaced.
+pub fn register_user_map(dir: &Path) -> Result<&'static MapDefinition, String> {
+    let descriptor = read_descriptor(dir)?;
+    let definition: &'static MapDefinition = Box::leak(Box::new(MapDefinition {
+        name: leak(descriptor.name),
+        path: leak(dir.join(descriptor.scene).to_string_lossy().into_owned()),
+        preview: descriptor
+            .preview
+            .map(|preview| leak(dir.join(preview).to_string_lossy().into_owned())),
+        music: Playlist {
+            ambient: &[],
+            combat: USER_MAP_MUSIC,
+        },
+    }));
+
+    let mut user_maps = USER_MAPS.lock().unwrap();
+    user_maps.retain(|map| map.name != definition.name);
+    user_maps.push(definition);
+    Ok(definition)
+}
+
+/// Registers every installed user map, must be called once on start.
+pub fn load_user_maps() {
+    let dirs = std::fs::read_dir(paths::maps_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for dir in dirs {
+        // Unfinished installation.
+        if dir
+            .file_name()
+            .map_or(true, |name| name.to_string_lossy().starts_with('.'))
+        {
+            continue;
+        }
+        match register_user_map(&dir) {
+            Ok(map) => logger::info(Category::Level, format!("Found user map {}.", map.name)),
+            Err(e) => logger::warning(
+                Category::Level,
+                format!("User map {} is skipped: {}.", dir.display(), e),
+            ),
+        }
+    }
+}
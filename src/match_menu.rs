@@ -1,7 +1,11 @@
 use crate::{
-    gui::{create_scroll_bar, ScrollBarData},
+    gui::{create_check_box, create_scroll_bar, ScrollBarData},
+    localization::tr,
+    maps,
     message::Message,
-    DeathMatch, MatchOptions,
+    settings::Settings,
+    DeathMatch, Difficulty, HealthDensity, ItemEconomy, MatchOptions, Mutators, Overtime, Survival,
+    MAX_AMMO_SCALE, MAX_TIME_SCALE, MIN_AMMO_SCALE, MIN_TIME_SCALE,
 };
 use rg3d::{
     core::pool::Handle,
@@ -9,79 +13,153 @@ use rg3d::{
     gui::{
         border::BorderBuilder,
         button::{ButtonBuilder, ButtonMessage},
+        check_box::CheckBoxMessage,
         decorator::DecoratorBuilder,
-        dropdown_list::DropdownListBuilder,
+        dropdown_list::{DropdownList, DropdownListBuilder, DropdownListMessage},
         grid::{Column, GridBuilder, Row},
-        message::UiMessage,
-        scroll_bar::ScrollBar,
+        message::{MessageDirection, UiMessage},
+        scroll_bar::{ScrollBar, ScrollBarMessage},
         text::TextBuilder,
-        text_box::TextBoxBuilder,
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowTitle},
         HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
 };
-use std::sync::mpsc::Sender;
+use std::{
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc, RwLock},
+};
+
+const OVERTIME_CHOICES: [(&str, Overtime); 4] = [
+    ("None", Overtime::None),
+    ("Sudden Death", Overtime::SuddenDeath),
+    ("+2 Minutes", Overtime::ExtraTime { secs: 120.0 }),
+    ("+5 Minutes", Overtime::ExtraTime { secs: 300.0 }),
+];
+
+const MUTATOR_CHOICES: [(&str, Mutators); 7] = [
+    ("Low Gravity", Mutators::LOW_GRAVITY),
+    ("Instagib", Mutators::INSTAGIB),
+    ("Vampire", Mutators::VAMPIRE),
+    ("Big Heads", Mutators::BIG_HEADS),
+    ("Practice", Mutators::PRACTICE),
+    ("Regeneration", Mutators::REGENERATION),
+    ("Heavy Flag", Mutators::HEAVY_FLAG),
+];
+
+// Mutators are listed one per row right after item economy options.
+const FIRST_MUTATOR_ROW: usize = 9;
+
+// Index of survival in match type selector.
+const SURVIVAL_INDEX: usize = 3;
 
 pub struct MatchMenu {
     sender: Sender<Message>,
     pub window: Handle<UiNode>,
+    dd_match_type: Handle<UiNode>,
     sb_frag_limit: Handle<UiNode>,
     sb_time_limit: Handle<UiNode>,
+    sb_time_scale: Handle<UiNode>,
+    dd_overtime: Handle<UiNode>,
+    dd_difficulty: Handle<UiNode>,
+    sb_ammo_scale: Handle<UiNode>,
+    dd_health_density: Handle<UiNode>,
+    cb_weapon_stay: Handle<UiNode>,
+    weapon_stay: bool,
+    cb_mutators: Vec<Handle<UiNode>>,
+    mutators: Mutators,
     start_button: Handle<UiNode>,
+    settings: Arc<RwLock<Settings>>,
 }
 
 impl MatchMenu {
-    pub fn new(ui: &mut UserInterface, sender: Sender<Message>) -> Self {
+    pub fn new(
+        ui: &mut UserInterface,
+        settings: Arc<RwLock<Settings>>,
+        sender: Sender<Message>,
+    ) -> Self {
         let common_row = Row::strict(36.0);
+        let difficulty = settings.read().unwrap().difficulty;
+        let economy = ItemEconomy::for_difficulty(difficulty);
 
         let ctx = &mut ui.build_ctx();
+
+        let mut cb_mutators = Vec::new();
+        let mut mutator_rows = Vec::new();
+        for (i, (name, _)) in MUTATOR_CHOICES.iter().enumerate() {
+            let row = FIRST_MUTATOR_ROW + i;
+            mutator_rows.push(
+                TextBuilder::new(WidgetBuilder::new().on_row(row).on_column(0))
+                    .with_text(tr(name))
+                    .build(ctx),
+            );
+            let check_box = create_check_box(ctx, row, 1, false);
+            mutator_rows.push(check_box);
+            cb_mutators.push(check_box);
+        }
+
+        let dd_match_type;
         let sb_frag_limit;
         let sb_time_limit;
+        let sb_time_scale;
+        let dd_overtime;
+        let dd_difficulty;
+        let sb_ammo_scale;
+        let dd_health_density;
+        let cb_weapon_stay;
         let start_button;
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(500.0))
-            .with_title(WindowTitle::text("Match Options"))
+            .with_title(WindowTitle::text(tr("Match Options")))
             .open(false)
             .with_content(
                 GridBuilder::new(
                     WidgetBuilder::new()
                         .with_child(
                             TextBuilder::new(WidgetBuilder::new().on_row(0).on_column(0))
-                                .with_text("Match Type")
-                                .build(ctx),
-                        )
-                        .with_child(
-                            DropdownListBuilder::new(WidgetBuilder::new().on_column(1).on_row(0))
-                                .with_items({
-                                    let mut items = Vec::new();
-                                    for mode in
-                                        ["Deathmatch", "Team Deathmatch", "Capture The Flag"].iter()
-                                    {
-                                        let item = DecoratorBuilder::new(BorderBuilder::new(
-                                            WidgetBuilder::new().with_height(30.0).with_child(
-                                                TextBuilder::new(
-                                                    WidgetBuilder::new()
-                                                        .with_horizontal_alignment(
-                                                            HorizontalAlignment::Center,
-                                                        )
-                                                        .with_vertical_alignment(
-                                                            VerticalAlignment::Center,
-                                                        ),
-                                                )
-                                                .with_text(mode)
-                                                .build(ctx),
-                                            ),
-                                        ))
-                                        .build(ctx);
-                                        items.push(item);
-                                    }
-                                    items
-                                })
+                                .with_text(tr("Match Type"))
                                 .build(ctx),
                         )
+                        .with_child({
+                            dd_match_type = DropdownListBuilder::new(
+                                WidgetBuilder::new().on_column(1).on_row(0),
+                            )
+                            .with_items({
+                                let mut items = Vec::new();
+                                for mode in [
+                                    "Deathmatch",
+                                    "Team Deathmatch",
+                                    "Capture The Flag",
+                                    "Survival",
+                                ]
+                                .iter()
+                                {
+                                    let item = DecoratorBuilder::new(BorderBuilder::new(
+                                        WidgetBuilder::new().with_height(30.0).with_child(
+                                            TextBuilder::new(
+                                                WidgetBuilder::new()
+                                                    .with_horizontal_alignment(
+                                                        HorizontalAlignment::Center,
+                                                    )
+                                                    .with_vertical_alignment(
+                                                        VerticalAlignment::Center,
+                                                    ),
+                                            )
+                                            .with_text(tr(mode))
+                                            .build(ctx),
+                                        ),
+                                    ))
+                                    .build(ctx);
+                                    items.push(item);
+                                }
+                                items
+                            })
+                            .with_selected(0)
+                            .build(ctx);
+                            dd_match_type
+                        })
                         .with_child(
                             TextBuilder::new(WidgetBuilder::new().on_row(1).on_column(0))
-                                .with_text("Time Limit (min)")
+                                .with_text(tr("Time Limit (min)"))
                                 .build(ctx),
                         )
                         .with_child({
@@ -103,7 +181,7 @@ impl MatchMenu {
                         })
                         .with_child(
                             TextBuilder::new(WidgetBuilder::new().on_row(2).on_column(0))
-                                .with_text("Frag Limit")
+                                .with_text(tr("Frag Limit"))
                                 .build(ctx),
                         )
                         .with_child({
@@ -124,41 +202,195 @@ impl MatchMenu {
                             sb_frag_limit
                         })
                         .with_child(
-                            TextBuilder::new(
-                                WidgetBuilder::new()
-                                    .on_row(3)
-                                    .on_column(0)
-                                    .with_margin(Thickness::uniform(2.0)),
+                            TextBuilder::new(WidgetBuilder::new().on_row(3).on_column(0))
+                                .with_text(tr("Game Speed"))
+                                .build(ctx),
+                        )
+                        .with_child({
+                            sb_time_scale = create_scroll_bar(
+                                ctx,
+                                ScrollBarData {
+                                    min: MIN_TIME_SCALE,
+                                    max: MAX_TIME_SCALE,
+                                    value: 1.0,
+                                    step: 0.1,
+                                    row: 3,
+                                    column: 1,
+                                    margin: Thickness::uniform(2.0),
+                                    show_value: true,
+                                    orientation: Orientation::Horizontal,
+                                },
+                            );
+                            sb_time_scale
+                        })
+                        .with_child(
+                            TextBuilder::new(WidgetBuilder::new().on_row(4).on_column(0))
+                                .with_text(tr("Overtime"))
+                                .build(ctx),
+                        )
+                        .with_child({
+                            dd_overtime = DropdownListBuilder::new(
+                                WidgetBuilder::new().on_column(1).on_row(4),
+                            )
+                            .with_items(
+                                OVERTIME_CHOICES
+                                    .iter()
+                                    .map(|(name, _)| {
+                                        DecoratorBuilder::new(BorderBuilder::new(
+                                            WidgetBuilder::new().with_height(30.0).with_child(
+                                                TextBuilder::new(
+                                                    WidgetBuilder::new()
+                                                        .with_horizontal_alignment(
+                                                            HorizontalAlignment::Center,
+                                                        )
+                                                        .with_vertical_alignment(
+                                                            VerticalAlignment::Center,
+                                                        ),
+                                                )
+                                                .with_text(tr(name))
+                                                .build(ctx),
+                                            ),
+                                        ))
+                                        .build(ctx)
+                                    })
+                                    .collect(),
+                            )
+                            .with_selected(1)
+                            .build(ctx);
+                            dd_overtime
+                        })
+                        .with_child(
+                            TextBuilder::new(WidgetBuilder::new().on_row(5).on_column(0))
+                                .with_text(tr("Difficulty"))
+                                .build(ctx),
+                        )
+                        .with_child({
+                            dd_difficulty = DropdownListBuilder::new(
+                                WidgetBuilder::new().on_column(1).on_row(5),
+                            )
+                            .with_items(
+                                Difficulty::ALL
+                                    .iter()
+                                    .map(|difficulty| {
+                                        DecoratorBuilder::new(BorderBuilder::new(
+                                            WidgetBuilder::new().with_height(30.0).with_child(
+                                                TextBuilder::new(
+                                                    WidgetBuilder::new()
+                                                        .with_horizontal_alignment(
+                                                            HorizontalAlignment::Center,
+                                                        )
+                                                        .with_vertical_alignment(
+                                                            VerticalAlignment::Center,
+                                                        ),
+                                                )
+                                                .with_text(tr(difficulty.name()))
+                                                .build(ctx),
+                                            ),
+                                        ))
+                                        .build(ctx)
+                                    })
+                                    .collect(),
                             )
-                            .with_text("Player Name")
-                            .with_vertical_text_alignment(VerticalAlignment::Center)
-                            .build(ctx),
+                            .with_selected(
+                                Difficulty::ALL
+                                    .iter()
+                                    .position(|d| *d == difficulty)
+                                    .unwrap_or_default(),
+                            )
+                            .build(ctx);
+                            dd_difficulty
+                        })
+                        .with_child(
+                            TextBuilder::new(WidgetBuilder::new().on_row(6).on_column(0))
+                                .with_text(tr("Ammo"))
+                                .build(ctx),
                         )
+                        .with_child({
+                            sb_ammo_scale = create_scroll_bar(
+                                ctx,
+                                ScrollBarData {
+                                    min: MIN_AMMO_SCALE,
+                                    max: MAX_AMMO_SCALE,
+                                    value: economy.ammo_scale,
+                                    step: 0.25,
+                                    row: 6,
+                                    column: 1,
+                                    margin: Thickness::uniform(2.0),
+                                    show_value: true,
+                                    orientation: Orientation::Horizontal,
+                                },
+                            );
+                            sb_ammo_scale
+                        })
                         .with_child(
-                            TextBoxBuilder::new(
-                                WidgetBuilder::new()
-                                    .on_row(3)
-                                    .on_column(1)
-                                    .with_margin(Thickness::uniform(2.0)),
+                            TextBuilder::new(WidgetBuilder::new().on_row(7).on_column(0))
+                                .with_text(tr("Health Packs"))
+                                .build(ctx),
+                        )
+                        .with_child({
+                            dd_health_density = DropdownListBuilder::new(
+                                WidgetBuilder::new().on_column(1).on_row(7),
+                            )
+                            .with_items(
+                                HealthDensity::ALL
+                                    .iter()
+                                    .map(|density| {
+                                        DecoratorBuilder::new(BorderBuilder::new(
+                                            WidgetBuilder::new().with_height(30.0).with_child(
+                                                TextBuilder::new(
+                                                    WidgetBuilder::new()
+                                                        .with_horizontal_alignment(
+                                                            HorizontalAlignment::Center,
+                                                        )
+                                                        .with_vertical_alignment(
+                                                            VerticalAlignment::Center,
+                                                        ),
+                                                )
+                                                .with_text(tr(density.name()))
+                                                .build(ctx),
+                                            ),
+                                        ))
+                                        .build(ctx)
+                                    })
+                                    .collect(),
                             )
-                            .with_text("Unnamed Player".to_owned())
-                            .build(ctx),
+                            .with_selected(
+                                HealthDensity::ALL
+                                    .iter()
+                                    .position(|d| *d == economy.health_density)
+                                    .unwrap_or_default(),
+                            )
+                            .build(ctx);
+                            dd_health_density
+                        })
+                        .with_child(
+                            TextBuilder::new(WidgetBuilder::new().on_row(8).on_column(0))
+                                .with_text(tr("Weapon Stay"))
+                                .build(ctx),
                         )
                         .with_child({
-                            start_button =
-                                ButtonBuilder::new(WidgetBuilder::new().on_row(4).on_column(1))
-                                    .with_text("Start")
-                                    .build(ctx);
+                            cb_weapon_stay = create_check_box(ctx, 8, 1, economy.weapon_stay);
+                            cb_weapon_stay
+                        })
+                        .with_children(mutator_rows)
+                        .with_child({
+                            start_button = ButtonBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(FIRST_MUTATOR_ROW + MUTATOR_CHOICES.len())
+                                    .on_column(1),
+                            )
+                            .with_text(&tr("Start"))
+                            .build(ctx);
                             start_button
                         }),
                 )
                 .add_column(Column::strict(200.0))
                 .add_column(Column::stretch())
-                .add_row(common_row)
-                .add_row(common_row)
-                .add_row(common_row)
-                .add_row(common_row)
-                .add_row(common_row)
+                .add_rows(
+                    (0..FIRST_MUTATOR_ROW + MUTATOR_CHOICES.len() + 1)
+                        .map(|_| common_row)
+                        .collect(),
+                )
                 .add_row(Row::stretch())
                 .build(ctx),
             )
@@ -166,9 +398,20 @@ impl MatchMenu {
         Self {
             sender,
             window,
+            dd_match_type,
             sb_frag_limit,
             sb_time_limit,
+            sb_time_scale,
+            dd_overtime,
+            dd_difficulty,
+            sb_ammo_scale,
+            dd_health_density,
+            cb_weapon_stay,
+            weapon_stay: economy.weapon_stay,
+            cb_mutators,
+            mutators: Mutators::NONE,
             start_button,
+            settings,
         }
     }
 
@@ -191,12 +434,118 @@ impl MatchMenu {
                         0.0
                     };
 
-                let options = MatchOptions::DeathMatch(DeathMatch {
-                    time_limit_secs: time_limit_minutes * 60.0,
-                    frag_limit: frag_limit as u32,
-                });
+                let time_scale =
+                    if let Some(scroll_bar) = ui.node(self.sb_time_scale).cast::<ScrollBar>() {
+                        scroll_bar.value()
+                    } else {
+                        1.0
+                    };
 
-                self.sender.send(Message::StartNewGame { options }).unwrap();
+                let overtime = ui
+                    .node(self.dd_overtime)
+                    .cast::<DropdownList>()
+                    .and_then(|dropdown| dropdown.selection())
+                    .map_or_else(Default::default, |index| OVERTIME_CHOICES[index].1);
+
+                let difficulty = ui
+                    .node(self.dd_difficulty)
+                    .cast::<DropdownList>()
+                    .and_then(|dropdown| dropdown.selection())
+                    .map_or_else(Default::default, |index| Difficulty::ALL[index]);
+                {
+                    let mut settings = self.settings.write().unwrap();
+                    if settings.difficulty != difficulty {
+                        settings.difficulty = difficulty;
+                        settings.save();
+                    }
+                }
+
+                let ammo_scale =
+                    if let Some(scroll_bar) = ui.node(self.sb_ammo_scale).cast::<ScrollBar>() {
+                        scroll_bar.value()
+                    } else {
+                        1.0
+                    };
+
+                let health_density = ui
+                    .node(self.dd_health_density)
+                    .cast::<DropdownList>()
+                    .and_then(|dropdown| dropdown.selection())
+                    .map_or_else(Default::default, |index| HealthDensity::ALL[index]);
+
+                let economy = ItemEconomy {
+                    ammo_scale,
+                    health_density,
+                    weapon_stay: self.weapon_stay,
+                };
+
+                let match_type = ui
+                    .node(self.dd_match_type)
+                    .cast::<DropdownList>()
+                    .and_then(|dropdown| dropdown.selection());
+
+                // Team modes are not playable yet, they start a deathmatch.
+                let options = if match_type == Some(SURVIVAL_INDEX) {
+                    MatchOptions::Survival(Survival {
+                        time_scale,
+                        mutators: self.mutators,
+                        difficulty,
+                        economy,
+                    })
+                } else {
+                    MatchOptions::DeathMatch(DeathMatch {
+                        time_limit_secs: time_limit_minutes * 60.0,
+                        frag_limit: frag_limit as u32,
+                        time_scale,
+                        overtime,
+                        mutators: self.mutators,
+                        difficulty,
+                        economy,
+                    })
+                };
+
+                self.sender
+                    .send(Message::StartNewGame {
+                        options,
+                        map: PathBuf::from(maps::default_map().path),
+                    })
+                    .unwrap();
+            }
+        } else if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
+            // Item economy follows difficulty, it can be adjusted afterwards.
+            if message.destination() == self.dd_difficulty
+                && message.direction() == MessageDirection::FromWidget
+            {
+                let economy = ItemEconomy::for_difficulty(Difficulty::ALL[*index]);
+                ui.send_message(ScrollBarMessage::value(
+                    self.sb_ammo_scale,
+                    MessageDirection::ToWidget,
+                    economy.ammo_scale,
+                ));
+                ui.send_message(DropdownListMessage::selection(
+                    self.dd_health_density,
+                    MessageDirection::ToWidget,
+                    HealthDensity::ALL
+                        .iter()
+                        .position(|d| *d == economy.health_density),
+                ));
+                ui.send_message(CheckBoxMessage::checked(
+                    self.cb_weapon_stay,
+                    MessageDirection::ToWidget,
+                    Some(economy.weapon_stay),
+                ));
+                self.weapon_stay = economy.weapon_stay;
+            }
+        } else if let Some(CheckBoxMessage::Check(value)) = message.data() {
+            if message.destination() == self.cb_weapon_stay {
+                self.weapon_stay = value.unwrap_or(false);
+            } else if let Some(index) = self
+                .cb_mutators
+                .iter()
+                .position(|check_box| *check_box == message.destination())
+            {
+                self.mutators
+                    .set(MUTATOR_CHOICES[index].1, value.unwrap_or(false));
             }
         }
     }
@@ -0,0 +1,335 @@
+//! Match recorder keeps log of everything significant that happened during a match and
+//! is able to export it as newline-delimited JSON (one event per line) so external tools
+//! can build ladders, heatmaps and so on. The same events are exported as JSON timeline as
+//! well - single object `{"v": 2, "events": [...]}` with events in order of time, for tools
+//! that prefer to read whole document at once.
+//!
+//! Schema is stable, every change in it must bump `SCHEMA_VERSION`. Each line is a JSON
+//! object with following common fields:
+//!
+//! - `v` - schema version, number.
+//! - `t` - match time in seconds when event happened, number.
+//! - `type` - type of event, string, one of described below.
+//!
+//! Positions are arrays of three numbers `[x, y, z]`, actors are identified by their
+//! unique names. Event types and their specific fields:
+//!
+//! - `match_start` - `mode` (`"dm"`, `"tdm"`, `"ctf"`), `map` (string).
+//! - `spawn` - `actor` (string), `position`.
+//! - `damage` - `victim` (string), `attacker` (string or `null` for environment),
+//!   `amount` (number), `position` (position of victim).
+//! - `kill` - `victim` (string), `killer` (string or `null`), `position`.
+//! - `pickup` - `actor` (string), `item` (string), `position`.
+//! - `flag_take` - `team` (`"red"` or `"blue"`, owner of the flag), `actor` (string), `position`.
+//! - `flag_return` - `team`, `actor` (string or `null` if flag returned by timeout), `position`.
+//! - `flag_capture` - `team` (owner of captured flag), `actor` (string), `position`.
+//! - `match_end` - `scores` - array of objects with `name`, `kills`, `deaths` fields.
+
+use crate::{character::Team, leader_board::LeaderBoard, MatchOptions};
+use rg3d::core::algebra::Vector3;
+use std::{
+    fmt::Write as FmtWrite,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Clone, Debug)]
+pub enum MatchEvent {
+    MatchStarted {
+        mode: &'static str,
+        map: String,
+    },
+    Spawned {
+        actor: String,
+        position: Vector3<f32>,
+    },
+    Damaged {
+        victim: String,
+        attacker: Option<String>,
+        amount: f32,
+        position: Vector3<f32>,
+    },
+    Killed {
+        victim: String,
+        killer: Option<String>,
+        position: Vector3<f32>,
+    },
+    PickedUp {
+        actor: String,
+        item: String,
+        position: Vector3<f32>,
+    },
+    FlagTaken {
+        team: Team,
+        actor: String,
+        position: Vector3<f32>,
+    },
+    FlagReturned {
+        team: Team,
+        actor: Option<String>,
+        position: Vector3<f32>,
+    },
+    FlagCaptured {
+        team: Team,
+        actor: String,
+        position: Vector3<f32>,
+    },
+    MatchEnded {
+        scores: Vec<(String, u32, u32)>,
+    },
+}
+
+pub struct MatchRecord {
+    pub time: f32,
+    pub event: MatchEvent,
+}
+
+#[derive(Default)]
+pub struct MatchRecorder {
+    records: Vec<MatchRecord>,
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_optional_string(out: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => write_string(out, s),
+        None => out.push_str("null"),
+    }
+}
+
+// JSON has no NaN or infinity, such values are written as null.
+fn write_number(out: &mut String, value: f32) {
+    if value.is_finite() {
+        write!(out, "{}", value).unwrap();
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn write_position(out: &mut String, position: &Vector3<f32>) {
+    out.push('[');
+    write_number(out, position.x);
+    out.push(',');
+    write_number(out, position.y);
+    out.push(',');
+    write_number(out, position.z);
+    out.push(']');
+}
+
+fn team_id(team: Team) -> &'static str {
+    match team {
+        Team::None => "none",
+        Team::Red => "red",
+        Team::Blue => "blue",
+    }
+}
+
+pub fn mode_id(options: &MatchOptions) -> &'static str {
+    match options {
+        MatchOptions::DeathMatch(_) => "dm",
+        MatchOptions::TeamDeathMatch(_) => "tdm",
+        MatchOptions::CaptureTheFlag(_) => "ctf",
+        MatchOptions::Survival(_) => "survival",
+    }
+}
+
+impl MatchRecord {
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write!(out, "{{\"v\":{},\"t\":", SCHEMA_VERSION).unwrap();
+        write_number(&mut out, self.time);
+        out.push_str(",\"type\":");
+        match &self.event {
+            MatchEvent::MatchStarted { mode, map } => {
+                out.push_str("\"match_start\",\"mode\":");
+                write_string(&mut out, mode);
+                out.push_str(",\"map\":");
+                write_string(&mut out, map);
+            }
+            MatchEvent::Spawned { actor, position } => {
+                out.push_str("\"spawn\",\"actor\":");
+                write_string(&mut out, actor);
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::Damaged {
+                victim,
+                attacker,
+                amount,
+                position,
+            } => {
+                out.push_str("\"damage\",\"victim\":");
+                write_string(&mut out, victim);
+                out.push_str(",\"attacker\":");
+                write_optional_string(&mut out, attacker.as_deref());
+                out.push_str(",\"amount\":");
+                write_number(&mut out, *amount);
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::Killed {
+                victim,
+                killer,
+                position,
+            } => {
+                out.push_str("\"kill\",\"victim\":");
+                write_string(&mut out, victim);
+                out.push_str(",\"killer\":");
+                write_optional_string(&mut out, killer.as_deref());
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::PickedUp {
+                actor,
+                item,
+                position,
+            } => {
+                out.push_str("\"pickup\",\"actor\":");
+                write_string(&mut out, actor);
+                out.push_str(",\"item\":");
+                write_string(&mut out, item);
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::FlagTaken {
+                team,
+                actor,
+                position,
+            } => {
+                write!(
+                    out,
+                    "\"flag_take\",\"team\":\"{}\",\"actor\":",
+                    team_id(*team)
+                )
+                .unwrap();
+                write_string(&mut out, actor);
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::FlagReturned {
+                team,
+                actor,
+                position,
+            } => {
+                write!(
+                    out,
+                    "\"flag_return\",\"team\":\"{}\",\"actor\":",
+                    team_id(*team)
+                )
+                .unwrap();
+                write_optional_string(&mut out, actor.as_deref());
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::FlagCaptured {
+                team,
+                actor,
+                position,
+            } => {
+                write!(
+                    out,
+                    "\"flag_capture\",\"team\":\"{}\",\"actor\":",
+                    team_id(*team)
+                )
+                .unwrap();
+                write_string(&mut out, actor);
+                out.push_str(",\"position\":");
+                write_position(&mut out, position);
+            }
+            MatchEvent::MatchEnded { scores } => {
+                out.push_str("\"match_end\",\"scores\":[");
+                for (i, (name, kills, deaths)) in scores.iter().enumerate() {
+                    if i != 0 {
+                        out.push(',');
+                    }
+                    out.push_str("{\"name\":");
+                    write_string(&mut out, name);
+                    write!(out, ",\"kills\":{},\"deaths\":{}}}", kills, deaths).unwrap();
+                }
+                out.push(']');
+            }
+        }
+        out.push('}');
+        out
+    }
+}
+
+impl MatchRecorder {
+    pub fn record(&mut self, time: f32, event: MatchEvent) {
+        self.records.push(MatchRecord { time, event })
+    }
+
+    pub fn records(&self) -> &[MatchRecord] {
+        &self.records
+    }
+
+    pub fn record_match_end(&mut self, time: f32, leader_board: &LeaderBoard) {
+        let mut scores = leader_board
+            .values()
+            .iter()
+            .map(|(name, score)| (name.clone(), score.kills, score.deaths))
+            .collect::<Vec<_>>();
+        // Sort by score to make output deterministic.
+        scores.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        self.record(time, MatchEvent::MatchEnded { scores });
+    }
+
+    pub fn write_ndjson<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for record in self.records.iter() {
+            writeln!(writer, "{}", record.to_json())?;
+        }
+        Ok(())
+    }
+
+    /// Writes every event into single JSON document `{"v": .., "events": [...]}`, see
+    /// module docs.
+    pub fn write_timeline<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{{\"v\":{},\"events\":[", SCHEMA_VERSION)?;
+        for (i, record) in self.records.iter().enumerate() {
+            if i != 0 {
+                write!(writer, ",")?;
+            }
+            write!(writer, "\n{}", record.to_json())?;
+        }
+        writeln!(writer, "\n]}}")
+    }
+
+    /// Writes log and timeline into new files in given directory, returns path to the
+    /// timeline, log has the same name with `ndjson` extension.
+    pub fn export(&self, dir: &Path) -> io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = dir.join(format!("match_{}.ndjson", timestamp));
+        let mut file = io::BufWriter::new(File::create(&path)?);
+        self.write_ndjson(&mut file)?;
+        file.flush()?;
+
+        let path = path.with_extension("json");
+        let mut file = io::BufWriter::new(File::create(&path)?);
+        self.write_timeline(&mut file)?;
+        file.flush()?;
+        Ok(path)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
-    control_scheme::ControlScheme, match_menu::MatchMenu, message::Message,
-    options_menu::OptionsMenu,
+    campaign_menu::CampaignMenu, control_scheme::ControlScheme, gui::UiScale, lan_menu::LanMenu,
+    load_menu::LoadMenu, localization::tr, maps, match_menu::MatchMenu, message::Message,
+    mods_menu::ModsMenu, options_menu::OptionsMenu, profile_menu::ProfileMenu, saves,
+    settings::Settings,
 };
 use rg3d::{
     core::pool::Handle,
@@ -10,156 +12,278 @@ use rg3d::{
         button::{ButtonBuilder, ButtonMessage},
         grid::{Column, GridBuilder, Row},
         message::{MessageDirection, UiMessage},
-        ttf::{Font, SharedFont},
         widget::{WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowMessage, WindowTitle},
-        Thickness, UiNode, UserInterface,
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
 };
-use std::{
-    path::Path,
-    sync::{mpsc::Sender, Arc, Mutex, RwLock},
-};
+use std::sync::{mpsc::Sender, Arc, RwLock};
 
 pub struct Menu {
     sender: Sender<Message>,
     root: Handle<UiNode>,
     btn_new_game: Handle<UiNode>,
+    btn_campaign: Handle<UiNode>,
+    btn_training: Handle<UiNode>,
+    btn_lan_games: Handle<UiNode>,
     btn_save_game: Handle<UiNode>,
     btn_settings: Handle<UiNode>,
+    btn_profile: Handle<UiNode>,
+    btn_mods: Handle<UiNode>,
+    btn_editor: Handle<UiNode>,
     btn_load_game: Handle<UiNode>,
     btn_quit_game: Handle<UiNode>,
     options_menu: OptionsMenu,
     match_menu: MatchMenu,
+    campaign_menu: CampaignMenu,
+    load_menu: LoadMenu,
+    lan_menu: LanMenu,
+    profile_menu: ProfileMenu,
+    mods_menu: ModsMenu,
 }
 
 impl Menu {
     pub fn new(
         engine: &mut Engine,
         control_scheme: Arc<RwLock<ControlScheme>>,
+        settings: Arc<RwLock<Settings>>,
         sender: Sender<Message>,
+        ui_scale: &mut UiScale,
     ) -> Self {
         let frame_size = engine.renderer.get_frame_size();
 
-        let font: Font = rg3d::core::futures::executor::block_on(Font::from_file(
-            Path::new("data/ui/SquaresBold.ttf"),
-            31.0,
-            Font::default_char_set(),
-        ))
-        .unwrap();
-        let font = SharedFont(Arc::new(Mutex::new(font)));
+        let font = ui_scale.font("data/ui/SquaresBold.ttf", 31.0);
 
         let ctx = &mut engine.user_interface.build_ctx();
 
         let btn_new_game;
+        let btn_campaign;
+        let btn_training;
+        let btn_lan_games;
         let btn_settings;
+        let btn_profile;
+        let btn_mods;
+        let btn_editor;
         let btn_save_game;
         let btn_load_game;
         let btn_quit_game;
+        let window;
         let root: Handle<UiNode> = GridBuilder::new(
             WidgetBuilder::new()
                 .with_width(frame_size.0 as f32)
                 .with_height(frame_size.1 as f32)
-                .with_child(
-                    WindowBuilder::new(WidgetBuilder::new().on_row(1).on_column(1))
-                        .can_resize(false)
-                        .can_minimize(false)
-                        .can_close(false)
-                        .with_title(WindowTitle::text("Rusty Shooter"))
-                        .with_content(
-                            GridBuilder::new(
-                                WidgetBuilder::new()
-                                    .with_margin(Thickness::uniform(20.0))
-                                    .with_child({
-                                        btn_new_game = ButtonBuilder::new(
-                                            WidgetBuilder::new()
-                                                .on_column(0)
-                                                .on_row(0)
-                                                .with_margin(Thickness::uniform(4.0)),
-                                        )
-                                        .with_text("New Game")
-                                        .with_font(font.clone())
-                                        .build(ctx);
-                                        btn_new_game
-                                    })
-                                    .with_child({
-                                        btn_save_game = ButtonBuilder::new(
-                                            WidgetBuilder::new()
-                                                .on_column(0)
-                                                .on_row(1)
-                                                .with_margin(Thickness::uniform(4.0)),
-                                        )
-                                        .with_text("Save Game")
-                                        .with_font(font.clone())
-                                        .build(ctx);
-                                        btn_save_game
-                                    })
-                                    .with_child({
-                                        btn_load_game = ButtonBuilder::new(
-                                            WidgetBuilder::new()
-                                                .on_column(0)
-                                                .on_row(2)
-                                                .with_margin(Thickness::uniform(4.0)),
-                                        )
-                                        .with_text("Load Game")
-                                        .with_font(font.clone())
-                                        .build(ctx);
-                                        btn_load_game
-                                    })
-                                    .with_child({
-                                        btn_settings = ButtonBuilder::new(
-                                            WidgetBuilder::new()
-                                                .on_column(0)
-                                                .on_row(3)
-                                                .with_margin(Thickness::uniform(4.0)),
-                                        )
-                                        .with_text("Settings")
-                                        .with_font(font.clone())
-                                        .build(ctx);
-                                        btn_settings
-                                    })
-                                    .with_child({
-                                        btn_quit_game = ButtonBuilder::new(
-                                            WidgetBuilder::new()
-                                                .on_column(0)
-                                                .on_row(4)
-                                                .with_margin(Thickness::uniform(4.0)),
-                                        )
-                                        .with_text("Quit")
-                                        .with_font(font)
-                                        .build(ctx);
-                                        btn_quit_game
-                                    }),
-                            )
-                            .add_column(Column::stretch())
-                            .add_row(Row::strict(75.0))
-                            .add_row(Row::strict(75.0))
-                            .add_row(Row::strict(75.0))
-                            .add_row(Row::strict(75.0))
-                            .add_row(Row::strict(75.0))
-                            .build(ctx),
+                .with_child({
+                    window = WindowBuilder::new(
+                        WidgetBuilder::new()
+                            .with_width(400.0)
+                            .with_height(900.0)
+                            .with_horizontal_alignment(HorizontalAlignment::Center)
+                            .with_vertical_alignment(VerticalAlignment::Center),
+                    )
+                    .can_resize(false)
+                    .can_minimize(false)
+                    .can_close(false)
+                    .with_title(WindowTitle::text(tr("Rusty Shooter")))
+                    .with_content(
+                        GridBuilder::new(
+                            WidgetBuilder::new()
+                                .with_margin(Thickness::uniform(20.0))
+                                .with_child({
+                                    btn_new_game = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(0)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("New Game"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_new_game
+                                })
+                                .with_child({
+                                    btn_campaign = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(1)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Campaign"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_campaign
+                                })
+                                .with_child({
+                                    btn_training = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(2)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Training"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_training
+                                })
+                                .with_child({
+                                    btn_lan_games = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(3)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("LAN Games"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_lan_games
+                                })
+                                .with_child({
+                                    btn_save_game = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(4)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Save Game"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_save_game
+                                })
+                                .with_child({
+                                    btn_load_game = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(5)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Load Game"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_load_game
+                                })
+                                .with_child({
+                                    btn_settings = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(6)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Settings"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_settings
+                                })
+                                .with_child({
+                                    btn_profile = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(7)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Profile"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_profile
+                                })
+                                .with_child({
+                                    btn_mods = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(8)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Mods"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_mods
+                                })
+                                .with_child({
+                                    btn_editor = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(9)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Editor"))
+                                    .with_font(font.clone())
+                                    .build(ctx);
+                                    btn_editor
+                                })
+                                .with_child({
+                                    btn_quit_game = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .on_row(10)
+                                            .with_margin(Thickness::uniform(4.0)),
+                                    )
+                                    .with_text(&tr("Quit"))
+                                    .with_font(font)
+                                    .build(ctx);
+                                    btn_quit_game
+                                }),
                         )
+                        .add_column(Column::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
+                        .add_row(Row::stretch())
                         .build(ctx),
-                ),
+                    )
+                    .build(ctx);
+                    window
+                }),
         )
         .add_row(Row::stretch())
-        .add_row(Row::strict(500.0))
-        .add_row(Row::stretch())
-        .add_column(Column::stretch())
-        .add_column(Column::strict(400.0))
         .add_column(Column::stretch())
         .build(ctx);
 
+        let options_menu =
+            OptionsMenu::new(engine, control_scheme, settings.clone(), sender.clone());
+        let match_menu =
+            MatchMenu::new(&mut engine.user_interface, settings.clone(), sender.clone());
+        let campaign_menu =
+            CampaignMenu::new(&mut engine.user_interface, settings.clone(), sender.clone());
+        let load_menu = LoadMenu::new(&mut engine.user_interface, sender.clone());
+        let lan_menu = LanMenu::new(&mut engine.user_interface);
+        let profile_menu = ProfileMenu::new(&mut engine.user_interface, settings.clone());
+        let mods_menu = ModsMenu::new(&mut engine.user_interface, settings, sender.clone());
+
+        ui_scale.register(window, 400.0, 900.0);
+        ui_scale.register(options_menu.window, 500.0, 0.0);
+        ui_scale.register(match_menu.window, 500.0, 0.0);
+        ui_scale.register(campaign_menu.window, 500.0, 0.0);
+        ui_scale.register(load_menu.window, 400.0, 0.0);
+        ui_scale.register(lan_menu.window, 500.0, 0.0);
+        ui_scale.register(profile_menu.window, 400.0, 0.0);
+        ui_scale.register(mods_menu.window, 500.0, 0.0);
+
         Self {
-            sender: sender.clone(),
+            sender,
             root,
             btn_new_game,
+            btn_campaign,
+            btn_training,
+            btn_lan_games,
             btn_settings,
+            btn_profile,
+            btn_mods,
+            btn_editor,
             btn_save_game,
             btn_load_game,
             btn_quit_game,
-            options_menu: OptionsMenu::new(engine, control_scheme, sender.clone()),
-            match_menu: MatchMenu::new(&mut engine.user_interface, sender),
+            options_menu,
+            match_menu,
+            campaign_menu,
+            load_menu,
+            lan_menu,
+            profile_menu,
+            mods_menu,
         }
     }
 
@@ -178,13 +302,45 @@ impl Menu {
                 self.match_menu.window,
                 MessageDirection::ToWidget,
             ));
+            ui.send_message(WindowMessage::close(
+                self.campaign_menu.window,
+                MessageDirection::ToWidget,
+            ));
+            ui.send_message(WindowMessage::close(
+                self.load_menu.window,
+                MessageDirection::ToWidget,
+            ));
+            ui.send_message(WindowMessage::close(
+                self.lan_menu.window,
+                MessageDirection::ToWidget,
+            ));
+            ui.send_message(WindowMessage::close(
+                self.profile_menu.window,
+                MessageDirection::ToWidget,
+            ));
+            ui.send_message(WindowMessage::close(
+                self.mods_menu.window,
+                MessageDirection::ToWidget,
+            ));
         }
     }
 
+    pub fn show_load_error(&self, ui: &mut UserInterface, error: &str) {
+        self.load_menu.show_error(ui, error);
+    }
+
+    pub fn show_install_result(&mut self, ui: &mut UserInterface, result: &Result<String, String>) {
+        self.mods_menu.show_install_result(ui, result);
+    }
+
     pub fn is_visible(&self, ui: &UserInterface) -> bool {
         ui.node(self.root).visibility()
     }
 
+    pub fn update(&mut self, ui: &mut UserInterface) {
+        self.lan_menu.update(ui);
+    }
+
     pub fn process_input_event(&mut self, engine: &mut Engine, event: &Event<()>) {
         if let Event::WindowEvent { event, .. } = event {
             if let WindowEvent::Resized(new_size) = event {
@@ -212,10 +368,20 @@ impl Menu {
                     MessageDirection::ToWidget,
                     true,
                 ));
+            } else if message.destination() == self.btn_campaign {
+                self.campaign_menu.open(&mut engine.user_interface);
+            } else if message.destination() == self.btn_training {
+                self.sender.send(Message::StartTraining).unwrap();
+            } else if message.destination() == self.btn_lan_games {
+                self.lan_menu.open(&mut engine.user_interface);
             } else if message.destination() == self.btn_save_game {
-                self.sender.send(Message::SaveGame).unwrap();
+                self.sender
+                    .send(Message::SaveGame {
+                        path: saves::save_path(),
+                    })
+                    .unwrap();
             } else if message.destination() == self.btn_load_game {
-                self.sender.send(Message::LoadGame).unwrap();
+                self.load_menu.open(&mut engine.user_interface);
             } else if message.destination() == self.btn_quit_game {
                 self.sender.send(Message::QuitGame).unwrap();
             } else if message.destination() == self.btn_settings {
@@ -224,10 +390,31 @@ impl Menu {
                     MessageDirection::ToWidget,
                     true,
                 ));
+            } else if message.destination() == self.btn_profile {
+                engine.user_interface.send_message(WindowMessage::open(
+                    self.profile_menu.window,
+                    MessageDirection::ToWidget,
+                    true,
+                ));
+            } else if message.destination() == self.btn_mods {
+                self.mods_menu.open(&mut engine.user_interface);
+            } else if message.destination() == self.btn_editor {
+                self.sender
+                    .send(Message::StartEditor {
+                        map: maps::default_map().path.into(),
+                    })
+                    .unwrap();
             }
         }
 
         self.options_menu.handle_ui_event(engine, message);
         self.match_menu.handle_ui_event(engine, message);
+        self.campaign_menu
+            .handle_ui_event(&mut engine.user_interface, message);
+        self.load_menu.handle_ui_event(message);
+        self.profile_menu
+            .handle_ui_event(&mut engine.user_interface, message);
+        self.mods_menu
+            .handle_ui_event(&mut engine.user_interface, message);
     }
 }
@@ -0,0 +1,122 @@
+//! Background of main menu - a map diorama slowly circled by a camera. Scene is loaded on a
+//! separate thread, so menu is shown right away and background appears once it is ready.
+
+use crate::{
+    logger::{self, Category},
+    maps,
+};
+use rg3d::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
+    engine::{
+        resource_manager::{MaterialSearchOptions, ResourceManager},
+        Engine,
+    },
+    scene::{base::BaseBuilder, camera::CameraBuilder, node::Node, Scene},
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Angular speed (in radians per second) of camera.
+const ORBIT_SPEED: f32 = 0.05;
+
+const ORBIT_RADIUS: f32 = 18.0;
+
+const ORBIT_HEIGHT: f32 = 7.0;
+
+const ORBIT_PITCH: f32 = 0.3;
+
+pub struct MenuScene {
+    scene: Handle<Scene>,
+    camera: Handle<Node>,
+    angle: f32,
+    // Filled by loader thread, taken on first update after loading is done.
+    loaded: Arc<Mutex<Option<(Scene, Handle<Node>)>>>,
+}
+
+async fn load(resource_manager: ResourceManager) -> Option<(Scene, Handle<Node>)> {
+    let mut scene = Scene::new();
+
+    let model = resource_manager
+        .request_model(
+            maps::default_map().path,
+            MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures")),
+        )
+        .await;
+    match model {
+        Ok(model) => {
+            model.instantiate_geometry(&mut scene);
+        }
+        Err(e) => {
+            logger::warning(
+                Category::General,
+                format!("Unable to load menu background. Reason: {:?}", e),
+            );
+            return None;
+        }
+    }
+
+    let camera = CameraBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+
+    Some((scene, camera))
+}
+
+impl MenuScene {
+    pub fn new(engine: &Engine) -> Self {
+        let loaded = Arc::new(Mutex::new(None));
+
+        let resource_manager = engine.resource_manager.clone();
+        let result = loaded.clone();
+        std::thread::spawn(move || {
+            if let Some(scene) = rg3d::core::futures::executor::block_on(load(resource_manager)) {
+                *result.lock().unwrap() = Some(scene);
+            }
+        });
+
+        Self {
+            scene: Handle::NONE,
+            camera: Handle::NONE,
+            angle: 0.0,
+            loaded,
+        }
+    }
+
+    /// Background is rendered only when `visible` is set, so it never draws over a level.
+    pub fn update(&mut self, engine: &mut Engine, visible: bool, dt: f32) {
+        if self.scene.is_none() {
+            if let Ok(mut loaded) = self.loaded.try_lock() {
+                if let Some((scene, camera)) = loaded.take() {
+                    self.scene = engine.scenes.add(scene);
+                    self.camera = camera;
+                }
+            }
+            if self.scene.is_none() {
+                return;
+            }
+        }
+
+        self.angle += ORBIT_SPEED * dt;
+
+        let position = Vector3::new(
+            self.angle.sin() * ORBIT_RADIUS,
+            ORBIT_HEIGHT,
+            self.angle.cos() * ORBIT_RADIUS,
+        );
+        // Camera looks along its Z axis, so it is turned around to face center of the map.
+        let rotation =
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.angle + std::f32::consts::PI)
+                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), ORBIT_PITCH);
+
+        if let Node::Camera(camera) = &mut engine.scenes[self.scene].graph[self.camera] {
+            camera
+                .set_enabled(visible)
+                .local_transform_mut()
+                .set_position(position)
+                .set_rotation(rotation);
+        }
+    }
+}
@@ -11,10 +11,19 @@
 
 use crate::{
     actor::Actor,
+    announcer::Announcement,
     bot::BotKind,
+    debug_overlay::DebugOverlay,
     effects::EffectKind,
+    hitbox::HitZone,
     item::{Item, ItemKind},
+    match_recorder::MatchEvent,
+    mine::Mine,
     projectile::ProjectileKind,
+    score::ScoreEvent,
+    squad::Order,
+    turret::Turret,
+    vote::VoteSubject,
     weapon::{Weapon, WeaponKind},
     MatchOptions,
 };
@@ -57,6 +66,11 @@ pub enum Message {
         actor: Handle<Actor>,
         item: Handle<Item>,
     },
+    /// Adds event to log of match, `time` is match time when event happened.
+    RecordMatchEvent {
+        time: f32,
+        event: MatchEvent,
+    },
     SpawnItem {
         kind: ItemKind,
         position: Vector3<f32>,
@@ -76,6 +90,23 @@ pub enum Message {
         initial_velocity: Vector3<f32>,
         direction: Option<Vector3<f32>>,
     },
+    /// Must be sent when shooter stops holding the trigger, single and burst fire modes
+    /// won't fire again until then.
+    ReleaseTrigger {
+        weapon: Handle<Weapon>,
+    },
+    CycleFireMode {
+        weapon: Handle<Weapon>,
+    },
+    /// Player gives order to friendly bots, see `squad` module.
+    IssueOrder {
+        order: Order,
+    },
+    /// Player marks a point player looks at for teammates. Kind of the ping depends on what
+    /// is at that point, unless it is explicitly marked as dangerous.
+    Ping {
+        danger: bool,
+    },
     PlaySound {
         path: PathBuf,
         position: Vector3<f32>,
@@ -93,30 +124,140 @@ pub enum Message {
         /// or not from any actor.
         who: Handle<Actor>,
         amount: f32,
+        /// Direction in which damage was travelling (from source to victim), used by bots to
+        /// figure out where damage came from. Can be None if direction is unknown.
+        direction: Option<Vector3<f32>>,
+        /// Part of body that was hit, None if damage has no point of impact.
+        zone: Option<HitZone>,
+    },
+    /// Sets actor on fire, `who` is credited for burn damage.
+    IgniteActor {
+        actor: Handle<Actor>,
+        who: Handle<Actor>,
+    },
+    DamageTurret {
+        turret: Handle<Turret>,
+        amount: f32,
+    },
+    /// Projectile at given position flies towards a bot, bot may try to dodge it.
+    ProjectileThreat {
+        actor: Handle<Actor>,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+    },
+    /// Blows up a mine, for example when it was shot.
+    DetonateMine {
+        mine: Handle<Mine>,
     },
     CreateEffect {
         kind: EffectKind,
         position: Vector3<f32>,
     },
+    /// Shakes camera of player if it is within given radius of position.
+    ShakeCamera {
+        position: Vector3<f32>,
+        radius: f32,
+    },
     SpawnPlayer,
+    /// Brings dead player back to the latest checkpoint of campaign level.
+    RestartFromCheckpoint,
     /// HUD listens such events and puts them into queue.
     AddNotification {
         text: String,
     },
+    /// Announcer calls out change of match state.
+    Announce {
+        announcement: Announcement,
+    },
+    /// Shows subtitle of a voice line on HUD.
+    ShowSubtitle {
+        speaker: String,
+        text: String,
+    },
+    /// Player has hit another actor, HUD flashes hit marker over crosshair.
+    HitConfirmed {
+        killed: bool,
+        headshot: bool,
+    },
+    /// Shows toast on HUD about item that player has picked up.
+    ShowPickupToast {
+        kind: ItemKind,
+    },
+    /// Player has earned (or lost) points, HUD shows "+N" popup.
+    ShowScorePopup {
+        event: ScoreEvent,
+        points: i32,
+    },
+    /// Shows current objective of training level on HUD.
+    ShowTrainingPrompt {
+        text: String,
+    },
+    /// Chat line of a player, team messages are meant for teammates only.
+    ChatMessage {
+        sender: String,
+        text: String,
+        team: bool,
+    },
+    /// Player calls a vote, see `vote` module.
+    CallVote {
+        caller: String,
+        subject: VoteSubject,
+    },
+    CastVote {
+        voter: String,
+        yes: bool,
+    },
+    /// Shows prompt of vote in progress on HUD, None hides it.
+    ShowVote {
+        text: Option<String>,
+    },
+    /// Turns observer mode on or off, see `observer` module.
+    ToggleObserver,
+    /// Toggles debug overlay, None turns every overlay off.
+    ToggleDebugOverlay {
+        overlay: Option<DebugOverlay>,
+    },
+    /// Slows down or speeds up the whole game for debugging, 1.0 is normal speed.
+    SetTimeScale {
+        scale: f32,
+    },
     /// Removes specified actor and creates new one at random spawn point.
     RespawnActor {
         actor: Handle<Actor>,
     },
-    /// Save game state to a file. TODO: Add filename field.
-    SaveGame,
-    /// Loads game state from a file. TODO: Add filename field.
-    LoadGame,
+    /// Saves game state to a file.
+    SaveGame {
+        path: PathBuf,
+    },
+    /// Loads game state from a file.
+    LoadGame {
+        path: PathBuf,
+    },
+    /// Installation of user map has finished, result is name of the map or error.
+    MapInstalled {
+        result: Result<String, String>,
+    },
     StartNewGame {
         options: MatchOptions,
+        map: PathBuf,
+    },
+    /// Starts training level that teaches basics of the game.
+    StartTraining,
+    /// Starts campaign from given level, index in `campaign::LEVELS`.
+    StartCampaign {
+        level: u32,
     },
+    /// Opens level editor on given map, level that is played is destroyed.
+    StartEditor {
+        map: PathBuf,
+    },
+    StopEditor,
     QuitGame,
     SetMusicVolume {
         volume: f32,
     },
+    SetLanguage {
+        language: String,
+    },
     EndMatch,
 }
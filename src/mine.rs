@@ -0,0 +1,325 @@
+//! Proximity mines. Mine is placed on a surface by mine layer, it arms after a short delay and
+//! then explodes when an enemy of its owner comes close. Mines can be destroyed by shooting at
+//! them, which detonates them as well.
+
+use crate::{
+    actor::{Actor, ActorContainer},
+    character::Team,
+    effects::EffectKind,
+    message::Message,
+};
+use rg3d::{
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        color::Color,
+        math::ray::Ray,
+        pool::{Handle, Pool},
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder,
+        },
+        node::Node,
+        transform::TransformBuilder,
+        Scene,
+    },
+};
+use std::{
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc, RwLock},
+};
+
+/// Time (in seconds) between placement and arming of a mine.
+const ARM_TIME: f32 = 1.5;
+
+/// Enemy closer than this distance to an armed mine triggers it.
+const TRIGGER_RADIUS: f32 = 2.0;
+
+const BLAST_RADIUS: f32 = 4.0;
+
+/// Damage at the center of blast, it falls off linearly to zero at blast radius.
+const BLAST_DAMAGE: f32 = 80.0;
+
+/// Radius of sphere which is tested against shots.
+const HIT_RADIUS: f32 = 0.3;
+
+/// Oldest mine of an owner is removed when owner places a mine over this limit.
+const MAX_MINES_PER_OWNER: usize = 3;
+
+const MINE_RADIUS: f32 = 0.15;
+
+const MINE_HEIGHT: f32 = 0.05;
+
+const UNARMED_COLOR: Color = Color::opaque(120, 120, 120);
+
+const ARMED_COLOR: Color = Color::opaque(255, 0, 0);
+
+pub struct Mine {
+    model: Handle<Node>,
+    owner: Handle<Actor>,
+    // Team of owner at the moment of placement, owner may die while mine is still there.
+    team: Team,
+    // Time since placement.
+    age: f32,
+}
+
+impl Default for Mine {
+    fn default() -> Self {
+        Self {
+            model: Default::default(),
+            owner: Default::default(),
+            team: Default::default(),
+            age: 0.0,
+        }
+    }
+}
+
+impl Visit for Mine {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.model.visit("Model", visitor)?;
+        self.owner.visit("Owner", visitor)?;
+        self.team.visit("Team", visitor)?;
+        self.age.visit("Age", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl Mine {
+    /// Creates mine lying on a surface with given normal.
+    pub fn new(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        normal: Vector3<f32>,
+        owner: Handle<Actor>,
+        team: Team,
+    ) -> Self {
+        let rotation = UnitQuaternion::rotation_between(&Vector3::y(), &normal)
+            .unwrap_or_else(UnitQuaternion::identity);
+
+        let data = SurfaceData::make_cylinder(
+            12,
+            MINE_RADIUS,
+            MINE_HEIGHT,
+            true,
+            // Cylinder is centered at origin, lift it so it lies on the surface.
+            &Matrix4::new_translation(&Vector3::new(0.0, MINE_HEIGHT * 0.5, 0.0)),
+        );
+        let mut surface = SurfaceBuilder::new(Arc::new(RwLock::new(data))).build();
+        surface.set_color(UNARMED_COLOR);
+
+        let model = MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .with_local_rotation(rotation)
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![surface])
+        .build(graph);
+
+        Self {
+            model,
+            owner,
+            team,
+            age: 0.0,
+        }
+    }
+
+    pub fn position(&self, graph: &Graph) -> Vector3<f32> {
+        graph[self.model].global_position()
+    }
+
+    fn is_armed(&self) -> bool {
+        self.age >= ARM_TIME
+    }
+
+    fn update(&mut self, graph: &mut Graph, dt: f32) {
+        let was_armed = self.is_armed();
+        self.age += dt;
+        if !was_armed && self.is_armed() {
+            if let Node::Mesh(mesh) = &mut graph[self.model] {
+                for surface in mesh.surfaces_mut() {
+                    surface.set_color(ARMED_COLOR);
+                }
+            }
+        }
+    }
+
+    fn is_enemy(&self, handle: Handle<Actor>, actor: &Actor) -> bool {
+        handle != self.owner
+            && !actor.is_dead()
+            && (self.team == Team::None || actor.team() != self.team)
+    }
+}
+
+pub struct MineContainer {
+    pool: Pool<Mine>,
+}
+
+impl Default for MineContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MineContainer {
+    pub fn new() -> Self {
+        Self { pool: Pool::new() }
+    }
+
+    /// Adds new mine, oldest mine of the same owner is removed if owner has too many of them.
+    pub fn add(&mut self, mine: Mine, graph: &mut Graph) -> Handle<Mine> {
+        let owned = self
+            .pool
+            .pair_iter()
+            .filter(|(_, other)| other.owner == mine.owner)
+            .map(|(handle, other)| (handle, other.age))
+            .collect::<Vec<_>>();
+        if owned.len() >= MAX_MINES_PER_OWNER {
+            let (oldest, _) = owned
+                .into_iter()
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap();
+            self.remove(oldest, graph);
+        }
+        self.pool.spawn(mine)
+    }
+
+    fn remove(&mut self, handle: Handle<Mine>, graph: &mut Graph) {
+        let mine = self.pool.free(handle);
+        graph.remove_node(mine.model);
+    }
+
+    pub fn contains(&self, handle: Handle<Mine>) -> bool {
+        self.pool.is_valid_handle(handle)
+    }
+
+    /// Returns closest mine hit by a ray and ray parameter of the hit. Parameter is in
+    /// [0; 1] range, so the ray must span the whole tested segment.
+    pub fn hit_test(&self, ray: &Ray, graph: &Graph) -> Option<(Handle<Mine>, f32)> {
+        self.pool
+            .pair_iter()
+            .filter_map(|(handle, mine)| {
+                ray.sphere_intersection(&mine.position(graph), HIT_RADIUS)
+                    .filter(|result| result.min <= 1.0 && result.max >= 0.0)
+                    .map(|result| (handle, result.min.max(0.0)))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+    }
+
+    /// Blows up given mine, actors within blast radius take damage that is credited to owner
+    /// of the mine. Other mines in blast radius are detonated too.
+    pub fn detonate(
+        &mut self,
+        handle: Handle<Mine>,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        sender: &Sender<Message>,
+    ) {
+        if !self.contains(handle) {
+            return;
+        }
+
+        let position = self.pool[handle].position(&scene.graph);
+        let owner = self.pool[handle].owner;
+        self.remove(handle, &mut scene.graph);
+
+        // Owner may be gone already, then damage is credited to nobody.
+        let who = if actors.contains(owner) {
+            owner
+        } else {
+            Handle::NONE
+        };
+        for (actor_handle, actor) in actors.pair_iter() {
+            if actor.is_dead() {
+                continue;
+            }
+            let offset = actor.position(&scene.physics) - position;
+            let distance = offset.norm();
+            if distance < BLAST_RADIUS {
+                sender
+                    .send(Message::DamageActor {
+                        actor: actor_handle,
+                        who,
+                        amount: BLAST_DAMAGE * (1.0 - distance / BLAST_RADIUS),
+                        direction: Some(offset),
+                        zone: None,
+                    })
+                    .unwrap();
+            }
+        }
+
+        for (other, mine) in self.pool.pair_iter() {
+            if mine.position(&scene.graph).metric_distance(&position) < BLAST_RADIUS {
+                sender.send(Message::DetonateMine { mine: other }).unwrap();
+            }
+        }
+
+        sender
+            .send(Message::CreateEffect {
+                kind: EffectKind::BulletImpact,
+                position,
+            })
+            .unwrap();
+        sender
+            .send(Message::PlaySound {
+                path: PathBuf::from("data/sounds/explosion.ogg"),
+                position,
+                gain: 1.0,
+                rolloff_factor: 4.0,
+                radius: 3.0,
+            })
+            .unwrap();
+        sender
+            .send(Message::ShakeCamera {
+                position,
+                radius: 8.0,
+            })
+            .unwrap();
+    }
+
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        dt: f32,
+        sender: &Sender<Message>,
+    ) {
+        let mut triggered = Vec::new();
+        for (handle, mine) in self.pool.pair_iter_mut() {
+            mine.update(&mut scene.graph, dt);
+            if !mine.is_armed() {
+                continue;
+            }
+            let position = mine.position(&scene.graph);
+            if actors.pair_iter().any(|(actor_handle, actor)| {
+                mine.is_enemy(actor_handle, actor)
+                    && actor.position(&scene.physics).metric_distance(&position) < TRIGGER_RADIUS
+            }) {
+                triggered.push(handle);
+            }
+        }
+
+        for handle in triggered {
+            self.detonate(handle, scene, actors, sender);
+        }
+    }
+}
+
+impl Visit for MineContainer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.pool.visit("Pool", visitor)?;
+
+        visitor.leave_region()
+    }
+}
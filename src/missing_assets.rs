@@ -0,0 +1,128 @@
+//! Dialog which tells player about assets that are missing or broken, see `assets` module.
+//! Assets reported while the dialog is open are appended to the list.
+
+use crate::localization::tr;
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        scroll_viewer::ScrollViewerBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::path::PathBuf;
+
+pub struct MissingAssetsDialog {
+    pub window: Handle<UiNode>,
+    text: Handle<UiNode>,
+    ok: Handle<UiNode>,
+    paths: Vec<PathBuf>,
+    open: bool,
+}
+
+impl MissingAssetsDialog {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let text;
+        let ok;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(500.0).with_height(300.0))
+            .with_title(WindowTitle::text(tr("Missing Assets")))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            ScrollViewerBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .with_margin(Thickness::uniform(4.0)),
+                            )
+                            .with_content({
+                                text = TextBuilder::new(WidgetBuilder::new()).build(ctx);
+                                text
+                            })
+                            .build(ctx),
+                        )
+                        .with_child({
+                            ok = ButtonBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_width(100.0)
+                                    .with_margin(Thickness::uniform(2.0))
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .with_vertical_alignment(VerticalAlignment::Center),
+                            )
+                            .with_text(&tr("OK"))
+                            .build(ctx);
+                            ok
+                        }),
+                )
+                .add_column(Column::stretch())
+                .add_row(Row::stretch())
+                .add_row(Row::strict(32.0))
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            text,
+            ok,
+            paths: Default::default(),
+            open: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn show(&mut self, ui: &mut UserInterface, paths: Vec<PathBuf>) {
+        self.paths.extend(paths);
+        let list = self
+            .paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.send_message(TextMessage::text(
+            self.text,
+            MessageDirection::ToWidget,
+            format!(
+                "These assets are missing or broken, placeholders are used instead. \
+                 Reinstall the game to restore them.\n\n{}",
+                list
+            ),
+        ));
+        if !self.open {
+            self.open = true;
+            ui.send_message(WindowMessage::open(
+                self.window,
+                MessageDirection::ToWidget,
+                true,
+            ));
+        }
+    }
+
+    pub fn handle_ui_event(&mut self, ui: &mut UserInterface, message: &UiMessage) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.ok {
+                ui.send_message(WindowMessage::close(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        } else if let Some(WindowMessage::Close) = message.data() {
+            if message.destination() == self.window {
+                self.open = false;
+                self.paths.clear();
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Mods are archives in `mods` folder of user data directory, see `pak` module. The folder is
+//! scanned on start: new mods are enabled and appended to load order, mods which were removed
+//! are forgotten. Load order and enabled state are kept in settings and can be changed in mods
+//! menu, changes take effect after restart because the engine caches loaded assets.
+//!
+//! A mod can have a manifest named `mod.txt` in root of its archive:
+//!
+//! ```text
+//! name = Heavy Arsenal
+//! redirect data/sounds/ak47.ogg = data/heavy_arsenal/ak47.ogg
+//! redirect data/models/dm6.fbx = data/heavy_arsenal/arena.fbx
+//! ```
+//!
+//! Redirects replace assets which weapons, bots, items, maps and sounds refer to without
+//! mirroring layout of game data. Mod that is later in load order wins when two mods redirect
+//! or override the same asset.
+
+use crate::{
+    logger::{self, Category},
+    pak, paths,
+};
+use rg3d::core::visitor::{Visit, VisitResult, Visitor};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default)]
+pub struct ModEntry {
+    /// File name of mod archive in mods folder.
+    pub file: String,
+    pub enabled: bool,
+}
+
+impl Visit for ModEntry {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.file.visit("File", visitor)?;
+        self.enabled.visit("Enabled", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+pub struct ModInfo {
+    /// Name from manifest, file name if there is no manifest.
+    pub name: String,
+    pub redirects: Vec<(PathBuf, PathBuf)>,
+}
+
+fn parse_manifest(file: &str, text: &str) -> ModInfo {
+    let mut info = ModInfo {
+        name: file.to_owned(),
+        redirects: Default::default(),
+    };
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()));
+        match parsed {
+            Some(("name", value)) if !value.is_empty() => info.name = value.to_owned(),
+            Some((key, target)) if key.starts_with("redirect ") => {
+                let source = key["redirect ".len()..].trim();
+                info.redirects
+                    .push((PathBuf::from(source), PathBuf::from(target)));
+            }
+            _ => logger::warning(
+                Category::General,
+                format!("Unknown line \"{}\" in manifest of mod {}.", line, file),
+            ),
+        }
+    }
+    info
+}
+
+pub fn read_info(file: &str) -> ModInfo {
+    let text = pak::read_text(&paths::mods_dir().join(file), pak::MANIFEST_FILE);
+    parse_manifest(file, text.as_deref().unwrap_or_default())
+}
+
+/// Syncs entries with archives in mods folder, returns true if entries have changed.
+pub fn scan(entries: &mut Vec<ModEntry>) -> bool {
+    let files = pak::find_archives(&paths::mods_dir())
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    let count = entries.len();
+    entries.retain(|entry| files.contains(&entry.file));
+    let mut changed = entries.len() != count;
+
+    for file in files {
+        if !entries.iter().any(|entry| entry.file == file) {
+            logger::info(Category::General, format!("Found new mod {}.", file));
+            entries.push(ModEntry {
+                file,
+                enabled: true,
+            });
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Mounts base archives and enabled mods in load order, must be called once on start.
+pub fn mount(entries: &[ModEntry]) {
+    let mut archives = pak::base_archives();
+    let mut redirects = Vec::new();
+    for entry in entries.iter().filter(|entry| entry.enabled) {
+        let info = read_info(&entry.file);
+        logger::info(
+            Category::General,
+            format!("Loading mod {} ({}).", info.name, entry.file),
+        );
+        archives.push(paths::mods_dir().join(&entry.file));
+        redirects.extend(info.redirects);
+    }
+    pak::mount(&archives);
+    pak::set_redirects(redirects);
+}
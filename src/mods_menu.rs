@@ -0,0 +1,295 @@
+use crate::{
+    localization::{tr, tr_format},
+    map_install,
+    message::Message,
+    mods,
+    settings::Settings,
+};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        text_box::{TextBoxBuilder, TextBoxMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::sync::{mpsc::Sender, Arc, RwLock};
+
+struct ModRow {
+    toggle: Handle<UiNode>,
+    up: Handle<UiNode>,
+    down: Handle<UiNode>,
+}
+
+pub struct ModsMenu {
+    pub window: Handle<UiNode>,
+    list: Handle<UiNode>,
+    items: Vec<Handle<UiNode>>,
+    // Row of every mod in load order.
+    rows: Vec<ModRow>,
+    settings: Arc<RwLock<Settings>>,
+    sender: Sender<Message>,
+    tb_source: Handle<UiNode>,
+    install_button: Handle<UiNode>,
+    install_status: Handle<UiNode>,
+    // Path or URL of map package entered by player.
+    source: String,
+    installing: bool,
+}
+
+impl ModsMenu {
+    pub fn new(
+        ui: &mut UserInterface,
+        settings: Arc<RwLock<Settings>>,
+        sender: Sender<Message>,
+    ) -> Self {
+        let ctx = &mut ui.build_ctx();
+
+        let list;
+        let tb_source;
+        let install_button;
+        let install_status;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(500.0))
+            .with_title(WindowTitle::text(tr("Mods")))
+            .open(false)
+            .with_content(
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new().with_margin(Thickness::uniform(4.0)),
+                            )
+                            .with_text(
+                                "Mods lower in the list override mods above them. \
+                                 Changes take effect after restart.",
+                            )
+                            .build(ctx),
+                        )
+                        .with_child({
+                            list = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+                            list
+                        })
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new().with_margin(Thickness::uniform(4.0)),
+                            )
+                            .with_text(tr("Install map from file, folder or URL:"))
+                            .build(ctx),
+                        )
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_height(36.0)
+                                    .with_child({
+                                        tb_source = TextBoxBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .with_margin(Thickness::uniform(2.0)),
+                                        )
+                                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                                        .build(ctx);
+                                        tb_source
+                                    })
+                                    .with_child({
+                                        install_button = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(1)
+                                                .with_margin(Thickness::uniform(2.0)),
+                                        )
+                                        .with_text(&tr("Install"))
+                                        .build(ctx);
+                                        install_button
+                                    }),
+                            )
+                            .add_column(Column::stretch())
+                            .add_column(Column::strict(100.0))
+                            .add_row(Row::stretch())
+                            .build(ctx),
+                        )
+                        .with_child({
+                            install_status = TextBuilder::new(
+                                WidgetBuilder::new().with_margin(Thickness::uniform(4.0)),
+                            )
+                            .build(ctx);
+                            install_status
+                        }),
+                )
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            list,
+            items: Default::default(),
+            rows: Default::default(),
+            settings,
+            sender,
+            tb_source,
+            install_button,
+            install_status,
+            source: String::new(),
+            installing: false,
+        }
+    }
+
+    fn rebuild(&mut self, ui: &mut UserInterface) {
+        for item in self.items.drain(..) {
+            ui.send_message(WidgetMessage::remove(item, MessageDirection::ToWidget));
+        }
+        self.rows.clear();
+
+        let entries = self.settings.read().unwrap().mods.clone();
+        if entries.is_empty() {
+            let text = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(4.0)))
+                .with_text(tr("No mods installed"))
+                .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                text,
+                MessageDirection::ToWidget,
+                self.list,
+            ));
+            self.items.push(text);
+        }
+
+        for entry in entries {
+            let info = mods::read_info(&entry.file);
+            let ctx = &mut ui.build_ctx();
+            let toggle = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .on_column(0)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(&format!(
+                "[{}] {}",
+                if entry.enabled { "x" } else { " " },
+                info.name
+            ))
+            .build(ctx);
+            let up = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .on_column(1)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(&tr("Up"))
+            .build(ctx);
+            let down = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .on_column(2)
+                    .with_margin(Thickness::uniform(2.0)),
+            )
+            .with_text(&tr("Down"))
+            .build(ctx);
+            let root = GridBuilder::new(
+                WidgetBuilder::new()
+                    .with_height(36.0)
+                    .with_child(toggle)
+                    .with_child(up)
+                    .with_child(down),
+            )
+            .add_column(Column::stretch())
+            .add_column(Column::strict(60.0))
+            .add_column(Column::strict(60.0))
+            .add_row(Row::stretch())
+            .build(ctx);
+            ui.send_message(WidgetMessage::link(
+                root,
+                MessageDirection::ToWidget,
+                self.list,
+            ));
+            self.items.push(root);
+            self.rows.push(ModRow { toggle, up, down });
+        }
+    }
+
+    /// Opens the window, list is rebuilt every time since mods folder is rescanned.
+    pub fn open(&mut self, ui: &mut UserInterface) {
+        {
+            let mut settings = self.settings.write().unwrap();
+            if mods::scan(&mut settings.mods) {
+                settings.save();
+            }
+        }
+        self.rebuild(ui);
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    fn set_install_status(&self, ui: &mut UserInterface, status: String) {
+        ui.send_message(TextMessage::text(
+            self.install_status,
+            MessageDirection::ToWidget,
+            status,
+        ));
+    }
+
+    fn install(&mut self, ui: &mut UserInterface) {
+        if self.installing {
+            return;
+        }
+        self.installing = true;
+        self.set_install_status(ui, "Installing...".to_owned());
+
+        let source = self.source.clone();
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let result = map_install::install(&source).map(|map| map.name.to_owned());
+            sender.send(Message::MapInstalled { result }).unwrap();
+        });
+    }
+
+    pub fn show_install_result(&mut self, ui: &mut UserInterface, result: &Result<String, String>) {
+        self.installing = false;
+        let status = match result {
+            Ok(name) => tr_format("Map {} is installed.", &[name]),
+            Err(e) => tr_format("Unable to install map: {}.", &[e]),
+        };
+        self.set_install_status(ui, status);
+    }
+
+    pub fn handle_ui_event(&mut self, ui: &mut UserInterface, message: &UiMessage) {
+        if let Some(TextBoxMessage::Text(text)) = message.data() {
+            if message.destination() == self.tb_source {
+                self.source = text.clone();
+            }
+        }
+
+        if let Some(ButtonMessage::Click) = message.data() {
+            let destination = message.destination();
+            if destination == self.install_button {
+                self.install(ui);
+                return;
+            }
+            let count = self.rows.len();
+            let changed = if let Some(index) = self.rows.iter().position(|row| {
+                row.toggle == destination || row.up == destination || row.down == destination
+            }) {
+                let row = &self.rows[index];
+                let mut settings = self.settings.write().unwrap();
+                if row.toggle == destination {
+                    settings.mods[index].enabled = !settings.mods[index].enabled;
+                } else if row.up == destination && index > 0 {
+                    settings.mods.swap(index, index - 1);
+                } else if row.down == destination && index + 1 < count {
+                    settings.mods.swap(index, index + 1);
+                }
+                settings.save();
+                true
+            } else {
+                false
+            };
+            if changed {
+                self.rebuild(ui);
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Music manager. Menu has its own track, in a match music switches between ambient and combat
+//! layers of map's playlist depending on how intense the fight around player is. Switching
+//! between tracks is done by crossfading.
+
+use crate::{
+    logger::{self, Category},
+    pak,
+};
+use rg3d::{
+    core::{pool::Handle, rand::seq::SliceRandom},
+    engine::{resource_manager::ResourceManager, Engine},
+    rand,
+    sound::{
+        context::SoundContext,
+        source::{generic::GenericSourceBuilder, SoundSource, Status},
+    },
+};
+
+const MENU_TRACK: &str = "data/sounds/Antonio_Bizarro_Berzerker.ogg";
+
+/// How long (in seconds) crossfade between two tracks takes.
+const CROSSFADE_TIME: f32 = 3.0;
+
+/// Combat layer starts playing when intensity rises above this value.
+const COMBAT_THRESHOLD: f32 = 0.5;
+
+/// Ambient layer returns when intensity falls below this value.
+const AMBIENT_THRESHOLD: f32 = 0.2;
+
+/// How much combat intensity fades per second.
+const INTENSITY_DECAY: f32 = 0.05;
+
+/// Music of a map, a random track of a layer is picked every time the layer starts playing.
+/// Empty layer means silence.
+pub struct Playlist {
+    pub ambient: &'static [&'static str],
+    pub combat: &'static [&'static str],
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MusicLayer {
+    Menu,
+    Ambient,
+    Combat,
+}
+
+struct Track {
+    source: Handle<SoundSource>,
+    // Fraction of music volume, goes up for current track and down for others.
+    weight: f32,
+}
+
+pub struct MusicManager {
+    context: SoundContext,
+    resource_manager: ResourceManager,
+    playlist: Option<&'static Playlist>,
+    layer: Option<MusicLayer>,
+    current: Option<Track>,
+    fading: Vec<Track>,
+    intensity: f32,
+    volume: f32,
+}
+
+impl MusicManager {
+    pub fn new(engine: &mut Engine) -> Self {
+        let context = SoundContext::new();
+        engine
+            .sound_engine
+            .lock()
+            .unwrap()
+            .add_context(context.clone());
+
+        let mut music = Self {
+            context,
+            resource_manager: engine.resource_manager.clone(),
+            playlist: None,
+            layer: None,
+            current: None,
+            fading: Default::default(),
+            intensity: 0.0,
+            volume: 0.25,
+        };
+        music.switch_to(MusicLayer::Menu);
+        music
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+
+    /// Sets playlist of current level, None means that no level is running and menu track
+    /// must be played.
+    pub fn set_playlist(&mut self, playlist: Option<&'static Playlist>) {
+        self.playlist = playlist;
+        self.intensity = 0.0;
+        self.switch_to(if playlist.is_some() {
+            MusicLayer::Ambient
+        } else {
+            MusicLayer::Menu
+        });
+    }
+
+    /// Must be called when player deals or takes damage.
+    pub fn on_combat_event(&mut self) {
+        self.intensity = (self.intensity + 0.35).min(1.0);
+    }
+
+    fn pick_track(&self, layer: MusicLayer) -> Option<&'static str> {
+        let tracks: &[&str] = match (layer, self.playlist) {
+            (MusicLayer::Menu, _) | (_, None) => &[MENU_TRACK],
+            (MusicLayer::Ambient, Some(playlist)) => playlist.ambient,
+            (MusicLayer::Combat, Some(playlist)) => playlist.combat,
+        };
+        tracks.choose(&mut rand::thread_rng()).copied()
+    }
+
+    fn switch_to(&mut self, layer: MusicLayer) {
+        if self.layer == Some(layer) {
+            return;
+        }
+        self.layer = Some(layer);
+
+        if let Some(track) = self.current.take() {
+            self.fading.push(track);
+        }
+
+        if let Some(path) = self.pick_track(layer) {
+            match rg3d::core::futures::executor::block_on(
+                self.resource_manager
+                    .request_sound_buffer(pak::resolve(path), true),
+            ) {
+                Ok(buffer) => {
+                    let source = self.context.state().add_source(
+                        GenericSourceBuilder::new()
+                            .with_buffer(buffer.into())
+                            .with_looping(true)
+                            .with_status(Status::Playing)
+                            .with_gain(0.0)
+                            .build_source()
+                            .unwrap(),
+                    );
+                    self.current = Some(Track {
+                        source,
+                        weight: 0.0,
+                    });
+                }
+                Err(e) => logger::error(
+                    Category::Audio,
+                    format!("Unable to load music track {}. Reason: {:?}", path, e),
+                ),
+            }
+        }
+    }
+
+    /// `threats` is amount of enemies near player, they keep combat music playing.
+    pub fn update(&mut self, dt: f32, threats: usize) {
+        let pressure = (threats as f32 * 0.25).min(COMBAT_THRESHOLD);
+        self.intensity = (self.intensity - INTENSITY_DECAY * dt).max(pressure);
+
+        if self.playlist.is_some() {
+            if self.intensity >= COMBAT_THRESHOLD {
+                self.switch_to(MusicLayer::Combat);
+            } else if self.intensity < AMBIENT_THRESHOLD {
+                self.switch_to(MusicLayer::Ambient);
+            }
+        }
+
+        let step = dt / CROSSFADE_TIME;
+        let mut state = self.context.state();
+        if let Some(track) = self.current.as_mut() {
+            track.weight = (track.weight + step).min(1.0);
+            state
+                .source_mut(track.source)
+                .set_gain(track.weight * self.volume);
+        }
+        for track in self.fading.iter_mut() {
+            track.weight = (track.weight - step).max(0.0);
+            state
+                .source_mut(track.source)
+                .set_gain(track.weight * self.volume);
+        }
+        self.fading.retain(|track| {
+            if track.weight > 0.0 {
+                true
+            } else {
+                state.remove_source(track.source);
+                false
+            }
+        });
+    }
+}
@@ -0,0 +1,46 @@
+//! Actor names are shown on leader board, in kill feed and notifications, and leader board
+//! tracks scores by name, so every actor in a match must have a distinct one.
+
+use crate::bot::BotKind;
+use rg3d::{core::rand::seq::SliceRandom, rand};
+
+pub fn name_pool(kind: BotKind) -> &'static [&'static str] {
+    match kind {
+        BotKind::Mutant => &[
+            "Grunt", "Brute", "Ripper", "Gnasher", "Hulk", "Mangler", "Crusher", "Thrasher",
+        ],
+        BotKind::Parasite => &[
+            "Leech", "Tick", "Wriggler", "Burrower", "Sucker", "Crawler", "Itch", "Lamprey",
+        ],
+        BotKind::Maw => &[
+            "Chomper", "Gulper", "Glutton", "Devourer", "Muncher", "Jaws", "Gobbler", "Fang",
+        ],
+    }
+}
+
+/// Returns name as is if it is free, otherwise appends smallest free number to it,
+/// for example "Player (2)".
+pub fn make_unique<F: Fn(&str) -> bool>(name: &str, is_taken: F) -> String {
+    if !is_taken(name) {
+        return name.to_owned();
+    }
+    (2..)
+        .map(|i| format!("{} ({})", name, i))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap()
+}
+
+/// Picks random free name from themed pool of given kind of bot. When the pool is
+/// exhausted, names from it are reused with a number suffix.
+pub fn generate_bot_name<F: Fn(&str) -> bool>(kind: BotKind, is_taken: F) -> String {
+    let pool = name_pool(kind);
+    let free = pool
+        .iter()
+        .filter(|name| !is_taken(name))
+        .collect::<Vec<_>>();
+    let mut rng = rand::thread_rng();
+    match free.choose(&mut rng) {
+        Some(name) => (**name).to_owned(),
+        None => make_unique(pool.choose(&mut rng).unwrap(), is_taken),
+    }
+}
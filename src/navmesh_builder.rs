@@ -0,0 +1,222 @@
+//! Navmesh generation from collision geometry of a level. Every triangle of the `Polygon` node
+//! that is flat enough to stand on and has enough free space above it becomes walkable, shared
+//! vertices are welded so the navmesh knows which triangles are connected. Generation is not
+//! free, so result is cached in a `.navmesh` file next to the map and rebuilt only when the map
+//! is newer than the cache. This way community maps do not need hand-made navigation data.
+
+use crate::{
+    logger::{self, Category},
+    pak, saves,
+};
+use rg3d::{
+    core::{
+        algebra::{Point3, Vector3},
+        math::TriangleDefinition,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    physics3d::{rapier::geometry::InteractionGroups, RayCastOptions},
+    scene::{
+        mesh::{buffer::VertexAttributeUsage, Mesh},
+        node::Node,
+        physics::Physics,
+    },
+    utils::navmesh::Navmesh,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Steepest slope (in radians) bots can walk on.
+const MAX_SLOPE: f32 = 45.0 * std::f32::consts::PI / 180.0;
+
+/// Free space (in meters) required above a triangle to walk on it.
+const AGENT_HEIGHT: f32 = 1.8;
+
+/// Vertices closer than this distance are considered the same vertex.
+const WELD_DISTANCE: f32 = 0.01;
+
+/// Triangles with smaller area are skipped, they are just noise of modelling.
+const MIN_AREA: f32 = 0.0001;
+
+#[derive(Default)]
+pub struct NavmeshData {
+    vertices: Vec<Vector3<f32>>,
+    // Three indices per triangle.
+    indices: Vec<u32>,
+}
+
+impl Visit for NavmeshData {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.vertices.visit("Vertices", visitor)?;
+        self.indices.visit("Indices", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl NavmeshData {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn to_navmesh(&self) -> Navmesh {
+        let triangles = self
+            .indices
+            .chunks(3)
+            .map(|triangle| TriangleDefinition([triangle[0], triangle[1], triangle[2]]))
+            .collect::<Vec<_>>();
+        Navmesh::new(&triangles, &self.vertices)
+    }
+
+    fn add_vertex(&mut self, welded: &mut HashMap<[i32; 3], u32>, vertex: Vector3<f32>) -> u32 {
+        let key = [
+            (vertex.x / WELD_DISTANCE).round() as i32,
+            (vertex.y / WELD_DISTANCE).round() as i32,
+            (vertex.z / WELD_DISTANCE).round() as i32,
+        ];
+        let vertices = &mut self.vertices;
+        *welded.entry(key).or_insert_with(|| {
+            vertices.push(vertex);
+            vertices.len() as u32 - 1
+        })
+    }
+}
+
+/// Returns path of navmesh cache of given map.
+pub fn cache_path(map: &Path) -> PathBuf {
+    map.with_extension("navmesh")
+}
+
+fn has_headroom(position: Vector3<f32>, physics: &Physics) -> bool {
+    let mut query_buffer = Vec::default();
+    physics.cast_ray(
+        RayCastOptions {
+            // Start a bit above the surface, so the triangle itself is not hit.
+            ray_origin: Point3::from(position + Vector3::new(0.0, 0.05, 0.0)),
+            ray_direction: Vector3::y(),
+            max_len: AGENT_HEIGHT,
+            groups: InteractionGroups::all(),
+            sort_results: false,
+        },
+        &mut query_buffer,
+    );
+    !query_buffer.iter().any(|hit| {
+        physics
+            .colliders
+            .get(&hit.collider)
+            .unwrap()
+            .shape()
+            .as_trimesh()
+            .is_some()
+    })
+}
+
+/// Builds walkable surface of given collision mesh. Physics must already have trimesh of the
+/// mesh, it is used to check free space above triangles.
+pub fn generate(mesh: &Mesh, physics: &Physics) -> NavmeshData {
+    let mut data = NavmeshData::default();
+    let mut welded = HashMap::new();
+    let min_normal_y = MAX_SLOPE.cos();
+    let global_transform = mesh.global_transform();
+
+    for surface in mesh.surfaces() {
+        let shared_data = surface.data();
+        let shared_data = shared_data.read().unwrap();
+
+        let positions = shared_data
+            .vertex_buffer
+            .iter()
+            .map(|vertex| {
+                let position = vertex.read_3_f32(VertexAttributeUsage::Position).unwrap();
+                global_transform
+                    .transform_point(&Point3::from(position))
+                    .coords
+            })
+            .collect::<Vec<_>>();
+
+        for triangle in shared_data.geometry_buffer.iter() {
+            let a = positions[triangle[0] as usize];
+            let b = positions[triangle[1] as usize];
+            let c = positions[triangle[2] as usize];
+
+            let normal = (b - a).cross(&(c - a));
+            let double_area = normal.norm();
+            if double_area * 0.5 < MIN_AREA || normal.y / double_area < min_normal_y {
+                continue;
+            }
+
+            let center = (a + b + c).scale(1.0 / 3.0);
+            if !has_headroom(center, physics) {
+                continue;
+            }
+
+            let a = data.add_vertex(&mut welded, a);
+            let b = data.add_vertex(&mut welded, b);
+            let c = data.add_vertex(&mut welded, c);
+            data.indices.extend_from_slice(&[a, b, c]);
+        }
+    }
+
+    data
+}
+
+fn is_cache_valid(map: &Path, cache: &Path) -> bool {
+    let modified = |path: &Path| path.metadata().and_then(|metadata| metadata.modified());
+    match (modified(map), modified(cache)) {
+        (Ok(map), Ok(cache)) => cache >= map,
+        _ => false,
+    }
+}
+
+async fn load_cache(path: &Path) -> Option<NavmeshData> {
+    let mut visitor = Visitor::load_binary(path).await.ok()?;
+    let mut data = NavmeshData::default();
+    data.visit("Navmesh", &mut visitor).ok()?;
+    Some(data)
+}
+
+fn save_cache(data: &mut NavmeshData, path: &Path) {
+    let mut visitor = Visitor::new();
+    let result = data
+        .visit("Navmesh", &mut visitor)
+        .and_then(|_| saves::save_atomically(&mut visitor, path));
+    if let Err(e) = result {
+        // Not fatal, navmesh will be generated again next time.
+        logger::warning(
+            Category::Ai,
+            format!("Unable to save navmesh cache {:?}. Reason: {:?}", path, e),
+        );
+    }
+}
+
+/// Returns navmesh of given map, it is taken from cache if the cache is up to date, otherwise
+/// it is generated from collision mesh `polygon` and cached.
+pub async fn load_or_generate(map: &Path, polygon: &Node, physics: &Physics) -> Navmesh {
+    // Map can be replaced by a mod, cache is kept next to the file that was loaded.
+    let map = pak::resolve(map);
+    let cache = cache_path(&map);
+    if is_cache_valid(&map, &cache) {
+        if let Some(data) = load_cache(&cache).await {
+            return data.to_navmesh();
+        }
+        logger::warning(
+            Category::Ai,
+            format!("Navmesh cache {:?} is damaged, rebuilding it.", cache),
+        );
+    }
+
+    let mut data = generate(polygon.as_mesh(), physics);
+    logger::info(
+        Category::Ai,
+        format!(
+            "Generated navmesh of {} triangles for {:?}.",
+            data.triangle_count(),
+            map
+        ),
+    );
+    save_cache(&mut data, &cache);
+    data.to_navmesh()
+}
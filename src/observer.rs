@@ -0,0 +1,243 @@
+//! Observer mode for casting matches. Observer leaves the match and flies around with free
+//! camera, every player is outlined and HUD shows summary of both teams on the top bar.
+//!
+//! Movement keys fly, jump and crouch keys go up and down, camera turns while right mouse
+//! button is held. Ctrl+1..4 bookmarks current camera position and 1..4 goes back to it.
+//! F jumps to the latest fight, every next press goes to an older one.
+
+use crate::control_scheme::{ControlButton, ControlScheme};
+use rg3d::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    event::{DeviceEvent, ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent},
+    scene::camera::Camera,
+};
+
+const FLY_SPEED: f32 = 10.0;
+
+const BOOKMARK_COUNT: usize = 4;
+
+/// Amount of recent fights observer can jump to.
+const MAX_ACTIONS: usize = 8;
+
+/// Hits closer than this distance to the latest fight are part of that fight.
+const SAME_ACTION_RADIUS: f32 = 5.0;
+
+/// Camera that jumps to a fight is placed this far from it and looks at it from above.
+const ACTION_DISTANCE: f32 = 6.0;
+
+const ACTION_HEIGHT: f32 = 3.0;
+
+#[derive(Copy, Clone)]
+struct Bookmark {
+    position: Vector3<f32>,
+    yaw: f32,
+    pitch: f32,
+}
+
+#[derive(Default)]
+struct Controller {
+    move_forward: bool,
+    move_backward: bool,
+    move_left: bool,
+    move_right: bool,
+    move_up: bool,
+    move_down: bool,
+    look: bool,
+    ctrl: bool,
+}
+
+#[derive(Default)]
+pub struct Observer {
+    position: Vector3<f32>,
+    // Angles in degrees, same as player camera.
+    yaw: f32,
+    pitch: f32,
+    // Not serialized, input state.
+    controller: Controller,
+    // Not serialized, bookmarks are set again after loading.
+    bookmarks: [Option<Bookmark>; BOOKMARK_COUNT],
+    // Not serialized, places of recent fights, latest is last.
+    actions: Vec<Vector3<f32>>,
+    // Not serialized, how many fights back camera has jumped.
+    action_cursor: usize,
+}
+
+impl Visit for Observer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.position.visit("Position", visitor)?;
+        self.yaw.visit("Yaw", visitor)?;
+        self.pitch.visit("Pitch", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+fn bookmark_index(key: VirtualKeyCode) -> Option<usize> {
+    match key {
+        VirtualKeyCode::Key1 => Some(0),
+        VirtualKeyCode::Key2 => Some(1),
+        VirtualKeyCode::Key3 => Some(2),
+        VirtualKeyCode::Key4 => Some(3),
+        _ => None,
+    }
+}
+
+impl Observer {
+    pub fn new(position: Vector3<f32>, yaw: f32) -> Self {
+        Self {
+            position,
+            yaw,
+            ..Default::default()
+        }
+    }
+
+    fn rotation(&self) -> UnitQuaternion<f32> {
+        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw.to_radians())
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch.to_radians())
+    }
+
+    /// Remembers place of a hit, hits next to the latest fight do not make a new one.
+    pub fn add_action(&mut self, position: Vector3<f32>) {
+        if let Some(last) = self.actions.last_mut() {
+            if last.metric_distance(&position) < SAME_ACTION_RADIUS {
+                *last = position;
+                return;
+            }
+        }
+        if self.actions.len() >= MAX_ACTIONS {
+            self.actions.remove(0);
+        }
+        self.actions.push(position);
+        self.action_cursor = 0;
+    }
+
+    fn jump_to_action(&mut self) {
+        if self.actions.is_empty() {
+            return;
+        }
+        let index = self.actions.len() - 1 - self.action_cursor % self.actions.len();
+        self.action_cursor += 1;
+        let target = self.actions[index];
+
+        // Keep looking in the same direction, so jumps do not disorient.
+        let look = self.rotation() * Vector3::z();
+        let flat = Vector3::new(look.x, 0.0, look.z)
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+        self.position =
+            target - flat.scale(ACTION_DISTANCE) + Vector3::new(0.0, ACTION_HEIGHT, 0.0);
+        self.pitch = ACTION_HEIGHT.atan2(ACTION_DISTANCE).to_degrees();
+    }
+
+    pub fn process_input_event(&mut self, event: &Event<()>, control_scheme: &ControlScheme) {
+        match event {
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                if self.controller.look {
+                    let sens = if control_scheme.mouse_y_inverse {
+                        -control_scheme.mouse_sens
+                    } else {
+                        control_scheme.mouse_sens
+                    };
+                    self.yaw -= delta.0 as f32 * control_scheme.mouse_sens;
+                    self.pitch = (self.pitch + delta.1 as f32 * sens).max(-90.0).min(90.0);
+                }
+            }
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::MouseInput { button, state, .. } => {
+                    if *button == MouseButton::Right {
+                        self.controller.look = *state == ElementState::Pressed;
+                    }
+                }
+                WindowEvent::KeyboardInput { input, .. } => {
+                    if let Some(key) = input.virtual_keycode {
+                        let pressed = input.state == ElementState::Pressed;
+                        if let VirtualKeyCode::LControl | VirtualKeyCode::RControl = key {
+                            self.controller.ctrl = pressed;
+                        }
+                        if pressed {
+                            if let Some(index) = bookmark_index(key) {
+                                if self.controller.ctrl {
+                                    self.bookmarks[index] = Some(Bookmark {
+                                        position: self.position,
+                                        yaw: self.yaw,
+                                        pitch: self.pitch,
+                                    });
+                                } else if let Some(bookmark) = self.bookmarks[index] {
+                                    self.position = bookmark.position;
+                                    self.yaw = bookmark.yaw;
+                                    self.pitch = bookmark.pitch;
+                                }
+                            } else if key == VirtualKeyCode::F {
+                                self.jump_to_action();
+                            }
+                        }
+
+                        let button = ControlButton::Key(key);
+                        if button == control_scheme.move_forward.button {
+                            self.controller.move_forward = pressed;
+                        } else if button == control_scheme.move_backward.button {
+                            self.controller.move_backward = pressed;
+                        } else if button == control_scheme.move_left.button {
+                            self.controller.move_left = pressed;
+                        } else if button == control_scheme.move_right.button {
+                            self.controller.move_right = pressed;
+                        } else if button == control_scheme.jump.button {
+                            self.controller.move_up = pressed;
+                        } else if button == control_scheme.crouch.button {
+                            self.controller.move_down = pressed;
+                        }
+                    }
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    /// Moves observer and puts given camera at its place.
+    pub fn update(&mut self, camera: &mut Camera, dt: f32) {
+        let rotation = self.rotation();
+        let look = rotation * Vector3::z();
+        let side = rotation * Vector3::x();
+        let mut velocity = Vector3::default();
+        if self.controller.move_forward {
+            velocity += look;
+        }
+        if self.controller.move_backward {
+            velocity -= look;
+        }
+        if self.controller.move_left {
+            velocity += side;
+        }
+        if self.controller.move_right {
+            velocity -= side;
+        }
+        if self.controller.move_up {
+            velocity += Vector3::y();
+        }
+        if self.controller.move_down {
+            velocity -= Vector3::y();
+        }
+        if let Some(direction) = velocity.try_normalize(std::f32::EPSILON) {
+            self.position += direction.scale(FLY_SPEED * dt);
+        }
+
+        camera
+            .local_transform_mut()
+            .set_position(self.position)
+            .set_rotation(rotation);
+    }
+}
+
+/// Help line of observer's top bar.
+pub const HELP: &str =
+    "Move keys fly, RMB looks, 1-4 go to bookmark, Ctrl+1-4 set bookmark, F jumps to fight, \
+     /observe to play";
@@ -1,7 +1,15 @@
 use crate::{
     control_scheme::{ControlButton, ControlScheme},
-    gui::{create_check_box, create_scroll_bar, create_scroll_viewer, ScrollBarData},
+    display::{self, DisplayMode},
+    gui::{
+        create_check_box, create_scroll_bar, create_scroll_viewer, ScrollBarData, MAX_UI_SCALE,
+        MIN_UI_SCALE,
+    },
+    localization::{tr, LANGUAGES},
+    logger::{self, Category},
     message::Message,
+    settings::{Settings, BACKGROUND_FPS_LIMITS, FPS_LIMITS, TICK_RATES},
+    theme::Palette,
 };
 use rg3d::{
     core::pool::Handle,
@@ -12,6 +20,7 @@ use rg3d::{
         button::{Button, ButtonBuilder, ButtonMessage},
         check_box::CheckBoxMessage,
         decorator::DecoratorBuilder,
+        dropdown_list::{DropdownListBuilder, DropdownListMessage},
         grid::{Column, GridBuilder, Row},
         list_view::{ListViewBuilder, ListViewMessage},
         message::{MessageDirection, UiMessage},
@@ -20,21 +29,95 @@ use rg3d::{
         text::{TextBuilder, TextMessage},
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowTitle},
-        HorizontalAlignment, Orientation, Thickness, UiNode, VerticalAlignment,
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, VerticalAlignment,
     },
     monitor::VideoMode,
-    utils::log::{Log, MessageKind},
-    window::Fullscreen,
 };
 use std::sync::{mpsc::Sender, Arc, RwLock};
 
+/// Video modes of given monitor that are large enough to play in.
+fn video_modes(engine: &Engine, monitor: u32) -> Vec<VideoMode> {
+    let window = engine.get_window();
+    display::pick_monitor(
+        window.available_monitors(),
+        window.primary_monitor(),
+        monitor,
+    )
+    .map_or_else(Vec::new, |monitor| {
+        monitor
+            .video_modes()
+            .filter(|vm| vm.size().width > 800 && vm.size().height > 600 && vm.bit_depth() == 32)
+            .collect()
+    })
+}
+
+fn make_video_mode_items(ctx: &mut BuildContext, video_modes: &[VideoMode]) -> Vec<Handle<UiNode>> {
+    let mut items = Vec::new();
+    for video_mode in video_modes {
+        let size = video_mode.size();
+        let rate = video_mode.refresh_rate();
+        let item = DecoratorBuilder::new(
+            BorderBuilder::new(
+                WidgetBuilder::new().with_child(
+                    TextBuilder::new(
+                        WidgetBuilder::new()
+                            .on_column(0)
+                            .with_height(25.0)
+                            .with_width(200.0),
+                    )
+                    .with_text(format!("{} x {} @ {}Hz", size.width, size.height, rate).as_str())
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                    .build(ctx),
+                ),
+            )
+            .with_stroke_thickness(Thickness {
+                left: 1.0,
+                top: 0.0,
+                right: 1.0,
+                bottom: 1.0,
+            }),
+        )
+        .build(ctx);
+        items.push(item)
+    }
+    items
+}
+
+fn fps_limit_name(limit: u32) -> String {
+    if limit == 0 {
+        tr("Unlimited")
+    } else {
+        format!("{} FPS", limit)
+    }
+}
+
+fn make_dropdown_item(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
+    DecoratorBuilder::new(BorderBuilder::new(
+        WidgetBuilder::new().with_height(30.0).with_child(
+            TextBuilder::new(
+                WidgetBuilder::new()
+                    .with_horizontal_alignment(HorizontalAlignment::Center)
+                    .with_vertical_alignment(VerticalAlignment::Center),
+            )
+            .with_text(text)
+            .build(ctx),
+        ),
+    ))
+    .build(ctx)
+}
+
 pub struct OptionsMenu {
     pub window: Handle<UiNode>,
     sender: Sender<Message>,
     sb_sound_volume: Handle<UiNode>,
     pub sb_music_volume: Handle<UiNode>,
+    sb_voice_volume: Handle<UiNode>,
+    cb_subtitles: Handle<UiNode>,
+    cb_captions: Handle<UiNode>,
     lb_video_modes: Handle<UiNode>,
-    cb_fullscreen: Handle<UiNode>,
+    dd_display_mode: Handle<UiNode>,
+    dd_monitor: Handle<UiNode>,
     cb_spot_shadows: Handle<UiNode>,
     cb_soft_spot_shadows: Handle<UiNode>,
     cb_point_shadows: Handle<UiNode>,
@@ -42,6 +125,22 @@ pub struct OptionsMenu {
     sb_point_shadow_distance: Handle<UiNode>,
     sb_spot_shadow_distance: Handle<UiNode>,
     cb_use_light_scatter: Handle<UiNode>,
+    sb_tracer_density: Handle<UiNode>,
+    cb_minimal_hud: Handle<UiNode>,
+    sb_lod_near_distance: Handle<UiNode>,
+    sb_lod_far_distance: Handle<UiNode>,
+    dd_tick_rate: Handle<UiNode>,
+    dd_palette: Handle<UiNode>,
+    sb_ui_scale: Handle<UiNode>,
+    dd_language: Handle<UiNode>,
+    cb_weapon_telemetry: Handle<UiNode>,
+    cb_match_logs: Handle<UiNode>,
+    cb_host_on_lan: Handle<UiNode>,
+    cb_hit_markers: Handle<UiNode>,
+    cb_kill_marker: Handle<UiNode>,
+    sb_afk_timeout: Handle<UiNode>,
+    dd_fps_limit: Handle<UiNode>,
+    dd_background_fps_limit: Handle<UiNode>,
     video_modes: Vec<VideoMode>,
     control_scheme: Arc<RwLock<ControlScheme>>,
     control_scheme_buttons: Vec<Handle<UiNode>>,
@@ -50,24 +149,30 @@ pub struct OptionsMenu {
     cb_mouse_y_inverse: Handle<UiNode>,
     cb_smooth_mouse: Handle<UiNode>,
     cb_shake_camera: Handle<UiNode>,
+    sb_camera_effects: Handle<UiNode>,
+    cb_reduced_motion: Handle<UiNode>,
+    sb_damage_effects: Handle<UiNode>,
     btn_reset_control_scheme: Handle<UiNode>,
     cb_use_hrtf: Handle<UiNode>,
     btn_reset_audio_settings: Handle<UiNode>,
+    game_settings: Arc<RwLock<Settings>>,
 }
 
 impl OptionsMenu {
     pub fn new(
         engine: &mut Engine,
         control_scheme: Arc<RwLock<ControlScheme>>,
+        game_settings: Arc<RwLock<Settings>>,
         sender: Sender<Message>,
     ) -> Self {
-        let video_modes: Vec<VideoMode> = engine
+        let monitor = game_settings.read().unwrap().monitor;
+        let video_modes = video_modes(engine, monitor);
+        let monitor_names = engine
             .get_window()
-            .primary_monitor()
-            .unwrap()
-            .video_modes()
-            .filter(|vm| vm.size().width > 800 && vm.size().height > 600 && vm.bit_depth() == 32)
-            .collect();
+            .available_monitors()
+            .enumerate()
+            .map(|(i, monitor)| display::monitor_name(i, &monitor))
+            .collect::<Vec<_>>();
 
         let ctx = &mut engine.user_interface.build_ctx();
 
@@ -79,8 +184,12 @@ impl OptionsMenu {
 
         let sb_sound_volume;
         let sb_music_volume;
+        let sb_voice_volume;
+        let cb_subtitles;
+        let cb_captions;
         let lb_video_modes;
-        let cb_fullscreen;
+        let dd_display_mode;
+        let dd_monitor;
         let cb_spot_shadows;
         let cb_soft_spot_shadows;
         let cb_point_shadows;
@@ -91,16 +200,35 @@ impl OptionsMenu {
         let cb_mouse_y_inverse;
         let cb_smooth_mouse;
         let cb_shake_camera;
+        let sb_camera_effects;
+        let cb_reduced_motion;
+        let sb_damage_effects;
         let btn_reset_control_scheme;
         let mut control_scheme_buttons = Vec::new();
         let cb_use_hrtf;
         let btn_reset_audio_settings;
         let cb_use_light_scatter;
+        let sb_tracer_density;
+        let cb_minimal_hud;
+        let sb_lod_near_distance;
+        let sb_lod_far_distance;
+        let dd_tick_rate;
+        let dd_palette;
+        let sb_ui_scale;
+        let dd_language;
+        let cb_weapon_telemetry;
+        let cb_match_logs;
+        let cb_host_on_lan;
+        let cb_hit_markers;
+        let cb_kill_marker;
+        let sb_afk_timeout;
+        let dd_fps_limit;
+        let dd_background_fps_limit;
         let tab_control = TabControlBuilder::new(WidgetBuilder::new())
             .with_tab(TabDefinition {
                 header: {
                     TextBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(30.0))
-                        .with_text("Graphics")
+                        .with_text(tr("Graphics"))
                         .build(ctx)
                 },
                 content: {
@@ -114,7 +242,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Resolution")
+                                .with_text(tr("Resolution"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -126,210 +254,709 @@ impl OptionsMenu {
                                         .with_margin(margin),
                                 )
                                 .with_scroll_viewer(create_scroll_viewer(ctx))
-                                .with_items({
-                                    let mut items = Vec::new();
-                                    for video_mode in video_modes.iter() {
-                                        let size = video_mode.size();
-                                        let rate = video_mode.refresh_rate();
-                                        let item = DecoratorBuilder::new(
-                                            BorderBuilder::new(
-                                                WidgetBuilder::new().with_child(
+                                .with_items(make_video_mode_items(ctx, &video_modes))
+                                .build(ctx);
+                                lb_video_modes
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(1)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Display Mode"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                let display_mode = game_settings.read().unwrap().display_mode;
+                                dd_display_mode = DropdownListBuilder::new(
+                                    WidgetBuilder::new().on_column(0).with_margin(margin),
+                                )
+                                .with_items(
+                                    DisplayMode::ALL
+                                        .iter()
+                                        .map(|mode| make_dropdown_item(ctx, &tr(mode.name())))
+                                        .collect(),
+                                )
+                                .with_selected(display_mode.id() as usize)
+                                .build(ctx);
+                                dd_monitor = DropdownListBuilder::new(
+                                    WidgetBuilder::new().on_column(1).with_margin(margin),
+                                )
+                                .with_items(
+                                    monitor_names
+                                        .iter()
+                                        .map(|name| make_dropdown_item(ctx, name))
+                                        .collect(),
+                                )
+                                .with_selected(
+                                    (monitor as usize).min(monitor_names.len().saturating_sub(1)),
+                                )
+                                .build(ctx);
+                                GridBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(1)
+                                        .on_column(1)
+                                        .with_child(dd_display_mode)
+                                        .with_child(dd_monitor),
+                                )
+                                .add_row(Row::stretch())
+                                .add_column(Column::stretch())
+                                .add_column(Column::stretch())
+                                .build(ctx)
+                            })
+                            // Spot Shadows Enabled
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(2)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Spot Shadows"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_spot_shadows =
+                                    create_check_box(ctx, 2, 1, settings.spot_shadows_enabled);
+                                cb_spot_shadows
+                            })
+                            // Soft Spot Shadows
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(3)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Soft Spot Shadows"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_soft_spot_shadows =
+                                    create_check_box(ctx, 3, 1, settings.spot_soft_shadows);
+                                cb_soft_spot_shadows
+                            })
+                            // Spot Shadows Distance
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(4)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Spot Shadows Distance"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_spot_shadow_distance = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 1.0,
+                                        max: 15.0,
+                                        value: settings.spot_shadows_distance,
+                                        step: 0.25,
+                                        row: 4,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_spot_shadow_distance
+                            })
+                            // Point Shadows Enabled
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(5)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Point Shadows"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_point_shadows =
+                                    create_check_box(ctx, 5, 1, settings.point_shadows_enabled);
+                                cb_point_shadows
+                            })
+                            // Soft Point Shadows
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(6)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Soft Point Shadows"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_soft_point_shadows =
+                                    create_check_box(ctx, 6, 1, settings.point_soft_shadows);
+                                cb_soft_point_shadows
+                            })
+                            // Point Shadows Distance
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(7)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Point Shadows Distance"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_point_shadow_distance = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 1.0,
+                                        max: 15.0,
+                                        value: settings.point_shadows_distance,
+                                        step: 0.25,
+                                        row: 7,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_point_shadow_distance
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(8)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Use Light Scatter"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_use_light_scatter =
+                                    create_check_box(ctx, 8, 1, settings.light_scatter_enabled);
+                                cb_use_light_scatter
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(9)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Tracer Density"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_tracer_density = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 0.0,
+                                        max: 1.0,
+                                        value: game_settings.read().unwrap().tracer_density,
+                                        step: 0.1,
+                                        row: 9,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_tracer_density
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(10)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Animation Cull Distance"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_lod_near_distance = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 5.0,
+                                        max: 50.0,
+                                        value: game_settings.read().unwrap().lod_near_distance,
+                                        step: 1.0,
+                                        row: 10,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_lod_near_distance
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(11)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Shadow Cull Distance"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_lod_far_distance = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 10.0,
+                                        max: 100.0,
+                                        value: game_settings.read().unwrap().lod_far_distance,
+                                        step: 1.0,
+                                        row: 11,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_lod_far_distance
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(12)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Tick Rate"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                let tick_rate = game_settings.read().unwrap().tick_rate;
+                                dd_tick_rate = DropdownListBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(12)
+                                        .on_column(1)
+                                        .with_margin(margin),
+                                )
+                                .with_items(
+                                    TICK_RATES
+                                        .iter()
+                                        .map(|rate| {
+                                            DecoratorBuilder::new(BorderBuilder::new(
+                                                WidgetBuilder::new().with_height(30.0).with_child(
                                                     TextBuilder::new(
                                                         WidgetBuilder::new()
-                                                            .on_column(0)
-                                                            .with_height(25.0)
-                                                            .with_width(200.0),
-                                                    )
-                                                    .with_text(
-                                                        format!(
-                                                            "{} x {} @ {}Hz",
-                                                            size.width, size.height, rate
-                                                        )
-                                                        .as_str(),
+                                                            .with_horizontal_alignment(
+                                                                HorizontalAlignment::Center,
+                                                            )
+                                                            .with_vertical_alignment(
+                                                                VerticalAlignment::Center,
+                                                            ),
                                                     )
-                                                    .with_vertical_text_alignment(
-                                                        VerticalAlignment::Center,
+                                                    .with_text(format!("{} Hz", rate))
+                                                    .build(ctx),
+                                                ),
+                                            ))
+                                            .build(ctx)
+                                        })
+                                        .collect(),
+                                )
+                                .with_selected(
+                                    TICK_RATES.iter().position(|r| *r == tick_rate).unwrap_or(1),
+                                )
+                                .build(ctx);
+                                dd_tick_rate
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(13)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Color Palette"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                let palette = game_settings.read().unwrap().palette;
+                                dd_palette = DropdownListBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(13)
+                                        .on_column(1)
+                                        .with_margin(margin),
+                                )
+                                .with_items(
+                                    Palette::ALL
+                                        .iter()
+                                        .map(|palette| {
+                                            DecoratorBuilder::new(BorderBuilder::new(
+                                                WidgetBuilder::new().with_height(30.0).with_child(
+                                                    TextBuilder::new(
+                                                        WidgetBuilder::new()
+                                                            .with_horizontal_alignment(
+                                                                HorizontalAlignment::Center,
+                                                            )
+                                                            .with_vertical_alignment(
+                                                                VerticalAlignment::Center,
+                                                            ),
                                                     )
-                                                    .with_horizontal_text_alignment(
-                                                        HorizontalAlignment::Center,
+                                                    .with_text(tr(palette.name()))
+                                                    .build(ctx),
+                                                ),
+                                            ))
+                                            .build(ctx)
+                                        })
+                                        .collect(),
+                                )
+                                .with_selected(palette.id() as usize)
+                                .build(ctx);
+                                dd_palette
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(14)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("UI Scale"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_ui_scale = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: MIN_UI_SCALE,
+                                        max: MAX_UI_SCALE,
+                                        value: game_settings.read().unwrap().ui_scale,
+                                        step: 0.1,
+                                        row: 14,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_ui_scale
+                            }),
+                    )
+                    .add_row(Row::strict(200.0))
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_column(Column::strict(250.0))
+                    .add_column(Column::stretch())
+                    .build(ctx)
+                },
+            })
+            .with_tab(TabDefinition {
+                header: {
+                    TextBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(30.0))
+                        .with_text(tr("General"))
+                        .build(ctx)
+                },
+                content: {
+                    GridBuilder::new(
+                        WidgetBuilder::new()
+                            .with_margin(Thickness::uniform(5.0))
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(0)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Language"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                let language = game_settings.read().unwrap().language.clone();
+                                dd_language = DropdownListBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(0)
+                                        .on_column(1)
+                                        .with_margin(margin),
+                                )
+                                .with_items(
+                                    LANGUAGES
+                                        .iter()
+                                        .map(|language| {
+                                            DecoratorBuilder::new(BorderBuilder::new(
+                                                WidgetBuilder::new().with_height(30.0).with_child(
+                                                    TextBuilder::new(
+                                                        WidgetBuilder::new()
+                                                            .with_horizontal_alignment(
+                                                                HorizontalAlignment::Center,
+                                                            )
+                                                            .with_vertical_alignment(
+                                                                VerticalAlignment::Center,
+                                                            ),
                                                     )
+                                                    .with_text(language.name)
                                                     .build(ctx),
                                                 ),
-                                            )
-                                            .with_stroke_thickness(Thickness {
-                                                left: 1.0,
-                                                top: 0.0,
-                                                right: 1.0,
-                                                bottom: 1.0,
-                                            }),
-                                        )
-                                        .build(ctx);
-                                        items.push(item)
-                                    }
-                                    items
-                                })
+                                            ))
+                                            .build(ctx)
+                                        })
+                                        .collect(),
+                                )
+                                .with_selected(
+                                    LANGUAGES
+                                        .iter()
+                                        .position(|l| l.code == language)
+                                        .unwrap_or(0),
+                                )
                                 .build(ctx);
-                                lb_video_modes
+                                dd_language
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(1)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Minimal HUD"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_minimal_hud = create_check_box(
+                                    ctx,
+                                    1,
+                                    1,
+                                    game_settings.read().unwrap().minimal_hud,
+                                );
+                                cb_minimal_hud
                             })
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(1)
+                                        .on_row(2)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Fullscreen")
+                                .with_text(tr("Weapon Telemetry"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_fullscreen = create_check_box(ctx, 1, 1, false);
-                                cb_fullscreen
+                                cb_weapon_telemetry = create_check_box(
+                                    ctx,
+                                    2,
+                                    1,
+                                    game_settings.read().unwrap().weapon_telemetry,
+                                );
+                                cb_weapon_telemetry
                             })
-                            // Spot Shadows Enabled
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(2)
+                                        .on_row(3)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Spot Shadows")
+                                .with_text(tr("Crosshair Hit Markers"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_spot_shadows =
-                                    create_check_box(ctx, 2, 1, settings.spot_shadows_enabled);
-                                cb_spot_shadows
+                                cb_hit_markers = create_check_box(
+                                    ctx,
+                                    3,
+                                    1,
+                                    game_settings.read().unwrap().hit_markers,
+                                );
+                                cb_hit_markers
                             })
-                            // Soft Spot Shadows
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(3)
+                                        .on_row(4)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Soft Spot Shadows")
+                                .with_text(tr("Crosshair Kill Marker"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_soft_spot_shadows =
-                                    create_check_box(ctx, 3, 1, settings.spot_soft_shadows);
-                                cb_soft_spot_shadows
+                                cb_kill_marker = create_check_box(
+                                    ctx,
+                                    4,
+                                    1,
+                                    game_settings.read().unwrap().kill_marker,
+                                );
+                                cb_kill_marker
                             })
-                            // Spot Shadows Distance
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(4)
+                                        .on_row(5)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Spot Shadows Distance")
+                                .with_text(tr("AFK Timeout (s)"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                sb_spot_shadow_distance = create_scroll_bar(
+                                sb_afk_timeout = create_scroll_bar(
                                     ctx,
                                     ScrollBarData {
-                                        min: 1.0,
-                                        max: 15.0,
-                                        value: settings.spot_shadows_distance,
-                                        step: 0.25,
-                                        row: 4,
+                                        min: 0.0,
+                                        max: 600.0,
+                                        value: game_settings.read().unwrap().afk_timeout,
+                                        step: 15.0,
+                                        row: 5,
                                         column: 1,
                                         margin,
                                         show_value: true,
                                         orientation: Orientation::Horizontal,
                                     },
                                 );
-                                sb_spot_shadow_distance
+                                sb_afk_timeout
                             })
-                            // Point Shadows Enabled
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(5)
+                                        .on_row(6)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Point Shadows")
+                                .with_text(tr("FPS Limit"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_point_shadows =
-                                    create_check_box(ctx, 5, 1, settings.point_shadows_enabled);
-                                cb_point_shadows
+                                let fps_limit = game_settings.read().unwrap().fps_limit;
+                                dd_fps_limit = DropdownListBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(6)
+                                        .on_column(1)
+                                        .with_margin(margin),
+                                )
+                                .with_items(
+                                    FPS_LIMITS
+                                        .iter()
+                                        .map(|limit| {
+                                            make_dropdown_item(ctx, &fps_limit_name(*limit))
+                                        })
+                                        .collect(),
+                                )
+                                .with_selected(
+                                    FPS_LIMITS.iter().position(|l| *l == fps_limit).unwrap_or(0),
+                                )
+                                .build(ctx);
+                                dd_fps_limit
                             })
-                            // Soft Point Shadows
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(6)
+                                        .on_row(7)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Soft Point Shadows")
+                                .with_text(tr("Background FPS Limit"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_soft_point_shadows =
-                                    create_check_box(ctx, 6, 1, settings.point_soft_shadows);
-                                cb_soft_point_shadows
+                                let limit = game_settings.read().unwrap().background_fps_limit;
+                                dd_background_fps_limit = DropdownListBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(7)
+                                        .on_column(1)
+                                        .with_margin(margin),
+                                )
+                                .with_items(
+                                    BACKGROUND_FPS_LIMITS
+                                        .iter()
+                                        .map(|limit| {
+                                            make_dropdown_item(ctx, &fps_limit_name(*limit))
+                                        })
+                                        .collect(),
+                                )
+                                .with_selected(
+                                    BACKGROUND_FPS_LIMITS
+                                        .iter()
+                                        .position(|l| *l == limit)
+                                        .unwrap_or(1),
+                                )
+                                .build(ctx);
+                                dd_background_fps_limit
                             })
-                            // Point Shadows Distance
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(7)
+                                        .on_row(8)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Point Shadows Distance")
+                                .with_text(tr("Match Logs"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                sb_point_shadow_distance = create_scroll_bar(
+                                cb_match_logs = create_check_box(
                                     ctx,
-                                    ScrollBarData {
-                                        min: 1.0,
-                                        max: 15.0,
-                                        value: settings.point_shadows_distance,
-                                        step: 0.25,
-                                        row: 7,
-                                        column: 1,
-                                        margin,
-                                        show_value: true,
-                                        orientation: Orientation::Horizontal,
-                                    },
+                                    8,
+                                    1,
+                                    game_settings.read().unwrap().match_logs,
                                 );
-                                sb_point_shadow_distance
+                                cb_match_logs
                             })
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(8)
+                                        .on_row(9)
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Use Light Scatter")
+                                .with_text(tr("Host on LAN"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_use_light_scatter =
-                                    create_check_box(ctx, 8, 1, settings.light_scatter_enabled);
-                                cb_use_light_scatter
+                                cb_host_on_lan = create_check_box(
+                                    ctx,
+                                    9,
+                                    1,
+                                    game_settings.read().unwrap().host_on_lan,
+                                );
+                                cb_host_on_lan
                             }),
                     )
-                    .add_row(Row::strict(200.0))
+                    .add_row(common_row)
+                    .add_row(common_row)
                     .add_row(common_row)
                     .add_row(common_row)
                     .add_row(common_row)
@@ -346,7 +973,7 @@ impl OptionsMenu {
             .with_tab(TabDefinition {
                 header: {
                     TextBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(30.0))
-                        .with_text("Sound")
+                        .with_text(tr("Sound"))
                         .build(ctx)
                 },
                 content: {
@@ -359,7 +986,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Sound Volume")
+                                .with_text(tr("Sound Volume"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -387,7 +1014,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Music Volume")
+                                .with_text(tr("Music Volume"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -415,19 +1042,87 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Use HRTF")
+                                .with_text(tr("Voice Volume"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_voice_volume = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 0.0,
+                                        max: 1.0,
+                                        value: game_settings.read().unwrap().voice_volume,
+                                        step: 0.025,
+                                        row: 2,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_voice_volume
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(3)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Subtitles"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_subtitles = create_check_box(
+                                    ctx,
+                                    3,
+                                    1,
+                                    game_settings.read().unwrap().subtitles,
+                                );
+                                cb_subtitles
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(4)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Captions"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_captions = create_check_box(
+                                    ctx,
+                                    4,
+                                    1,
+                                    game_settings.read().unwrap().captions,
+                                );
+                                cb_captions
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(5)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Use HRTF"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
                             .with_child({
-                                cb_use_hrtf = create_check_box(ctx, 2, 1, true);
+                                cb_use_hrtf = create_check_box(ctx, 5, 1, true);
                                 cb_use_hrtf
                             })
                             .with_child({
                                 btn_reset_audio_settings = ButtonBuilder::new(
-                                    WidgetBuilder::new().on_row(3).with_margin(margin),
+                                    WidgetBuilder::new().on_row(6).with_margin(margin),
                                 )
-                                .with_text("Reset")
+                                .with_text(&tr("Reset"))
                                 .build(ctx);
                                 btn_reset_audio_settings
                             }),
@@ -436,6 +1131,9 @@ impl OptionsMenu {
                     .add_row(common_row)
                     .add_row(common_row)
                     .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
                     .add_column(Column::strict(250.0))
                     .add_column(Column::stretch())
                     .build(ctx)
@@ -444,7 +1142,7 @@ impl OptionsMenu {
             .with_tab(TabDefinition {
                 header: {
                     TextBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(30.0))
-                        .with_text("Controls")
+                        .with_text(tr("Controls"))
                         .build(ctx)
                 },
                 content: {
@@ -453,7 +1151,7 @@ impl OptionsMenu {
                     for (row, button) in control_scheme.read().unwrap().buttons().iter().enumerate()
                     {
                         // Offset by total amount of rows that goes before
-                        let row = row + 4;
+                        let row = row + 7;
 
                         let text = TextBuilder::new(
                             WidgetBuilder::new()
@@ -461,7 +1159,7 @@ impl OptionsMenu {
                                 .on_column(0)
                                 .with_margin(margin),
                         )
-                        .with_text(button.description.as_str())
+                        .with_text(tr(&button.description))
                         .with_vertical_text_alignment(VerticalAlignment::Center)
                         .build(ctx);
                         children.push(text);
@@ -487,7 +1185,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Mouse Sensitivity")
+                                .with_text(tr("Mouse Sensitivity"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -515,7 +1213,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Inverse Mouse Y")
+                                .with_text(tr("Inverse Mouse Y"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -535,7 +1233,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Smooth Mouse")
+                                .with_text(tr("Smooth Mouse"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -555,7 +1253,7 @@ impl OptionsMenu {
                                         .on_column(0)
                                         .with_margin(margin),
                                 )
-                                .with_text("Shake Camera")
+                                .with_text(tr("Shake Camera"))
                                 .with_vertical_text_alignment(VerticalAlignment::Center)
                                 .build(ctx),
                             )
@@ -568,13 +1266,89 @@ impl OptionsMenu {
                                 );
                                 cb_shake_camera
                             })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(4)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Camera Effects"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_camera_effects = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 0.0,
+                                        max: 1.0,
+                                        value: game_settings.read().unwrap().camera_effects,
+                                        step: 0.1,
+                                        row: 4,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_camera_effects
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(5)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Reduced Motion"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                cb_reduced_motion = create_check_box(
+                                    ctx,
+                                    5,
+                                    1,
+                                    game_settings.read().unwrap().reduced_motion,
+                                );
+                                cb_reduced_motion
+                            })
+                            .with_child(
+                                TextBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(6)
+                                        .on_column(0)
+                                        .with_margin(margin),
+                                )
+                                .with_text(tr("Damage Effects"))
+                                .with_vertical_text_alignment(VerticalAlignment::Center)
+                                .build(ctx),
+                            )
+                            .with_child({
+                                sb_damage_effects = create_scroll_bar(
+                                    ctx,
+                                    ScrollBarData {
+                                        min: 0.0,
+                                        max: 1.0,
+                                        value: game_settings.read().unwrap().damage_effects,
+                                        step: 0.1,
+                                        row: 6,
+                                        column: 1,
+                                        margin,
+                                        show_value: true,
+                                        orientation: Orientation::Horizontal,
+                                    },
+                                );
+                                sb_damage_effects
+                            })
                             .with_child({
                                 btn_reset_control_scheme = ButtonBuilder::new(
                                     WidgetBuilder::new()
-                                        .on_row(4 + control_scheme.read().unwrap().buttons().len())
+                                        .on_row(7 + control_scheme.read().unwrap().buttons().len())
                                         .with_margin(margin),
                                 )
-                                .with_text("Reset")
+                                .with_text(&tr("Reset"))
                                 .build(ctx);
                                 btn_reset_control_scheme
                             })
@@ -586,6 +1360,9 @@ impl OptionsMenu {
                     .add_row(common_row)
                     .add_row(common_row)
                     .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
+                    .add_row(common_row)
                     .add_rows(
                         (0..control_scheme.read().unwrap().buttons().len())
                             .map(|_| common_row)
@@ -599,7 +1376,7 @@ impl OptionsMenu {
 
         let options_window: Handle<UiNode> =
             WindowBuilder::new(WidgetBuilder::new().with_width(500.0))
-                .with_title(WindowTitle::text("Options"))
+                .with_title(WindowTitle::text(tr("Options")))
                 .open(false)
                 .with_content(tab_control)
                 .build(ctx);
@@ -609,8 +1386,12 @@ impl OptionsMenu {
             window: options_window,
             sb_sound_volume,
             sb_music_volume,
+            sb_voice_volume,
+            cb_subtitles,
+            cb_captions,
             lb_video_modes,
-            cb_fullscreen,
+            dd_display_mode,
+            dd_monitor,
             cb_spot_shadows,
             cb_soft_spot_shadows,
             cb_point_shadows,
@@ -625,10 +1406,30 @@ impl OptionsMenu {
             cb_mouse_y_inverse,
             cb_smooth_mouse,
             cb_shake_camera,
+            sb_camera_effects,
+            cb_reduced_motion,
+            sb_damage_effects,
             btn_reset_control_scheme,
             cb_use_hrtf,
             btn_reset_audio_settings,
             cb_use_light_scatter,
+            sb_tracer_density,
+            cb_minimal_hud,
+            sb_lod_near_distance,
+            sb_lod_far_distance,
+            dd_tick_rate,
+            dd_palette,
+            sb_ui_scale,
+            dd_language,
+            cb_weapon_telemetry,
+            cb_match_logs,
+            cb_host_on_lan,
+            cb_hit_markers,
+            cb_kill_marker,
+            sb_afk_timeout,
+            dd_fps_limit,
+            dd_background_fps_limit,
+            game_settings,
         }
     }
 
@@ -652,6 +1453,42 @@ impl OptionsMenu {
         sync_check_box(self.cb_mouse_y_inverse, control_scheme.mouse_y_inverse);
         sync_check_box(self.cb_smooth_mouse, control_scheme.smooth_mouse);
         sync_check_box(self.cb_shake_camera, control_scheme.shake_camera);
+        sync_check_box(
+            self.cb_reduced_motion,
+            self.game_settings.read().unwrap().reduced_motion,
+        );
+        sync_check_box(
+            self.cb_minimal_hud,
+            self.game_settings.read().unwrap().minimal_hud,
+        );
+        sync_check_box(
+            self.cb_subtitles,
+            self.game_settings.read().unwrap().subtitles,
+        );
+        sync_check_box(
+            self.cb_captions,
+            self.game_settings.read().unwrap().captions,
+        );
+        sync_check_box(
+            self.cb_weapon_telemetry,
+            self.game_settings.read().unwrap().weapon_telemetry,
+        );
+        sync_check_box(
+            self.cb_match_logs,
+            self.game_settings.read().unwrap().match_logs,
+        );
+        sync_check_box(
+            self.cb_host_on_lan,
+            self.game_settings.read().unwrap().host_on_lan,
+        );
+        sync_check_box(
+            self.cb_hit_markers,
+            self.game_settings.read().unwrap().hit_markers,
+        );
+        sync_check_box(
+            self.cb_kill_marker,
+            self.game_settings.read().unwrap().kill_marker,
+        );
         let is_hrtf = true; /*if let rg3d::sound::renderer::Renderer::HrtfRenderer(_) =
                                 engine.sound_context.lock().unwrap().renderer()
                             {
@@ -674,10 +1511,73 @@ impl OptionsMenu {
         );
         sync_scroll_bar(self.sb_spot_shadow_distance, settings.spot_shadows_distance);
         sync_scroll_bar(self.sb_mouse_sens, control_scheme.mouse_sens);
+        sync_scroll_bar(
+            self.sb_camera_effects,
+            self.game_settings.read().unwrap().camera_effects,
+        );
+        sync_scroll_bar(
+            self.sb_damage_effects,
+            self.game_settings.read().unwrap().damage_effects,
+        );
+        sync_scroll_bar(
+            self.sb_tracer_density,
+            self.game_settings.read().unwrap().tracer_density,
+        );
+        sync_scroll_bar(
+            self.sb_lod_near_distance,
+            self.game_settings.read().unwrap().lod_near_distance,
+        );
+        sync_scroll_bar(
+            self.sb_lod_far_distance,
+            self.game_settings.read().unwrap().lod_far_distance,
+        );
+        let tick_rate = self.game_settings.read().unwrap().tick_rate;
+        ui.send_message(DropdownListMessage::selection(
+            self.dd_tick_rate,
+            MessageDirection::ToWidget,
+            TICK_RATES.iter().position(|r| *r == tick_rate),
+        ));
+        let fps_limit = self.game_settings.read().unwrap().fps_limit;
+        ui.send_message(DropdownListMessage::selection(
+            self.dd_fps_limit,
+            MessageDirection::ToWidget,
+            FPS_LIMITS.iter().position(|l| *l == fps_limit),
+        ));
+        let background_fps_limit = self.game_settings.read().unwrap().background_fps_limit;
+        ui.send_message(DropdownListMessage::selection(
+            self.dd_background_fps_limit,
+            MessageDirection::ToWidget,
+            BACKGROUND_FPS_LIMITS
+                .iter()
+                .position(|l| *l == background_fps_limit),
+        ));
+        ui.send_message(DropdownListMessage::selection(
+            self.dd_palette,
+            MessageDirection::ToWidget,
+            Some(self.game_settings.read().unwrap().palette.id() as usize),
+        ));
+        sync_scroll_bar(
+            self.sb_ui_scale,
+            self.game_settings.read().unwrap().ui_scale,
+        );
+        sync_scroll_bar(
+            self.sb_afk_timeout,
+            self.game_settings.read().unwrap().afk_timeout,
+        );
+        let language = self.game_settings.read().unwrap().language.clone();
+        ui.send_message(DropdownListMessage::selection(
+            self.dd_language,
+            MessageDirection::ToWidget,
+            LANGUAGES.iter().position(|l| l.code == language),
+        ));
         sync_scroll_bar(
             self.sb_sound_volume,
             engine.sound_engine.lock().unwrap().master_gain(),
         );
+        sync_scroll_bar(
+            self.sb_voice_volume,
+            self.game_settings.read().unwrap().voice_volume,
+        );
 
         for (btn, def) in self
             .control_scheme_buttons
@@ -770,6 +1670,41 @@ impl OptionsMenu {
                     settings.spot_shadows_distance = *new_value;
                 } else if message.destination() == self.sb_mouse_sens {
                     self.control_scheme.write().unwrap().mouse_sens = *new_value;
+                } else if message.destination() == self.sb_camera_effects {
+                    self.game_settings.write().unwrap().camera_effects = *new_value;
+                } else if message.destination() == self.sb_damage_effects {
+                    self.game_settings.write().unwrap().damage_effects = *new_value;
+                } else if message.destination() == self.sb_tracer_density {
+                    self.game_settings.write().unwrap().tracer_density = *new_value;
+                } else if message.destination() == self.sb_lod_near_distance {
+                    let mut game_settings = self.game_settings.write().unwrap();
+                    game_settings.lod_near_distance = *new_value;
+                    // Far distance is never less than near one, other slider follows.
+                    if game_settings.lod_far_distance < *new_value {
+                        game_settings.lod_far_distance = *new_value;
+                        engine.user_interface.send_message(ScrollBarMessage::value(
+                            self.sb_lod_far_distance,
+                            MessageDirection::ToWidget,
+                            *new_value,
+                        ));
+                    }
+                } else if message.destination() == self.sb_lod_far_distance {
+                    let mut game_settings = self.game_settings.write().unwrap();
+                    game_settings.lod_far_distance = *new_value;
+                    if game_settings.lod_near_distance > *new_value {
+                        game_settings.lod_near_distance = *new_value;
+                        engine.user_interface.send_message(ScrollBarMessage::value(
+                            self.sb_lod_near_distance,
+                            MessageDirection::ToWidget,
+                            *new_value,
+                        ));
+                    }
+                } else if message.destination() == self.sb_ui_scale {
+                    self.game_settings.write().unwrap().ui_scale = *new_value;
+                } else if message.destination() == self.sb_afk_timeout {
+                    self.game_settings.write().unwrap().afk_timeout = *new_value;
+                } else if message.destination() == self.sb_voice_volume {
+                    self.game_settings.write().unwrap().voice_volume = *new_value;
                 } else if message.destination() == self.sb_music_volume {
                     self.sender
                         .send(Message::SetMusicVolume { volume: *new_value })
@@ -779,10 +1714,81 @@ impl OptionsMenu {
         } else if let Some(ListViewMessage::SelectionChanged(new_value)) = message.data() {
             if message.destination() == self.lb_video_modes {
                 if let Some(index) = new_value {
+                    // Picking a resolution switches to exclusive fullscreen with it.
                     let video_mode = self.video_modes[*index].clone();
+                    let mut game_settings = self.game_settings.write().unwrap();
+                    game_settings.display_mode = DisplayMode::Fullscreen;
+                    display::apply(
+                        engine.get_window(),
+                        DisplayMode::Fullscreen,
+                        game_settings.monitor,
+                        Some(video_mode),
+                    );
                     engine
-                        .get_window()
-                        .set_fullscreen(Some(Fullscreen::Exclusive(video_mode)))
+                        .user_interface
+                        .send_message(DropdownListMessage::selection(
+                            self.dd_display_mode,
+                            MessageDirection::ToWidget,
+                            Some(DisplayMode::Fullscreen.id() as usize),
+                        ));
+                }
+            }
+        } else if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
+            if message.destination() == self.dd_tick_rate
+                && message.direction() == MessageDirection::FromWidget
+            {
+                self.game_settings.write().unwrap().tick_rate = TICK_RATES[*index];
+            } else if message.destination() == self.dd_fps_limit
+                && message.direction() == MessageDirection::FromWidget
+            {
+                self.game_settings.write().unwrap().fps_limit = FPS_LIMITS[*index];
+            } else if message.destination() == self.dd_background_fps_limit
+                && message.direction() == MessageDirection::FromWidget
+            {
+                self.game_settings.write().unwrap().background_fps_limit =
+                    BACKGROUND_FPS_LIMITS[*index];
+            } else if message.destination() == self.dd_palette
+                && message.direction() == MessageDirection::FromWidget
+            {
+                self.game_settings.write().unwrap().palette = Palette::ALL[*index];
+            } else if message.destination() == self.dd_language
+                && message.direction() == MessageDirection::FromWidget
+            {
+                let language = LANGUAGES[*index].code.to_owned();
+                self.game_settings.write().unwrap().language = language.clone();
+                self.sender.send(Message::SetLanguage { language }).unwrap();
+            } else if message.destination() == self.dd_display_mode
+                && message.direction() == MessageDirection::FromWidget
+            {
+                let mode = DisplayMode::ALL[*index];
+                let mut game_settings = self.game_settings.write().unwrap();
+                if game_settings.display_mode != mode {
+                    game_settings.display_mode = mode;
+                    display::apply(engine.get_window(), mode, game_settings.monitor, None);
+                }
+            } else if message.destination() == self.dd_monitor
+                && message.direction() == MessageDirection::FromWidget
+            {
+                let mut game_settings = self.game_settings.write().unwrap();
+                if game_settings.monitor != *index as u32 {
+                    game_settings.monitor = *index as u32;
+                    display::apply(
+                        engine.get_window(),
+                        game_settings.display_mode,
+                        game_settings.monitor,
+                        None,
+                    );
+                    // Every monitor has its own video modes.
+                    self.video_modes = video_modes(engine, game_settings.monitor);
+                    let items = make_video_mode_items(
+                        &mut engine.user_interface.build_ctx(),
+                        &self.video_modes,
+                    );
+                    engine.user_interface.send_message(ListViewMessage::items(
+                        self.lb_video_modes,
+                        MessageDirection::ToWidget,
+                        items,
+                    ));
                 }
             }
         } else if let Some(CheckBoxMessage::Check(value)) = message.data() {
@@ -804,13 +1810,45 @@ impl OptionsMenu {
                 control_scheme.shake_camera = value;
             } else if message.destination() == self.cb_use_light_scatter {
                 settings.light_scatter_enabled = value;
+            } else if message.destination() == self.cb_minimal_hud {
+                self.game_settings.write().unwrap().minimal_hud = value;
+            } else if message.destination() == self.cb_subtitles {
+                self.game_settings.write().unwrap().subtitles = value;
+            } else if message.destination() == self.cb_captions {
+                self.game_settings.write().unwrap().captions = value;
+            } else if message.destination() == self.cb_reduced_motion {
+                self.game_settings.write().unwrap().reduced_motion = value;
+            } else if message.destination() == self.cb_weapon_telemetry {
+                self.game_settings.write().unwrap().weapon_telemetry = value;
+            } else if message.destination() == self.cb_match_logs {
+                self.game_settings.write().unwrap().match_logs = value;
+            } else if message.destination() == self.cb_host_on_lan {
+                self.game_settings.write().unwrap().host_on_lan = value;
+            } else if message.destination() == self.cb_hit_markers {
+                self.game_settings.write().unwrap().hit_markers = value;
+            } else if message.destination() == self.cb_kill_marker {
+                self.game_settings.write().unwrap().kill_marker = value;
             }
         } else if let Some(ButtonMessage::Click) = message.data() {
             if message.destination() == self.btn_reset_control_scheme {
                 self.control_scheme.write().unwrap().reset();
+                {
+                    let mut game_settings = self.game_settings.write().unwrap();
+                    let defaults = Settings::default();
+                    game_settings.camera_effects = defaults.camera_effects;
+                    game_settings.reduced_motion = defaults.reduced_motion;
+                    game_settings.damage_effects = defaults.damage_effects;
+                }
                 self.sync_to_model(engine);
             } else if message.destination() == self.btn_reset_audio_settings {
                 engine.sound_engine.lock().unwrap().set_master_gain(1.0);
+                {
+                    let mut game_settings = self.game_settings.write().unwrap();
+                    let defaults = Settings::default();
+                    game_settings.voice_volume = defaults.voice_volume;
+                    game_settings.subtitles = defaults.subtitles;
+                    game_settings.captions = defaults.captions;
+                }
                 self.sync_to_model(engine);
             }
 
@@ -831,8 +1869,8 @@ impl OptionsMenu {
 
         if settings != old_settings {
             if let Err(err) = engine.renderer.set_quality_settings(&settings) {
-                Log::writeln(
-                    MessageKind::Error,
+                logger::error(
+                    Category::General,
                     format!("Failed to set renderer quality settings! Reason: {:?}", err),
                 );
             }
@@ -0,0 +1,249 @@
+//! Loading of game data from archives. Besides loose `data` folder, game data can come from
+//! zip archives (`.pak` or `.zip`) placed in `paks` folder of the game and in `mods` folder of
+//! user data directory. Archive contains files at the same paths as loose data, for example
+//! `data/models/rocket.FBX`.
+//!
+//! Archives are mounted in order: base archives first, then enabled mods in load order, see
+//! `mods` module. File of later archive overrides the same file of earlier archives and loose
+//! data, so a mod can ship as a single archive. Engine loads files from file system only, so
+//! mounted archives are extracted into one overlay directory which is reused until set of
+//! archives changes.
+//!
+//! Besides overriding files, paths can be redirected, so a mod can replace an asset with a
+//! file of its own layout. Redirects are resolved before archives are searched.
+
+use crate::{
+    logger::{self, Category},
+    paths,
+};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+const BASE_ARCHIVES_DIR: &str = "paks";
+
+const EXTENSIONS: [&str; 2] = ["pak", "zip"];
+
+/// File in overlay directory which describes archives the overlay was extracted from.
+const STAMP_FILE: &str = "mounted.txt";
+
+/// Manifest of a mod in root of its archive, it is not extracted.
+pub const MANIFEST_FILE: &str = "mod.txt";
+
+// Overlay directory, None if no archive is mounted.
+static OVERLAY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+// Pairs of source and target paths, later pairs override earlier ones with the same source.
+static REDIRECTS: Mutex<Vec<(PathBuf, PathBuf)>> = Mutex::new(Vec::new());
+
+fn is_archive(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().map_or(false, |ext| {
+            EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Returns archives of a directory sorted by name.
+pub fn find_archives(dir: &Path) -> Vec<PathBuf> {
+    let mut archives = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| is_archive(path))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    archives.sort();
+    archives
+}
+
+/// Returns archives of the game, they are mounted before mods.
+pub fn base_archives() -> Vec<PathBuf> {
+    find_archives(Path::new(BASE_ARCHIVES_DIR))
+}
+
+// Describes archives by path, size and modification time, overlay is extracted again if the
+// description changes.
+fn make_stamp(archives: &[PathBuf]) -> String {
+    archives
+        .iter()
+        .map(|archive| {
+            let (size, modified) = std::fs::metadata(archive)
+                .map(|metadata| {
+                    let modified = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+                        .map_or(0, |duration| duration.as_secs());
+                    (metadata.len(), modified)
+                })
+                .unwrap_or((0, 0));
+            format!("{}|{}|{}\n", archive.display(), size, modified)
+        })
+        .collect()
+}
+
+/// Extracts an archive into a directory, returns amount of extracted files.
+pub fn extract(archive: &Path, dir: &Path) -> Result<usize, String> {
+    let file = File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut count = 0;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        // Entries with absolute paths or `..` must not be written outside of the directory.
+        let relative = match entry.enclosed_name() {
+            Some(relative) => relative.to_owned(),
+            None => {
+                logger::warning(
+                    Category::General,
+                    format!(
+                        "Entry {} of archive {} is skipped, its path is unsafe.",
+                        entry.name(),
+                        archive.display()
+                    ),
+                );
+                continue;
+            }
+        };
+        if relative == Path::new(MANIFEST_FILE) {
+            continue;
+        }
+        let path = dir.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let mut output = File::create(&path).map_err(|e| e.to_string())?;
+            std::io::copy(&mut entry, &mut output).map_err(|e| e.to_string())?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Reads a text file from an archive, None if there is no such file.
+pub fn read_text(archive: &Path, name: &str) -> Option<String> {
+    let file = File::open(archive).ok()?;
+    let mut zip = zip::ZipArchive::new(file).ok()?;
+    let mut entry = zip.by_name(name).ok()?;
+    let mut text = String::new();
+    entry.read_to_string(&mut text).ok()?;
+    Some(text)
+}
+
+/// Mounts given archives, lowest priority first, previously mounted archives are unmounted.
+pub fn mount(archives: &[PathBuf]) {
+    if archives.is_empty() {
+        *OVERLAY.lock().unwrap() = None;
+        return;
+    }
+
+    let overlay = paths::data_dir().join("overlay");
+    let stamp = make_stamp(archives);
+    let stamp_path = overlay.join(STAMP_FILE);
+    if std::fs::read_to_string(&stamp_path).map_or(false, |existing| existing == stamp) {
+        logger::info(
+            Category::General,
+            format!("{} archive(s) mounted from cache.", archives.len()),
+        );
+        *OVERLAY.lock().unwrap() = Some(overlay);
+        return;
+    }
+
+    let _ = std::fs::remove_dir_all(&overlay);
+    if let Err(e) = std::fs::create_dir_all(&overlay) {
+        logger::error(
+            Category::General,
+            format!(
+                "Unable to create overlay directory {}, archives are not mounted. Reason: {:?}",
+                overlay.display(),
+                e
+            ),
+        );
+        *OVERLAY.lock().unwrap() = None;
+        return;
+    }
+
+    for archive in archives {
+        match extract(archive, &overlay) {
+            Ok(count) => logger::info(
+                Category::General,
+                format!("Mounted archive {} ({} files).", archive.display(), count),
+            ),
+            Err(e) => logger::error(
+                Category::General,
+                format!(
+                    "Unable to mount archive {}. Reason: {}",
+                    archive.display(),
+                    e
+                ),
+            ),
+        }
+    }
+
+    // Stamp is written last, so interrupted extraction is redone on next start.
+    if let Err(e) = File::create(&stamp_path).and_then(|mut file| file.write_all(stamp.as_bytes()))
+    {
+        logger::warning(
+            Category::General,
+            format!("Unable to write {}. Reason: {:?}", stamp_path.display(), e),
+        );
+    }
+    *OVERLAY.lock().unwrap() = Some(overlay);
+}
+
+/// Replaces redirects, they are applied in given order.
+pub fn set_redirects(redirects: Vec<(PathBuf, PathBuf)>) {
+    *REDIRECTS.lock().unwrap() = redirects;
+}
+
+fn redirect(path: &Path) -> PathBuf {
+    REDIRECTS
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(source, _)| source == path)
+        .map_or_else(|| path.to_owned(), |(_, target)| target.clone())
+}
+
+/// Returns path of a file of mounted archive if an archive has the file, otherwise the path
+/// itself. Redirects are applied first. Every path of game data must be resolved before it is
+/// passed to the engine.
+pub fn resolve<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = redirect(path.as_ref());
+    let path = path.as_path();
+    if let Some(overlay) = OVERLAY.lock().unwrap().as_ref() {
+        if path.is_relative() {
+            let overlaid = overlay.join(path);
+            if overlaid.is_file() {
+                return overlaid;
+            }
+        }
+    }
+    path.to_owned()
+}
+
+/// Returns directory in which textures of a model are searched, the model path must be
+/// resolved. Model from an archive uses textures of the archive, so archive that overrides a
+/// model must ship its textures too. Model that has `textures` folder next to it (user maps
+/// do) uses that folder.
+pub fn textures_dir(model: &Path) -> PathBuf {
+    let textures = Path::new("data/textures");
+    let local = model.parent().map(|dir| dir.join("textures"));
+    match OVERLAY.lock().unwrap().as_ref() {
+        Some(overlay) if model.starts_with(overlay) => overlay.join(textures),
+        _ => match local {
+            Some(local) if local.is_dir() => local,
+            _ => textures.to_owned(),
+        },
+    }
+}
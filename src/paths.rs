@@ -0,0 +1,77 @@
+//! Locations of files written by the game. Settings go to config directory of the platform
+//! and saves and logs go to its data directory, for example `~/.config/rusty-shooter` and
+//! `~/.local/share/rusty-shooter` on Linux. Saves have their own directory, so it can be
+//! synced by cloud storage as a whole.
+//!
+//! Both directories can be overridden with `--user-dir <path>` command line argument, in this
+//! case every file is written into that directory, which makes portable installs possible.
+
+use crate::logger::{self, Category};
+use std::path::PathBuf;
+
+const GAME_DIR: &str = "rusty-shooter";
+
+fn user_dir_override() -> Option<PathBuf> {
+    std::env::args()
+        .skip_while(|arg| arg != "--user-dir")
+        .nth(1)
+        .map(PathBuf::from)
+}
+
+// Falls back to working directory if platform has no such directory.
+fn resolve(platform_dir: Option<PathBuf>) -> PathBuf {
+    let dir = user_dir_override()
+        .or_else(|| platform_dir.map(|dir| dir.join(GAME_DIR)))
+        .unwrap_or_else(|| PathBuf::from("."));
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        logger::warning(
+            Category::General,
+            format!(
+                "Unable to create directory {}. Reason: {:?}",
+                dir.display(),
+                e
+            ),
+        );
+    }
+    dir
+}
+
+pub fn config_dir() -> PathBuf {
+    resolve(dirs::config_dir())
+}
+
+pub fn data_dir() -> PathBuf {
+    resolve(dirs::data_dir())
+}
+
+pub fn logs_dir() -> PathBuf {
+    let dir = data_dir().join("logs");
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+/// Installed user maps, see `map_install` module.
+pub fn maps_dir() -> PathBuf {
+    let dir = data_dir().join("maps");
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+/// Match logs and timelines are exported here when enabled in settings, unless other directory
+/// is given on command line.
+pub fn matches_dir() -> PathBuf {
+    data_dir().join("matches")
+}
+
+/// Archives of mods, see `pak` module.
+pub fn mods_dir() -> PathBuf {
+    let dir = data_dir().join("mods");
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+pub fn saves_dir() -> PathBuf {
+    let dir = data_dir().join("saves");
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
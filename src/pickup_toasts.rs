@@ -0,0 +1,186 @@
+//! Toasts about items that player picks up. Every pickup shows a short line with an icon,
+//! repeated pickups of the same item stack into one toast with a counter. First pickup of an
+//! item kind in a session also shows a short description of the item.
+
+use crate::{item::ItemKind, localization::tr, message::Message, pak};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    engine::resource_manager::ResourceManager,
+    gui::{
+        brush::Brush,
+        image::ImageBuilder,
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+    utils,
+};
+use std::collections::HashSet;
+
+/// How long (in seconds) each toast stays on screen.
+const TOAST_TIME: f32 = 3.0;
+
+/// Toasts with description stay longer, so there is time to read it.
+const DESCRIPTION_TIME: f32 = 6.0;
+
+const MAX_TOASTS: usize = 5;
+
+struct Toast {
+    kind: ItemKind,
+    count: u32,
+    widget: Handle<UiNode>,
+    text: Handle<UiNode>,
+    timeout: f32,
+}
+
+pub struct PickupToasts {
+    panel: Handle<UiNode>,
+    toasts: Vec<Toast>,
+    seen: HashSet<u32>,
+    resource_manager: ResourceManager,
+}
+
+impl PickupToasts {
+    pub fn new(ui: &mut UserInterface, resource_manager: ResourceManager) -> Self {
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .on_column(2)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_horizontal_alignment(HorizontalAlignment::Right)
+                .with_margin(Thickness {
+                    left: 0.0,
+                    top: 0.0,
+                    right: 20.0,
+                    bottom: 200.0,
+                }),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            panel,
+            toasts: Default::default(),
+            seen: Default::default(),
+            resource_manager,
+        }
+    }
+
+    /// Returns handle of toasts panel, it must be attached to HUD.
+    pub fn widget(&self) -> Handle<UiNode> {
+        self.panel
+    }
+
+    fn show(&mut self, ui: &mut UserInterface, kind: ItemKind) {
+        let first_time = self.seen.insert(kind.id());
+
+        if let Some(toast) = self.toasts.iter_mut().find(|t| t.kind == kind) {
+            toast.count += 1;
+            toast.timeout = toast.timeout.max(TOAST_TIME);
+            ui.send_message(TextMessage::text(
+                toast.text,
+                MessageDirection::ToWidget,
+                format!("{} x{}", kind.toast(), toast.count),
+            ));
+            return;
+        }
+
+        if self.toasts.len() >= MAX_TOASTS {
+            let oldest = self.toasts.remove(0);
+            ui.send_message(WidgetMessage::remove(
+                oldest.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let ctx = &mut ui.build_ctx();
+        let text = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_foreground(Brush::Solid(Color::opaque(255, 255, 255))),
+        )
+        .with_text(kind.toast())
+        .build(ctx);
+        let line = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_child(
+                    ImageBuilder::new(
+                        WidgetBuilder::new()
+                            .with_width(20.0)
+                            .with_height(20.0)
+                            .with_margin(Thickness::right(4.0)),
+                    )
+                    .with_texture(utils::into_gui_texture(
+                        self.resource_manager
+                            .request_texture(pak::resolve(kind.icon()), None),
+                    ))
+                    .build(ctx),
+                )
+                .with_child(text),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+        let mut content = WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0))
+            .with_horizontal_alignment(HorizontalAlignment::Right)
+            .with_child(line);
+        if first_time {
+            content = content.with_child(
+                TextBuilder::new(
+                    WidgetBuilder::new()
+                        .with_foreground(Brush::Solid(Color::opaque(180, 180, 180))),
+                )
+                .with_text(tr(kind.description()))
+                .build(ctx),
+            );
+        }
+        let widget = StackPanelBuilder::new(content).build(ctx);
+        ui.send_message(WidgetMessage::link(
+            widget,
+            MessageDirection::ToWidget,
+            self.panel,
+        ));
+
+        self.toasts.push(Toast {
+            kind,
+            count: 1,
+            widget,
+            text,
+            timeout: if first_time {
+                DESCRIPTION_TIME
+            } else {
+                TOAST_TIME
+            },
+        });
+    }
+
+    pub fn handle_message(&mut self, ui: &mut UserInterface, message: &Message) {
+        if let Message::ShowPickupToast { kind } = message {
+            self.show(ui, *kind);
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        for toast in self.toasts.iter_mut() {
+            toast.timeout -= dt;
+            if toast.timeout <= 0.0 {
+                ui.send_message(WidgetMessage::remove(
+                    toast.widget,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        }
+        self.toasts.retain(|toast| toast.timeout > 0.0);
+    }
+
+    /// Removes every toast, used when level is unloaded.
+    pub fn clear(&mut self, ui: &mut UserInterface) {
+        for toast in self.toasts.drain(..) {
+            ui.send_message(WidgetMessage::remove(
+                toast.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+}
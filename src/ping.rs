@@ -0,0 +1,97 @@
+//! Pings are temporary world markers placed by player at the point player looks at. Pings are
+//! visible to teammates of player and friendly bots treat them as a hint where to go or whom
+//! to attack.
+
+use crate::{character::Team, theme::Theme};
+use rg3d::core::{algebra::Vector3, color::Color};
+
+/// Max distance of a point that can be pinged.
+pub const MAX_DISTANCE: f32 = 200.0;
+
+/// Ray that passes closer than this to an enemy marks the enemy.
+pub const ENEMY_RADIUS: f32 = 1.5;
+
+/// Friendly bots further than this from a ping ignore it.
+pub const HINT_RADIUS: f32 = 40.0;
+
+const LIFETIME: f32 = 8.0;
+
+const COOLDOWN: f32 = 0.75;
+
+/// Older pings are removed when a team places more than this amount of pings.
+const MAX_PINGS_PER_TEAM: usize = 3;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PingKind {
+    Move,
+    Enemy,
+    Danger,
+}
+
+impl PingKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PingKind::Move => "Go here",
+            PingKind::Enemy => "Enemy",
+            PingKind::Danger => "Danger",
+        }
+    }
+
+    pub fn color(self, theme: &Theme) -> Color {
+        match self {
+            PingKind::Move => theme.ping,
+            PingKind::Enemy => theme.enemy_ping,
+            PingKind::Danger => theme.danger_ping,
+        }
+    }
+}
+
+pub struct Ping {
+    pub kind: PingKind,
+    pub position: Vector3<f32>,
+    pub team: Team,
+    time_left: f32,
+}
+
+#[derive(Default)]
+pub struct Pings {
+    pings: Vec<Ping>,
+    cooldown: f32,
+}
+
+impl Pings {
+    pub fn can_ping(&self) -> bool {
+        self.cooldown <= 0.0
+    }
+
+    pub fn add(&mut self, kind: PingKind, position: Vector3<f32>, team: Team) {
+        if self.pings.iter().filter(|ping| ping.team == team).count() >= MAX_PINGS_PER_TEAM {
+            let oldest = self
+                .pings
+                .iter()
+                .position(|ping| ping.team == team)
+                .unwrap();
+            self.pings.remove(oldest);
+        }
+        self.pings.push(Ping {
+            kind,
+            position,
+            team,
+            time_left: LIFETIME,
+        });
+        self.cooldown = COOLDOWN;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.cooldown -= dt;
+        for ping in self.pings.iter_mut() {
+            ping.time_left -= dt;
+        }
+        self.pings.retain(|ping| ping.time_left > 0.0);
+    }
+
+    /// Pings visible to given team, from oldest to newest.
+    pub fn visible_to(&self, team: Team) -> impl Iterator<Item = &Ping> {
+        self.pings.iter().filter(move |ping| ping.team == team)
+    }
+}
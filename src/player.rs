@@ -1,19 +1,23 @@
 use crate::{
-    character::Character,
+    camera_effects::CameraEffects,
+    character::{Character, Team},
     control_scheme::{ControlButton, ControlScheme},
     level::UpdateContext,
     message::Message,
+    squad::Order,
+    viewmodel::Viewmodel,
+    weapon_wheel::WeaponWheel,
 };
 use rg3d::sound::context::SoundContext;
 use rg3d::{
     core::rand::Rng,
     core::{
-        algebra::{Matrix3, UnitQuaternion, Vector3},
+        algebra::{Matrix3, UnitQuaternion, Vector2, Vector3},
         math::Vector3Ext,
         pool::Handle,
         visitor::{Visit, VisitResult, Visitor},
     },
-    event::{DeviceEvent, ElementState, Event, MouseScrollDelta, WindowEvent},
+    event::{DeviceEvent, ElementState, Event, MouseScrollDelta, VirtualKeyCode, WindowEvent},
     physics3d::rapier::{
         dynamics::{RigidBodyBuilder, RigidBodyType},
         geometry::ColliderBuilder,
@@ -27,6 +31,22 @@ use std::{
     sync::{mpsc::Sender, Arc, RwLock},
 };
 
+/// Maps number keys to weapon slots, `1` selects first weapon and so on.
+fn weapon_slot(code: VirtualKeyCode) -> Option<usize> {
+    match code {
+        VirtualKeyCode::Key1 => Some(0),
+        VirtualKeyCode::Key2 => Some(1),
+        VirtualKeyCode::Key3 => Some(2),
+        VirtualKeyCode::Key4 => Some(3),
+        VirtualKeyCode::Key5 => Some(4),
+        VirtualKeyCode::Key6 => Some(5),
+        VirtualKeyCode::Key7 => Some(6),
+        VirtualKeyCode::Key8 => Some(7),
+        VirtualKeyCode::Key9 => Some(8),
+        _ => None,
+    }
+}
+
 pub struct Controller {
     move_forward: bool,
     move_backward: bool,
@@ -73,13 +93,19 @@ pub struct Player {
     head_position: Vector3<f32>,
     look_direction: Vector3<f32>,
     up_direction: Vector3<f32>,
-    weapon_offset: Vector3<f32>,
-    weapon_dest_offset: Vector3<f32>,
-    weapon_shake_factor: f32,
+    viewmodel: Viewmodel,
+    camera_effects: CameraEffects,
+    /// Fastest downward speed since player left the ground, used for landing dip.
+    fall_speed: f32,
     crouch_speed: f32,
     stand_up_speed: f32,
     listener_basis: Matrix3<f32>,
     control_scheme: Option<Arc<RwLock<ControlScheme>>>,
+    // Not serialized, open while weapon wheel key is held.
+    weapon_wheel: Option<WeaponWheel>,
+    // Not serialized, open while bot commands key is held. Orders are picked the same way as
+    // weapons on weapon wheel.
+    command_rose: Option<WeaponWheel>,
 }
 
 impl Deref for Player {
@@ -118,13 +144,15 @@ impl Default for Player {
             head_position: Vector3::default(),
             look_direction: Vector3::default(),
             up_direction: Vector3::default(),
-            weapon_offset: Default::default(),
-            weapon_dest_offset: Default::default(),
-            weapon_shake_factor: 0.0,
+            viewmodel: Default::default(),
+            camera_effects: Default::default(),
+            fall_speed: 0.0,
             crouch_speed: 0.15,
             stand_up_speed: 0.12,
             listener_basis: Default::default(),
             control_scheme: None,
+            weapon_wheel: None,
+            command_rose: None,
         }
     }
 }
@@ -258,6 +286,24 @@ impl Player {
         self.camera
     }
 
+    /// Returns angle (in degrees) of horizontal rotation of player's body.
+    pub fn yaw(&self) -> f32 {
+        self.yaw
+    }
+
+    /// Shakes camera, `strength` is in [0; 1] range.
+    pub fn shake_camera(&mut self, strength: f32) {
+        self.camera_effects.shake(strength);
+    }
+
+    /// Flinches camera on taking damage, `direction` is direction in which damage travelled.
+    pub fn flinch(&mut self, amount: f32, direction: Option<Vector3<f32>>) {
+        let side = direction
+            .and_then(|d| d.try_normalize(std::f32::EPSILON))
+            .map_or(0.0, |d| d.dot(&self.listener_basis.column(0)));
+        self.camera_effects.flinch(amount / 50.0, side);
+    }
+
     pub fn set_control_scheme(&mut self, control_scheme: Arc<RwLock<ControlScheme>>) {
         self.control_scheme = Some(control_scheme);
     }
@@ -287,7 +333,7 @@ impl Player {
             self.run_speed_multiplier
         } else {
             1.0
-        };
+        } * self.character.speed_scale;
 
         let body = context
             .scene
@@ -296,6 +342,15 @@ impl Player {
             .get_mut(&self.character.body)
             .unwrap();
         body.set_angvel(Default::default(), true);
+
+        if has_ground_contact {
+            if self.fall_speed > 0.0 {
+                self.camera_effects.land(self.fall_speed);
+                self.fall_speed = 0.0;
+            }
+        } else {
+            self.fall_speed = self.fall_speed.max(-body.linvel().y);
+        }
         if let Some(normalized_velocity) = velocity.try_normalize(std::f32::EPSILON) {
             body.set_linvel(
                 Vector3::new(
@@ -306,18 +361,12 @@ impl Player {
                 true,
             );
 
-            self.weapon_dest_offset.x = 0.01 * (self.weapon_shake_factor * 0.5).cos();
-            self.weapon_dest_offset.y = 0.005 * self.weapon_shake_factor.sin();
-            self.weapon_shake_factor += 0.23;
-
             if has_ground_contact {
                 let k = (context.time.elapsed * 15.0) as f32;
                 self.camera_dest_offset.x = 0.05 * (k * 0.5).cos();
                 self.camera_dest_offset.y = 0.1 * k.sin();
-                self.path_len += 0.1;
+                self.path_len += context.time.per_frame(0.1);
             }
-        } else {
-            self.weapon_dest_offset = Vector3::default();
         }
 
         // Damping to prevent sliding.
@@ -325,17 +374,12 @@ impl Player {
         // models yet.
         if has_ground_contact {
             let mut vel = *body.linvel();
-            vel.x *= 0.9;
-            vel.z *= 0.9;
+            let damping = 1.0 - context.time.smoothing(0.1);
+            vel.x *= damping;
+            vel.z *= damping;
             body.set_linvel(vel, true);
         }
 
-        self.weapon_offset.follow(&self.weapon_dest_offset, 0.1);
-
-        context.scene.graph[self.character.weapon_pivot]
-            .local_transform_mut()
-            .set_position(self.weapon_offset);
-
         if self.controller.jump {
             if has_ground_contact {
                 let mut vel = *body.linvel();
@@ -357,16 +401,23 @@ impl Player {
             .read()
             .unwrap()
             .shake_camera
+            && !context.reduced_motion
         {
-            self.camera_offset.follow(&self.camera_dest_offset, 0.1);
+            self.camera_offset
+                .follow(&self.camera_dest_offset, context.time.smoothing(0.1));
         } else {
             self.camera_offset = Vector3::default();
         }
 
+        let (effects_offset, effects_rotation) = self
+            .camera_effects
+            .update(context.time, context.camera_effects);
+
         let camera_node = &mut context.scene.graph[self.camera];
         camera_node
             .local_transform_mut()
-            .set_position(self.camera_offset);
+            .set_position(self.camera_offset + effects_offset)
+            .set_rotation(effects_rotation);
 
         self.head_position = camera_node.global_position();
         self.look_direction = camera_node.look_vector();
@@ -385,8 +436,9 @@ impl Player {
             .unwrap()
             .smooth_mouse
         {
-            self.yaw += (self.dest_yaw - self.yaw) * 0.2;
-            self.pitch += (self.dest_pitch - self.pitch) * 0.2;
+            let k = context.time.smoothing(0.2);
+            self.yaw += (self.dest_yaw - self.yaw) * k;
+            self.pitch += (self.dest_pitch - self.pitch) * k;
         } else {
             self.yaw = self.dest_yaw;
             self.pitch = self.dest_pitch;
@@ -412,6 +464,14 @@ impl Player {
         listener.set_position(self.head_position);
     }
 
+    pub fn weapon_wheel(&self) -> Option<&WeaponWheel> {
+        self.weapon_wheel.as_ref()
+    }
+
+    pub fn command_rose(&self) -> Option<&WeaponWheel> {
+        self.command_rose.as_ref()
+    }
+
     pub fn can_be_removed(&self) -> bool {
         self.character.is_dead()
     }
@@ -431,6 +491,13 @@ impl Player {
         if let Event::DeviceEvent { event, .. } = event {
             match event {
                 DeviceEvent::MouseMotion { delta } => {
+                    if let Some(weapon_wheel) =
+                        self.weapon_wheel.as_mut().or(self.command_rose.as_mut())
+                    {
+                        weapon_wheel.push(Vector2::new(delta.0 as f32, delta.1 as f32));
+                        return false;
+                    }
+
                     self.dest_yaw -= delta.0 as f32 * control_scheme.mouse_sens;
 
                     let sens = if control_scheme.mouse_y_inverse {
@@ -459,9 +526,11 @@ impl Player {
                 DeviceEvent::MouseWheel { delta } => {
                     if let MouseScrollDelta::LineDelta(_, y) = delta {
                         if *y < 0.0 {
-                            self.prev_weapon();
+                            control_button = Some(ControlButton::WheelDown);
+                            control_button_state = ElementState::Pressed;
                         } else if *y > 0.0 {
-                            self.next_weapon();
+                            control_button = Some(ControlButton::WheelUp);
+                            control_button_state = ElementState::Pressed;
                         }
                     }
                 }
@@ -504,11 +573,56 @@ impl Player {
                     self.controller.run = true;
                 } else if control_button == control_scheme.jump.button {
                     self.controller.jump = true;
+                } else if control_button == control_scheme.next_weapon.button {
+                    self.next_weapon();
+                } else if control_button == control_scheme.prev_weapon.button {
+                    self.prev_weapon();
+                } else if control_button == control_scheme.last_weapon.button {
+                    self.switch_to_last_weapon();
+                } else if control_button == control_scheme.weapon_wheel.button {
+                    // Held key repeats press, wheel must keep its cursor.
+                    if self.weapon_wheel.is_none() && !self.character.weapons.is_empty() {
+                        self.weapon_wheel = Some(Default::default());
+                    }
+                } else if control_button == control_scheme.command_rose.button {
+                    // Bots take orders only from teammates.
+                    if self.command_rose.is_none() && self.character.team() != Team::None {
+                        self.command_rose = Some(Default::default());
+                    }
+                } else if control_button == control_scheme.ping.button
+                    || control_button == control_scheme.danger_ping.button
+                {
+                    self.sender
+                        .as_ref()
+                        .unwrap()
+                        .send(Message::Ping {
+                            danger: control_button == control_scheme.danger_ping.button,
+                        })
+                        .unwrap();
+                } else if control_button == control_scheme.fire_mode.button {
+                    if let Some(weapon) = self.weapons.get(self.current_weapon as usize) {
+                        self.sender
+                            .as_ref()
+                            .unwrap()
+                            .send(Message::CycleFireMode { weapon: *weapon })
+                            .unwrap();
+                    }
+                } else if let ControlButton::Key(code) = control_button {
+                    if let Some(slot) = weapon_slot(code) {
+                        self.set_current_weapon(slot);
+                    }
                 }
             }
             ElementState::Released => {
                 if control_button == control_scheme.shoot.button {
                     self.controller.shoot = false;
+                    if let Some(weapon) = self.weapons.get(self.current_weapon as usize) {
+                        self.sender
+                            .as_ref()
+                            .unwrap()
+                            .send(Message::ReleaseTrigger { weapon: *weapon })
+                            .unwrap();
+                    }
                 } else if control_button == control_scheme.move_forward.button {
                     self.controller.move_forward = false;
                 } else if control_button == control_scheme.move_backward.button {
@@ -521,6 +635,24 @@ impl Player {
                     self.controller.crouch = false;
                 } else if control_button == control_scheme.run.button {
                     self.controller.run = false;
+                } else if control_button == control_scheme.weapon_wheel.button {
+                    if let Some(weapon_wheel) = self.weapon_wheel.take() {
+                        if let Some(slot) = weapon_wheel.selection(self.character.weapons.len()) {
+                            self.set_current_weapon(slot);
+                        }
+                    }
+                } else if control_button == control_scheme.command_rose.button {
+                    if let Some(command_rose) = self.command_rose.take() {
+                        if let Some(slot) = command_rose.selection(Order::ALL.len()) {
+                            self.sender
+                                .as_ref()
+                                .unwrap()
+                                .send(Message::IssueOrder {
+                                    order: Order::ALL[slot],
+                                })
+                                .unwrap();
+                        }
+                    }
                 }
             }
         }
@@ -531,19 +663,39 @@ impl Player {
     pub fn update(&mut self, context: &mut UpdateContext) {
         self.update_movement(context);
 
+        let velocity = *context
+            .scene
+            .physics
+            .bodies
+            .get(&self.character.body)
+            .unwrap()
+            .linvel();
+
+        // Viewmodel bobs only while player walks on the ground.
+        let speed = if self.character.has_ground_contact(&context.scene.physics) {
+            Vector3::new(velocity.x, 0.0, velocity.z).norm()
+                / (self.move_speed * self.run_speed_multiplier)
+        } else {
+            0.0
+        };
+        let weapons = context.weapons;
+        let weapon = self
+            .character
+            .weapons
+            .get(self.character.current_weapon as usize)
+            .map(|handle| (*handle, &weapons[*handle]));
+        self.viewmodel.update(
+            &mut context.scene.graph[self.character.weapon_pivot],
+            weapon,
+            speed,
+            context.time,
+        );
+
         if let Some(current_weapon_handle) = self
             .character
             .weapons
             .get(self.character.current_weapon as usize)
         {
-            let velocity = context
-                .scene
-                .physics
-                .bodies
-                .get(&self.character.body)
-                .unwrap()
-                .linvel();
-
             if self.controller.shoot {
                 self.character
                     .sender
@@ -551,7 +703,7 @@ impl Player {
                     .unwrap()
                     .send(Message::ShootWeapon {
                         weapon: *current_weapon_handle,
-                        initial_velocity: *velocity,
+                        initial_velocity: velocity,
                         direction: None,
                     })
                     .unwrap();
@@ -0,0 +1,54 @@
+//! Practice mode for testing weapons and aim. It is a mutator - every bot of practice match is a
+//! target dummy that never fights back, half of the dummies walk back and forth, weapons never
+//! run out of ammo and items never despawn. Hits of player on dummies are shown on HUD along
+//! with damage per second.
+
+use std::collections::VecDeque;
+
+/// Damage per second is averaged over this many seconds.
+const DPS_WINDOW: f32 = 3.0;
+
+/// Damage of last hit is shown for this many seconds after the hit.
+const HIT_FEEDBACK_TIME: f32 = 0.75;
+
+#[derive(Default)]
+pub struct Practice {
+    // Time and damage of hits within DPS window.
+    hits: VecDeque<(f32, f32)>,
+    last_hit: Option<(f32, f32)>,
+    total_damage: f32,
+}
+
+impl Practice {
+    pub fn record_hit(&mut self, time: f32, damage: f32) {
+        self.hits.push_back((time, damage));
+        self.last_hit = Some((time, damage));
+        self.total_damage += damage;
+    }
+
+    fn dps(&mut self, time: f32) -> f32 {
+        while let Some(&(hit_time, _)) = self.hits.front() {
+            if time - hit_time > DPS_WINDOW {
+                self.hits.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.hits.iter().map(|(_, damage)| damage).sum::<f32>() / DPS_WINDOW
+    }
+
+    /// Text of practice readout on HUD at given time.
+    pub fn readout(&mut self, time: f32) -> String {
+        let mut readout = format!(
+            "DPS: {:.0}  Total: {:.0}",
+            self.dps(time),
+            self.total_damage
+        );
+        if let Some((hit_time, damage)) = self.last_hit {
+            if time - hit_time <= HIT_FEEDBACK_TIME {
+                readout += &format!("\nHit! -{:.0}", damage);
+            }
+        }
+        readout
+    }
+}
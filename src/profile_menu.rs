@@ -0,0 +1,177 @@
+use crate::{
+    localization::tr,
+    settings::{Profile, Settings, SKINS},
+};
+use rg3d::{
+    core::pool::Handle,
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        button::{ButtonBuilder, ButtonMessage},
+        decorator::DecoratorBuilder,
+        dropdown_list::{DropdownListBuilder, DropdownListMessage},
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        text::TextBuilder,
+        text_box::{TextBoxBuilder, TextBoxMessage},
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use std::sync::{Arc, RwLock};
+
+pub struct ProfileMenu {
+    pub window: Handle<UiNode>,
+    tb_name: Handle<UiNode>,
+    dd_skin: Handle<UiNode>,
+    save_button: Handle<UiNode>,
+    settings: Arc<RwLock<Settings>>,
+    // Edited copy of profile, name and skin are written to settings only when player hits Save.
+    profile: Profile,
+}
+
+impl ProfileMenu {
+    pub fn new(ui: &mut UserInterface, settings: Arc<RwLock<Settings>>) -> Self {
+        let profile = settings.read().unwrap().profile.clone();
+
+        let common_row = Row::strict(36.0);
+
+        let ctx = &mut ui.build_ctx();
+        let tb_name;
+        let dd_skin;
+        let save_button;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0))
+            .with_title(WindowTitle::text(tr("Profile")))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .on_column(0)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_text(tr("Player Name"))
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .build(ctx),
+                        )
+                        .with_child({
+                            tb_name = TextBoxBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .on_column(1)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_text(profile.name.clone())
+                            .build(ctx);
+                            tb_name
+                        })
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .on_column(0)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_text(tr("Skin"))
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .build(ctx),
+                        )
+                        .with_child({
+                            dd_skin = DropdownListBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .on_column(1)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_items(
+                                SKINS
+                                    .iter()
+                                    .map(|(name, color)| {
+                                        DecoratorBuilder::new(BorderBuilder::new(
+                                            WidgetBuilder::new().with_height(30.0).with_child(
+                                                TextBuilder::new(
+                                                    WidgetBuilder::new()
+                                                        .with_foreground(Brush::Solid(*color))
+                                                        .with_horizontal_alignment(
+                                                            HorizontalAlignment::Center,
+                                                        )
+                                                        .with_vertical_alignment(
+                                                            VerticalAlignment::Center,
+                                                        ),
+                                                )
+                                                .with_text(name)
+                                                .build(ctx),
+                                            ),
+                                        ))
+                                        .build(ctx)
+                                    })
+                                    .collect(),
+                            )
+                            .with_selected(profile.skin as usize)
+                            .build(ctx);
+                            dd_skin
+                        })
+                        .with_child({
+                            save_button =
+                                ButtonBuilder::new(WidgetBuilder::new().on_row(2).on_column(1))
+                                    .with_text(&tr("Save"))
+                                    .build(ctx);
+                            save_button
+                        }),
+                )
+                .add_column(Column::strict(150.0))
+                .add_column(Column::stretch())
+                .add_row(common_row)
+                .add_row(common_row)
+                .add_row(common_row)
+                .add_row(Row::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            tb_name,
+            dd_skin,
+            save_button,
+            settings,
+            profile,
+        }
+    }
+
+    pub fn handle_ui_event(&mut self, ui: &mut UserInterface, message: &UiMessage) {
+        if message.direction() != MessageDirection::FromWidget {
+            return;
+        }
+
+        if let Some(TextBoxMessage::Text(text)) = message.data() {
+            if message.destination() == self.tb_name {
+                self.profile.name = text.clone();
+            }
+        } else if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
+            if message.destination() == self.dd_skin {
+                self.profile.skin = *index as u32;
+            }
+        } else if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.save_button {
+                let name = self.profile.name.trim();
+                if !name.is_empty() {
+                    self.profile.name = name.to_owned();
+                    let mut settings = self.settings.write().unwrap();
+                    // Campaign progress might have changed since menu was created.
+                    settings.profile.name = self.profile.name.clone();
+                    settings.profile.skin = self.profile.skin;
+                    settings.save();
+                }
+                ui.send_message(WindowMessage::close(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        }
+    }
+}
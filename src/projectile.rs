@@ -1,19 +1,23 @@
 use crate::{
     actor::{Actor, ActorContainer},
-    effects::EffectKind,
+    assets,
+    effects::{EffectKind, EffectPool},
+    hitbox::HitZone,
     message::Message,
+    mine::{Mine, MineContainer},
+    pak,
+    turret::TurretContainer,
     weapon::{Weapon, WeaponContainer},
-    GameTime,
+    GameTime, Mutators, FIXED_FPS,
 };
 use rg3d::core::algebra::Point3;
-use rg3d::engine::resource_manager::MaterialSearchOptions;
 use rg3d::{
     core::rand::Rng,
     core::{
         algebra::{Isometry3, Matrix3, Translation3, UnitQuaternion, Vector3},
         color::Color,
         math::{ray::Ray, Vector3Ext},
-        pool::{Handle, Pool, PoolIteratorMut},
+        pool::{Handle, Pool, PoolIteratorMut, PoolPairIterator},
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::ResourceManager,
@@ -37,11 +41,23 @@ use rg3d::{
 };
 use std::{collections::HashSet, path::PathBuf, sync::mpsc::Sender};
 
+/// Radius of head hit sphere when big heads mutator is active.
+const BIG_HEAD_RADIUS: f32 = 0.6;
+
+/// Flames start small and grow by this amount of meters until they fade out.
+const FLAME_SIZE: f32 = 0.1;
+
+const FLAME_GROWTH: f32 = 0.5;
+
+/// Bots are warned about projectiles that will pass near them within this distance (in meters).
+const THREAT_DISTANCE: f32 = 15.0;
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum ProjectileKind {
     Plasma,
     Bullet,
     Rocket,
+    Flame,
 }
 
 impl ProjectileKind {
@@ -50,6 +66,7 @@ impl ProjectileKind {
             0 => Ok(ProjectileKind::Plasma),
             1 => Ok(ProjectileKind::Bullet),
             2 => Ok(ProjectileKind::Rocket),
+            3 => Ok(ProjectileKind::Flame),
             _ => Err(format!("Invalid projectile kind id {}", id)),
         }
     }
@@ -59,6 +76,7 @@ impl ProjectileKind {
             ProjectileKind::Plasma => 0,
             ProjectileKind::Bullet => 1,
             ProjectileKind::Rocket => 2,
+            ProjectileKind::Flame => 3,
         }
     }
 }
@@ -83,6 +101,9 @@ pub struct Projectile {
     definition: &'static ProjectileDefinition,
     pub sender: Option<Sender<Message>>,
     hits: HashSet<Hit>,
+    has_trail: bool,
+    // Not serialized, actors that were warned about this projectile already.
+    warned: Vec<Handle<Actor>>,
 }
 
 impl Default for Projectile {
@@ -100,6 +121,8 @@ impl Default for Projectile {
             definition: Self::get_definition(ProjectileKind::Plasma),
             sender: None,
             hits: Default::default(),
+            has_trail: false,
+            warned: Default::default(),
         }
     }
 }
@@ -111,7 +134,28 @@ pub struct ProjectileDefinition {
     /// Means that movement of projectile controlled by code, not physics.
     /// However projectile still could have rigid body to detect collisions.
     is_kinematic: bool,
-    impact_sound: &'static str,
+    /// None means that projectile silently fades out without impact effect.
+    impact_sound: Option<&'static str>,
+    trail_color: Color,
+    trail_radius: f32,
+    /// How long each segment of trail stays visible, short lifetime gives a tracer, long
+    /// lifetime gives a ribbon trail.
+    trail_lifetime: f32,
+    /// Camera of player closer than this distance to impact point is shaken, zero means
+    /// that impact does not shake camera.
+    shake_radius: f32,
+    /// Sets actors on fire.
+    ignites: bool,
+    /// Bots closer than this distance to flight path of projectile try to dodge it, zero means
+    /// that projectile is not worth dodging.
+    dodge_radius: f32,
+}
+
+impl ProjectileDefinition {
+    /// Speed in meters per second, `speed` is defined per fixed frame.
+    pub fn flight_speed(&self) -> f32 {
+        self.speed * FIXED_FPS
+    }
 }
 
 impl Projectile {
@@ -123,7 +167,13 @@ impl Projectile {
                     speed: 0.15,
                     lifetime: 10.0,
                     is_kinematic: true,
-                    impact_sound: "data/sounds/bullet_impact_concrete.ogg",
+                    impact_sound: Some("data/sounds/bullet_impact_concrete.ogg"),
+                    trail_color: Color::opaque(0, 162, 232),
+                    trail_radius: 0.03,
+                    trail_lifetime: 0.3,
+                    shake_radius: 0.0,
+                    ignites: false,
+                    dodge_radius: 0.0,
                 };
                 &DEFINITION
             }
@@ -133,7 +183,13 @@ impl Projectile {
                     speed: 0.75,
                     lifetime: 10.0,
                     is_kinematic: true,
-                    impact_sound: "data/sounds/bullet_impact_concrete.ogg",
+                    impact_sound: Some("data/sounds/bullet_impact_concrete.ogg"),
+                    trail_color: Color::opaque(255, 220, 140),
+                    trail_radius: 0.01,
+                    trail_lifetime: 0.05,
+                    shake_radius: 0.0,
+                    ignites: false,
+                    dodge_radius: 0.0,
                 };
                 &DEFINITION
             }
@@ -143,7 +199,29 @@ impl Projectile {
                     speed: 0.5,
                     lifetime: 10.0,
                     is_kinematic: true,
-                    impact_sound: "data/sounds/explosion.ogg",
+                    impact_sound: Some("data/sounds/explosion.ogg"),
+                    trail_color: Color::opaque(255, 127, 0),
+                    trail_radius: 0.04,
+                    trail_lifetime: 0.5,
+                    shake_radius: 8.0,
+                    ignites: false,
+                    dodge_radius: 3.0,
+                };
+                &DEFINITION
+            }
+            ProjectileKind::Flame => {
+                static DEFINITION: ProjectileDefinition = ProjectileDefinition {
+                    damage: 3.0,
+                    speed: 0.25,
+                    lifetime: 0.35,
+                    is_kinematic: true,
+                    impact_sound: None,
+                    trail_color: Color::opaque(255, 100, 0),
+                    trail_radius: 0.05,
+                    trail_lifetime: 0.1,
+                    shake_radius: 0.0,
+                    ignites: true,
+                    dodge_radius: 0.0,
                 };
                 &DEFINITION
             }
@@ -180,7 +258,8 @@ impl Projectile {
                     .with_size(size)
                     .with_color(color)
                     .with_texture(
-                        resource_manager.request_texture("data/particles/light_01.png", None),
+                        resource_manager
+                            .request_texture(pak::resolve("data/particles/light_01.png"), None),
                     )
                     .build(&mut scene.graph);
 
@@ -204,23 +283,38 @@ impl Projectile {
                     )
                     .with_size(0.05)
                     .with_texture(
-                        resource_manager.request_texture("data/particles/light_01.png", None),
+                        resource_manager
+                            .request_texture(pak::resolve("data/particles/light_01.png"), None),
+                    )
+                    .build(&mut scene.graph);
+
+                    (model, None)
+                }
+                ProjectileKind::Flame => {
+                    let model = SpriteBuilder::new(
+                        BaseBuilder::new().with_local_transform(
+                            TransformBuilder::new()
+                                .with_local_position(position)
+                                .build(),
+                        ),
+                    )
+                    .with_size(FLAME_SIZE)
+                    .with_color(Color::opaque(255, 120, 20))
+                    .with_texture(
+                        resource_manager
+                            .request_texture(pak::resolve("data/particles/circle_05.png"), None),
                     )
                     .build(&mut scene.graph);
 
                     (model, None)
                 }
                 ProjectileKind::Rocket => {
-                    let resource = resource_manager
-                        .request_model(
-                            "data/models/rocket.FBX",
-                            MaterialSearchOptions::MaterialsDirectory(PathBuf::from(
-                                "data/textures",
-                            )),
-                        )
-                        .await
-                        .unwrap();
-                    let model = resource.instantiate_geometry(scene);
+                    let model = assets::instantiate_model(
+                        &resource_manager,
+                        "data/models/rocket.FBX",
+                        scene,
+                    )
+                    .await;
                     scene.graph[model]
                         .local_transform_mut()
                         .set_rotation(UnitQuaternion::from_matrix(&basis))
@@ -256,15 +350,133 @@ impl Projectile {
         self.lifetime <= 0.0
     }
 
+    /// Warns bots which are in the way of projectile, so they can dodge it.
+    fn warn_threatened(
+        &mut self,
+        position: Vector3<f32>,
+        scene: &Scene,
+        actors: &ActorContainer,
+        weapons: &WeaponContainer,
+    ) {
+        // Shooter knows where its own projectile goes.
+        let shooter = if weapons.contains(self.owner) {
+            weapons[self.owner].owner()
+        } else {
+            Handle::NONE
+        };
+        for (handle, actor) in actors.pair_iter() {
+            if handle == shooter
+                || !matches!(actor, Actor::Bot(_))
+                || actor.is_dead()
+                || self.warned.contains(&handle)
+            {
+                continue;
+            }
+            let offset = actor.position(&scene.physics) - position;
+            let along = offset.dot(&self.dir);
+            if along < 0.0 || along > THREAT_DISTANCE {
+                continue;
+            }
+            if (offset - self.dir.scale(along)).norm() < self.definition.dodge_radius {
+                self.warned.push(handle);
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::ProjectileThreat {
+                        actor: handle,
+                        position,
+                        direction: self.dir,
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    fn detonate_mine(&mut self, mine: Handle<Mine>) {
+        self.kill();
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Message::DetonateMine { mine })
+            .unwrap();
+    }
+
     pub fn kill(&mut self) {
         self.lifetime = 0.0;
     }
 
+    pub fn set_trail_enabled(&mut self, enabled: bool) {
+        self.has_trail = enabled;
+    }
+
+    pub fn kind(&self) -> ProjectileKind {
+        self.kind
+    }
+
+    fn set_position(&mut self, scene: &mut Scene, position: Vector3<f32>) {
+        if let Some(body) = self.body.as_ref() {
+            scene.physics.bodies.get_mut(body).unwrap().set_position(
+                Isometry3 {
+                    rotation: Default::default(),
+                    translation: Translation3 { vector: position },
+                },
+                true,
+            );
+        } else {
+            scene.graph[self.model]
+                .local_transform_mut()
+                .set_position(position);
+        }
+    }
+
+    /// Brings projectile taken from pool of free projectiles back to life, reusing its
+    /// scene node and rigid body.
+    #[allow(clippy::too_many_arguments)]
+    pub fn respawn(
+        &mut self,
+        scene: &mut Scene,
+        dir: Vector3<f32>,
+        position: Vector3<f32>,
+        owner: Handle<Weapon>,
+        initial_velocity: Vector3<f32>,
+        sender: Sender<Message>,
+        basis: Matrix3<f32>,
+    ) {
+        self.lifetime = self.definition.lifetime;
+        self.dir = dir.try_normalize(std::f32::EPSILON).unwrap_or(Vector3::y());
+        self.owner = owner;
+        self.initial_velocity = initial_velocity;
+        self.last_position = position;
+        self.rotation_angle = 0.0;
+        self.sender = Some(sender);
+        self.hits.clear();
+        self.warned.clear();
+        self.has_trail = false;
+        self.set_position(scene, position);
+        let node = &mut scene.graph[self.model];
+        node.set_visibility(true);
+        if self.kind == ProjectileKind::Rocket {
+            node.local_transform_mut()
+                .set_rotation(UnitQuaternion::from_matrix(&basis));
+        }
+    }
+
+    /// Hides dead projectile so it can be put in pool of free projectiles.
+    fn deactivate(&mut self, scene: &mut Scene) {
+        scene.graph[self.model].set_visibility(false);
+        // Move it away from level so its body won't interact with anything.
+        self.set_position(scene, Vector3::new(0.0, -10000.0, 0.0));
+    }
+
     pub fn update(
         &mut self,
         scene: &mut Scene,
         actors: &ActorContainer,
         weapons: &WeaponContainer,
+        mines: &MineContainer,
+        turrets: &TurretContainer,
+        effects: &mut EffectPool,
+        mutators: Mutators,
         time: GameTime,
     ) {
         // Fetch current position of projectile.
@@ -281,6 +493,10 @@ impl Projectile {
             scene.graph[self.model].global_position()
         };
 
+        if self.definition.dodge_radius > 0.0 {
+            self.warn_threatened(position, scene, actors, weapons);
+        }
+
         let mut effect_position = None;
 
         // Do ray based intersection tests for every kind of projectiles. This will help to handle
@@ -298,8 +514,55 @@ impl Projectile {
             &mut query_buffer,
         );
 
+        // Big heads mutator: heads are tested separately as enlarged spheres, they're hit only
+        // if nothing else is in front of them.
+        let mut head_hit = None;
+        if mutators.contains(Mutators::BIG_HEADS) && self.owner.is_some() {
+            let weapon_owner = weapons[self.owner].owner();
+            for (actor_handle, actor) in actors.pair_iter() {
+                if actor_handle == weapon_owner || actor.is_dead() {
+                    continue;
+                }
+                let center = actor.head_position(&scene.physics);
+                if let Some(result) = ray.sphere_intersection(&center, BIG_HEAD_RADIUS) {
+                    if result.min <= 1.0 && result.max >= 0.0 {
+                        let t = result.min.max(0.0);
+                        if head_hit.map_or(true, |(best, _, _)| t < best) {
+                            head_hit = Some((t, actor_handle, ray.get_point(t)));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Mines have no colliders, so they're tested the same way as big heads.
+        let mut mine_hit = mines.hit_test(&ray, &scene.graph);
+
         // List of hits sorted by distance from ray origin.
         'hit_loop: for hit in query_buffer.iter() {
+            if let Some((mine, t)) = mine_hit {
+                if (hit.position.coords - ray.origin).norm() > t * ray.dir.norm() {
+                    self.detonate_mine(mine);
+                    effect_position = Some(ray.get_point(t));
+                    mine_hit = None;
+                    break 'hit_loop;
+                }
+            }
+
+            if let Some((t, actor_handle, head_position)) = head_hit {
+                if (hit.position.coords - ray.origin).norm() > t * ray.dir.norm() {
+                    self.hits.insert(Hit {
+                        actor: actor_handle,
+                        who: weapons[self.owner].owner(),
+                        zone: HitZone::Head,
+                    });
+                    self.kill();
+                    effect_position = Some(head_position);
+                    head_hit = None;
+                    break 'hit_loop;
+                }
+            }
+
             let collider = scene.physics.colliders.get(&hit.collider).unwrap();
             let body = scene
                 .physics
@@ -322,6 +585,10 @@ impl Projectile {
                             self.hits.insert(Hit {
                                 actor: actor_handle,
                                 who: weapon.owner(),
+                                zone: HitZone::resolve(
+                                    actor.position(&scene.physics),
+                                    hit.position.coords,
+                                ),
                             });
 
                             self.kill();
@@ -330,12 +597,48 @@ impl Projectile {
                         }
                     }
                 }
+                if let Some(turret) = turrets.find_by_body(body) {
+                    self.sender
+                        .as_ref()
+                        .unwrap()
+                        .send(Message::DamageTurret {
+                            turret,
+                            amount: self.definition.damage,
+                        })
+                        .unwrap();
+                    self.kill();
+                    effect_position = Some(hit.position.coords);
+                    break 'hit_loop;
+                }
+            }
+        }
+
+        // Nothing was in front of the head.
+        if let Some((_, actor_handle, head_position)) = head_hit {
+            if !self.is_dead() {
+                self.hits.insert(Hit {
+                    actor: actor_handle,
+                    who: weapons[self.owner].owner(),
+                    zone: HitZone::Head,
+                });
+                self.kill();
+                effect_position = Some(head_position);
+            }
+        }
+
+        // Nothing was in front of the mine.
+        if let Some((mine, t)) = mine_hit {
+            if !self.is_dead() {
+                self.detonate_mine(mine);
+                effect_position = Some(ray.get_point(t));
             }
         }
 
         // Movement of kinematic projectiles are controlled explicitly.
         if self.definition.is_kinematic {
-            let total_velocity = self.dir.scale(self.definition.speed);
+            // Speed is defined per fixed frame, convert it using actual delta so game speed
+            // mutator and tick rate affect projectiles correctly.
+            let total_velocity = self.dir.scale(time.per_frame(self.definition.speed));
 
             // Special case for projectiles with rigid body.
             if let Some(body) = self.body.as_ref() {
@@ -358,16 +661,25 @@ impl Projectile {
 
         if let Node::Sprite(sprite) = &mut scene.graph[self.model] {
             sprite.set_rotation(self.rotation_angle);
-            self.rotation_angle += 1.5;
+            self.rotation_angle += time.per_frame(1.5);
+            if self.kind == ProjectileKind::Flame {
+                let age = 1.0 - self.lifetime / self.definition.lifetime;
+                sprite.set_size(FLAME_SIZE + FLAME_GROWTH * age);
+            }
         }
 
         // Reduce initial velocity down to zero over time. This is needed because projectile
         // stabilizes its movement over time.
-        self.initial_velocity.follow(&Vector3::default(), 0.15);
+        self.initial_velocity
+            .follow(&Vector3::default(), time.smoothing(0.15));
 
         self.lifetime -= time.delta;
 
-        if self.lifetime <= 0.0 {
+        let impact_sound = self
+            .definition
+            .impact_sound
+            .filter(|_| self.lifetime <= 0.0);
+        if let Some(impact_sound) = impact_sound {
             let pos = effect_position.unwrap_or_else(|| self.get_position(&scene.graph));
 
             self.sender
@@ -383,25 +695,59 @@ impl Projectile {
                 .as_ref()
                 .unwrap()
                 .send(Message::PlaySound {
-                    path: PathBuf::from(self.definition.impact_sound),
+                    path: PathBuf::from(impact_sound),
                     position: pos,
                     gain: 1.0,
                     rolloff_factor: 4.0,
                     radius: 3.0,
                 })
                 .unwrap();
+
+            if self.definition.shake_radius > 0.0 {
+                self.sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Message::ShakeCamera {
+                        position: pos,
+                        radius: self.definition.shake_radius,
+                    })
+                    .unwrap();
+            }
+        }
+
+        if self.has_trail {
+            let end = effect_position.unwrap_or(position);
+            if end.sqr_distance(&self.last_position) > std::f32::EPSILON {
+                effects.spawn_trail_segment(
+                    &mut scene.graph,
+                    self.last_position,
+                    end,
+                    self.definition.trail_radius,
+                    self.definition.trail_color,
+                    self.definition.trail_lifetime,
+                );
+            }
         }
 
         for hit in self.hits.drain() {
-            self.sender
-                .as_ref()
-                .unwrap()
+            let sender = self.sender.as_ref().unwrap();
+            sender
                 .send(Message::DamageActor {
                     actor: hit.actor,
                     who: hit.who,
                     amount: self.definition.damage,
+                    direction: Some(self.dir),
+                    zone: Some(hit.zone),
                 })
                 .unwrap();
+            if self.definition.ignites {
+                sender
+                    .send(Message::IgniteActor {
+                        actor: hit.actor,
+                        who: hit.who,
+                    })
+                    .unwrap();
+            }
         }
 
         self.last_position = position;
@@ -460,9 +806,21 @@ impl Projectile {
                         // Prevent self-damage.
                         let weapon = &weapons[self.owner];
                         if weapon.owner() != actor_handle {
+                            let projectile_position = scene
+                                .physics
+                                .bodies
+                                .get(&self_body)
+                                .unwrap()
+                                .position()
+                                .translation
+                                .vector;
                             self.hits.insert(Hit {
                                 actor: actor_handle,
                                 who: weapon.owner(),
+                                zone: HitZone::resolve(
+                                    actor.position(&scene.physics),
+                                    projectile_position,
+                                ),
                             });
                         } else {
                             // Make sure that projectile won't die on contact with owner.
@@ -496,6 +854,7 @@ impl Projectile {
 struct Hit {
     actor: Handle<Actor>,
     who: Handle<Actor>,
+    zone: HitZone,
 }
 
 impl Visit for Projectile {
@@ -523,36 +882,71 @@ impl Visit for Projectile {
 
 pub struct ProjectileContainer {
     pool: Pool<Projectile>,
+    // Dead projectiles ready for reuse.
+    free: Vec<Projectile>,
+    dead: Vec<Handle<Projectile>>,
 }
 
 impl ProjectileContainer {
+    const MAX_FREE_PROJECTILES: usize = 64;
+
     pub fn new() -> Self {
-        Self { pool: Pool::new() }
+        Self {
+            pool: Pool::new(),
+            free: Default::default(),
+            dead: Default::default(),
+        }
     }
 
     pub fn add(&mut self, projectile: Projectile) -> Handle<Projectile> {
         self.pool.spawn(projectile)
     }
 
+    /// Takes free projectile of given kind from pool, it must be respawned before adding it back.
+    pub fn take_free(&mut self, kind: ProjectileKind) -> Option<Projectile> {
+        self.free
+            .iter()
+            .position(|projectile| projectile.kind == kind)
+            .map(|index| self.free.swap_remove(index))
+    }
+
     pub fn iter_mut(&mut self) -> PoolIteratorMut<Projectile> {
         self.pool.iter_mut()
     }
 
+    pub fn pair_iter(&self) -> PoolPairIterator<Projectile> {
+        self.pool.pair_iter()
+    }
+
     pub fn update(
         &mut self,
         scene: &mut Scene,
         actors: &ActorContainer,
         weapons: &WeaponContainer,
+        mines: &MineContainer,
+        turrets: &TurretContainer,
+        effects: &mut EffectPool,
+        mutators: Mutators,
         time: GameTime,
     ) {
-        for projectile in self.pool.iter_mut() {
-            projectile.update(scene, actors, weapons, time);
+        for (handle, projectile) in self.pool.pair_iter_mut() {
+            projectile.update(
+                scene, actors, weapons, mines, turrets, effects, mutators, time,
+            );
             if projectile.is_dead() {
-                projectile.clean_up(scene);
+                self.dead.push(handle);
             }
         }
 
-        self.pool.retain(|proj| !proj.is_dead());
+        for handle in self.dead.drain(..) {
+            let mut projectile = self.pool.free(handle);
+            if self.free.len() < Self::MAX_FREE_PROJECTILES {
+                projectile.deactivate(scene);
+                self.free.push(projectile);
+            } else {
+                projectile.clean_up(scene);
+            }
+        }
     }
 }
 
@@ -561,6 +955,7 @@ impl Visit for ProjectileContainer {
         visitor.enter_region(name)?;
 
         self.pool.visit("Pool", visitor)?;
+        self.free.visit("Free", visitor)?;
 
         visitor.leave_region()
     }
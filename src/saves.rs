@@ -0,0 +1,184 @@
+//! Save slots. There is one slot for saves made from menu, one for quicksaves and a few
+//! autosave slots which are overwritten in turn, oldest first.
+//!
+//! Every save starts with format version, saves of newer versions are rejected and older ones
+//! are migrated by Visit implementations - they check `read_version` to find out which fields
+//! save has.
+//!
+//! Game saves are stored as zip archive with single deflated entry. Saves made before
+//! compression was added are plain visitor files, they are still loaded as is.
+
+use crate::paths;
+use rg3d::core::visitor::{Visit, VisitError, VisitResult, Visitor};
+use std::{
+    cell::Cell,
+    fs::File,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Version of save format, must be increased on every change of saved data.
+pub const SAVE_VERSION: u32 = 17;
+
+thread_local! {
+    static READ_VERSION: Cell<u32> = Cell::new(SAVE_VERSION);
+}
+
+/// Name of the entry in compressed save archive.
+const SAVE_ENTRY: &str = "save.bin";
+
+/// Every zip archive starts with local file header signature.
+const ZIP_SIGNATURE: &[u8; 4] = b"PK\x03\x04";
+
+/// Amount of autosave slots.
+pub const AUTOSAVE_SLOTS: usize = 3;
+
+/// How often (in seconds) game is autosaved while match is played.
+pub const AUTOSAVE_INTERVAL: f32 = 180.0;
+
+pub fn save_path() -> PathBuf {
+    paths::saves_dir().join("save.bin")
+}
+
+pub fn quicksave_path() -> PathBuf {
+    paths::saves_dir().join("quicksave.bin")
+}
+
+pub fn autosave_path(slot: usize) -> PathBuf {
+    paths::saves_dir().join(format!("autosave{}.bin", slot))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Returns path of first empty autosave slot or of the oldest one if every slot is taken.
+pub fn next_autosave_path() -> PathBuf {
+    (0..AUTOSAVE_SLOTS)
+        .map(autosave_path)
+        .min_by_key(|path| modified(path))
+        .unwrap()
+}
+
+pub struct SaveInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+/// Lists existing saves, most recent first.
+pub fn list_saves() -> Vec<SaveInfo> {
+    let mut slots = vec![
+        ("Save".to_owned(), save_path()),
+        ("Quicksave".to_owned(), quicksave_path()),
+    ];
+    for slot in 0..AUTOSAVE_SLOTS {
+        slots.push((format!("Autosave {}", slot + 1), autosave_path(slot)));
+    }
+
+    let mut saves = slots
+        .into_iter()
+        .filter_map(|(name, path)| {
+            modified(&path).map(|modified| SaveInfo {
+                name,
+                path,
+                modified,
+            })
+        })
+        .collect::<Vec<_>>();
+    saves.sort_by(|a, b| b.modified.cmp(&a.modified));
+    saves
+}
+
+/// Returns version of save that is being read, or current version when saving.
+pub fn read_version(visitor: &Visitor) -> u32 {
+    if visitor.is_reading() {
+        READ_VERSION.with(|version| version.get())
+    } else {
+        SAVE_VERSION
+    }
+}
+
+pub fn write_header(visitor: &mut Visitor) -> VisitResult {
+    let mut version = SAVE_VERSION;
+    version.visit("SaveVersion", visitor)
+}
+
+/// Reads version of save and remembers it for `read_version`. Returns user-friendly error if
+/// save can't be loaded by this version of the game.
+pub fn read_header(visitor: &mut Visitor) -> Result<u32, String> {
+    let mut version = 0u32;
+    // Saves without version were made before versioning was added.
+    if version.visit("SaveVersion", visitor).is_err() {
+        version = 0;
+    }
+    if version > SAVE_VERSION {
+        return Err("This save was made by a newer version of the game".to_owned());
+    }
+    READ_VERSION.with(|read_version| read_version.set(version));
+    Ok(version)
+}
+
+/// Writes file next to destination first and then replaces destination with it, so crash in
+/// the middle of writing never damages existing file.
+pub fn save_atomically(visitor: &mut Visitor, path: &Path) -> VisitResult {
+    let temp_path = path.with_extension("tmp");
+    visitor.save_binary(&temp_path)?;
+    std::fs::rename(&temp_path, path).map_err(VisitError::Io)
+}
+
+fn zip_error(error: zip::result::ZipError) -> VisitError {
+    VisitError::Io(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        error.to_string(),
+    ))
+}
+
+/// Same as `save_atomically`, but visitor data is compressed. Visitor can only write to a file,
+/// so uncompressed data goes to a temporary file first.
+pub fn save_compressed(visitor: &mut Visitor, path: &Path) -> VisitResult {
+    let raw_path = path.with_extension("raw");
+    visitor.save_binary(&raw_path)?;
+    let mut data = Vec::new();
+    File::open(&raw_path)
+        .and_then(|mut file| file.read_to_end(&mut data))
+        .map_err(VisitError::Io)?;
+    let _ = std::fs::remove_file(&raw_path);
+
+    let temp_path = path.with_extension("tmp");
+    let mut zip = zip::ZipWriter::new(File::create(&temp_path).map_err(VisitError::Io)?);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(SAVE_ENTRY, options).map_err(zip_error)?;
+    zip.write_all(&data).map_err(VisitError::Io)?;
+    zip.finish().map_err(zip_error)?;
+    std::fs::rename(&temp_path, path).map_err(VisitError::Io)
+}
+
+fn is_compressed(path: &Path) -> bool {
+    let mut signature = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .map_or(false, |_| &signature == ZIP_SIGNATURE)
+}
+
+/// Loads save made by `save_compressed` or uncompressed save of older version.
+pub async fn load(path: &Path) -> Result<Visitor, VisitError> {
+    if !is_compressed(path) {
+        return Visitor::load_binary(path).await;
+    }
+
+    let mut zip =
+        zip::ZipArchive::new(File::open(path).map_err(VisitError::Io)?).map_err(zip_error)?;
+    let mut entry = zip.by_name(SAVE_ENTRY).map_err(zip_error)?;
+    let raw_path = path.with_extension("raw");
+    File::create(&raw_path)
+        .and_then(|mut file| std::io::copy(&mut entry, &mut file))
+        .map_err(VisitError::Io)?;
+    let result = Visitor::load_binary(&raw_path).await;
+    let _ = std::fs::remove_file(&raw_path);
+    result
+}
@@ -0,0 +1,92 @@
+//! Score events. Every action that earns points is an event, point values of events depend on
+//! game mode, see `MatchOptions::points`. Damage dealt to a victim is remembered for a while, so
+//! everyone who helped to kill the victim, except the killer, gets an assist.
+
+use crate::actor::Actor;
+use rg3d::core::pool::Handle;
+use std::collections::HashMap;
+
+/// Damage dealt longer than this many seconds before the kill does not count as an assist.
+const ASSIST_WINDOW: f32 = 8.0;
+
+/// Attacker must deal at least this much damage to the victim to get an assist.
+const MIN_ASSIST_DAMAGE: f32 = 20.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScoreEvent {
+    Kill,
+    Assist,
+    FlagCapture,
+    FlagReturn,
+    PointCapture,
+    Suicide,
+}
+
+impl ScoreEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoreEvent::Kill => "Kill",
+            ScoreEvent::Assist => "Assist",
+            ScoreEvent::FlagCapture => "Flag Capture",
+            ScoreEvent::FlagReturn => "Flag Return",
+            ScoreEvent::PointCapture => "Point Capture",
+            ScoreEvent::Suicide => "Suicide",
+        }
+    }
+}
+
+struct Contribution {
+    attacker: Handle<Actor>,
+    damage: f32,
+    time: f32,
+}
+
+#[derive(Default)]
+pub struct AssistTracker {
+    contributions: HashMap<Handle<Actor>, Vec<Contribution>>,
+}
+
+impl AssistTracker {
+    pub fn record(
+        &mut self,
+        victim: Handle<Actor>,
+        attacker: Handle<Actor>,
+        damage: f32,
+        time: f32,
+    ) {
+        let contributions = self.contributions.entry(victim).or_insert_with(Vec::new);
+        contributions.retain(|c| time - c.time < ASSIST_WINDOW);
+        match contributions.iter_mut().find(|c| c.attacker == attacker) {
+            Some(contribution) => {
+                contribution.damage += damage;
+                contribution.time = time;
+            }
+            None => contributions.push(Contribution {
+                attacker,
+                damage,
+                time,
+            }),
+        }
+    }
+
+    /// Returns everyone who helped to kill given victim, except the killer, and forgets damage
+    /// dealt to the victim.
+    pub fn take_assists(
+        &mut self,
+        victim: Handle<Actor>,
+        killer: Handle<Actor>,
+        time: f32,
+    ) -> Vec<Handle<Actor>> {
+        self.contributions
+            .remove(&victim)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| {
+                c.attacker != killer
+                    && time - c.time < ASSIST_WINDOW
+                    && c.damage >= MIN_ASSIST_DAMAGE
+            })
+            .map(|c| c.attacker)
+            .collect()
+    }
+}
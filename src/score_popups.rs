@@ -0,0 +1,135 @@
+//! "+N" popups under the crosshair for points that player earns. Popups of the same event that
+//! follow each other quickly are merged into one with summed points.
+
+use crate::{message::Message, score::ScoreEvent};
+use rg3d::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        brush::Brush,
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+
+/// How long (in seconds) each popup stays on screen.
+const POPUP_TIME: f32 = 1.5;
+
+const MAX_POPUPS: usize = 4;
+
+struct Popup {
+    event: ScoreEvent,
+    points: i32,
+    widget: Handle<UiNode>,
+    timeout: f32,
+}
+
+pub struct ScorePopups {
+    panel: Handle<UiNode>,
+    popups: Vec<Popup>,
+}
+
+fn popup_text(event: ScoreEvent, points: i32) -> String {
+    format!("{:+} {}", points, event.name())
+}
+
+impl ScorePopups {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .on_column(1)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_margin(Thickness::top(200.0)),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            panel,
+            popups: Default::default(),
+        }
+    }
+
+    /// Returns handle of popups panel, it must be attached to HUD.
+    pub fn widget(&self) -> Handle<UiNode> {
+        self.panel
+    }
+
+    fn show(&mut self, ui: &mut UserInterface, event: ScoreEvent, points: i32) {
+        if let Some(popup) = self.popups.last_mut().filter(|popup| popup.event == event) {
+            popup.points += points;
+            popup.timeout = POPUP_TIME;
+            ui.send_message(TextMessage::text(
+                popup.widget,
+                MessageDirection::ToWidget,
+                popup_text(event, popup.points),
+            ));
+            return;
+        }
+
+        if self.popups.len() >= MAX_POPUPS {
+            let oldest = self.popups.remove(0);
+            ui.send_message(WidgetMessage::remove(
+                oldest.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let widget = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(1.0))
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_foreground(Brush::Solid(if points < 0 {
+                    Color::opaque(220, 60, 60)
+                } else {
+                    Color::opaque(255, 200, 0)
+                })),
+        )
+        .with_text(popup_text(event, points))
+        .build(&mut ui.build_ctx());
+        ui.send_message(WidgetMessage::link(
+            widget,
+            MessageDirection::ToWidget,
+            self.panel,
+        ));
+
+        self.popups.push(Popup {
+            event,
+            points,
+            widget,
+            timeout: POPUP_TIME,
+        });
+    }
+
+    pub fn handle_message(&mut self, ui: &mut UserInterface, message: &Message) {
+        if let Message::ShowScorePopup { event, points } = message {
+            self.show(ui, *event, *points);
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut UserInterface, dt: f32) {
+        for popup in self.popups.iter_mut() {
+            popup.timeout -= dt;
+            if popup.timeout <= 0.0 {
+                ui.send_message(WidgetMessage::remove(
+                    popup.widget,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        }
+        self.popups.retain(|popup| popup.timeout > 0.0);
+    }
+
+    /// Removes every popup, used when level is unloaded.
+    pub fn clear(&mut self, ui: &mut UserInterface) {
+        for popup in self.popups.drain(..) {
+            ui.send_message(WidgetMessage::remove(
+                popup.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{
+    display::DisplayMode,
+    localization::DEFAULT_LANGUAGE,
+    logger::{self, Category},
+    mods::ModEntry,
+    paths, saves,
+    theme::Palette,
+    Difficulty,
+};
+use rg3d::core::{
+    color::Color,
+    visitor::{Visit, VisitResult, Visitor},
+};
+
+/// Simulation tick rates that can be selected in options.
+pub const TICK_RATES: [u32; 3] = [30, 60, 120];
+
+/// Frame rate limits that can be selected in options, zero means no limit.
+pub const FPS_LIMITS: [u32; 6] = [0, 30, 60, 120, 144, 240];
+
+/// Frame rate limits of unfocused window, simulation still catches up with real time at the
+/// lowest of them.
+pub const BACKGROUND_FPS_LIMITS: [u32; 3] = [15, 30, 60];
+
+const SETTINGS_FILE: &str = "settings.bin";
+
+/// Skins which can be picked in profile, player is marked with skin color on leader board.
+pub const SKINS: [(&str, Color); 6] = [
+    ("White", Color::WHITE),
+    ("Crimson", Color::opaque(220, 20, 60)),
+    ("Azure", Color::opaque(0, 127, 255)),
+    ("Lime", Color::opaque(50, 205, 50)),
+    ("Amber", Color::opaque(255, 191, 0)),
+    ("Violet", Color::opaque(138, 43, 226)),
+];
+
+/// Appearance of player in matches. A copy of profile is stored in every level, so changing
+/// profile takes effect in next match.
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    /// Index in [`SKINS`].
+    pub skin: u32,
+    /// Amount of completed campaign levels, every level up to this one is unlocked.
+    pub campaign_progress: u32,
+    /// Most waves cleared in a single survival match.
+    pub best_survival_wave: u32,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: "Unnamed Player".to_owned(),
+            skin: 0,
+            campaign_progress: 0,
+            best_survival_wave: 0,
+        }
+    }
+}
+
+impl Profile {
+    pub fn color(&self) -> Color {
+        SKINS
+            .get(self.skin as usize)
+            .map_or(Color::WHITE, |(_, color)| *color)
+    }
+}
+
+impl Visit for Profile {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.name.visit("Name", visitor)?;
+        self.skin.visit("Skin", visitor)?;
+        self.campaign_progress.visit("CampaignProgress", visitor)?;
+        // Profiles made before survival mode have no record.
+        if self
+            .best_survival_wave
+            .visit("BestSurvivalWave", visitor)
+            .is_err()
+        {
+            self.best_survival_wave = 0;
+        }
+
+        visitor.leave_region()
+    }
+}
+
+/// Gameplay settings which are not part of control scheme or renderer quality settings.
+pub struct Settings {
+    /// Fraction of projectiles that leave tracers or trails behind, zero disables them.
+    pub tracer_density: f32,
+    /// Hide health, armor and ammo widgets when their values do not change for a while.
+    pub minimal_hud: bool,
+    /// Flash marker over crosshair when player hits someone.
+    pub hit_markers: bool,
+    /// Show a cross over crosshair when player kills someone.
+    pub kill_marker: bool,
+    /// Bots closer than this distance to the camera are animated every frame, further ones
+    /// are animated less often.
+    pub lod_near_distance: f32,
+    /// Bots further than this distance are animated rarely and do not cast shadows.
+    pub lod_far_distance: f32,
+    /// Amount of simulation ticks per second, one of [`TICK_RATES`].
+    pub tick_rate: u32,
+    /// Volume of voice lines of characters.
+    pub voice_volume: f32,
+    /// Show subtitles of voice lines.
+    pub subtitles: bool,
+    /// Show captions for significant sounds like gunfire and explosions.
+    pub captions: bool,
+    /// Scale of camera shake, landing dip and damage flinch.
+    pub camera_effects: f32,
+    /// Disables camera effects and head bob.
+    pub reduced_motion: bool,
+    /// Strength of damage vignette, low health desaturation and armor break flash, zero
+    /// disables them along with heartbeat.
+    pub damage_effects: f32,
+    /// Colors of teams and HUD, colorblind players can pick a palette that suits them.
+    pub palette: Palette,
+    /// Scale of HUD and menus on top of scale factor of the display.
+    pub ui_scale: f32,
+    /// Code of language of UI text, see `localization::LANGUAGES`.
+    pub language: String,
+    /// Difficulty of last started match, it is also used by campaign and training.
+    pub difficulty: Difficulty,
+    /// Add weapon counters of every match to aggregate file, see `weapon_stats` module.
+    pub weapon_telemetry: bool,
+    /// Export log and timeline of every match, see `match_recorder` module.
+    pub match_logs: bool,
+    /// Announce regular matches to local network, see `lan` module.
+    pub host_on_lan: bool,
+    /// Seconds without input after which player is moved to observers, zero disables it.
+    pub afk_timeout: f32,
+    pub display_mode: DisplayMode,
+    /// Max frames per second, one of [`FPS_LIMITS`].
+    pub fps_limit: u32,
+    /// Max frames per second while window is unfocused or minimized, one of
+    /// [`BACKGROUND_FPS_LIMITS`].
+    pub background_fps_limit: u32,
+    /// Index of monitor in the list of available monitors.
+    pub monitor: u32,
+    pub profile: Profile,
+    /// Mods in load order, see `mods` module.
+    pub mods: Vec<ModEntry>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tracer_density: 1.0,
+            minimal_hud: false,
+            hit_markers: true,
+            kill_marker: true,
+            lod_near_distance: 15.0,
+            lod_far_distance: 35.0,
+            tick_rate: 60,
+            voice_volume: 1.0,
+            subtitles: true,
+            captions: false,
+            camera_effects: 1.0,
+            reduced_motion: false,
+            damage_effects: 1.0,
+            palette: Default::default(),
+            ui_scale: 1.0,
+            language: DEFAULT_LANGUAGE.to_owned(),
+            difficulty: Default::default(),
+            weapon_telemetry: false,
+            match_logs: false,
+            host_on_lan: false,
+            afk_timeout: 120.0,
+            display_mode: Default::default(),
+            fps_limit: 0,
+            background_fps_limit: 30,
+            monitor: 0,
+            profile: Default::default(),
+            mods: Default::default(),
+        }
+    }
+}
+
+impl Visit for Settings {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.tracer_density.visit("TracerDensity", visitor)?;
+        self.minimal_hud.visit("MinimalHud", visitor)?;
+        self.lod_near_distance.visit("LodNearDistance", visitor)?;
+        self.lod_far_distance.visit("LodFarDistance", visitor)?;
+        self.tick_rate.visit("TickRate", visitor)?;
+        self.voice_volume.visit("VoiceVolume", visitor)?;
+        self.subtitles.visit("Subtitles", visitor)?;
+        self.captions.visit("Captions", visitor)?;
+        self.camera_effects.visit("CameraEffects", visitor)?;
+        self.reduced_motion.visit("ReducedMotion", visitor)?;
+        self.palette.visit("Palette", visitor)?;
+        self.ui_scale.visit("UiScale", visitor)?;
+        self.language.visit("Language", visitor)?;
+        self.difficulty.visit("Difficulty", visitor)?;
+        self.profile.visit("Profile", visitor)?;
+        // Settings made before mods have no load order, mods folder is scanned anyway.
+        if self.mods.visit("Mods", visitor).is_err() {
+            self.mods.clear();
+        }
+        // Telemetry is opt-in, settings made before it leave it off.
+        if self
+            .weapon_telemetry
+            .visit("WeaponTelemetry", visitor)
+            .is_err()
+        {
+            self.weapon_telemetry = false;
+        }
+        if self.match_logs.visit("MatchLogs", visitor).is_err() {
+            self.match_logs = false;
+        }
+        if self.host_on_lan.visit("HostOnLan", visitor).is_err() {
+            self.host_on_lan = false;
+        }
+        if self.damage_effects.visit("DamageEffects", visitor).is_err() {
+            self.damage_effects = 1.0;
+        }
+        if self.hit_markers.visit("HitMarkers", visitor).is_err() {
+            self.hit_markers = true;
+        }
+        if self.kill_marker.visit("KillMarker", visitor).is_err() {
+            self.kill_marker = true;
+        }
+        if self.afk_timeout.visit("AfkTimeout", visitor).is_err() {
+            self.afk_timeout = 120.0;
+        }
+        if self.display_mode.visit("DisplayMode", visitor).is_err() {
+            self.display_mode = DisplayMode::Windowed;
+        }
+        if self.monitor.visit("Monitor", visitor).is_err() {
+            self.monitor = 0;
+        }
+        if self.fps_limit.visit("FpsLimit", visitor).is_err() {
+            self.fps_limit = 0;
+        }
+        if self
+            .background_fps_limit
+            .visit("BackgroundFpsLimit", visitor)
+            .is_err()
+        {
+            self.background_fps_limit = 30;
+        }
+
+        visitor.leave_region()
+    }
+}
+
+impl Settings {
+    /// Loads settings from settings file, falls back to defaults if there is no such file or
+    /// it is corrupted.
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        if let Ok(mut visitor) = rg3d::core::futures::executor::block_on(Visitor::load_binary(
+            paths::config_dir().join(SETTINGS_FILE),
+        )) {
+            if let Err(e) = settings.visit("Settings", &mut visitor) {
+                logger::warning(
+                    Category::General,
+                    format!(
+                        "Unable to read settings, defaults will be used. Reason: {:?}",
+                        e
+                    ),
+                );
+                settings = Self::default();
+            }
+        }
+        settings
+    }
+
+    pub fn save(&mut self) {
+        let mut visitor = Visitor::new();
+        let result = self.visit("Settings", &mut visitor).and_then(|_| {
+            saves::save_atomically(&mut visitor, &paths::config_dir().join(SETTINGS_FILE))
+        });
+        if let Err(e) = result {
+            logger::error(
+                Category::General,
+                format!("Unable to save settings. Reason: {:?}", e),
+            );
+        }
+    }
+
+    /// Restores default options, profile and mods are kept as is.
+    pub fn reset(&mut self) {
+        let profile = std::mem::take(&mut self.profile);
+        let mods = std::mem::take(&mut self.mods);
+        *self = Self {
+            profile,
+            mods,
+            ..Default::default()
+        };
+    }
+
+    pub fn fixed_timestep(&self) -> f32 {
+        1.0 / self.tick_rate as f32
+    }
+}
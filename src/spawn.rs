@@ -0,0 +1,281 @@
+//! Spawn points. Every spawn point can be tagged with a team and with match modes it is used
+//! in, untagged spawn point is used by everyone in every mode. Spawn point is picked for an
+//! actor among points that suit team of the actor and mode of the match, points that are
+//! occupied by another actor or seen by an enemy are avoided if possible.
+//!
+//! Scene markers are tagged by name suffix - `SpawnPoint_Red`, `SpawnPoint_Blue`; placement
+//! layer of a map can tag modes as well, see `map_layout` module.
+
+use crate::{actor::ActorContainer, character::Team, level, saves, MatchOptions};
+use rg3d::{
+    core::{
+        algebra::Vector3,
+        rand::seq::SliceRandom,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    rand,
+    scene::physics::Physics,
+};
+
+/// Spawn point closer than this distance to an actor is occupied.
+const OCCUPIED_RADIUS: f32 = 1.5;
+
+/// Height of eyes of an actor above center of its body, line of sight is checked from there.
+const EYE_HEIGHT: f32 = 0.6;
+
+/// Spawned actor appears this high above its spawn point.
+pub const SPAWN_HEIGHT: f32 = 1.5;
+
+/// Set of match modes, every mode is a single bit.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SpawnModes(u32);
+
+impl SpawnModes {
+    pub const DEATH_MATCH: SpawnModes = SpawnModes(1);
+    pub const TEAM_DEATH_MATCH: SpawnModes = SpawnModes(1 << 1);
+    pub const CAPTURE_THE_FLAG: SpawnModes = SpawnModes(1 << 2);
+    pub const SURVIVAL: SpawnModes = SpawnModes(1 << 3);
+    pub const ALL: SpawnModes = SpawnModes(0b1111);
+
+    /// Short names used in placement layer, in order of bits.
+    const NAMES: [&'static str; 4] = ["dm", "tdm", "ctf", "survival"];
+
+    pub fn of(options: &MatchOptions) -> Self {
+        match options {
+            MatchOptions::DeathMatch(_) => SpawnModes::DEATH_MATCH,
+            MatchOptions::TeamDeathMatch(_) => SpawnModes::TEAM_DEATH_MATCH,
+            MatchOptions::CaptureTheFlag(_) => SpawnModes::CAPTURE_THE_FLAG,
+            MatchOptions::Survival(_) => SpawnModes::SURVIVAL,
+        }
+    }
+
+    pub fn contains(self, other: SpawnModes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Parses comma-separated list of short names, for example `tdm,ctf`.
+    pub fn parse(names: &str) -> Result<Self, String> {
+        let mut modes = 0;
+        for name in names.split(',') {
+            match Self::NAMES.iter().position(|known| *known == name) {
+                Some(bit) => modes |= 1 << bit,
+                None => return Err(format!("unknown mode \"{}\"", name)),
+            }
+        }
+        Ok(SpawnModes(modes))
+    }
+
+    pub fn names(self) -> String {
+        Self::NAMES
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| self.0 & (1 << bit) != 0)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl Default for SpawnModes {
+    fn default() -> Self {
+        SpawnModes::ALL
+    }
+}
+
+impl Visit for SpawnModes {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        self.0.visit(name, visitor)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SpawnStrategy {
+    /// Suitable points are used in turn.
+    RoundRobin,
+    /// Point farthest from the closest enemy is used.
+    FarthestFromEnemies,
+}
+
+impl SpawnStrategy {
+    pub fn of(options: &MatchOptions) -> Self {
+        match options {
+            // Waves of survival come from every side instead of the farthest corner.
+            MatchOptions::Survival(_) => SpawnStrategy::RoundRobin,
+            _ => SpawnStrategy::FarthestFromEnemies,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SpawnPoint {
+    pub position: Vector3<f32>,
+    /// Team::None means point is used by every team.
+    pub team: Team,
+    pub modes: SpawnModes,
+}
+
+impl Visit for SpawnPoint {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.position.visit("Position", visitor)?;
+        if saves::read_version(visitor) >= 9 {
+            self.team.visit("Team", visitor)?;
+            self.modes.visit("Modes", visitor)?;
+        }
+
+        visitor.leave_region()
+    }
+}
+
+impl SpawnPoint {
+    fn suits(&self, team: Team, modes: SpawnModes) -> bool {
+        self.modes.contains(modes)
+            && (self.team == Team::None || team == Team::None || self.team == team)
+    }
+}
+
+#[derive(Default)]
+pub struct SpawnPoints {
+    points: Vec<SpawnPoint>,
+    // Not serialized, round robin of loaded game starts from the first point.
+    next: usize,
+}
+
+impl Visit for SpawnPoints {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        self.points.visit(name, visitor)
+    }
+}
+
+fn is_enemy(team: Team, other: Team) -> bool {
+    team == Team::None || other != team
+}
+
+fn is_seen_by_enemy(
+    position: Vector3<f32>,
+    team: Team,
+    actors: &ActorContainer,
+    physics: &Physics,
+) -> bool {
+    let target = position + Vector3::new(0.0, SPAWN_HEIGHT, 0.0);
+    actors
+        .iter()
+        .filter(|actor| !actor.is_dead() && is_enemy(team, actor.team()))
+        .any(|actor| {
+            let eye = actor.position(physics) + Vector3::new(0.0, EYE_HEIGHT, 0.0);
+            let d = target - eye;
+            let distance = d.norm();
+            match d.try_normalize(std::f32::EPSILON) {
+                Some(direction) => {
+                    level::cast_to_static_geometry(physics, eye, direction, distance).is_none()
+                }
+                None => true,
+            }
+        })
+}
+
+fn is_occupied(position: Vector3<f32>, actors: &ActorContainer, physics: &Physics) -> bool {
+    let center = position + Vector3::new(0.0, SPAWN_HEIGHT, 0.0);
+    actors
+        .iter()
+        .any(|actor| actor.position(physics).metric_distance(&center) < OCCUPIED_RADIUS)
+}
+
+impl SpawnPoints {
+    pub fn new(points: Vec<SpawnPoint>) -> Self {
+        Self { points, next: 0 }
+    }
+
+    /// Center of spawn points of given team, None if map has no points tagged with the team.
+    pub fn base(&self, team: Team) -> Option<Vector3<f32>> {
+        if team == Team::None {
+            return None;
+        }
+        let (sum, count) = self
+            .points
+            .iter()
+            .filter(|point| point.team == team)
+            .fold((Vector3::default(), 0), |(sum, count), point| {
+                (sum + point.position, count + 1)
+            });
+        if count == 0 {
+            None
+        } else {
+            Some(sum.scale(1.0 / count as f32))
+        }
+    }
+
+    /// Picks spawn point for an actor of given team, None if map has no spawn points. Points
+    /// that are free and hidden from enemies are preferred, then points that are just free,
+    /// then any suitable point.
+    pub fn select(
+        &mut self,
+        team: Team,
+        options: &MatchOptions,
+        actors: &ActorContainer,
+        physics: &Physics,
+    ) -> Option<Vector3<f32>> {
+        let modes = SpawnModes::of(options);
+        let mut candidates = (0..self.points.len())
+            .filter(|&i| self.points[i].suits(team, modes))
+            .collect::<Vec<_>>();
+        // Map is not tagged for this mode or team, any point is better than none.
+        if candidates.is_empty() {
+            candidates = (0..self.points.len()).collect();
+        }
+
+        let free = candidates
+            .iter()
+            .copied()
+            .filter(|&i| !is_occupied(self.points[i].position, actors, physics))
+            .collect::<Vec<_>>();
+        let safe = free
+            .iter()
+            .copied()
+            .filter(|&i| !is_seen_by_enemy(self.points[i].position, team, actors, physics))
+            .collect::<Vec<_>>();
+        let pool = if !safe.is_empty() {
+            safe
+        } else if !free.is_empty() {
+            free
+        } else {
+            candidates
+        };
+
+        let index = match SpawnStrategy::of(options) {
+            SpawnStrategy::RoundRobin => {
+                let count = self.points.len();
+                let index = (0..count)
+                    .map(|offset| (self.next + offset) % count)
+                    .find(|i| pool.contains(i));
+                if let Some(index) = index {
+                    self.next = index + 1;
+                }
+                index
+            }
+            SpawnStrategy::FarthestFromEnemies => {
+                let enemies = actors
+                    .iter()
+                    .filter(|actor| !actor.is_dead() && is_enemy(team, actor.team()))
+                    .map(|actor| actor.position(physics))
+                    .collect::<Vec<_>>();
+                if enemies.is_empty() {
+                    pool.choose(&mut rand::thread_rng()).copied()
+                } else {
+                    pool.iter().copied().max_by(|&a, &b| {
+                        let distance = |i: usize| {
+                            enemies
+                                .iter()
+                                .map(|enemy| enemy.metric_distance(&self.points[i].position))
+                                .fold(std::f32::MAX, f32::min)
+                        };
+                        distance(a).partial_cmp(&distance(b)).unwrap()
+                    })
+                }
+            }
+        };
+
+        index.map(|index| self.points[index].position)
+    }
+}
@@ -0,0 +1,156 @@
+//! Squad coordinator assigns roles to bots of a team. By default every bot roams the map on its
+//! own, orders of player send part of the squad to defend own base, attack enemy flag, follow
+//! or cover the player. Order is weighted by its share of the squad - bots closest to the
+//! objective of the order take the role, the rest keep roaming so the team does not collapse
+//! into a single crowd.
+
+use crate::{actor::Actor, character::Team};
+use rg3d::core::{algebra::Vector3, pool::Handle};
+use std::collections::HashMap;
+
+/// Roles are reassigned this often, so followers keep up with moving leader.
+const ASSIGN_INTERVAL: f32 = 0.5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Order {
+    FollowMe,
+    CoverMe,
+    AttackFlag,
+    DefendBase,
+}
+
+impl Order {
+    /// Orders as they are placed on command rose, clockwise from the top.
+    pub const ALL: [Order; 4] = [
+        Order::FollowMe,
+        Order::CoverMe,
+        Order::AttackFlag,
+        Order::DefendBase,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Order::FollowMe => "Follow Me",
+            Order::CoverMe => "Cover Me",
+            Order::AttackFlag => "Attack Flag",
+            Order::DefendBase => "Defend Base",
+        }
+    }
+
+    /// What player says in team chat when giving the order.
+    pub fn callout(self) -> &'static str {
+        match self {
+            Order::FollowMe => "Follow me!",
+            Order::CoverMe => "Cover me!",
+            Order::AttackFlag => "Go for their flag!",
+            Order::DefendBase => "Defend our base!",
+        }
+    }
+
+    /// Share of the squad that follows the order.
+    fn share(self) -> f32 {
+        match self {
+            Order::FollowMe => 1.0,
+            Order::CoverMe => 0.5,
+            Order::AttackFlag => 0.75,
+            Order::DefendBase => 0.5,
+        }
+    }
+
+    /// Bots closer than this to the objective of the order hold their position.
+    pub fn hold_radius(self) -> f32 {
+        match self {
+            Order::FollowMe => 3.0,
+            Order::CoverMe => 6.0,
+            Order::AttackFlag => 4.0,
+            Order::DefendBase => 8.0,
+        }
+    }
+}
+
+/// Where a bot must go to carry out the order.
+#[derive(Copy, Clone, Debug)]
+pub struct Role {
+    pub order: Order,
+    pub destination: Vector3<f32>,
+    /// Direction to watch, covering bots look the same way as the leader.
+    pub facing: Option<Vector3<f32>>,
+}
+
+pub struct Member {
+    pub handle: Handle<Actor>,
+    pub team: Team,
+    pub position: Vector3<f32>,
+}
+
+/// Objective of an order, position and facing.
+pub type Objective = (Vector3<f32>, Option<Vector3<f32>>);
+
+#[derive(Default)]
+pub struct SquadCoordinator {
+    orders: HashMap<Team, Order>,
+    roles: HashMap<Handle<Actor>, Role>,
+    timer: f32,
+}
+
+impl SquadCoordinator {
+    /// Replaces current order of the team, None lets the squad roam again.
+    pub fn issue(&mut self, team: Team, order: Option<Order>) {
+        match order {
+            Some(order) => {
+                self.orders.insert(team, order);
+            }
+            None => {
+                self.orders.remove(&team);
+            }
+        }
+        // New order is carried out at once.
+        self.timer = 0.0;
+    }
+
+    pub fn order(&self, team: Team) -> Option<Order> {
+        self.orders.get(&team).cloned()
+    }
+
+    pub fn role(&self, bot: Handle<Actor>) -> Option<&Role> {
+        self.roles.get(&bot)
+    }
+
+    /// Assigns roles to given bots. `objective` returns position of objective of the order for
+    /// given team, None if the order can not be carried out on this map.
+    pub fn update<F>(&mut self, dt: f32, members: &[Member], mut objective: F)
+    where
+        F: FnMut(Team, Order) -> Option<Objective>,
+    {
+        self.timer -= dt;
+        if self.timer > 0.0 {
+            return;
+        }
+        self.timer = ASSIGN_INTERVAL;
+
+        self.roles.clear();
+        for (&team, &order) in self.orders.iter() {
+            let (destination, facing) = match objective(team, order) {
+                Some(objective) => objective,
+                None => continue,
+            };
+            let mut squad = members
+                .iter()
+                .filter(|member| member.team == team)
+                .map(|member| (member.handle, member.position.metric_distance(&destination)))
+                .collect::<Vec<_>>();
+            squad.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            let count = (squad.len() as f32 * order.share()).ceil() as usize;
+            for &(handle, _) in squad.iter().take(count) {
+                self.roles.insert(
+                    handle,
+                    Role {
+                        order,
+                        destination,
+                        facing,
+                    },
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Checks that are made before the game starts and reporting of errors that prevent it from
+//! starting. Such error is logged, printed to stderr and shown in a message box when there is
+//! a desktop to show it on, then the game exits.
+
+use crate::logger::{self, Category};
+use std::{any::Any, path::Path, process::Command};
+
+/// Directories of game data that must exist next to the executable's working directory.
+const DATA_DIRS: [&str; 6] = [
+    "data/levels",
+    "data/models",
+    "data/sounds",
+    "data/textures",
+    "data/ui",
+    "data/languages",
+];
+
+const TITLE: &str = "Rusty Shooter";
+
+/// Returns user-friendly error if game data is missing.
+pub fn check_data() -> Result<(), String> {
+    let cwd = std::env::current_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|_| "unknown".to_owned());
+
+    if !Path::new("data").is_dir() {
+        return Err(format!(
+            "Game data folder \"data\" was not found in {}.\n\
+             Start the game from its installation folder, the one which contains \"data\".",
+            cwd
+        ));
+    }
+
+    let missing = DATA_DIRS
+        .iter()
+        .filter(|dir| !Path::new(dir).is_dir())
+        .copied()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Game data in {} is incomplete, missing: {}.\n\
+             Reinstall the game or restore these folders.",
+            cwd,
+            missing.join(", ")
+        ))
+    }
+}
+
+/// Turns payload of a panic into text.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown error".to_owned()
+    }
+}
+
+fn has_desktop() -> bool {
+    if cfg!(target_os = "linux") {
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    } else {
+        true
+    }
+}
+
+// There is no message box in the engine, so tools of the platform are used. Nothing is shown
+// if there is no such tool, the error is printed to stderr anyway.
+fn show_message_box(text: &str) {
+    let status = if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -AssemblyName PresentationFramework; \
+             [System.Windows.MessageBox]::Show('{}', '{}', 'OK', 'Error')",
+            text.replace('\'', "''"),
+            TITLE
+        );
+        Command::new("powershell")
+            .args(&["-NoProfile", "-Command", &script])
+            .status()
+    } else if cfg!(target_os = "macos") {
+        let script = format!(
+            "display alert \"{}\" message \"{}\" as critical",
+            TITLE,
+            text.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        Command::new("osascript").args(&["-e", &script]).status()
+    } else {
+        Command::new("zenity")
+            .args(&["--error", "--title", TITLE, "--text", text])
+            .status()
+            .or_else(|_| {
+                Command::new("kdialog")
+                    .args(&["--title", TITLE, "--error", text])
+                    .status()
+            })
+    };
+    if let Err(e) = status {
+        logger::warning(
+            Category::General,
+            format!("Unable to show message box. Reason: {:?}", e),
+        );
+    }
+}
+
+/// Reports error that prevents the game from starting, caller must exit afterwards.
+pub fn report(error: &str) {
+    logger::error(Category::General, format!("Unable to start: {}", error));
+    eprintln!("{}: unable to start.\n{}", TITLE, error);
+    if has_desktop() {
+        show_message_box(error);
+    }
+}
@@ -0,0 +1,105 @@
+//! Teleporters placed by level editor, see `map_layout` module. Actor that steps on a teleporter
+//! is moved to its destination keeping its velocity.
+
+use rg3d::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        color::Color,
+        pool::Handle,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder,
+        },
+        node::Node,
+        transform::TransformBuilder,
+    },
+};
+use std::sync::{Arc, RwLock};
+
+/// Actor whose body center is closer than this distance to a teleporter is teleported, body
+/// center of standing actor is about a meter above the floor.
+const TRIGGER_RADIUS: f32 = 1.5;
+
+/// Actor appears this high above destination, so it does not get stuck in the floor.
+const EXIT_HEIGHT: f32 = 1.5;
+
+const RADIUS: f32 = 1.0;
+
+const HEIGHT: f32 = 0.1;
+
+const COLOR: Color = Color::opaque(80, 160, 255);
+
+#[derive(Default)]
+pub struct Teleporter {
+    model: Handle<Node>,
+    position: Vector3<f32>,
+    destination: Vector3<f32>,
+}
+
+impl Visit for Teleporter {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.model.visit("Model", visitor)?;
+        self.position.visit("Position", visitor)?;
+        self.destination.visit("Destination", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl Teleporter {
+    pub fn new(graph: &mut Graph, position: Vector3<f32>, destination: Vector3<f32>) -> Self {
+        Self {
+            model: make_pad(graph, position, RADIUS, HEIGHT, COLOR),
+            position,
+            destination,
+        }
+    }
+
+    pub fn is_triggered_by(&self, position: Vector3<f32>) -> bool {
+        (self.position - position).norm() < TRIGGER_RADIUS
+    }
+
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    /// Returns position at which teleported actor appears.
+    pub fn exit(&self) -> Vector3<f32> {
+        self.destination + Vector3::new(0.0, EXIT_HEIGHT, 0.0)
+    }
+}
+
+/// Creates flat cylinder lying on the ground, used for teleporters and jump pads of editor.
+pub fn make_pad(
+    graph: &mut Graph,
+    position: Vector3<f32>,
+    radius: f32,
+    height: f32,
+    color: Color,
+) -> Handle<Node> {
+    let data = SurfaceData::make_cylinder(
+        16,
+        radius,
+        height,
+        true,
+        &Matrix4::new_translation(&Vector3::new(0.0, height * 0.5, 0.0)),
+    );
+    let mut surface = SurfaceBuilder::new(Arc::new(RwLock::new(data))).build();
+    surface.set_color(color);
+    MeshBuilder::new(
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(position)
+                .build(),
+        ),
+    )
+    .with_surfaces(vec![surface])
+    .build(graph)
+}
@@ -0,0 +1,160 @@
+//! Colors of teams and HUD. Every color that carries meaning must be taken from here, so
+//! colorblind palettes can remap it.
+
+use crate::character::Team;
+use rg3d::core::{
+    color::Color,
+    visitor::{Visit, VisitResult, Visitor},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Palette {
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::Default
+    }
+}
+
+pub struct Theme {
+    pub red_team: Color,
+    pub blue_team: Color,
+    pub health: Color,
+    pub armor: Color,
+    pub shield: Color,
+    pub ammo: Color,
+    /// Colors of crosshair hit markers.
+    pub hit: Color,
+    pub headshot: Color,
+    pub kill: Color,
+    /// Colors of pings placed by player.
+    pub ping: Color,
+    pub enemy_ping: Color,
+    pub danger_ping: Color,
+}
+
+impl Theme {
+    pub fn team_color(&self, team: Team) -> Color {
+        match team {
+            Team::None => Color::WHITE,
+            Team::Red => self.red_team,
+            Team::Blue => self.blue_team,
+        }
+    }
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Default,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+    ];
+
+    pub fn id(self) -> u32 {
+        match self {
+            Palette::Default => 0,
+            Palette::Deuteranopia => 1,
+            Palette::Protanopia => 2,
+            Palette::Tritanopia => 3,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(Palette::Default),
+            1 => Ok(Palette::Deuteranopia),
+            2 => Ok(Palette::Protanopia),
+            3 => Ok(Palette::Tritanopia),
+            _ => Err(format!("Invalid palette id {}!", id)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Default => "Default",
+            Palette::Deuteranopia => "Deuteranopia",
+            Palette::Protanopia => "Protanopia",
+            Palette::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Colorblind palettes are built from Okabe-Ito colors, pairs of them stay distinct
+    /// for given kind of color vision deficiency.
+    pub fn theme(self) -> Theme {
+        match self {
+            Palette::Default => Theme {
+                red_team: Color::opaque(220, 40, 40),
+                blue_team: Color::opaque(40, 80, 230),
+                health: Color::opaque(180, 14, 22),
+                armor: Color::opaque(255, 100, 26),
+                shield: Color::opaque(0, 210, 255),
+                ammo: Color::opaque(79, 79, 255),
+                hit: Color::opaque(255, 255, 255),
+                headshot: Color::opaque(255, 200, 0),
+                kill: Color::opaque(230, 30, 30),
+                ping: Color::opaque(0, 220, 120),
+                enemy_ping: Color::opaque(230, 30, 30),
+                danger_ping: Color::opaque(255, 200, 0),
+            },
+            Palette::Deuteranopia => Theme {
+                red_team: Color::opaque(230, 159, 0),
+                blue_team: Color::opaque(0, 114, 178),
+                health: Color::opaque(230, 159, 0),
+                armor: Color::opaque(86, 180, 233),
+                shield: Color::opaque(0, 114, 178),
+                ammo: Color::opaque(240, 228, 66),
+                hit: Color::opaque(255, 255, 255),
+                headshot: Color::opaque(86, 180, 233),
+                kill: Color::opaque(230, 159, 0),
+                ping: Color::opaque(86, 180, 233),
+                enemy_ping: Color::opaque(230, 159, 0),
+                danger_ping: Color::opaque(240, 228, 66),
+            },
+            Palette::Protanopia => Theme {
+                red_team: Color::opaque(240, 228, 66),
+                blue_team: Color::opaque(0, 114, 178),
+                health: Color::opaque(240, 228, 66),
+                armor: Color::opaque(86, 180, 233),
+                shield: Color::opaque(0, 114, 178),
+                ammo: Color::opaque(204, 121, 167),
+                hit: Color::opaque(255, 255, 255),
+                headshot: Color::opaque(86, 180, 233),
+                kill: Color::opaque(230, 159, 0),
+                ping: Color::opaque(86, 180, 233),
+                enemy_ping: Color::opaque(230, 159, 0),
+                danger_ping: Color::opaque(204, 121, 167),
+            },
+            Palette::Tritanopia => Theme {
+                red_team: Color::opaque(213, 94, 0),
+                blue_team: Color::opaque(0, 158, 115),
+                health: Color::opaque(213, 94, 0),
+                armor: Color::opaque(0, 158, 115),
+                shield: Color::opaque(86, 180, 233),
+                ammo: Color::opaque(204, 121, 167),
+                hit: Color::opaque(255, 255, 255),
+                headshot: Color::opaque(204, 121, 167),
+                kill: Color::opaque(213, 94, 0),
+                ping: Color::opaque(86, 180, 233),
+                enemy_ping: Color::opaque(213, 94, 0),
+                danger_ping: Color::opaque(204, 121, 167),
+            },
+        }
+    }
+}
+
+impl Visit for Palette {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut id = self.id();
+        id.visit(name, visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! Training level teaches basics of the game. Player goes through a list of scripted objectives
+//! one by one, current objective is shown on HUD along with buttons that are bound to required
+//! actions in control scheme. Bots of training level are target dummies - they stand still and
+//! never fight back.
+
+use crate::control_scheme::ControlScheme;
+use rg3d::core::{
+    algebra::Vector3,
+    visitor::{Visit, VisitResult, Visitor},
+};
+
+/// Distance player has to walk to complete movement objective.
+const MOVE_DISTANCE: f32 = 5.0;
+
+/// How high above ground player has to get to complete jump objective.
+const JUMP_HEIGHT: f32 = 0.4;
+
+/// Amount of hits on target dummies needed to complete the training.
+pub const TARGETS_TO_HIT: u32 = 5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Objective {
+    Move,
+    Jump,
+    PickUpWeapon,
+    HitTargets,
+    Complete,
+}
+
+impl Default for Objective {
+    fn default() -> Self {
+        Objective::Move
+    }
+}
+
+impl Objective {
+    fn from_id(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(Objective::Move),
+            1 => Ok(Objective::Jump),
+            2 => Ok(Objective::PickUpWeapon),
+            3 => Ok(Objective::HitTargets),
+            4 => Ok(Objective::Complete),
+            _ => Err(format!("Invalid training objective {}", id)),
+        }
+    }
+
+    fn id(self) -> u32 {
+        match self {
+            Objective::Move => 0,
+            Objective::Jump => 1,
+            Objective::PickUpWeapon => 2,
+            Objective::HitTargets => 3,
+            Objective::Complete => 4,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Objective::Move => Objective::Jump,
+            Objective::Jump => Objective::PickUpWeapon,
+            Objective::PickUpWeapon => Objective::HitTargets,
+            Objective::HitTargets | Objective::Complete => Objective::Complete,
+        }
+    }
+}
+
+impl Visit for Objective {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut id = self.id();
+        id.visit(name, visitor)?;
+        if visitor.is_reading() {
+            *self = Self::from_id(id)?;
+        }
+        Ok(())
+    }
+}
+
+/// State of the player that objectives are checked against.
+pub struct PlayerState {
+    pub position: Vector3<f32>,
+    pub has_ground_contact: bool,
+    pub armed: bool,
+}
+
+#[derive(Default)]
+pub struct Training {
+    objective: Objective,
+    // Position at which current objective was started, None until first update.
+    start_position: Option<Vector3<f32>>,
+    ground_height: f32,
+    hits: u32,
+    // Not serialized, prompt is re-sent after loading.
+    prompt: Option<String>,
+}
+
+impl Visit for Training {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.objective.visit("Objective", visitor)?;
+        self.start_position.visit("StartPosition", visitor)?;
+        self.ground_height.visit("GroundHeight", visitor)?;
+        self.hits.visit("Hits", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl Training {
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    /// Player starts the training unarmed, weapons are given on respawn only after the weapon
+    /// objective is done.
+    pub fn is_player_armed(&self) -> bool {
+        self.objective.id() > Objective::PickUpWeapon.id()
+    }
+
+    /// Checks current objective and advances to next one when it is done. Returns true if
+    /// objective has changed.
+    pub fn update(&mut self, player: &PlayerState) -> bool {
+        let start_position = *self.start_position.get_or_insert(player.position);
+        if player.has_ground_contact {
+            self.ground_height = player.position.y;
+        }
+
+        let done = match self.objective {
+            Objective::Move => {
+                let mut offset = player.position - start_position;
+                offset.y = 0.0;
+                offset.norm() >= MOVE_DISTANCE
+            }
+            Objective::Jump => {
+                !player.has_ground_contact && player.position.y - self.ground_height >= JUMP_HEIGHT
+            }
+            Objective::PickUpWeapon => player.armed,
+            Objective::HitTargets => self.hits >= TARGETS_TO_HIT,
+            Objective::Complete => false,
+        };
+
+        if done {
+            self.objective = self.objective.next();
+            self.start_position = Some(player.position);
+        }
+        done
+    }
+
+    pub fn on_target_hit(&mut self) {
+        if self.objective == Objective::HitTargets {
+            self.hits += 1;
+        }
+    }
+
+    fn describe(&self, control_scheme: &ControlScheme) -> String {
+        match self.objective {
+            Objective::Move => format!(
+                "Move around using {}, {}, {} and {}",
+                control_scheme.move_forward.button.name(),
+                control_scheme.move_left.button.name(),
+                control_scheme.move_backward.button.name(),
+                control_scheme.move_right.button.name()
+            ),
+            Objective::Jump => format!("Press {} to jump", control_scheme.jump.button.name()),
+            Objective::PickUpWeapon => "Pick up the rifle in front of you".to_owned(),
+            Objective::HitTargets => format!(
+                "Shoot the target dummies with {} ({}/{})",
+                control_scheme.shoot.button.name(),
+                self.hits,
+                TARGETS_TO_HIT
+            ),
+            Objective::Complete => "Training complete! Press Esc to return to menu".to_owned(),
+        }
+    }
+
+    /// Returns new prompt for HUD if it has changed since last call. Prompt is rebuilt every
+    /// time so it always shows current bindings, even if player rebinds buttons mid-training.
+    pub fn poll_prompt(&mut self, control_scheme: &ControlScheme) -> Option<String> {
+        let prompt = self.describe(control_scheme);
+        if self.prompt.as_ref() != Some(&prompt) {
+            self.prompt = Some(prompt.clone());
+            Some(prompt)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! Automated sentries. Turrets are placed by map (nodes named `Turret`, optionally with team
+//! suffix like `Turret_Red`) or deployed by players with sentry kit. Turret does not think
+//! on its own, it picks closest visible enemy from target descriptors which actors build
+//! every frame and shoots it with instant hits.
+
+use crate::{
+    actor::{Actor, TargetDescriptor},
+    character::Team,
+    effects::{EffectKind, EffectPool},
+    message::Message,
+};
+use rg3d::{
+    core::{
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector3},
+        color::Color,
+        math::ray::Ray,
+        pool::{Handle, Pool},
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    physics3d::{
+        rapier::{
+            dynamics::{RigidBodyBuilder, RigidBodyType},
+            geometry::{ColliderBuilder, InteractionGroups},
+        },
+        RayCastOptions, RigidBodyHandle,
+    },
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder,
+        },
+        node::Node,
+        physics::Physics,
+        transform::TransformBuilder,
+        Scene,
+    },
+};
+use std::{
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc, RwLock},
+};
+
+const MAX_HEALTH: f32 = 150.0;
+
+/// Max distance (in meters) at which turret sees targets.
+const RANGE: f32 = 20.0;
+
+/// Angular speeds are in rad/s.
+const TURN_SPEED: f32 = 2.5;
+
+const IDLE_TURN_SPEED: f32 = 0.5;
+
+/// Turret opens fire when it is turned to target closer than this angle (in radians).
+const FIRE_ANGLE: f32 = 0.1;
+
+const SHOOT_INTERVAL: f32 = 0.2;
+
+const DAMAGE: f32 = 6.0;
+
+/// Height of turret's head above the surface it stands on.
+const HEIGHT: f32 = 1.0;
+
+const SHOT_SOUND: &str = "data/sounds/m4_shot.ogg";
+
+const TRACER_COLOR: Color = Color::opaque(255, 220, 120);
+
+/// Player may have only this amount of turrets, deploying one more removes the oldest.
+const MAX_TURRETS_PER_OWNER: usize = 1;
+
+pub struct Turret {
+    pivot: Handle<Node>,
+    head: Handle<Node>,
+    body: RigidBodyHandle,
+    // NONE for turrets placed by map.
+    owner: Handle<Actor>,
+    team: Team,
+    health: f32,
+    yaw: f32,
+    shoot_timer: f32,
+}
+
+impl Default for Turret {
+    fn default() -> Self {
+        Self {
+            pivot: Default::default(),
+            head: Default::default(),
+            body: Default::default(),
+            owner: Default::default(),
+            team: Default::default(),
+            health: MAX_HEALTH,
+            yaw: 0.0,
+            shoot_timer: 0.0,
+        }
+    }
+}
+
+impl Visit for Turret {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.pivot.visit("Pivot", visitor)?;
+        self.head.visit("Head", visitor)?;
+        self.body.visit("Body", visitor)?;
+        self.owner.visit("Owner", visitor)?;
+        self.team.visit("Team", visitor)?;
+        self.health.visit("Health", visitor)?;
+        self.yaw.visit("Yaw", visitor)?;
+        self.shoot_timer.visit("ShootTimer", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+/// Returns true if there is level geometry between two points.
+fn is_occluded(from: Vector3<f32>, to: Vector3<f32>, physics: &Physics) -> bool {
+    let ray = Ray::from_two_points(from, to);
+    let mut query_buffer = Vec::default();
+    physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(ray.origin),
+            ray_direction: ray.dir,
+            max_len: ray.dir.norm(),
+            groups: InteractionGroups::all(),
+            sort_results: false,
+        },
+        &mut query_buffer,
+    );
+    query_buffer.iter().any(|hit| {
+        physics
+            .colliders
+            .get(&hit.collider)
+            .unwrap()
+            .shape()
+            .as_trimesh()
+            .is_some()
+    })
+}
+
+impl Turret {
+    /// Creates turret standing on a floor at given position.
+    pub fn new(
+        scene: &mut Scene,
+        position: Vector3<f32>,
+        owner: Handle<Actor>,
+        team: Team,
+    ) -> Self {
+        let color = match team {
+            Team::None => Color::opaque(150, 150, 150),
+            Team::Red => Color::opaque(200, 60, 60),
+            Team::Blue => Color::opaque(60, 90, 200),
+        };
+
+        let mut head_surface = SurfaceBuilder::new(Arc::new(RwLock::new(SurfaceData::make_cube(
+            Matrix4::new_nonuniform_scaling(&Vector3::new(0.4, 0.3, 0.7)),
+        ))))
+        .build();
+        head_surface.set_color(color);
+        let head = MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, HEIGHT, 0.0))
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![head_surface])
+        .build(&mut scene.graph);
+
+        let mut base_surface =
+            SurfaceBuilder::new(Arc::new(RwLock::new(SurfaceData::make_cylinder(
+                12,
+                0.25,
+                HEIGHT,
+                true,
+                &Matrix4::new_translation(&Vector3::new(0.0, HEIGHT * 0.5, 0.0)),
+            ))))
+            .build();
+        base_surface.set_color(Color::opaque(80, 80, 80));
+        let pivot = MeshBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[head])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+        )
+        .with_surfaces(vec![base_surface])
+        .build(&mut scene.graph);
+
+        let body = scene.physics.add_body(
+            RigidBodyBuilder::new(RigidBodyType::Static)
+                .translation(position + Vector3::new(0.0, HEIGHT * 0.5, 0.0))
+                .build(),
+        );
+        scene.physics.add_collider(
+            ColliderBuilder::cylinder(HEIGHT * 0.5 + 0.15, 0.35).build(),
+            &body,
+        );
+
+        Self {
+            pivot,
+            head,
+            body,
+            owner,
+            team,
+            ..Default::default()
+        }
+    }
+
+    fn eye_position(&self, graph: &Graph) -> Vector3<f32> {
+        graph[self.head].global_position()
+    }
+
+    fn is_enemy(&self, target: &TargetDescriptor) -> bool {
+        target.handle != self.owner
+            && target.health > 0.0
+            && (self.team == Team::None || target.team != self.team)
+    }
+
+    fn update(
+        &mut self,
+        scene: &mut Scene,
+        targets: &[TargetDescriptor],
+        effects: &mut EffectPool,
+        dt: f32,
+        sender: &Sender<Message>,
+    ) {
+        let eye = self.eye_position(&scene.graph);
+        let target = targets
+            .iter()
+            .filter(|target| self.is_enemy(target))
+            .map(|target| (target, target.position.metric_distance(&eye)))
+            .filter(|&(target, distance)| {
+                distance < RANGE && !is_occluded(eye, target.position, &scene.physics)
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(target, _)| target);
+
+        self.shoot_timer -= dt;
+
+        match target {
+            Some(target) => {
+                let direction = target.position - eye;
+                let desired_yaw = direction.x.atan2(direction.z);
+                let mut delta = desired_yaw - self.yaw;
+                // Turn by shortest arc.
+                while delta > std::f32::consts::PI {
+                    delta -= 2.0 * std::f32::consts::PI;
+                }
+                while delta < -std::f32::consts::PI {
+                    delta += 2.0 * std::f32::consts::PI;
+                }
+                let step = TURN_SPEED * dt;
+                self.yaw += delta.max(-step).min(step);
+
+                if delta.abs() < FIRE_ANGLE && self.shoot_timer <= 0.0 {
+                    self.shoot_timer = SHOOT_INTERVAL;
+                    // Owner may be gone already, then damage is credited to nobody.
+                    let who = if targets.iter().any(|other| other.handle == self.owner) {
+                        self.owner
+                    } else {
+                        Handle::NONE
+                    };
+                    sender
+                        .send(Message::DamageActor {
+                            actor: target.handle,
+                            who,
+                            amount: DAMAGE,
+                            direction: Some(direction),
+                            zone: None,
+                        })
+                        .unwrap();
+                    sender
+                        .send(Message::PlaySound {
+                            path: PathBuf::from(SHOT_SOUND),
+                            position: eye,
+                            gain: 1.0,
+                            rolloff_factor: 2.0,
+                            radius: 3.0,
+                        })
+                        .unwrap();
+                    effects.spawn_trail_segment(
+                        &mut scene.graph,
+                        eye,
+                        target.position,
+                        0.01,
+                        TRACER_COLOR,
+                        0.05,
+                    );
+                }
+            }
+            None => self.yaw += IDLE_TURN_SPEED * dt,
+        }
+
+        scene.graph[self.head]
+            .local_transform_mut()
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                self.yaw,
+            ));
+    }
+
+    fn clean_up(&mut self, scene: &mut Scene) {
+        scene.physics.remove_body(&self.body);
+        scene.graph.remove_node(self.pivot);
+    }
+}
+
+#[derive(Default)]
+pub struct TurretContainer {
+    pool: Pool<Turret>,
+}
+
+impl TurretContainer {
+    pub fn new() -> Self {
+        Self { pool: Pool::new() }
+    }
+
+    /// Adds new turret, oldest turret of the same owner is removed if owner has too many
+    /// of them. Turrets of map have no owner and are not limited.
+    pub fn add(&mut self, turret: Turret, scene: &mut Scene) -> Handle<Turret> {
+        if turret.owner.is_some() {
+            let owned = self
+                .pool
+                .pair_iter()
+                .filter(|(_, other)| other.owner == turret.owner)
+                .map(|(handle, _)| handle)
+                .collect::<Vec<_>>();
+            // Pool gives no order of insertion, but with single allowed turret any will do.
+            for handle in owned.into_iter().skip(MAX_TURRETS_PER_OWNER - 1) {
+                self.pool.free(handle).clean_up(scene);
+            }
+        }
+        self.pool.spawn(turret)
+    }
+
+    pub fn find_by_body(&self, body: RigidBodyHandle) -> Option<Handle<Turret>> {
+        self.pool
+            .pair_iter()
+            .find(|(_, turret)| turret.body == body)
+            .map(|(handle, _)| handle)
+    }
+
+    /// Destroyed turret explodes and disappears.
+    pub fn damage(
+        &mut self,
+        handle: Handle<Turret>,
+        amount: f32,
+        scene: &mut Scene,
+        sender: &Sender<Message>,
+    ) {
+        if !self.pool.is_valid_handle(handle) {
+            return;
+        }
+
+        let turret = &mut self.pool[handle];
+        turret.health -= amount;
+        if turret.health > 0.0 {
+            return;
+        }
+
+        let position = turret.eye_position(&scene.graph);
+        self.pool.free(handle).clean_up(scene);
+
+        sender
+            .send(Message::CreateEffect {
+                kind: EffectKind::BulletImpact,
+                position,
+            })
+            .unwrap();
+        sender
+            .send(Message::CreateEffect {
+                kind: EffectKind::Smoke,
+                position,
+            })
+            .unwrap();
+        sender
+            .send(Message::PlaySound {
+                path: PathBuf::from("data/sounds/explosion.ogg"),
+                position,
+                gain: 1.0,
+                rolloff_factor: 4.0,
+                radius: 3.0,
+            })
+            .unwrap();
+        sender
+            .send(Message::ShakeCamera {
+                position,
+                radius: 8.0,
+            })
+            .unwrap();
+    }
+
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        targets: &[TargetDescriptor],
+        effects: &mut EffectPool,
+        dt: f32,
+        sender: &Sender<Message>,
+    ) {
+        for turret in self.pool.iter_mut() {
+            turret.update(scene, targets, effects, dt, sender);
+        }
+    }
+}
+
+impl Visit for TurretContainer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.pool.visit("Pool", visitor)?;
+
+        visitor.leave_region()
+    }
+}
@@ -0,0 +1,98 @@
+//! First-person viewmodel of player's weapon. Player's weapons are attached to a pivot under
+//! the camera, the viewmodel animates that pivot procedurally: idle sway, bob that depends on
+//! movement speed and fire, reload and switch animations. Bots hold their weapons in hands of
+//! their models so they are not affected by any of this.
+
+use crate::{weapon::Weapon, GameTime};
+use rg3d::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
+    scene::node::Node,
+};
+
+/// Bob phase increment per frame at full running speed.
+const BOB_RATE: f32 = 0.3;
+
+/// How long (in seconds) freshly selected weapon is raised.
+const SWITCH_TIME: f32 = 0.35;
+
+/// How long (in seconds) reload animation plays.
+const RELOAD_TIME: f32 = 0.8;
+
+#[derive(Default)]
+pub struct Viewmodel {
+    weapon: Handle<Weapon>,
+    last_shot_time: f64,
+    ammo: u32,
+    bob_phase: f32,
+    bob_amplitude: f32,
+    kick: f32,
+    switch_time: f32,
+    reload_time: f32,
+}
+
+impl Viewmodel {
+    /// `speed` is ratio of current movement speed to max running speed, it must be zero
+    /// when player is in the air.
+    pub fn update(
+        &mut self,
+        pivot: &mut Node,
+        weapon: Option<(Handle<Weapon>, &Weapon)>,
+        speed: f32,
+        time: GameTime,
+    ) {
+        if let Some((handle, weapon)) = weapon {
+            if handle != self.weapon {
+                self.weapon = handle;
+                self.switch_time = SWITCH_TIME;
+                self.reload_time = 0.0;
+                self.kick = 0.0;
+            } else {
+                if weapon.last_shot_time() > self.last_shot_time {
+                    self.kick = weapon.fire_mode().recoil_multiplier();
+                }
+                // Weapons have no magazines, so topping up ammo is what plays reload.
+                if weapon.ammo() > self.ammo {
+                    self.reload_time = RELOAD_TIME;
+                }
+            }
+            self.last_shot_time = weapon.last_shot_time();
+            self.ammo = weapon.ammo();
+        }
+
+        self.switch_time = (self.switch_time - time.delta).max(0.0);
+        self.reload_time = (self.reload_time - time.delta).max(0.0);
+        self.kick -= self.kick * time.smoothing(0.15);
+        self.bob_amplitude += (speed.min(1.0) - self.bob_amplitude) * time.smoothing(0.1);
+        self.bob_phase += time.per_frame(BOB_RATE) * self.bob_amplitude;
+
+        // Idle sway fades out when player moves and bob takes over.
+        let idle = 1.0 - self.bob_amplitude;
+        let elapsed = time.elapsed as f32;
+        let lowered = self.switch_time / SWITCH_TIME;
+        // Goes from 0 to 1 and back during reload, weapon dips and tilts to the side.
+        let reload = (std::f32::consts::PI * self.reload_time / RELOAD_TIME).sin();
+
+        let position = Vector3::new(
+            idle * 0.003 * (elapsed * 0.7).cos()
+                + self.bob_amplitude * 0.012 * (self.bob_phase * 0.5).cos(),
+            idle * 0.002 * (elapsed * 1.4).sin()
+                + self.bob_amplitude * 0.006 * self.bob_phase.sin()
+                - 0.12 * lowered
+                - 0.05 * reload,
+            -0.02 * self.kick,
+        );
+        // Angles are in degrees, positive pitch points muzzle down.
+        let pitch = -4.0 * self.kick + 30.0 * lowered + 15.0 * reload;
+        let roll = idle * 1.5 * (elapsed * 0.7).sin() + 20.0 * reload;
+
+        let transform = pivot.local_transform_mut();
+        transform.set_position(position);
+        transform.set_rotation(
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch.to_radians())
+                * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), roll.to_radians()),
+        );
+    }
+}
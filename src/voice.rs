@@ -0,0 +1,142 @@
+//! Voice lines that characters say on match events. Every character has a voice set which is
+//! described by a text file `data/voices/<voice>.txt`, each line of the file has the form:
+//!
+//! `<Event> <path to sound or -> <subtitle>`
+//!
+//! For example `Kill data/sounds/voices/mutant_kill1.wav Fresh meat!`. Event is one of `Kill`,
+//! `Death`, `FlagTaken`. Sound can be `-` for lines that only have a subtitle. Empty lines and
+//! lines starting with `#` are ignored. Voice sets of player and bots are loaded when level
+//! starts, so files are not read in the middle of a fight.
+
+use crate::{
+    bot::{Bot, BotKind},
+    logger::{self, Category},
+};
+use rg3d::{core::rand::seq::SliceRandom, rand};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+/// Voice set of player's character.
+pub const PLAYER_VOICE: &str = "player";
+
+/// How long (in seconds) subtitle of a voice line is shown.
+pub const SUBTITLE_TIME: f32 = 3.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum VoiceEvent {
+    Kill,
+    Death,
+    FlagTaken,
+}
+
+impl VoiceEvent {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Kill" => Some(VoiceEvent::Kill),
+            "Death" => Some(VoiceEvent::Death),
+            "FlagTaken" => Some(VoiceEvent::FlagTaken),
+            _ => None,
+        }
+    }
+}
+
+pub struct VoiceLine {
+    pub sound: Option<PathBuf>,
+    pub subtitle: String,
+}
+
+#[derive(Default)]
+struct VoiceSet {
+    lines: HashMap<VoiceEvent, Vec<VoiceLine>>,
+}
+
+impl VoiceSet {
+    fn load(path: &Path) -> Self {
+        let mut set = VoiceSet::default();
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                logger::warning(
+                    Category::Audio,
+                    format!("Unable to load voice set {:?}. Reason: {:?}", path, e),
+                );
+                return set;
+            }
+        };
+
+        for line in BufReader::new(file).lines().filter_map(|line| line.ok()) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(3, char::is_whitespace);
+            let event = parts.next().and_then(VoiceEvent::from_name);
+            let sound = parts.next();
+            let subtitle = parts.next().unwrap_or_default().trim();
+
+            if let (Some(event), Some(sound)) = (event, sound) {
+                let sound = if sound == "-" {
+                    None
+                } else if Path::new(sound).exists() {
+                    Some(PathBuf::from(sound))
+                } else {
+                    logger::warning(
+                        Category::Audio,
+                        format!("Voice sound {} does not exist!", sound),
+                    );
+                    None
+                };
+
+                set.lines.entry(event).or_default().push(VoiceLine {
+                    sound,
+                    subtitle: subtitle.to_owned(),
+                });
+            } else {
+                logger::warning(
+                    Category::Audio,
+                    format!("Invalid voice line {:?} in {:?}", line, path),
+                );
+            }
+        }
+
+        set
+    }
+}
+
+#[derive(Default)]
+pub struct VoiceBank {
+    sets: HashMap<&'static str, VoiceSet>,
+}
+
+impl VoiceBank {
+    /// Creates bank with voice sets of player and every kind of bot.
+    pub fn new() -> Self {
+        let mut bank = Self::default();
+        bank.load(PLAYER_VOICE);
+        for &kind in BotKind::ALL.iter() {
+            bank.load(Bot::get_definition(kind).voice);
+        }
+        bank
+    }
+
+    fn load(&mut self, voice: &'static str) -> &VoiceSet {
+        self.sets.entry(voice).or_insert_with(|| {
+            VoiceSet::load(&Path::new("data/voices").join(voice).with_extension("txt"))
+        })
+    }
+
+    /// Picks random line of given voice for an event, returns None if voice has no lines
+    /// for the event.
+    pub fn pick(&mut self, voice: &'static str, event: VoiceEvent) -> Option<&VoiceLine> {
+        self.load(voice)
+            .lines
+            .get(&event)
+            .and_then(|lines| lines.choose(&mut rand::thread_rng()))
+    }
+}
@@ -0,0 +1,185 @@
+//! Votes. Any player can call a vote from chat - `/vote kick <name>`, `/vote map <name>`,
+//! `/vote mode <dm|tdm|ctf|survival>` or `/vote end` - and everyone in the match answers with
+//! `/yes` or `/no`. Bots vote as well, so votes work in matches against bots too.
+//!
+//! Calls and ballots are regular messages, level of the host counts them and enforces the
+//! result, so network protocol can carry votes the same way as any other message.
+
+use crate::{maps, MatchOptions};
+use rg3d::{core::rand::Rng, rand};
+use std::{collections::HashMap, path::PathBuf};
+
+/// How long (in seconds) vote lasts, vote that has no majority by then fails.
+const VOTE_TIME: f32 = 30.0;
+
+/// Bots think this many seconds at most before they vote.
+const MAX_BOT_DELAY: f32 = 8.0;
+
+/// Chance that bot agrees with a vote that does not concern it.
+const BOT_YES_CHANCE: f64 = 0.5;
+
+#[derive(Clone, Debug)]
+pub enum VoteSubject {
+    /// Removes player or bot with given name from the match.
+    Kick(String),
+    ChangeMap(PathBuf),
+    /// Restarts match on the same map in another mode.
+    ChangeMode(MatchOptions),
+    EndMatch,
+}
+
+impl VoteSubject {
+    /// Parses arguments of `/vote` command, for example `kick Bob` or `mode tdm`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut args = text.splitn(2, ' ');
+        let name = args.next().unwrap_or_default();
+        let arg = args.next().map(|arg| arg.trim()).unwrap_or_default();
+        match (name, arg) {
+            ("kick", player) if !player.is_empty() => Ok(VoteSubject::Kick(player.to_owned())),
+            ("map", map) if !map.is_empty() => maps::all()
+                .into_iter()
+                .find(|definition| definition.name.eq_ignore_ascii_case(map))
+                .map(|definition| VoteSubject::ChangeMap(PathBuf::from(definition.path)))
+                .ok_or_else(|| format!("Unknown map {}", map)),
+            ("mode", mode) => {
+                let id = match mode {
+                    "dm" => 0,
+                    "tdm" => 1,
+                    "ctf" => 2,
+                    "survival" => 3,
+                    _ => return Err("Modes: dm, tdm, ctf, survival".to_owned()),
+                };
+                Ok(VoteSubject::ChangeMode(MatchOptions::from_id(id)?))
+            }
+            ("end", _) => Ok(VoteSubject::EndMatch),
+            _ => Err(
+                "Votes: /vote kick <name>, /vote map <name>, /vote mode <mode>, /vote end"
+                    .to_owned(),
+            ),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            VoteSubject::Kick(player) => format!("kick {}", player),
+            VoteSubject::ChangeMap(path) => format!(
+                "change map to {}",
+                maps::find(path).map_or_else(
+                    || path.to_string_lossy().into_owned(),
+                    |map| map.name.to_owned()
+                )
+            ),
+            VoteSubject::ChangeMode(options) => format!("change mode to {}", options.name()),
+            VoteSubject::EndMatch => "end match".to_owned(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum VoteResult {
+    Pending,
+    Passed,
+    Failed,
+}
+
+pub struct Vote {
+    subject: VoteSubject,
+    caller: String,
+    time_left: f32,
+    // Amount of players and bots in the match when vote was called.
+    voters: usize,
+    ballots: HashMap<String, bool>,
+    // Bots that have not voted yet and time left until they do.
+    bots: Vec<(String, f32)>,
+    // Prompt that is shown on HUD right now.
+    shown_prompt: String,
+}
+
+impl Vote {
+    /// Starts a vote, caller votes for it right away.
+    pub fn new(subject: VoteSubject, caller: String, players: usize, bots: Vec<String>) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut ballots = HashMap::new();
+        ballots.insert(caller.clone(), true);
+        Self {
+            subject,
+            time_left: VOTE_TIME,
+            voters: players + bots.len(),
+            ballots,
+            bots: bots
+                .into_iter()
+                .filter(|bot| *bot != caller)
+                .map(|bot| (bot, rng.gen_range(1.0..MAX_BOT_DELAY)))
+                .collect(),
+            caller,
+            shown_prompt: Default::default(),
+        }
+    }
+
+    pub fn subject(&self) -> &VoteSubject {
+        &self.subject
+    }
+
+    /// Records ballot of a voter, voter can change their mind until vote is over.
+    pub fn cast(&mut self, voter: &str, yes: bool) {
+        self.ballots.insert(voter.to_owned(), yes);
+        self.bots.retain(|(bot, _)| bot != voter);
+    }
+
+    fn count(&self, yes: bool) -> usize {
+        self.ballots
+            .values()
+            .filter(|&&ballot| ballot == yes)
+            .count()
+    }
+
+    pub fn result(&self) -> VoteResult {
+        let needed = self.voters / 2 + 1;
+        if self.count(true) >= needed {
+            VoteResult::Passed
+        } else if self.count(false) > self.voters - needed || self.time_left <= 0.0 {
+            VoteResult::Failed
+        } else {
+            VoteResult::Pending
+        }
+    }
+
+    /// Counts down the vote and lets bots that made up their mind vote.
+    pub fn update(&mut self, dt: f32) {
+        self.time_left -= dt;
+
+        let mut rng = rand::thread_rng();
+        let mut decided = Vec::new();
+        for (bot, delay) in self.bots.iter_mut() {
+            *delay -= dt;
+            if *delay <= 0.0 {
+                let yes = match &self.subject {
+                    VoteSubject::Kick(player) if player == bot => false,
+                    _ => rng.gen_bool(BOT_YES_CHANCE),
+                };
+                decided.push((bot.clone(), yes));
+            }
+        }
+        for (bot, yes) in decided {
+            self.cast(&bot, yes);
+        }
+    }
+
+    /// Returns text of vote prompt on HUD if it has changed since last call.
+    pub fn poll_prompt(&mut self) -> Option<String> {
+        let prompt = format!(
+            "{} called a vote to {} ({}s)\nYes: {}  No: {}  - type /yes or /no in chat",
+            self.caller,
+            self.subject.describe(),
+            self.time_left.max(0.0).ceil() as u32,
+            self.count(true),
+            self.count(false)
+        );
+        if prompt != self.shown_prompt {
+            self.shown_prompt = prompt.clone();
+            Some(prompt)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Screen-space markers of objectives. Level tells which points of the world are important
+//! right now (like target dummies in training), HUD projects them onto the screen and shows
+//! a marker with distance over each one. Markers of points that are off-screen or behind the
+//! camera stick to the screen edge in direction to the point.
+
+use rg3d::{
+    core::{
+        algebra::{Matrix4, Vector2, Vector3, Vector4},
+        color::Color,
+        pool::Handle,
+    },
+    gui::{
+        brush::Brush,
+        canvas::CanvasBuilder,
+        message::MessageDirection,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, UiNode, UserInterface,
+    },
+};
+
+const MARKER_WIDTH: f32 = 140.0;
+
+const MARKER_HEIGHT: f32 = 40.0;
+
+/// Distance (in pixels) between screen edge and markers that are stuck to it.
+const EDGE_MARGIN: f32 = 10.0;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Waypoint {
+    pub label: String,
+    pub position: Vector3<f32>,
+    pub color: Color,
+}
+
+/// Camera the world is seen through.
+pub struct View {
+    pub view_projection: Matrix4<f32>,
+    pub eye: Vector3<f32>,
+}
+
+struct Marker {
+    widget: Handle<UiNode>,
+    label: String,
+    color: Color,
+    text: String,
+}
+
+pub struct Waypoints {
+    canvas: Handle<UiNode>,
+    markers: Vec<Marker>,
+    visible: bool,
+}
+
+/// Returns position of marker's top-left corner on the screen, arrow at the bottom of marker
+/// points to the waypoint.
+fn project(view: &View, position: Vector3<f32>, screen_size: Vector2<f32>) -> Vector2<f32> {
+    let clip = view.view_projection * Vector4::new(position.x, position.y, position.z, 1.0);
+    let behind = clip.w <= 0.0;
+    let w = clip.w.abs().max(std::f32::EPSILON);
+    let mut ndc = Vector2::new(clip.x / w, clip.y / w);
+    if behind {
+        // Point behind the camera is projected mirrored, it also must stick to an edge even
+        // if it is right behind the camera.
+        ndc = -ndc;
+        if ndc.norm() < std::f32::EPSILON {
+            ndc = Vector2::new(0.0, -1.0);
+        }
+    }
+    let max = ndc.x.abs().max(ndc.y.abs());
+    if behind || max > 1.0 {
+        ndc /= max;
+    }
+
+    let x = (ndc.x + 1.0) * 0.5 * screen_size.x - MARKER_WIDTH * 0.5;
+    let y = (1.0 - ndc.y) * 0.5 * screen_size.y - MARKER_HEIGHT;
+    Vector2::new(
+        x.max(EDGE_MARGIN)
+            .min(screen_size.x - MARKER_WIDTH - EDGE_MARGIN),
+        y.max(EDGE_MARGIN)
+            .min(screen_size.y - MARKER_HEIGHT - EDGE_MARGIN),
+    )
+}
+
+impl Waypoints {
+    /// Markers live in their own full-screen canvas, because cells of HUD grid are smaller
+    /// than the screen.
+    pub fn new(ui: &mut UserInterface, width: f32, height: f32) -> Self {
+        let canvas = CanvasBuilder::new(WidgetBuilder::new().with_width(width).with_height(height))
+            .build(&mut ui.build_ctx());
+
+        Self {
+            canvas,
+            markers: Default::default(),
+            visible: true,
+        }
+    }
+
+    pub fn resize(&self, ui: &mut UserInterface, width: f32, height: f32) {
+        ui.send_message(WidgetMessage::width(
+            self.canvas,
+            MessageDirection::ToWidget,
+            width,
+        ));
+        ui.send_message(WidgetMessage::height(
+            self.canvas,
+            MessageDirection::ToWidget,
+            height,
+        ));
+    }
+
+    fn rebuild(&mut self, ui: &mut UserInterface, waypoints: &[Waypoint]) {
+        for marker in self.markers.drain(..) {
+            ui.send_message(WidgetMessage::remove(
+                marker.widget,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        for waypoint in waypoints {
+            let widget = TextBuilder::new(
+                WidgetBuilder::new()
+                    .with_width(MARKER_WIDTH)
+                    .with_height(MARKER_HEIGHT)
+                    .with_foreground(Brush::Solid(waypoint.color)),
+            )
+            .with_horizontal_text_alignment(HorizontalAlignment::Center)
+            .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                widget,
+                MessageDirection::ToWidget,
+                self.canvas,
+            ));
+            self.markers.push(Marker {
+                widget,
+                label: waypoint.label.clone(),
+                color: waypoint.color,
+                text: String::new(),
+            });
+        }
+    }
+
+    /// Syncs markers with given waypoints, markers are hidden if there is no view.
+    pub fn update(&mut self, ui: &mut UserInterface, waypoints: &[Waypoint], view: Option<View>) {
+        let changed = self.markers.len() != waypoints.len()
+            || self
+                .markers
+                .iter()
+                .zip(waypoints)
+                .any(|(marker, waypoint)| {
+                    marker.label != waypoint.label || marker.color != waypoint.color
+                });
+        if changed {
+            self.rebuild(ui, waypoints);
+        }
+
+        if self.visible != view.is_some() {
+            self.visible = view.is_some();
+            ui.send_message(WidgetMessage::visibility(
+                self.canvas,
+                MessageDirection::ToWidget,
+                self.visible,
+            ));
+        }
+        let view = match view {
+            Some(view) => view,
+            None => return,
+        };
+
+        let screen_size = ui.screen_size();
+        for (marker, waypoint) in self.markers.iter_mut().zip(waypoints) {
+            ui.send_message(WidgetMessage::desired_position(
+                marker.widget,
+                MessageDirection::ToWidget,
+                project(&view, waypoint.position, screen_size),
+            ));
+
+            let distance = waypoint.position.metric_distance(&view.eye);
+            let text = format!("{} {:.0} m\nv", waypoint.label, distance);
+            if text != marker.text {
+                marker.text = text.clone();
+                ui.send_message(TextMessage::text(
+                    marker.widget,
+                    MessageDirection::ToWidget,
+                    text,
+                ));
+            }
+        }
+    }
+}
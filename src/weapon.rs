@@ -1,10 +1,15 @@
 use crate::{
-    actor::Actor, actor::ActorContainer, message::Message, projectile::ProjectileKind, GameTime,
+    actor::Actor,
+    actor::ActorContainer,
+    assets,
+    logger::{self, Category},
+    message::Message,
+    projectile::ProjectileKind,
+    GameTime,
 };
 use rg3d::core::algebra::Point3;
-use rg3d::engine::resource_manager::MaterialSearchOptions;
-use rg3d::utils::log::{Log, MessageKind};
 use rg3d::{
+    core::rand::Rng,
     core::{
         algebra::{Matrix3, Vector3},
         color::Color,
@@ -14,6 +19,7 @@ use rg3d::{
     },
     engine::resource_manager::ResourceManager,
     physics3d::{rapier::geometry::InteractionGroups, RayCastOptions},
+    rand,
     scene::{
         base::BaseBuilder,
         graph::Graph,
@@ -25,7 +31,7 @@ use rg3d::{
 };
 use std::{
     ops::{Index, IndexMut},
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::mpsc::Sender,
 };
 
@@ -35,6 +41,10 @@ pub enum WeaponKind {
     Ak47,
     PlasmaRifle,
     RocketLauncher,
+    Flamethrower,
+    LightningGun,
+    MineLayer,
+    SentryKit,
 }
 
 impl WeaponKind {
@@ -44,6 +54,10 @@ impl WeaponKind {
             WeaponKind::Ak47 => 1,
             WeaponKind::PlasmaRifle => 2,
             WeaponKind::RocketLauncher => 3,
+            WeaponKind::Flamethrower => 4,
+            WeaponKind::LightningGun => 5,
+            WeaponKind::MineLayer => 6,
+            WeaponKind::SentryKit => 7,
         }
     }
 
@@ -53,9 +67,77 @@ impl WeaponKind {
             1 => Ok(WeaponKind::Ak47),
             2 => Ok(WeaponKind::PlasmaRifle),
             3 => Ok(WeaponKind::RocketLauncher),
+            4 => Ok(WeaponKind::Flamethrower),
+            5 => Ok(WeaponKind::LightningGun),
+            6 => Ok(WeaponKind::MineLayer),
+            7 => Ok(WeaponKind::SentryKit),
             _ => Err(format!("unknown weapon kind {}", id)),
         }
     }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WeaponKind::M4 => "M4",
+            WeaponKind::Ak47 => "AK-47",
+            WeaponKind::PlasmaRifle => "Plasma Rifle",
+            WeaponKind::RocketLauncher => "Rocket Launcher",
+            WeaponKind::Flamethrower => "Flamethrower",
+            WeaponKind::LightningGun => "Lightning Gun",
+            WeaponKind::MineLayer => "Mine Layer",
+            WeaponKind::SentryKit => "Sentry Kit",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FireMode {
+    Auto,
+    Burst,
+    Single,
+}
+
+impl FireMode {
+    pub fn id(self) -> u32 {
+        match self {
+            FireMode::Auto => 0,
+            FireMode::Burst => 1,
+            FireMode::Single => 2,
+        }
+    }
+
+    pub fn new(id: u32) -> Result<Self, String> {
+        match id {
+            0 => Ok(FireMode::Auto),
+            1 => Ok(FireMode::Burst),
+            2 => Ok(FireMode::Single),
+            _ => Err(format!("unknown fire mode {}", id)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FireMode::Auto => "AUTO",
+            FireMode::Burst => "BURST",
+            FireMode::Single => "SINGLE",
+        }
+    }
+
+    /// Controlled fire is more precise.
+    pub fn spread_multiplier(self) -> f32 {
+        match self {
+            FireMode::Auto => 1.0,
+            FireMode::Burst => 0.6,
+            FireMode::Single => 0.3,
+        }
+    }
+
+    pub fn recoil_multiplier(self) -> f32 {
+        match self {
+            FireMode::Auto => 1.0,
+            FireMode::Burst => 0.8,
+            FireMode::Single => 0.5,
+        }
+    }
 }
 
 pub struct Weapon {
@@ -69,16 +151,41 @@ pub struct Weapon {
     shot_position: Vector3<f32>,
     owner: Handle<Actor>,
     ammo: u32,
+    fire_mode: FireMode,
+    burst_shots_left: u32,
+    trigger_released: bool,
     pub definition: &'static WeaponDefinition,
     pub sender: Option<Sender<Message>>,
 }
 
+/// What happens when weapon shoots.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Shot {
+    Projectile(ProjectileKind),
+    /// Beam hits instantly along the line of fire.
+    Beam,
+    /// Proximity mine is placed on a surface in front of the shooter.
+    Mine,
+    /// Sentry turret is placed on the floor in front of the shooter.
+    Turret,
+}
+
 pub struct WeaponDefinition {
     pub model: &'static str,
     pub shot_sound: &'static str,
     pub ammo: u32,
-    pub projectile: ProjectileKind,
+    pub shot: Shot,
     pub shoot_interval: f64,
+    /// First mode is default one.
+    pub fire_modes: &'static [FireMode],
+    /// Max deviation of projectile from aim direction in degrees in automatic fire mode.
+    pub spread: f32,
+    /// Bots prefer to attack holders of such weapons over closer targets.
+    pub high_priority: bool,
+    /// How much bots like the weapon, zero means that bots never use it.
+    pub bot_rating: f32,
+    /// Distances (in meters) to target at which bots pick the weapon.
+    pub bot_range: (f32, f32),
 }
 
 impl Default for Weapon {
@@ -94,6 +201,9 @@ impl Default for Weapon {
             shot_position: Vector3::default(),
             owner: Handle::NONE,
             ammo: 250,
+            fire_mode: FireMode::Auto,
+            burst_shots_left: 0,
+            trigger_released: true,
             definition: Self::get_definition(WeaponKind::M4),
             sender: None,
         }
@@ -119,11 +229,19 @@ impl Visit for Weapon {
         self.owner.visit("Owner", visitor)?;
         self.ammo.visit("Ammo", visitor)?;
 
+        let mut fire_mode_id = self.fire_mode.id();
+        fire_mode_id.visit("FireMode", visitor)?;
+        if visitor.is_reading() {
+            self.fire_mode = FireMode::new(fire_mode_id)?
+        }
+
         visitor.leave_region()
     }
 }
 
 impl Weapon {
+    const BURST_SIZE: u32 = 3;
+
     pub fn get_definition(kind: WeaponKind) -> &'static WeaponDefinition {
         match kind {
             WeaponKind::M4 => {
@@ -131,8 +249,13 @@ impl Weapon {
                     model: "data/models/m4.FBX",
                     shot_sound: "data/sounds/m4_shot.ogg",
                     ammo: 200,
-                    projectile: ProjectileKind::Bullet,
+                    shot: Shot::Projectile(ProjectileKind::Bullet),
                     shoot_interval: 0.15,
+                    fire_modes: &[FireMode::Auto, FireMode::Burst, FireMode::Single],
+                    spread: 1.5,
+                    high_priority: false,
+                    bot_rating: 1.0,
+                    bot_range: (0.0, 40.0),
                 };
                 &DEFINITION
             }
@@ -141,8 +264,13 @@ impl Weapon {
                     model: "data/models/ak47.FBX",
                     shot_sound: "data/sounds/ak47.ogg",
                     ammo: 200,
-                    projectile: ProjectileKind::Bullet,
+                    shot: Shot::Projectile(ProjectileKind::Bullet),
                     shoot_interval: 0.15,
+                    fire_modes: &[FireMode::Auto, FireMode::Single],
+                    spread: 2.5,
+                    high_priority: false,
+                    bot_rating: 0.9,
+                    bot_range: (0.0, 30.0),
                 };
                 &DEFINITION
             }
@@ -151,8 +279,13 @@ impl Weapon {
                     model: "data/models/plasma_rifle.FBX",
                     shot_sound: "data/sounds/plasma_shot.ogg",
                     ammo: 100,
-                    projectile: ProjectileKind::Plasma,
+                    shot: Shot::Projectile(ProjectileKind::Plasma),
                     shoot_interval: 0.25,
+                    fire_modes: &[FireMode::Auto, FireMode::Burst],
+                    spread: 1.0,
+                    high_priority: false,
+                    bot_rating: 1.2,
+                    bot_range: (3.0, 30.0),
                 };
                 &DEFINITION
             }
@@ -161,8 +294,79 @@ impl Weapon {
                     model: "data/models/Rpg7.FBX",
                     shot_sound: "data/sounds/grenade_launcher_fire.ogg",
                     ammo: 100,
-                    projectile: ProjectileKind::Rocket,
+                    shot: Shot::Projectile(ProjectileKind::Rocket),
                     shoot_interval: 1.5,
+                    fire_modes: &[FireMode::Auto],
+                    spread: 0.0,
+                    high_priority: false,
+                    bot_rating: 1.5,
+                    bot_range: (6.0, 40.0),
+                };
+                &DEFINITION
+            }
+            WeaponKind::Flamethrower => {
+                // There is no model of flamethrower yet, so it borrows one of plasma rifle.
+                static DEFINITION: WeaponDefinition = WeaponDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    shot_sound: "data/sounds/plasma_shot.ogg",
+                    ammo: 300,
+                    shot: Shot::Projectile(ProjectileKind::Flame),
+                    shoot_interval: 0.06,
+                    fire_modes: &[FireMode::Auto],
+                    spread: 10.0,
+                    high_priority: false,
+                    bot_rating: 1.4,
+                    bot_range: (0.0, 8.0),
+                };
+                &DEFINITION
+            }
+            WeaponKind::LightningGun => {
+                // Beam is continuous, so every shot is a tick of the beam which costs one unit
+                // of ammo - 20 units per second.
+                static DEFINITION: WeaponDefinition = WeaponDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    shot_sound: "data/sounds/plasma_shot.ogg",
+                    ammo: 400,
+                    shot: Shot::Beam,
+                    shoot_interval: 0.05,
+                    fire_modes: &[FireMode::Auto],
+                    spread: 0.0,
+                    high_priority: true,
+                    bot_rating: 1.3,
+                    bot_range: (0.0, 15.0),
+                };
+                &DEFINITION
+            }
+            WeaponKind::MineLayer => {
+                // Each shot places one mine, so ammo is the amount of mines. Just like
+                // flamethrower it borrows model of plasma rifle.
+                static DEFINITION: WeaponDefinition = WeaponDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    shot_sound: "data/sounds/bullet_impact_metal.ogg",
+                    ammo: 6,
+                    shot: Shot::Mine,
+                    shoot_interval: 1.0,
+                    fire_modes: &[FireMode::Single],
+                    spread: 0.0,
+                    high_priority: false,
+                    bot_rating: 0.0,
+                    bot_range: (0.0, 0.0),
+                };
+                &DEFINITION
+            }
+            WeaponKind::SentryKit => {
+                // Kit holds one turret which is deployed by a shot.
+                static DEFINITION: WeaponDefinition = WeaponDefinition {
+                    model: "data/models/plasma_rifle.FBX",
+                    shot_sound: "data/sounds/bullet_impact_metal.ogg",
+                    ammo: 1,
+                    shot: Shot::Turret,
+                    shoot_interval: 1.0,
+                    fire_modes: &[FireMode::Single],
+                    spread: 0.0,
+                    high_priority: false,
+                    bot_rating: 0.0,
+                    bot_range: (0.0, 0.0),
                 };
                 &DEFINITION
             }
@@ -177,14 +381,7 @@ impl Weapon {
     ) -> Weapon {
         let definition = Self::get_definition(kind);
 
-        let model = resource_manager
-            .request_model(
-                Path::new(definition.model),
-                MaterialSearchOptions::MaterialsDirectory(PathBuf::from("data/textures")),
-            )
-            .await
-            .unwrap()
-            .instantiate_geometry(scene);
+        let model = assets::instantiate_model(&resource_manager, definition.model, scene).await;
 
         let laser_dot = PointLightBuilder::new(
             BaseLightBuilder::new(BaseBuilder::new())
@@ -198,7 +395,7 @@ impl Weapon {
         let shot_point = scene.graph.find_by_name(model, "Weapon:ShotPoint");
 
         if shot_point.is_none() {
-            Log::writeln(MessageKind::Warning, "Shot point not found!".to_owned());
+            logger::warning(Category::General, "Shot point not found!".to_owned());
         }
 
         Weapon {
@@ -208,6 +405,7 @@ impl Weapon {
             shot_point,
             definition,
             ammo: definition.ammo,
+            fire_mode: definition.fire_modes[0],
             sender: Some(sender),
             ..Default::default()
         }
@@ -222,8 +420,27 @@ impl Weapon {
         self.model
     }
 
-    pub fn update(&mut self, scene: &mut Scene, actors: &ActorContainer) {
-        self.offset.follow(&self.dest_offset, 0.2);
+    pub fn update(
+        &mut self,
+        self_handle: Handle<Weapon>,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        time: GameTime,
+    ) {
+        self.offset.follow(&self.dest_offset, time.smoothing(0.2));
+
+        // Rest of the burst is fired regardless of trigger state.
+        if self.burst_shots_left > 0 {
+            if let Some(sender) = self.sender.as_ref() {
+                sender
+                    .send(Message::ShootWeapon {
+                        weapon: self_handle,
+                        initial_velocity: Vector3::default(),
+                        direction: None,
+                    })
+                    .unwrap();
+            }
+        }
 
         self.update_laser_sight(&mut scene.graph, &mut scene.physics, actors);
 
@@ -257,6 +474,50 @@ impl Weapon {
         self.ammo += amount;
     }
 
+    pub fn set_ammo(&mut self, ammo: u32) {
+        self.ammo = ammo;
+    }
+
+    pub fn last_shot_time(&self) -> f64 {
+        self.last_shot_time
+    }
+
+    pub fn fire_mode(&self) -> FireMode {
+        self.fire_mode
+    }
+
+    pub fn cycle_fire_mode(&mut self) {
+        let modes = self.definition.fire_modes;
+        if let Some(index) = modes.iter().position(|m| *m == self.fire_mode) {
+            self.fire_mode = modes[(index + 1) % modes.len()];
+            self.burst_shots_left = 0;
+        }
+    }
+
+    pub fn release_trigger(&mut self) {
+        self.trigger_released = true;
+    }
+
+    /// Randomly deviates given direction within a cone defined by spread of the weapon in
+    /// current fire mode.
+    pub fn apply_spread(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let spread = (self.definition.spread * self.fire_mode.spread_multiplier()).to_radians();
+        if spread <= 0.0 {
+            return direction;
+        }
+        let side = direction
+            .cross(&Vector3::y())
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::x);
+        let up = side.cross(&direction);
+        let mut rng = rand::thread_rng();
+        let deviation = rng.gen_range(0.0..spread).tan();
+        let phi = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
+        (direction + (side.scale(phi.cos()) + up.scale(phi.sin())).scale(deviation))
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or(direction)
+    }
+
     fn update_laser_sight(
         &self,
         graph: &mut Graph,
@@ -314,11 +575,42 @@ impl Weapon {
         self.owner = owner;
     }
 
+    fn can_shoot(&mut self, time: GameTime) -> bool {
+        let interval = if self.burst_shots_left > 0 {
+            // Shots in a burst follow each other faster.
+            self.definition.shoot_interval * 0.5
+        } else {
+            self.definition.shoot_interval
+        };
+        if self.ammo == 0 || time.elapsed - self.last_shot_time < interval {
+            return false;
+        }
+        match self.fire_mode {
+            FireMode::Auto => true,
+            FireMode::Single => self.trigger_released,
+            FireMode::Burst => {
+                if self.burst_shots_left > 0 {
+                    self.burst_shots_left -= 1;
+                    true
+                } else if self.trigger_released {
+                    self.burst_shots_left = Self::BURST_SIZE - 1;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
     pub fn try_shoot(&mut self, scene: &mut Scene, time: GameTime) -> bool {
-        if self.ammo != 0 && time.elapsed - self.last_shot_time >= self.definition.shoot_interval {
+        if self.can_shoot(time) {
             self.ammo -= 1;
+            self.trigger_released = false;
+            if self.ammo == 0 {
+                self.burst_shots_left = 0;
+            }
 
-            self.offset = Vector3::new(0.0, 0.0, -0.05);
+            self.offset = Vector3::new(0.0, 0.0, -0.05 * self.fire_mode.recoil_multiplier());
             self.last_shot_time = time.elapsed;
 
             let position = self.get_shot_position(&scene.graph);
@@ -373,9 +665,9 @@ impl WeaponContainer {
         self.pool.iter_mut()
     }
 
-    pub fn update(&mut self, scene: &mut Scene, actors: &ActorContainer) {
-        for weapon in self.pool.iter_mut() {
-            weapon.update(scene, actors)
+    pub fn update(&mut self, scene: &mut Scene, actors: &ActorContainer, time: GameTime) {
+        for (handle, weapon) in self.pool.pair_iter_mut() {
+            weapon.update(handle, scene, actors, time)
         }
     }
 }
@@ -0,0 +1,201 @@
+//! Weapon balance telemetry. Every match counts shots, hits, kills and damage of every weapon
+//! kind, report is written to the log and next to the match timeline when match ends.
+//!
+//! If telemetry is enabled in options, counters of every match are also added to aggregate
+//! file `weapon_stats.csv` in data directory, so weapon definitions can be tuned on numbers
+//! collected over many sessions.
+//!
+//! Counters are also kept for every actor, end-of-match awards are computed from them.
+
+use crate::weapon::WeaponKind;
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+const CSV_HEADER: &str = "weapon,shots,hits,kills,damage";
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct WeaponTally {
+    pub shots: u64,
+    pub hits: u64,
+    pub kills: u64,
+    pub damage: f64,
+}
+
+impl WeaponTally {
+    fn add(&mut self, other: &WeaponTally) {
+        self.shots += other.shots;
+        self.hits += other.hits;
+        self.kills += other.kills;
+        self.damage += other.damage;
+    }
+
+    fn accuracy(&self) -> f64 {
+        if self.shots > 0 {
+            self.hits as f64 / self.shots as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Counters of an actor over all weapons.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ActorTally {
+    pub shots: u64,
+    pub hits: u64,
+    pub kills: u64,
+    pub damage: f64,
+    pub captures: u64,
+}
+
+impl ActorTally {
+    /// Percentage of shots that hit, shotgun pellets may hit more than once per shot.
+    pub fn accuracy(&self) -> f64 {
+        if self.shots > 0 {
+            (self.hits as f64 / self.shots as f64 * 100.0).min(100.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Counters of every weapon kind, indexed by id of the kind.
+#[derive(Default)]
+pub struct WeaponStats {
+    tallies: Vec<WeaponTally>,
+    actors: HashMap<String, ActorTally>,
+}
+
+impl WeaponStats {
+    fn tally_mut(&mut self, kind: WeaponKind) -> &mut WeaponTally {
+        let index = kind.id() as usize;
+        if index >= self.tallies.len() {
+            self.tallies.resize(index + 1, Default::default());
+        }
+        &mut self.tallies[index]
+    }
+
+    pub fn record_shot(&mut self, kind: WeaponKind) {
+        self.tally_mut(kind).shots += 1;
+    }
+
+    pub fn record_hit(&mut self, kind: WeaponKind, damage: f32, killed: bool) {
+        let tally = self.tally_mut(kind);
+        tally.hits += 1;
+        tally.damage += damage as f64;
+        if killed {
+            tally.kills += 1;
+        }
+    }
+
+    fn actor_mut(&mut self, name: &str) -> &mut ActorTally {
+        self.actors.entry(name.to_owned()).or_default()
+    }
+
+    pub fn record_actor_shot(&mut self, name: &str) {
+        self.actor_mut(name).shots += 1;
+    }
+
+    pub fn record_actor_hit(&mut self, name: &str, damage: f32, killed: bool) {
+        let tally = self.actor_mut(name);
+        tally.hits += 1;
+        tally.damage += damage as f64;
+        if killed {
+            tally.kills += 1;
+        }
+    }
+
+    pub fn record_capture(&mut self, name: &str) {
+        self.actor_mut(name).captures += 1;
+    }
+
+    /// Counters of every actor that did anything in the match, sorted by name.
+    pub fn actors(&self) -> Vec<(&str, &ActorTally)> {
+        let mut actors = self
+            .actors
+            .iter()
+            .map(|(name, tally)| (name.as_str(), tally))
+            .collect::<Vec<_>>();
+        actors.sort_by(|a, b| a.0.cmp(b.0));
+        actors
+    }
+
+    fn used(&self) -> impl Iterator<Item = (WeaponKind, &WeaponTally)> {
+        self.tallies
+            .iter()
+            .enumerate()
+            .filter(|(_, tally)| tally.shots > 0 || tally.hits > 0)
+            .filter_map(|(id, tally)| WeaponKind::new(id as u32).ok().map(|kind| (kind, tally)))
+    }
+
+    /// Table of counters of weapons that were used in the match.
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "{:<16}{:>8}{:>8}{:>8}{:>8}{:>10}\n",
+            "Weapon", "Shots", "Hits", "Acc%", "Kills", "Damage"
+        );
+        for (kind, tally) in self.used() {
+            writeln!(
+                report,
+                "{:<16}{:>8}{:>8}{:>8.1}{:>8}{:>10.0}",
+                kind.name(),
+                tally.shots,
+                tally.hits,
+                tally.accuracy(),
+                tally.kills,
+                tally.damage
+            )
+            .unwrap();
+        }
+        report
+    }
+
+    pub fn write_report(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.report())
+    }
+
+    /// Adds counters of the match to aggregate file, file is created if it does not exist.
+    /// Rows of unknown weapons are kept as is, so file survives removal of a weapon.
+    pub fn merge_into(&self, path: &Path) -> io::Result<()> {
+        let mut totals = Vec::<(String, WeaponTally)>::new();
+        if let Ok(file) = File::open(path) {
+            for line in BufReader::new(file).lines().skip(1) {
+                let line = line?;
+                let fields = line.split(',').collect::<Vec<_>>();
+                if fields.len() != 5 {
+                    continue;
+                }
+                let tally = WeaponTally {
+                    shots: fields[1].parse().unwrap_or_default(),
+                    hits: fields[2].parse().unwrap_or_default(),
+                    kills: fields[3].parse().unwrap_or_default(),
+                    damage: fields[4].parse().unwrap_or_default(),
+                };
+                totals.push((fields[0].to_owned(), tally));
+            }
+        }
+
+        for (kind, tally) in self.used() {
+            match totals.iter_mut().find(|(name, _)| name == kind.name()) {
+                Some((_, total)) => total.add(tally),
+                None => totals.push((kind.name().to_owned(), *tally)),
+            }
+        }
+
+        let mut file = io::BufWriter::new(File::create(path)?);
+        writeln!(file, "{}", CSV_HEADER)?;
+        for (name, tally) in totals {
+            writeln!(
+                file,
+                "{},{},{},{},{:.1}",
+                name, tally.shots, tally.hits, tally.kills, tally.damage
+            )?;
+        }
+        file.flush()
+    }
+}
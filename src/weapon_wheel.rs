@@ -0,0 +1,52 @@
+//! Radial weapon selection. While weapon wheel key is held, mouse moves a cursor from center
+//! of the wheel instead of turning the camera, weapon in direction of the cursor is selected
+//! when key is released. Owned weapons are placed around the wheel clockwise starting from
+//! the top, in the same order as number keys select them.
+//!
+//! Engine has no gamepad input, so the wheel is driven by mouse only.
+
+use rg3d::core::algebra::Vector2;
+
+/// Cursor can not move further than this from center of the wheel.
+const RADIUS: f32 = 100.0;
+
+/// Cursor closer than this to the center selects nothing, so release without moving the mouse
+/// keeps current weapon.
+const DEAD_ZONE: f32 = 20.0;
+
+/// Game runs this much slower while wheel is open in local match.
+pub const TIME_SCALE: f32 = 0.3;
+
+#[derive(Default)]
+pub struct WeaponWheel {
+    cursor: Vector2<f32>,
+}
+
+impl WeaponWheel {
+    /// Moves cursor by mouse motion, screen Y axis points down.
+    pub fn push(&mut self, delta: Vector2<f32>) {
+        self.cursor += delta;
+        let length = self.cursor.norm();
+        if length > RADIUS {
+            self.cursor = self.cursor.scale(RADIUS / length);
+        }
+    }
+
+    /// Returns index of weapon cursor points to among `count` weapons.
+    pub fn selection(&self, count: usize) -> Option<usize> {
+        if count == 0 || self.cursor.norm() < DEAD_ZONE {
+            return None;
+        }
+        let sector = std::f32::consts::PI * 2.0 / count as f32;
+        // Angle is measured clockwise from the top, first sector is centered at the top.
+        let angle = self.cursor.x.atan2(-self.cursor.y) + sector * 0.5;
+        let angle = angle.rem_euclid(std::f32::consts::PI * 2.0);
+        Some((angle / sector) as usize % count)
+    }
+}
+
+/// Offset of slot center from center of the wheel on screen.
+pub fn slot_offset(index: usize, count: usize, radius: f32) -> Vector2<f32> {
+    let angle = std::f32::consts::PI * 2.0 * index as f32 / count as f32;
+    Vector2::new(angle.sin(), -angle.cos()).scale(radius)
+}